@@ -16,7 +16,9 @@ use aptos_api_types::{
     transaction::ReplayProtector, AptosErrorCode, AsConverter, BcsBlock, GasEstimation, LedgerInfo,
     ResourceGroup, TransactionOnChainData, TransactionSummary,
 };
-use aptos_config::config::{GasEstimationConfig, NodeConfig, RoleType};
+use aptos_config::config::{
+    transaction_filter_type::SharedFilter, GasEstimationConfig, NodeConfig, RoleType,
+};
 use aptos_crypto::HashValue;
 use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_logger::{error, info, Schema};
@@ -54,7 +56,7 @@ use futures::{channel::oneshot, SinkExt};
 use mini_moka::sync::Cache;
 use move_core_types::{
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
     move_resource::MoveResource,
 };
 use serde::Serialize;
@@ -83,6 +85,8 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub transaction_stream_active_connections: Arc<AtomicUsize>,
+    transaction_submission_filter: SharedFilter,
 }
 
 impl std::fmt::Debug for Context {
@@ -112,6 +116,8 @@ impl Context {
                 )),
             )
         };
+        let transaction_submission_filter =
+            SharedFilter::new(node_config.api.transaction_submission_filter.clone());
         Self {
             chain_id,
             db,
@@ -135,9 +141,25 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            transaction_stream_active_connections: Arc::new(AtomicUsize::new(0)),
+            transaction_submission_filter,
         }
     }
 
+    /// Uses the given transaction submission filter instead of the one from the config, so that
+    /// it can be updated at runtime (e.g., through the admin service).
+    pub fn with_transaction_submission_filter(mut self, filter: SharedFilter) -> Self {
+        self.transaction_submission_filter = filter;
+        self
+    }
+
+    /// Returns true iff the transaction submission filter allows the transaction
+    pub fn allows_transaction_submission(&self, txn: &SignedTransaction) -> bool {
+        self.transaction_submission_filter
+            .load()
+            .allows_transaction(txn)
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
@@ -215,6 +237,10 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_view_function_batch_size(&self) -> usize {
+        self.node_config.api.max_view_function_batch_size
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -1109,6 +1135,32 @@ impl Context {
         }
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_event_index: u64,
+        end_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(EventWithVersion, u64)>> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Internal indexer reader doesn't exist"))?
+            .get_events_by_type(
+                type_tag,
+                start_version,
+                start_event_index,
+                end_version,
+                limit as u64,
+                ledger_version,
+            )
+    }
+
+    pub fn event_by_type_index_enabled(&self) -> bool {
+        self.node_config.indexer_db_config.enable_event_by_type()
+    }
+
     pub fn get_indexer_reader(&self) -> Option<&Arc<dyn IndexerReader>> {
         self.indexer_reader.as_ref()
     }
@@ -9,8 +9,8 @@ use crate::{
     failpoint::fail_point_poem,
     page::Page,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        api_disabled, version_not_found, version_pruned, BadRequestError, BasicErrorWith404,
+        BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
     },
     ApiTags,
 };
@@ -19,7 +19,8 @@ use aptos_api_types::{
     verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
     MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::EventWithVersion, event::EventKey};
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::{
     param::{Path, Query},
     OpenApi,
@@ -149,6 +150,92 @@ impl EventsApi {
         })
         .await
     }
+
+    /// Get events by type
+    ///
+    /// This API returns events of the given fully qualified type emitted
+    /// within a range of ledger versions, in ascending order. Unlike the
+    /// other events APIs, it also covers module events, which aren't emitted
+    /// to an event handle. It is only available on nodes running the internal
+    /// indexer with the event by type index enabled.
+    ///
+    /// If more events may follow, the `X-Aptos-Cursor` header holds the
+    /// position of the next one as `<version>:<event_index>`. To get the next
+    /// page, pass them as `start_version` and `start_event_index`.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Fully qualified event type e.g. `0x1::fungible_asset::Deposit`
+        event_type: Path<MoveStructTag>,
+        /// Ledger version to start looking for events from
+        ///
+        /// If unspecified, defaults to the oldest ledger version
+        start_version: Query<Option<U64>>,
+        /// Index among the events of the transaction at `start_version` to
+        /// start from
+        ///
+        /// If unspecified, defaults to 0
+        start_event_index: Query<Option<U64>>,
+        /// Ledger version to stop looking for events before (exclusive)
+        ///
+        /// If unspecified, events up to the latest ledger version are returned
+        end_version: Query<Option<U64>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        event_type
+            .0
+            .verify(0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_type")?;
+        self.context
+            .check_api_output_enabled("Get events by type", &accept_type)?;
+        if !self.context.event_by_type_index_enabled() {
+            return Err(api_disabled("Get events by type"));
+        }
+        let type_tag: StructTag = (&event_type.0)
+            .try_into()
+            .context("Failed to parse given event type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let page = Page::new(None, limit.0, self.context.max_events_page_size());
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            let latest_ledger_info = api.context.get_latest_ledger_info()?;
+            let start_version = start_version
+                .0
+                .map_or(latest_ledger_info.oldest_ledger_version.0, |v| v.0);
+            if start_version < latest_ledger_info.oldest_ledger_version.0 {
+                return Err(version_pruned(start_version, &latest_ledger_info));
+            } else if start_version > latest_ledger_info.version() {
+                return Err(version_not_found(start_version, &latest_ledger_info));
+            }
+
+            api.list_by_type(
+                latest_ledger_info,
+                accept_type,
+                page,
+                TypeTag::Struct(Box::new(type_tag)),
+                start_version,
+                start_event_index.0.map_or(0, |v| v.0),
+                end_version.0.map_or(u64::MAX, |v| v.0),
+            )
+        })
+        .await
+    }
 }
 
 impl EventsApi {
@@ -178,6 +265,62 @@ impl EventsApi {
                 )
             })?;
 
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// List events of a type over a range of versions
+    fn list_by_type(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        page: Page,
+        type_tag: TypeTag,
+        start_version: u64,
+        start_event_index: u64,
+        end_version: u64,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let ledger_version = latest_ledger_info.version();
+        let limit = page.limit(&latest_ledger_info)?;
+        let events = self
+            .context
+            .get_events_by_type(
+                &type_tag,
+                start_version,
+                start_event_index,
+                end_version,
+                limit,
+                ledger_version,
+            )
+            .context(format!(
+                "Failed to find events by type {}",
+                type_tag.to_canonical_string()
+            ))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        // A full page may be followed by more events, starting right after its last one.
+        let cursor = match events.last() {
+            Some((event, idx)) if events.len() == limit as usize => {
+                Some(format!("{}:{}", event.transaction_version, idx + 1))
+            },
+            _ => None,
+        };
+        let events = events.into_iter().map(|(event, _)| event).collect();
+        self.render(latest_ledger_info, accept_type, events)
+            .map(|response| response.with_raw_cursor(cursor))
+    }
+
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let events = self
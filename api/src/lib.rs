@@ -23,6 +23,7 @@ mod runtime;
 mod set_failpoints;
 pub mod spec;
 mod state;
+mod stream;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
@@ -475,11 +475,16 @@ macro_rules! generate_success_response {
                )))
             }
 
-            pub fn with_cursor(mut self, new_cursor: Option<aptos_types::state_store::state_key::StateKey>) -> Self {
+            pub fn with_cursor(self, new_cursor: Option<aptos_types::state_store::state_key::StateKey>) -> Self {
+                self.with_raw_cursor(new_cursor.map(|c| aptos_api_types::StateKeyWrapper::from(c).to_string()))
+            }
+
+            /// Sets a cursor of an endpoint paginating over something else than state keys.
+            pub fn with_raw_cursor(mut self, new_cursor: Option<String>) -> Self {
                 match self {
                     $(
                     [<$enum_name>]::$name(_, _, _, _, _, _, _, _, _, ref mut cursor) => {
-                        *cursor = new_cursor.map(|c| aptos_api_types::StateKeyWrapper::from(c).to_string());
+                        *cursor = new_cursor;
                     }
                     )*
                 }
@@ -15,11 +15,12 @@ use crate::{
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
+    stream::StreamApi,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
 };
 use anyhow::{anyhow, Context as AnyhowContext};
-use aptos_config::config::{ApiConfig, NodeConfig};
+use aptos_config::config::{transaction_filter_type::SharedFilter, ApiConfig, NodeConfig};
 use aptos_logger::info;
 use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
@@ -47,11 +48,13 @@ pub fn bootstrap(
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
     port_tx: Option<oneshot::Sender<u16>>,
+    transaction_submission_filter: SharedFilter,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
     let runtime = aptos_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader)
+        .with_transaction_submission_filter(transaction_submission_filter);
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false, port_tx)
         .context("Failed to attach poem to runtime")?;
@@ -117,6 +120,7 @@ pub fn get_api_service(
         EventsApi,
         IndexApi,
         StateApi,
+        StreamApi,
         TransactionsApi,
         ViewFunctionApi,
     ),
@@ -142,6 +146,9 @@ pub fn get_api_service(
         StateApi {
             context: context.clone(),
         },
+        StreamApi {
+            context: context.clone(),
+        },
         TransactionsApi {
             context: context.clone(),
         },
@@ -300,7 +307,7 @@ mod tests {
     use super::bootstrap;
     use crate::runtime::get_max_runtime_workers;
     use aptos_api_test_context::{new_test_context, TestContext};
-    use aptos_config::config::{ApiConfig, NodeConfig};
+    use aptos_config::config::{transaction_filter_type::SharedFilter, ApiConfig, NodeConfig};
     use aptos_types::chain_id::ChainId;
     use std::time::Duration;
 
@@ -364,6 +371,7 @@ mod tests {
             context.mempool.ac_client.clone(),
             None,
             None,
+            SharedFilter::new(cfg.api.transaction_submission_filter.clone()),
         );
         assert!(ret.is_ok());
 
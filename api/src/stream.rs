@@ -0,0 +1,596 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    context::{api_spawn_blocking, Context},
+    failpoint::fail_point_poem,
+    metrics::TRANSACTION_STREAM_GAUGE,
+    response::{
+        api_disabled, version_pruned, BadRequestError, BasicErrorWith404, InternalError,
+        ServiceUnavailableError,
+    },
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, AsConverter, EntryFunctionId, LedgerInfo, MoveStructTag, Transaction,
+    TransactionOnChainData, VerifyInput, VerifyInputWithRecursion, VersionedEvent, U64,
+};
+use aptos_logger::warn;
+use aptos_protos::transaction::v1 as proto;
+use aptos_transaction_filter::{
+    EntryFunctionFilter, EntryFunctionFilterBuilder, EventFilter, EventFilterBuilder, Filterable,
+    MoveStructTagFilterBuilder, UserTransactionFilter, UserTransactionFilterBuilder,
+};
+use aptos_types::{
+    contract_event::{ContractEvent, EventWithVersion},
+    transaction::TransactionExecutableRef,
+};
+use futures::{stream, Stream, StreamExt};
+use move_core_types::language_storage::TypeTag;
+use poem_openapi::{param::Query, payload::EventStream, OpenApi};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+type StreamResult<T> = poem::Result<EventStream<stream::BoxStream<'static, T>>, BasicErrorWith404>;
+
+/// The number of batches without a match a stream reads in a row before waiting for the poll
+/// interval, so that a filter matching nothing does not keep scanning storage nonstop.
+const MAX_EMPTY_BATCHES_PER_POLL: usize = 10;
+
+/// API for streaming committed transactions and events as server-sent events
+#[derive(Clone)]
+pub struct StreamApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl StreamApi {
+    /// Stream committed transactions
+    ///
+    /// Opens a server-sent event stream that pushes committed transactions in version
+    /// order, one event per transaction, starting at `start_version`. The stream waits
+    /// for new transactions once it has caught up with the ledger and never ends on
+    /// its own.
+    ///
+    /// Transactions can be filtered by sender, entry function and event type. When
+    /// more than one filter is given, a transaction must match all of them. Event
+    /// types match on address, module and name; generic type parameters are ignored.
+    ///
+    /// If the start version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/stream/transactions",
+        method = "get",
+        operation_id = "stream_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn stream_transactions(
+        &self,
+        /// Ledger version to start streaming transactions from
+        ///
+        /// If not provided, only transactions committed after the stream is opened
+        /// are sent
+        start_version: Query<Option<U64>>,
+        /// Only send user transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only send user transactions calling this entry function e.g. `0x1::coin::transfer`
+        entry_function: Query<Option<EntryFunctionId>>,
+        /// Only send transactions emitting an event of this type e.g. `0x1::coin::CoinDeposit`
+        event_type: Query<Option<MoveStructTag>>,
+    ) -> StreamResult<Transaction> {
+        fail_point_poem("endpoint_stream_transactions")?;
+        let filter = StreamFilter::new(sender.0, entry_function.0, event_type.0)?;
+        let (start_version, guard) = self.open_stream("Stream transactions", start_version.0)?;
+
+        Ok(self.event_stream(stream_batches(
+            self.context.clone(),
+            filter,
+            start_version,
+            guard,
+            read_transactions,
+        )))
+    }
+
+    /// Stream committed events
+    ///
+    /// Opens a server-sent event stream that pushes the events of committed
+    /// transactions in version order, one event per event, starting at
+    /// `start_version`. The stream waits for new transactions once it has caught up
+    /// with the ledger and never ends on its own.
+    ///
+    /// Events can be filtered by their type, and by the sender and entry function of
+    /// the transaction that emitted them. When more than one filter is given, an event
+    /// must match all of them. Event types match on address, module and name; generic
+    /// type parameters are ignored.
+    ///
+    /// If the start version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/stream/events",
+        method = "get",
+        operation_id = "stream_events",
+        tag = "ApiTags::Events"
+    )]
+    async fn stream_events(
+        &self,
+        /// Ledger version to start streaming events from
+        ///
+        /// If not provided, only events committed after the stream is opened are sent
+        start_version: Query<Option<U64>>,
+        /// Only send events of user transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only send events of user transactions calling this entry function e.g.
+        /// `0x1::coin::transfer`
+        entry_function: Query<Option<EntryFunctionId>>,
+        /// Only send events of this type e.g. `0x1::coin::CoinDeposit`
+        event_type: Query<Option<MoveStructTag>>,
+    ) -> StreamResult<VersionedEvent> {
+        fail_point_poem("endpoint_stream_events")?;
+        let filter = StreamFilter::new(sender.0, entry_function.0, event_type.0)?;
+        let (start_version, guard) = self.open_stream("Stream events", start_version.0)?;
+
+        Ok(self.event_stream(stream_batches(
+            self.context.clone(),
+            filter,
+            start_version,
+            guard,
+            read_events,
+        )))
+    }
+}
+
+impl StreamApi {
+    /// Checks that a new stream can be opened and resolves the version it starts at
+    fn open_stream(
+        &self,
+        identifier: &'static str,
+        start_version: Option<U64>,
+    ) -> Result<(u64, StreamConnectionGuard), BasicErrorWith404> {
+        if !self.context.node_config.api.transaction_stream_enabled {
+            return Err(api_disabled(identifier));
+        }
+        self.context
+            .check_api_output_enabled(identifier, &AcceptType::Json)?;
+
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let start_version = match start_version {
+            Some(version) if version.0 < ledger_info.oldest_ledger_version.0 => {
+                return Err(version_pruned(version.0, &ledger_info));
+            },
+            Some(version) => version.0,
+            None => ledger_info.version() + 1,
+        };
+
+        let guard = StreamConnectionGuard::acquire(
+            &self.context.transaction_stream_active_connections,
+            self.context
+                .node_config
+                .api
+                .transaction_stream_max_active_connections,
+        )
+        .ok_or_else(|| {
+            BasicErrorWith404::service_unavailable_with_code(
+                "Too many open streams, try again later",
+                AptosErrorCode::TooManyStreams,
+                &ledger_info,
+            )
+        })?;
+
+        Ok((start_version, guard))
+    }
+
+    /// Sends the items as server-sent events, with keep-alives so that disconnected clients
+    /// are noticed even when nothing matches their filters
+    fn event_stream<T: Send + 'static>(
+        &self,
+        items: impl Stream<Item = T> + Send + 'static,
+    ) -> EventStream<stream::BoxStream<'static, T>> {
+        EventStream::new(items.boxed()).keep_alive(Duration::from_millis(
+            self.context
+                .node_config
+                .api
+                .transaction_stream_keep_alive_interval_ms,
+        ))
+    }
+}
+
+/// Holds one of the limited stream connection slots until the stream is dropped
+struct StreamConnectionGuard {
+    active_connections: Arc<AtomicUsize>,
+}
+
+impl StreamConnectionGuard {
+    fn acquire(active_connections: &Arc<AtomicUsize>, max_connections: usize) -> Option<Self> {
+        if active_connections.fetch_add(1, Ordering::Relaxed) >= max_connections {
+            active_connections.fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        TRANSACTION_STREAM_GAUGE.inc();
+        Some(Self {
+            active_connections: active_connections.clone(),
+        })
+    }
+}
+
+impl Drop for StreamConnectionGuard {
+    fn drop(&mut self) {
+        TRANSACTION_STREAM_GAUGE.dec();
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Filters of a stream, built from the predicates of the indexer transaction filter
+struct StreamFilter {
+    sender: Option<UserTransactionFilter>,
+    entry_function: Option<EntryFunctionFilter>,
+    event: Option<EventFilter>,
+}
+
+impl StreamFilter {
+    fn new(
+        sender: Option<Address>,
+        entry_function: Option<EntryFunctionId>,
+        event_type: Option<MoveStructTag>,
+    ) -> Result<Self, BasicErrorWith404> {
+        let invalid_input = |err: anyhow::Error| {
+            BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+        };
+
+        let sender = sender.map(|sender| {
+            UserTransactionFilterBuilder::default()
+                .sender(sender.to_string())
+                .build()
+                .expect("Sender filter has all fields set")
+        });
+        let entry_function = entry_function
+            .map(|entry_function| {
+                entry_function
+                    .verify()
+                    .context("'entry_function' invalid")
+                    .map_err(invalid_input)?;
+                Ok(EntryFunctionFilterBuilder::default()
+                    .address(entry_function.module.address.to_string())
+                    .module(entry_function.module.name.to_string())
+                    .function(entry_function.name.to_string())
+                    .build()
+                    .expect("Entry function filter has all fields set"))
+            })
+            .transpose()?;
+        let event = event_type
+            .map(|event_type| {
+                event_type
+                    .verify(0)
+                    .context("'event_type' invalid")
+                    .map_err(invalid_input)?;
+                let struct_type = MoveStructTagFilterBuilder::default()
+                    .address(event_type.address.to_string())
+                    .module(event_type.module.to_string())
+                    .name(event_type.name.to_string())
+                    .build()
+                    .expect("Event type filter has all fields set");
+                Ok(EventFilterBuilder::default()
+                    .struct_type(struct_type)
+                    .build()
+                    .expect("Event filter has all fields set"))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            sender,
+            entry_function,
+            event,
+        })
+    }
+
+    fn matches_transaction(&self, txn: &TransactionOnChainData) -> bool {
+        if self.sender.is_some() || self.entry_function.is_some() {
+            let filterable = filterable_transaction(txn);
+            if !self
+                .sender
+                .as_ref()
+                .map_or(true, |filter| filter.matches(&filterable))
+            {
+                return false;
+            }
+            if !self.entry_function.as_ref().map_or(true, |filter| {
+                filter.matches_opt(&entry_function_id(&filterable))
+            }) {
+                return false;
+            }
+        }
+        self.event.is_none() || txn.events.iter().any(|event| self.matches_event(event))
+    }
+
+    fn matches_event(&self, event: &ContractEvent) -> bool {
+        self.event
+            .as_ref()
+            .map_or(true, |filter| filter.matches(&filterable_event(event)))
+    }
+}
+
+/// Converts the parts of a transaction the stream filters look at into its protobuf form
+fn filterable_transaction(txn: &TransactionOnChainData) -> proto::Transaction {
+    let Some(signed_txn) = txn.transaction.try_as_signed_user_txn() else {
+        return proto::Transaction {
+            version: txn.version,
+            ..Default::default()
+        };
+    };
+
+    let payload = match signed_txn.executable_ref() {
+        Ok(TransactionExecutableRef::EntryFunction(entry_function)) => {
+            Some(proto::TransactionPayload {
+                r#type: proto::transaction_payload::Type::EntryFunctionPayload as i32,
+                payload: Some(proto::transaction_payload::Payload::EntryFunctionPayload(
+                    proto::EntryFunctionPayload {
+                        function: Some(proto::EntryFunctionId {
+                            module: Some(proto::MoveModuleId {
+                                address: entry_function.module().address().to_hex_literal(),
+                                name: entry_function.module().name().to_string(),
+                            }),
+                            name: entry_function.function().to_string(),
+                        }),
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            })
+        },
+        _ => None,
+    };
+
+    proto::Transaction {
+        version: txn.version,
+        r#type: proto::transaction::TransactionType::User as i32,
+        txn_data: Some(proto::transaction::TxnData::User(proto::UserTransaction {
+            request: Some(proto::UserTransactionRequest {
+                sender: signed_txn.sender().to_hex_literal(),
+                payload,
+                ..Default::default()
+            }),
+            events: vec![],
+        })),
+        ..Default::default()
+    }
+}
+
+fn entry_function_id(txn: &proto::Transaction) -> Option<proto::EntryFunctionId> {
+    let Some(proto::transaction::TxnData::User(user_txn)) = &txn.txn_data else {
+        return None;
+    };
+    match user_txn
+        .request
+        .as_ref()?
+        .payload
+        .as_ref()?
+        .payload
+        .as_ref()?
+    {
+        proto::transaction_payload::Payload::EntryFunctionPayload(payload) => {
+            payload.function.clone()
+        },
+        _ => None,
+    }
+}
+
+/// Converts the type of an event into its protobuf form for the event filter
+fn filterable_event(event: &ContractEvent) -> proto::Event {
+    let r#type = match event.type_tag() {
+        TypeTag::Struct(struct_tag) => proto::MoveType {
+            r#type: proto::MoveTypes::Struct as i32,
+            content: Some(proto::move_type::Content::Struct(proto::MoveStructTag {
+                address: struct_tag.address.to_hex_literal(),
+                module: struct_tag.module.to_string(),
+                name: struct_tag.name.to_string(),
+                generic_type_params: vec![],
+            })),
+        },
+        _ => proto::MoveType::default(),
+    };
+    proto::Event {
+        r#type: Some(r#type),
+        type_str: event.type_tag().to_canonical_string(),
+        ..Default::default()
+    }
+}
+
+/// Matching transactions of one storage read, with the timestamp of their block
+struct StreamBatch {
+    ledger_info: LedgerInfo,
+    transactions: Vec<(u64, TransactionOnChainData)>,
+    next_version: u64,
+}
+
+/// Reads the next batch of committed transactions starting at `start_version` and keeps
+/// the ones matching the filter. Returns `None` if there are no new transactions yet.
+fn read_batch(
+    context: &Context,
+    filter: &StreamFilter,
+    start_version: u64,
+) -> Result<Option<StreamBatch>, BasicErrorWith404> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let ledger_version = ledger_info.version();
+    if start_version > ledger_version {
+        return Ok(None);
+    }
+
+    let limit = (ledger_version - start_version + 1)
+        .min(context.node_config.api.transaction_stream_batch_size as u64) as u16;
+    let data = context
+        .get_transactions(start_version, limit, ledger_version)
+        .context("Failed to read raw transactions from storage")
+        .map_err(|err| {
+            BasicErrorWith404::internal_with_code(err, AptosErrorCode::InternalError, &ledger_info)
+        })?;
+    let next_version = start_version + data.len() as u64;
+
+    // Track the block timestamp over all transactions, not just the matching ones
+    let mut timestamp = context.get_block_timestamp(&ledger_info, start_version)?;
+    let mut transactions = vec![];
+    for txn in data {
+        if let Some(block_metadata) = txn.transaction.try_as_block_metadata_ext() {
+            timestamp = block_metadata.timestamp_usecs();
+        } else if let Some(block_metadata) = txn.transaction.try_as_block_metadata() {
+            timestamp = block_metadata.timestamp_usecs();
+        }
+        if filter.matches_transaction(&txn) {
+            transactions.push((timestamp, txn));
+        }
+    }
+
+    Ok(Some(StreamBatch {
+        ledger_info,
+        transactions,
+        next_version,
+    }))
+}
+
+fn read_transactions(
+    context: &Context,
+    filter: &StreamFilter,
+    start_version: u64,
+) -> Result<Option<(Vec<Transaction>, u64)>, BasicErrorWith404> {
+    let Some(batch) = read_batch(context, filter, start_version)? else {
+        return Ok(None);
+    };
+
+    let state_view = context.latest_state_view_poem(&batch.ledger_info)?;
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let transactions = batch
+        .transactions
+        .into_iter()
+        .map(|(timestamp, txn)| converter.try_into_onchain_transaction(timestamp, txn))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context("Failed to convert transaction data from storage")
+        .map_err(|err| {
+            BasicErrorWith404::internal_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &batch.ledger_info,
+            )
+        })?;
+
+    Ok(Some((transactions, batch.next_version)))
+}
+
+fn read_events(
+    context: &Context,
+    filter: &StreamFilter,
+    start_version: u64,
+) -> Result<Option<(Vec<VersionedEvent>, u64)>, BasicErrorWith404> {
+    let Some(batch) = read_batch(context, filter, start_version)? else {
+        return Ok(None);
+    };
+
+    let events = batch
+        .transactions
+        .into_iter()
+        .flat_map(|(_, txn)| {
+            let version = txn.version;
+            txn.events
+                .into_iter()
+                .filter(|event| filter.matches_event(event))
+                .map(move |event| EventWithVersion::new(version, event))
+        })
+        .collect::<Vec<_>>();
+    let events = context
+        .latest_state_view_poem(&batch.ledger_info)?
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .try_into_versioned_events(&events)
+        .context("Failed to convert events from storage into response")
+        .map_err(|err| {
+            BasicErrorWith404::internal_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &batch.ledger_info,
+            )
+        })?;
+
+    Ok(Some((events, batch.next_version)))
+}
+
+/// Sends the items produced by `read` one by one, starting at `start_version`
+///
+/// The next batch is only read from storage once the client has consumed the previous
+/// one, so a slow client slows down its own stream instead of buffering without bound.
+/// After `MAX_EMPTY_BATCHES_PER_POLL` batches in a row without a match, the stream waits
+/// for the poll interval before reading on.
+fn stream_batches<T, F>(
+    context: Arc<Context>,
+    filter: StreamFilter,
+    start_version: u64,
+    guard: StreamConnectionGuard,
+    read: F,
+) -> impl Stream<Item = T>
+where
+    T: Send + 'static,
+    F: Fn(&Context, &StreamFilter, u64) -> Result<Option<(Vec<T>, u64)>, BasicErrorWith404>
+        + Copy
+        + Send
+        + 'static,
+{
+    let poll_interval =
+        Duration::from_millis(context.node_config.api.transaction_stream_poll_interval_ms);
+    let state = StreamState {
+        context,
+        filter: Arc::new(filter),
+        next_version: start_version,
+        buffer: VecDeque::new(),
+        empty_batches: 0,
+        _guard: guard,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(item) = state.buffer.pop_front() {
+                return Some((item, state));
+            }
+
+            let context = state.context.clone();
+            let filter = state.filter.clone();
+            let next_version = state.next_version;
+            match api_spawn_blocking(move || read(&context, &filter, next_version)).await {
+                Ok(Some((items, next_version))) => {
+                    state.next_version = next_version;
+                    if items.is_empty() {
+                        state.empty_batches += 1;
+                        if state.empty_batches >= MAX_EMPTY_BATCHES_PER_POLL {
+                            state.empty_batches = 0;
+                            tokio::time::sleep(poll_interval).await;
+                        }
+                    } else {
+                        state.empty_batches = 0;
+                        state.buffer.extend(items);
+                    }
+                },
+                Ok(None) => {
+                    state.empty_batches = 0;
+                    tokio::time::sleep(poll_interval).await;
+                },
+                Err(err) => {
+                    warn!(
+                        "Closing transaction stream at version {}: {:?}",
+                        next_version, err
+                    );
+                    return None;
+                },
+            }
+        }
+    })
+}
+
+struct StreamState<T> {
+    context: Arc<Context>,
+    filter: Arc<StreamFilter>,
+    next_version: u64,
+    buffer: VecDeque<T>,
+    /// Batches read in a row without a match since the stream last waited
+    empty_batches: usize,
+    _guard: StreamConnectionGuard,
+}
@@ -211,6 +211,108 @@ async fn test_module_events() {
     assert_eq!(events.len(), 8);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+
+    let mut user = context.create_account().await;
+    let user_addr = user.address();
+    let named_addresses = vec![("event".to_string(), user_addr)];
+    let txn = futures::executor::block_on(async move {
+        let path = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"))
+            .join("../aptos-move/move-examples/event");
+        TestContext::build_package(path, named_addresses)
+    });
+    context.publish_package(&mut user, txn).await;
+
+    for num in ["3", "2"] {
+        context
+            .api_execute_entry_function(
+                &mut user,
+                &format!("0x{}::event::emit", user_addr.to_hex()),
+                json!([]),
+                json!([num]),
+            )
+            .await;
+    }
+    context.wait_for_internal_indexer_caught_up().await;
+
+    let event_type = utf8_percent_encode(
+        &format!("0x{}::event::MyEvent", user_addr.to_hex()),
+        NON_ALPHANUMERIC,
+    )
+    .to_string();
+    let resp = context
+        .get(format!("/events/by_type/{}", event_type).as_str())
+        .await;
+    let events = resp.as_array().unwrap();
+    assert_eq!(events.len(), 5);
+    let seqs: Vec<_> = events
+        .iter()
+        .map(|e| e["data"]["seq"].as_str().unwrap())
+        .collect();
+    assert_eq!(seqs, vec!["0", "1", "2", "0", "1"]);
+
+    // Pages are bounded by events, with a cursor pointing right after the last one, even within
+    // a transaction
+    let first_version: u64 = events[0]["version"].as_str().unwrap().parse().unwrap();
+    let mut pages = vec![];
+    let mut path = format!("/events/by_type/{}?limit=2", event_type);
+    loop {
+        let req = warp::test::request()
+            .method("GET")
+            .path(&format!("/v1{}", path));
+        let resp = context.reply(req).await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<serde_json::Value> = serde_json::from_slice(resp.body()).unwrap();
+        pages.push(page);
+        let Some(cursor) = resp.headers().get("X-Aptos-Cursor") else {
+            break;
+        };
+        let (version, event_index) = cursor.to_str().unwrap().split_once(':').unwrap();
+        path = format!(
+            "/events/by_type/{}?limit=2&start_version={}&start_event_index={}",
+            event_type, version, event_index
+        );
+    }
+    let page_sizes: Vec<_> = pages.iter().map(|page| page.len()).collect();
+    assert_eq!(page_sizes, vec![2, 2, 1]);
+    assert_eq!(pages.concat(), *events);
+
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version={}",
+                event_type,
+                first_version + 1
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 2);
+
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?end_version={}",
+                event_type, first_version
+            )
+            .as_str(),
+        )
+        .await;
+    assert!(resp.as_array().unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_without_index() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get("/events/by_type/0x1::reconfiguration::NewEpoch")
+        .await;
+}
+
 // until we have generics in the genesis
 #[ignore]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -18,6 +18,7 @@ mod resource_groups;
 mod secp256k1_ecdsa;
 mod simulation_test;
 mod state_test;
+mod stream_test;
 mod string_resource_test;
 mod transaction_vector_test;
 mod transactions_test;
@@ -39,7 +40,8 @@ fn new_test_context_with_config(test_name: String, node_config: NodeConfig) -> T
 fn new_test_context_with_db_sharding_and_internal_indexer(test_name: String) -> TestContext {
     let mut node_config = NodeConfig::default();
     node_config.storage.rocksdb_configs.enable_storage_sharding = true;
-    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, 0, true, 10);
+    node_config.indexer_db_config =
+        InternalIndexerDBConfig::new(true, true, true, 0, true, true, 10);
     let test_context = super_new_test_context(test_name, node_config, false, None);
     let _ = test_context
         .get_indexer_reader()
@@ -54,6 +56,7 @@ fn new_test_context_with_sharding_and_delayed_internal_indexer(
 ) -> TestContext {
     let mut node_config = NodeConfig::default();
     node_config.storage.rocksdb_configs.enable_storage_sharding = true;
-    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, 0, true, 1);
+    node_config.indexer_db_config =
+        InternalIndexerDBConfig::new(true, true, true, 0, true, true, 1);
     super_new_test_context(test_name, node_config, false, end_version)
 }
@@ -0,0 +1,280 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_config::config::NodeConfig;
+use aptos_types::transaction::SignedTransaction;
+use serde_json::Value;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+fn new_stream_test_context(test_name: String, max_connections: usize) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_stream_enabled = true;
+    node_config.api.transaction_stream_max_active_connections = max_connections;
+    node_config.api.transaction_stream_keep_alive_interval_ms = 100;
+    new_test_context_with_config(test_name, node_config)
+}
+
+/// Waits until all the stream slots are released
+async fn wait_for_released_connections(active_connections: &AtomicUsize) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while active_connections.load(Ordering::Relaxed) > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Stream slot was not released");
+}
+
+/// Reads the server-sent events of a stream, opened directly against the API server
+struct EventReader {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventReader {
+    async fn open(context: &TestContext, path: &str) -> Self {
+        let response = open_stream(context, path).await;
+        assert_eq!(response.status(), 200);
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// The data of the next event, as JSON
+    async fn next(&mut self) -> Value {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let event: String = self.buffer.drain(..end + 2).collect();
+                let data: String = event
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(str::trim_start)
+                    .collect();
+                // Comments, such as keep-alives, carry no data
+                if !data.is_empty() {
+                    return serde_json::from_str(&data).unwrap();
+                }
+                continue;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(10), self.response.chunk())
+                .await
+                .expect("Timed out waiting for an event")
+                .unwrap()
+                .expect("Stream ended");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+async fn open_stream(context: &TestContext, path: &str) -> reqwest::Response {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    reqwest::get(format!("http://{}/v1{}", address, path))
+        .await
+        .unwrap()
+}
+
+async fn version_of(context: &TestContext, txn: &SignedTransaction) -> String {
+    let txn = context
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            txn.committed_hash().to_hex_literal()
+        ))
+        .await;
+    txn["version"].as_str().unwrap().to_string()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_disabled() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_stream_enabled = false;
+
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    context
+        .expect_status_code(403)
+        .get("/stream/transactions")
+        .await;
+    context.expect_status_code(403).get("/stream/events").await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_disabled_by_default() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get("/stream/transactions")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_too_many_connections() {
+    let context = new_stream_test_context(current_function_name!(), 0);
+    let resp = context
+        .expect_status_code(503)
+        .get("/stream/transactions")
+        .await;
+    assert_eq!(resp["error_code"], "too_many_streams");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_invalid_filter() {
+    let context = new_stream_test_context(current_function_name!(), 100);
+    context
+        .expect_status_code(400)
+        .get("/stream/transactions?entry_function=0x1::coin")
+        .await;
+    context
+        .expect_status_code(400)
+        .get("/stream/events?event_type=0x1::coin::CoinDeposit<")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_from_start_version() {
+    let mut context = new_stream_test_context(current_function_name!(), 100);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+
+    let version = context.get_latest_ledger_info().version();
+    let mut stream = EventReader::open(
+        &context,
+        &format!("/stream/transactions?start_version={}", version - 2),
+    )
+    .await;
+    for expected in version - 2..=version {
+        assert_eq!(stream.next().await["version"], expected.to_string());
+    }
+
+    // Transactions committed after the stream caught up are pushed as well
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&[txn.clone()]).await;
+    let mint_version = version_of(&context, &txn).await;
+    loop {
+        let next = stream.next().await;
+        if next["version"] == mint_version {
+            assert_eq!(next["hash"], txn.committed_hash().to_hex_literal());
+            break;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_filters() {
+    let mut context = new_stream_test_context(current_function_name!(), 100);
+    let mut account = context.gen_account();
+    let create_txn = context.create_user_account(&account).await;
+    context.commit_block(&[create_txn.clone()]).await;
+    let mint_txn = context.mint_user_account(&account).await;
+    context.commit_block(&[mint_txn]).await;
+    let receiver = context.gen_account().address();
+    let transfer_txn = context.account_transfer_to(&mut account, receiver, 10);
+    context.commit_block(&[transfer_txn.clone()]).await;
+
+    let mut stream = EventReader::open(
+        &context,
+        &format!(
+            "/stream/transactions?start_version=0&sender={}",
+            account.address().to_hex_literal()
+        ),
+    )
+    .await;
+    let txn = stream.next().await;
+    assert_eq!(txn["hash"], transfer_txn.committed_hash().to_hex_literal());
+
+    let mut stream = EventReader::open(
+        &context,
+        "/stream/transactions?start_version=0&entry_function=0x1::aptos_account::create_account",
+    )
+    .await;
+    let txn = stream.next().await;
+    assert_eq!(txn["hash"], create_txn.committed_hash().to_hex_literal());
+    assert_eq!(
+        txn["payload"]["function"],
+        "0x1::aptos_account::create_account"
+    );
+
+    // Every user transaction emits a fee statement, the first one is the account creation's
+    let create_version = version_of(&context, &create_txn).await;
+    let mut stream = EventReader::open(
+        &context,
+        "/stream/events?start_version=0&event_type=0x1::transaction_fee::FeeStatement",
+    )
+    .await;
+    let event = stream.next().await;
+    assert_eq!(event["type"], "0x1::transaction_fee::FeeStatement");
+    assert_eq!(event["version"], create_version);
+
+    // Only the events of the sender's transactions are sent
+    let transfer_version = version_of(&context, &transfer_txn).await;
+    let mut stream = EventReader::open(
+        &context,
+        &format!(
+            "/stream/events?start_version=0&sender={}",
+            account.address().to_hex_literal()
+        ),
+    )
+    .await;
+    assert_eq!(stream.next().await["version"], transfer_version);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_connection_released_on_disconnect() {
+    let mut context = new_stream_test_context(current_function_name!(), 1);
+    let active_connections = context
+        .context
+        .transaction_stream_active_connections
+        .clone();
+
+    let mut stream = EventReader::open(&context, "/stream/transactions?start_version=0").await;
+    stream.next().await;
+    assert_eq!(active_connections.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        open_stream(&context, "/stream/transactions").await.status(),
+        503
+    );
+
+    // The server notices the disconnect once it sends the next transaction
+    drop(stream);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+    wait_for_released_connections(&active_connections).await;
+
+    assert_eq!(
+        open_stream(&context, "/stream/transactions").await.status(),
+        200
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_connection_released_without_matches() {
+    let mut context = new_stream_test_context(current_function_name!(), 1);
+    let active_connections = context
+        .context
+        .transaction_stream_active_connections
+        .clone();
+
+    // Nothing is ever sent on this stream but keep-alives
+    let sender = context.gen_account().address().to_hex_literal();
+    let response = open_stream(
+        &context,
+        &format!("/stream/transactions?start_version=0&sender={}", sender),
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(active_connections.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        open_stream(&context, "/stream/transactions").await.status(),
+        503
+    );
+
+    // The server notices the disconnect once it sends the next keep-alive
+    drop(response);
+    wait_for_released_connections(&active_connections).await;
+}
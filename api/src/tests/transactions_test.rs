@@ -8,7 +8,9 @@ use crate::tests::{
     new_test_context_with_sharding_and_delayed_internal_indexer,
 };
 use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
-use aptos_config::config::{GasEstimationStaticOverride, NodeConfig};
+use aptos_config::config::{
+    transaction_filter_type::Filter, GasEstimationStaticOverride, NodeConfig,
+};
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
@@ -1723,6 +1725,46 @@ async fn test_simulation_filter_allow_sender() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_transaction_submission_filter_deny_sender() {
+    let mut node_config = NodeConfig::default();
+
+    // Blocklist the root sender.
+    let mut filter = node_config.api.transaction_submission_filter.clone();
+    filter = filter.add_sender_filter(false, aptos_test_root_address());
+    node_config.api.transaction_submission_filter = filter;
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    let resp = context
+        .expect_status_code(403)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "rejected_by_filter");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_mempool_filter_deny_sender() {
+    let mut node_config = NodeConfig::default();
+
+    // Blocklist the root sender in mempool, the submission filter allows everything.
+    node_config.mempool.transaction_filter =
+        Filter::empty().add_sender_filter(false, aptos_test_root_address());
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    let resp = context
+        .expect_status_code(403)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "rejected_by_filter");
+    assert_eq!(resp["message"], "Transaction not allowed by mempool filter");
+}
+
 fn gen_string(len: u64) -> String {
     let mut rng = thread_rng();
     std::iter::repeat(())
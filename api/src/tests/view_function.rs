@@ -252,3 +252,74 @@ async fn test_view_aggregator() {
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut node_config = NodeConfig::default();
+
+    // Allowlist only the balance function.
+    node_config.api.view_filter = ViewFilter::Allowlist(vec![ViewFunctionId {
+        address: AccountAddress::from_str("0x1").unwrap(),
+        module: "coin".to_string(),
+        function_name: "balance".to_string(),
+    }]);
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!({
+                "requests": [
+                    { "request": build_coin_balance_request(&owner.address()) },
+                    { "request": build_coin_decimals_request() },
+                    {
+                        "request": build_coin_balance_request(&owner.address()),
+                        "max_gas_amount": "1",
+                    },
+                ],
+            }),
+        )
+        .await;
+
+    // The allowed call succeeds, while the filtered and out-of-gas calls fail on their own.
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["values"], json!(["100000"]));
+    assert!(results[0].get("error").is_none());
+    assert!(results[1]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("is not allowed"));
+    assert!(results[2]["values"].is_null());
+    assert!(results[2]["error"].is_object());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_too_large() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 1;
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!({
+                "requests": [
+                    { "request": build_coin_decimals_request() },
+                    { "request": build_coin_decimals_request() },
+                ],
+            }),
+        )
+        .await;
+
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
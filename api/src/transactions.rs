@@ -1358,6 +1358,13 @@ impl TransactionsApi {
 
     /// Submits a single transaction, and converts mempool codes to errors
     async fn create_internal(&self, txn: SignedTransaction) -> Result<(), AptosError> {
+        if !self.context.allows_transaction_submission(&txn) {
+            return Err(AptosError::new_with_error_code(
+                "Transaction not allowed by submission filter",
+                AptosErrorCode::RejectedByFilter,
+            ));
+        }
+
         let (mempool_status, vm_status_opt) = self
             .context
             .submit_transaction(txn)
@@ -1401,6 +1408,10 @@ impl TransactionsApi {
                 mempool_status.message,
                 AptosErrorCode::InvalidTransactionUpdate,
             )),
+            MempoolStatusCode::RejectedByFilter => Err(AptosError::new_with_error_code(
+                "Transaction not allowed by mempool filter",
+                AptosErrorCode::RejectedByFilter,
+            )),
             MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_error_code(
                 format!("Transaction was rejected with status {}", mempool_status,),
                 AptosErrorCode::InternalError,
@@ -1470,6 +1481,9 @@ impl TransactionsApi {
                         ledger_info,
                     ),
                 ),
+                AptosErrorCode::RejectedByFilter => Err(
+                    SubmitTransactionError::forbidden_from_aptos_error(error, ledger_info),
+                ),
                 _ => Err(SubmitTransactionError::internal_from_aptos_error(
                     error,
                     ledger_info,
@@ -14,10 +14,11 @@ use crate::{
 };
 use anyhow::Context as anyhowContext;
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED,
-    U64,
+    AptosError, AptosErrorCode, AsConverter, MoveValue, ViewBatchRequest, ViewBatchRequestItem,
+    ViewBatchResult, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_bcs_utils::serialize_uleb128;
+use aptos_storage_interface::state_store::state_view::db_state_view::DbStateView;
 use aptos_vm::AptosVM;
 use itertools::Itertools;
 use move_core_types::language_storage::TypeTag;
@@ -71,6 +72,47 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute multiple Move view functions against the same ledger version and return
+    /// their results in request order. Each call is limited by its own `max_gas_amount`,
+    /// capped at the node's view function gas limit, and fails independently: a failing
+    /// call is reported in its own result and does not fail the rest of the batch.
+    ///
+    /// The number of view functions in a batch is limited by the node configuration.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// View function requests with per-call gas limits
+        request: Json<ViewBatchRequest>,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<ViewBatchResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        self.context
+            .check_api_output_enabled("View function batch", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(BasicErrorWith404::bad_request_with_code_no_info(
+                "BCS is not supported for batch view functions",
+                AptosErrorCode::BcsNotSupported,
+            ));
+        }
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || view_batch_request(context, request.0, ledger_version)).await
+    }
 }
 
 fn view_request(
@@ -208,3 +250,143 @@ fn view_request(
     );
     result.map(|r| r.with_gas_used(Some(output.gas_used)))
 }
+
+fn view_batch_request(
+    context: Arc<Context>,
+    request: ViewBatchRequest,
+    ledger_version: Query<Option<U64>>,
+) -> BasicResultWith404<Vec<ViewBatchResult>> {
+    if request.requests.len() > context.max_view_function_batch_size() {
+        return Err(BasicErrorWith404::bad_request_with_code_no_info(
+            format!(
+                "Requested too many view functions: {}, while limit is {}",
+                request.requests.len(),
+                context.max_view_function_batch_size(),
+            ),
+            AptosErrorCode::InvalidInput,
+        ));
+    }
+
+    // All view functions in the batch are executed against the same state
+    let (ledger_info, requested_version) = context
+        .get_latest_ledger_info_and_verify_lookup_version(ledger_version.map(|inner| inner.0))?;
+
+    let state_view = context
+        .state_view_at_version(requested_version)
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &ledger_info,
+            )
+        })?;
+
+    let mut total_gas_used = 0;
+    let results = request
+        .requests
+        .into_iter()
+        .map(|item| {
+            let result = view_batch_item(&context, &state_view, item);
+            total_gas_used += result.gas_used.0;
+            result
+        })
+        .collect::<Vec<_>>();
+
+    BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+        .map(|r| r.with_gas_used(Some(total_gas_used)))
+}
+
+/// Executes a single view function of a batch, converting any failure into the item's error
+fn view_batch_item(
+    context: &Context,
+    state_view: &DbStateView,
+    item: ViewBatchRequestItem,
+) -> ViewBatchResult {
+    let failure = |error: AptosError, gas_used: u64| ViewBatchResult {
+        values: None,
+        error: Some(error),
+        gas_used: U64(gas_used),
+    };
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+
+    let view_function = match converter.convert_view_function(item.request) {
+        Ok(view_function) => view_function,
+        Err(err) => {
+            return failure(
+                AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput),
+                0,
+            )
+        },
+    };
+
+    // Reject the call if it's not allowed by the filter.
+    if !context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    ) {
+        return failure(
+            AptosError::new_with_error_code(
+                format!(
+                    "Function {}::{} is not allowed",
+                    view_function.module, view_function.function
+                ),
+                AptosErrorCode::InvalidInput,
+            ),
+            0,
+        );
+    }
+
+    let max_gas = context.node_config.api.max_gas_view_function;
+    let gas_budget = item
+        .max_gas_amount
+        .map_or(max_gas, |gas| gas.0.min(max_gas));
+    let output = AptosVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        gas_budget,
+    );
+    context.view_function_stats().increment(
+        FunctionStats::function_to_key(&view_function.module, &view_function.function),
+        output.gas_used,
+    );
+
+    let values = match output.values {
+        Ok(values) => values,
+        Err(err) => {
+            return failure(
+                AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput),
+                output.gas_used,
+            )
+        },
+    };
+    let move_values = converter
+        .function_return_types(&view_function)
+        .and_then(|tys| {
+            tys.iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .and_then(|return_types| {
+            values
+                .into_iter()
+                .zip(return_types.into_iter())
+                .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+                .collect::<anyhow::Result<Vec<_>>>()
+        });
+
+    match move_values {
+        Ok(move_values) => ViewBatchResult {
+            values: Some(move_values),
+            error: None,
+            gas_used: U64(output.gas_used),
+        },
+        Err(err) => failure(
+            AptosError::new_with_error_code(err, AptosErrorCode::InternalError),
+            output.gas_used,
+        ),
+    }
+}
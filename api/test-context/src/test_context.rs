@@ -185,7 +185,14 @@ pub fn new_test_context_inner(
     .unwrap();
     assert!(ret.is_some());
 
-    let mempool = MockSharedMempool::new_in_runtime(&db_rw, PooledVMValidator::new(db.clone(), 1));
+    // The mock mempool runs with the mempool config of the node, e.g., its transaction filter
+    let mut mempool_config = MockSharedMempool::default_config();
+    mempool_config.mempool = node_config.mempool.clone();
+    let mempool = MockSharedMempool::new_in_runtime_with_config(
+        mempool_config,
+        &db_rw,
+        PooledVMValidator::new(db.clone(), 1),
+    );
 
     node_config
         .storage
@@ -312,6 +312,7 @@ impl_poem_type!(
 impl_poem_parameter!(
     Address,
     AssetType,
+    EntryFunctionId,
     HashValue,
     IdentifierWrapper,
     HexEncodedBytes,
@@ -96,11 +96,15 @@ pub enum AptosErrorCode {
     SequenceNumberTooOld = 402,
     /// The submitted transaction failed VM checks.
     VmError = 403,
+    /// The submitted transaction was rejected by a transaction filter of the node.
+    RejectedByFilter = 404,
 
     /// Health check failed.
     HealthCheckFailed = 500,
     /// The mempool is full, no new transactions can be submitted.
     MempoolIsFull = 501,
+    /// The node has too many open streams, no new ones can be opened.
+    TooManyStreams = 502,
 
     /// Internal server error
     InternalError = 600,
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{AptosError, EntryFunctionId, MoveType, MoveValue, U64};
 use aptos_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
@@ -20,6 +20,41 @@ pub struct ViewRequest {
     pub arguments: Vec<serde_json::Value>,
 }
 
+/// Request for the batch View Function API
+///
+/// All view functions in the batch are executed against the same ledger version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ViewBatchRequest {
+    /// View functions to execute, in order
+    pub requests: Vec<ViewBatchRequestItem>,
+}
+
+/// A single view function call in a batch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ViewBatchRequestItem {
+    pub request: ViewRequest,
+    /// Maximum gas units this call may use
+    ///
+    /// If not provided, or larger than the node's view function gas limit, the node's
+    /// limit is used instead.
+    pub max_gas_amount: Option<U64>,
+}
+
+/// Result of a single view function call in a batch
+///
+/// Exactly one of `values` and `error` is set.
+#[derive(Clone, Debug, Serialize, Deserialize, Object)]
+pub struct ViewBatchResult {
+    /// Return values of the view function if it succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<MoveValue>>,
+    /// Error of the view function if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AptosError>,
+    /// Gas units used by the call
+    pub gas_used: U64,
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ViewFunction {
     pub module: ModuleId,
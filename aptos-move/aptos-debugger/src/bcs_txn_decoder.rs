@@ -3,7 +3,7 @@
 
 use crate::aptos_debugger::AptosDebugger;
 use anyhow::Result;
-use aptos_rest_client::Client;
+use aptos_rest_client::{AptosBaseUrl, Client, RetryPolicy};
 use aptos_types::transaction::SignedTransaction;
 use clap::Parser;
 use regex::Regex;
@@ -48,7 +48,11 @@ impl Command {
         println!("Network: {}", network);
 
         let endpoint = format!("https://{}.aptoslabs.com/v1", network);
-        let debugger = AptosDebugger::rest_client(Client::new(Url::parse(&endpoint)?))?;
+        let debugger = AptosDebugger::rest_client(
+            Client::builder(AptosBaseUrl::Custom(Url::parse(&endpoint)?))
+                .retry_policy(RetryPolicy::exponential())
+                .build(),
+        )?;
         let version = debugger
             .get_version_by_account_sequence(txn.sender(), txn.sequence_number())
             .await?
@@ -3,7 +3,7 @@
 
 use anyhow::{bail, Result};
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
-use aptos_rest_client::Client;
+use aptos_rest_client::{AptosBaseUrl, Client, RetryPolicy};
 use aptos_types::transaction::Transaction;
 use aptos_vm::AptosVM;
 use clap::{Parser, Subcommand};
@@ -38,9 +38,11 @@ async fn main() -> Result<()> {
     AptosVM::set_concurrency_level_once(1);
 
     let debugger = match args.target {
-        Target::Rest { endpoint } => {
-            AptosDebugger::rest_client(Client::new(Url::parse(&endpoint)?))?
-        },
+        Target::Rest { endpoint } => AptosDebugger::rest_client(
+            Client::builder(AptosBaseUrl::Custom(Url::parse(&endpoint)?))
+                .retry_policy(RetryPolicy::exponential())
+                .build(),
+        )?,
         Target::DB { path } => AptosDebugger::db(path)?,
     };
 
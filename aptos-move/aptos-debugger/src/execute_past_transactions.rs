@@ -3,7 +3,7 @@
 
 use crate::{aptos_debugger::AptosDebugger, common::Opts};
 use anyhow::Result;
-use aptos_rest_client::Client;
+use aptos_rest_client::{AptosBaseUrl, Client, RetryPolicy};
 use clap::Parser;
 use url::Url;
 
@@ -31,7 +31,11 @@ pub struct Command {
 impl Command {
     pub async fn run(self) -> Result<()> {
         let debugger = if let Some(rest_endpoint) = self.opts.target.rest_endpoint {
-            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+            AptosDebugger::rest_client(
+                Client::builder(AptosBaseUrl::Custom(Url::parse(&rest_endpoint)?))
+                    .retry_policy(RetryPolicy::exponential())
+                    .build(),
+            )?
         } else if let Some(db_path) = self.opts.target.db_path {
             AptosDebugger::db(db_path)?
         } else {
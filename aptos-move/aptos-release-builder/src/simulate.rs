@@ -14,7 +14,8 @@
 //!
 //! This simulation bypasses these challenges by patching specific Move functions
 //! with mock versions, most notably `fun resolve_multi_step_proposal`, thus allowing
-//! the governance process to be skipped altogether.
+//! the governance process to be skipped altogether. The patches are shared with simulation
+//! sessions, see `aptos_transaction_simulation_session::governance`.
 //!
 //! In other words, this simulation is intended for checking whether a governance
 //! proposal will execute successfully, assuming it gets approved, not whether the
@@ -22,48 +23,30 @@
 
 use crate::aptos_framework_path;
 use anyhow::{anyhow, bail, Context, Result};
-use aptos_crypto::HashValue;
 use aptos_gas_profiling::GasProfiler;
 use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_language_e2e_tests::account::AccountData;
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
 use aptos_rest_client::{AptosBaseUrl, Client};
 use aptos_transaction_simulation::{DeltaStateStore, SimulationStateStore};
+use aptos_transaction_simulation_session::governance::{
+    add_script_execution_hash, force_end_epoch, patch_aptos_governance, DUMMY_PROPOSAL_ID,
+    MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK,
+};
 use aptos_types::{
-    account_address::AccountAddress,
     account_config::ChainIdResource,
-    on_chain_config::{ApprovedExecutionHashes, GasScheduleV2, OnChainConfig},
+    on_chain_config::{GasScheduleV2, OnChainConfig},
     state_store::TStateView,
     transaction::{ExecutionStatus, Script, TransactionArgument, TransactionStatus},
 };
-use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::SessionId, AptosVM};
+use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
 use aptos_vm_environment::environment::AptosEnvironment;
 use aptos_vm_logging::log_schema::AdapterLogSchema;
-use aptos_vm_types::{
-    module_and_script_storage::AsAptosCodeStorage, module_write_set::ModuleWriteSet,
-    storage::change_set_configs::ChangeSetConfigs,
-};
+use aptos_vm_types::module_and_script_storage::AsAptosCodeStorage;
 use clap::Parser;
-use move_binary_format::{
-    access::ModuleAccess,
-    file_format::{
-        AddressIdentifierIndex, Bytecode, FunctionDefinition, FunctionHandle, FunctionHandleIndex,
-        IdentifierIndex, ModuleHandle, ModuleHandleIndex, Signature, SignatureIndex,
-        SignatureToken, Visibility,
-    },
-    CompiledModule,
-};
-use move_core_types::{
-    identifier::{IdentStr, Identifier},
-    language_storage::ModuleId,
-    value::MoveValue,
-};
-use move_vm_runtime::module_traversal::{TraversalContext, TraversalStorage};
-use move_vm_types::gas::UnmeteredGasMeter;
 use movement::{
     common::types::PromptOptions, governance::compile_in_temp_dir, move_tool::FrameworkPackageArgs,
 };
-use once_cell::sync::Lazy;
 use std::{
     io::Write,
     path::{Path, PathBuf},
@@ -71,295 +54,10 @@ use std::{
 use url::Url;
 use walkdir::WalkDir;
 
-/***************************************************************************************************
- * Compiled Module Helpers
- *
- **************************************************************************************************/
-fn find_function_def_by_name<'a>(
-    m: &'a mut CompiledModule,
-    name: &IdentStr,
-) -> Option<&'a mut FunctionDefinition> {
-    for (idx, func_def) in m.function_defs.iter().enumerate() {
-        let func_handle = m.function_handle_at(func_def.function);
-        let func_name = m.identifier_at(func_handle.name);
-        if name == func_name {
-            return Some(&mut m.function_defs[idx]);
-        }
-    }
-    None
-}
-
-fn get_or_add<T: PartialEq>(pool: &mut Vec<T>, val: T) -> usize {
-    match pool.iter().position(|elem| elem == &val) {
-        Some(idx) => idx,
-        None => {
-            let idx = pool.len();
-            pool.push(val);
-            idx
-        },
-    }
-}
-
-#[allow(dead_code)]
-fn get_or_add_addr(m: &mut CompiledModule, addr: AccountAddress) -> AddressIdentifierIndex {
-    AddressIdentifierIndex::new(get_or_add(&mut m.address_identifiers, addr) as u16)
-}
-
-fn get_or_add_ident(m: &mut CompiledModule, ident: Identifier) -> IdentifierIndex {
-    IdentifierIndex::new(get_or_add(&mut m.identifiers, ident) as u16)
-}
-
-#[allow(dead_code)]
-fn get_or_add_module_handle(
-    m: &mut CompiledModule,
-    addr: AccountAddress,
-    name: Identifier,
-) -> ModuleHandleIndex {
-    let addr = get_or_add_addr(m, addr);
-    let name = get_or_add_ident(m, name);
-    let module_handle = ModuleHandle {
-        address: addr,
-        name,
-    };
-    ModuleHandleIndex::new(get_or_add(&mut m.module_handles, module_handle) as u16)
-}
-
-fn get_or_add_signature(m: &mut CompiledModule, sig: Vec<SignatureToken>) -> SignatureIndex {
-    SignatureIndex::new(get_or_add(&mut m.signatures, Signature(sig)) as u16)
-}
-
-fn find_function_handle_by_name(
-    m: &CompiledModule,
-    addr: AccountAddress,
-    module_name: &IdentStr,
-    func_name: &IdentStr,
-) -> Option<FunctionHandleIndex> {
-    for (idx, func_handle) in m.function_handles().iter().enumerate() {
-        let module_handle = m.module_handle_at(func_handle.module);
-        if m.address_identifier_at(module_handle.address) == &addr
-            && m.identifier_at(module_handle.name) == module_name
-            && m.identifier_at(func_handle.name) == func_name
-        {
-            return Some(FunctionHandleIndex(idx as u16));
-        }
-    }
-    None
-}
-
-fn add_simple_native_function(
-    m: &mut CompiledModule,
-    func_name: Identifier,
-    params: Vec<SignatureToken>,
-    returns: Vec<SignatureToken>,
-) -> Result<FunctionHandleIndex> {
-    if let Some(func_handle_idx) =
-        find_function_handle_by_name(m, *m.self_addr(), m.self_name(), &func_name)
-    {
-        return Ok(func_handle_idx);
-    }
-
-    let name = get_or_add_ident(m, func_name);
-    let parameters = get_or_add_signature(m, params);
-    let return_ = get_or_add_signature(m, returns);
-    let func_handle = FunctionHandle {
-        module: m.self_handle_idx(),
-        name,
-        parameters,
-        return_,
-        type_parameters: vec![],
-        access_specifiers: None,
-        attributes: vec![],
-    };
-    let func_handle_idx = FunctionHandleIndex(m.function_handles.len() as u16);
-    m.function_handles.push(func_handle);
-
-    let func_def = FunctionDefinition {
-        function: func_handle_idx,
-        visibility: Visibility::Private,
-        is_entry: false,
-        acquires_global_resources: vec![],
-        code: None,
-    };
-    m.function_defs.push(func_def);
-
-    Ok(func_handle_idx)
-}
-
-/***************************************************************************************************
- * Patches
- *
- **************************************************************************************************/
-static MODULE_ID_APTOS_GOVERNANCE: Lazy<ModuleId> = Lazy::new(|| {
-    ModuleId::new(
-        AccountAddress::ONE,
-        Identifier::new("aptos_governance").unwrap(),
-    )
-});
-
-static FUNC_NAME_CREATE_SIGNER: Lazy<Identifier> =
-    Lazy::new(|| Identifier::new("create_signer").unwrap());
-
-static FUNC_NAME_RESOLVE_MULTI_STEP_PROPOSAL: Lazy<Identifier> =
-    Lazy::new(|| Identifier::new("resolve_multi_step_proposal").unwrap());
-
-const DUMMY_PROPOSAL_ID: u64 = u64::MAX;
-
-const MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK: u64 = 0xDEADBEEF;
-
-/// Helper to load a module from the state view, deserialize it, modify it with
-/// the provided callback, reserialize it and finally write it back.
-fn patch_module<F>(
-    state_view: &impl SimulationStateStore,
-    module_id: &ModuleId,
-    modify_module: F,
-) -> Result<()>
-where
-    F: FnOnce(&mut CompiledModule) -> Result<()>,
-{
-    let mut m = state_view
-        .get_module(module_id)?
-        .ok_or_else(|| anyhow!("module {} does not exist", module_id))?;
-
-    modify_module(&mut m)?;
-
-    // Sanity check to ensure the correctness of the check
-    move_bytecode_verifier::verify_module(&m).map_err(|err| {
-        anyhow!(
-            "patched module failed to verify -- check if the patch is correct: {}",
-            err
-        )
-    })?;
-
-    state_view.add_module(&m)?;
-
-    Ok(())
-}
-
-/// Patches `aptos_framework::aptos_governance::resolve_multi_step_proposal` so that
-/// it returns the requested signer directly, skipping the governance process altogether.
-fn patch_aptos_governance(
-    state_view: &impl SimulationStateStore,
-    forbid_next_execution_hash: bool,
-) -> Result<()> {
-    use Bytecode::*;
-
-    patch_module(state_view, &MODULE_ID_APTOS_GOVERNANCE, |m| {
-        // Inject `native fun create_signer`.
-        let create_signer_handle_idx = add_simple_native_function(
-            m,
-            FUNC_NAME_CREATE_SIGNER.clone(),
-            vec![SignatureToken::Address],
-            vec![SignatureToken::Signer],
-        )?;
-
-        // Patch `fun resolve_multi_step_proposal`.
-        let sig_u8_idx = get_or_add_signature(m, vec![SignatureToken::U8]);
-
-        let func_def = find_function_def_by_name(m, &FUNC_NAME_RESOLVE_MULTI_STEP_PROPOSAL)
-            .ok_or_else(|| {
-                anyhow!(
-                    "failed to locate `fun {}`",
-                    &*FUNC_NAME_RESOLVE_MULTI_STEP_PROPOSAL
-                )
-            })?;
-        func_def.acquires_global_resources = vec![];
-        let code = func_def.code.as_mut().ok_or_else(|| {
-            anyhow!(
-                "`fun {}` must have a Move-defined body",
-                &*FUNC_NAME_RESOLVE_MULTI_STEP_PROPOSAL
-            )
-        })?;
-
-        code.code.clear();
-        if forbid_next_execution_hash {
-            // If it is needed to forbid a next execution hash, inject additional Move
-            // code at the beginning that aborts with a magic number if the vector
-            // representing the hash is not empty.
-            //
-            //     if (!vector::is_empty(&next_execution_hash)) {
-            //         abort MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK;
-            //     }
-            //
-            // The magic number can later be checked in Rust to determine if such violation
-            // has happened.
-            code.code.extend([
-                ImmBorrowLoc(2),
-                VecLen(sig_u8_idx),
-                LdU64(0),
-                Eq,
-                BrTrue(7),
-                LdU64(MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK),
-                Abort,
-            ]);
-        }
-        // Replace the original logic with `create_signer(signer_address)`, bypassing
-        // the governance process.
-        code.code
-            .extend([MoveLoc(1), Call(create_signer_handle_idx), Ret]);
-
-        Ok(())
-    })
-}
-
-// Add the hash of the script to the list of approved hashes, so to enable the
-// alternative (higher) execution limits.
-fn add_script_execution_hash(
-    state_view: &impl SimulationStateStore,
-    hash: HashValue,
-) -> Result<()> {
-    let entry = (DUMMY_PROPOSAL_ID, hash.to_vec());
-
-    state_view.modify_on_chain_config(|approved_hashes: &mut ApprovedExecutionHashes| {
-        if !approved_hashes.entries.contains(&entry) {
-            approved_hashes.entries.push(entry);
-        }
-        Ok(())
-    })
-}
-
 /***************************************************************************************************
  * Simulation Workflow
  *
  **************************************************************************************************/
-fn force_end_epoch(state_view: &impl SimulationStateStore) -> Result<()> {
-    let env = AptosEnvironment::new_with_injected_create_signer_for_gov_sim(&state_view);
-    let vm = AptosVM::new(&env, &state_view);
-    let resolver = state_view.as_move_resolver();
-    let module_storage = state_view.as_aptos_code_storage(&env);
-
-    let gas_schedule =
-        GasScheduleV2::fetch_config(&state_view).context("failed to fetch gas schedule v2")?;
-    let gas_feature_version = gas_schedule.feature_version;
-
-    let change_set_configs =
-        ChangeSetConfigs::unlimited_at_gas_feature_version(gas_feature_version);
-
-    let traversal_storage = TraversalStorage::new();
-    let mut sess = vm.new_session(&resolver, SessionId::void(), None);
-    sess.execute_function_bypass_visibility(
-        &MODULE_ID_APTOS_GOVERNANCE,
-        IdentStr::new("force_end_epoch").unwrap(),
-        vec![],
-        vec![MoveValue::Signer(AccountAddress::ONE)
-            .simple_serialize()
-            .unwrap()],
-        &mut UnmeteredGasMeter,
-        &mut TraversalContext::new(&traversal_storage),
-        &module_storage,
-    )?;
-    let mut change_set = sess.finish(&change_set_configs, &module_storage)?;
-
-    change_set.try_materialize_aggregator_v1_delta_set(&resolver)?;
-    let (write_set, _events) = change_set
-        .try_combine_into_storage_change_set(ModuleWriteSet::empty())
-        .expect("Failed to convert to storage ChangeSet")
-        .into_inner();
-
-    state_view.apply_write_set(&write_set)?;
-
-    Ok(())
-}
-
 pub async fn simulate_multistep_proposal(
     remote_url: Url,
     proposal_dir: &Path,
@@ -477,13 +175,9 @@ pub async fn simulate_multistep_proposal(
         let txn = account
             .account()
             .transaction()
-            .script(Script::new(
-                script_blob,
-                vec![],
-                vec![
-                    TransactionArgument::U64(DUMMY_PROPOSAL_ID), // dummy proposal id, ignored by the patched function
-                ],
-            ))
+            .script(Script::new(script_blob, vec![], vec![
+                TransactionArgument::U64(DUMMY_PROPOSAL_ID), // dummy proposal id, ignored by the patched function
+            ]))
             .chain_id(chain_id.chain_id())
             .sequence_number(script_idx as u64)
             .gas_unit_price(gas_params.vm.txn.min_price_per_gas_unit.into())
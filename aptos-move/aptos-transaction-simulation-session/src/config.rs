@@ -29,6 +29,10 @@ pub struct Config {
 
     /// The number of operations the session has performed.
     pub ops: u64,
+
+    /// Whether transactions are executed under the gas profiler.
+    #[serde(default)]
+    pub profile_gas: bool,
 }
 
 impl Config {
@@ -37,6 +41,7 @@ impl Config {
         Self {
             base: BaseState::Empty,
             ops: 0,
+            profile_gas: false,
         }
     }
 
@@ -49,6 +54,7 @@ impl Config {
                 api_key,
             },
             ops: 0,
+            profile_gas: false,
         }
     }
 
@@ -0,0 +1,129 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state_store::HumanReadable;
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_resource_viewer::{AnnotatedMoveValue, AptosValueAnnotator};
+use aptos_types::{
+    access_path::Path as AccessPath,
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        StateView,
+    },
+};
+use move_core_types::language_storage::StructTag;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A single state entry that differs between two session states.
+///
+/// A missing value means the entry does not exist on that side.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EntryDiff {
+    pub left: Option<serde_json::Value>,
+    pub right: Option<serde_json::Value>,
+}
+
+/// The differences between two session states, keyed by human readable state key.
+///
+/// Resource groups are split up into their members, which are keyed as `<group key>/<member type>`,
+/// so only the members that differ are reported.
+pub type SessionDiff = BTreeMap<String, EntryDiff>;
+
+/// Compares two session states over the given state keys.
+///
+/// Resources are decoded with the annotator of their own side, so resources of modules published
+/// on one side only can still be displayed. Modules are compared by their hash, anything else
+/// by its raw bytes.
+pub fn diff_states<'a>(
+    left: &impl StateView,
+    right: &impl StateView,
+    keys: impl IntoIterator<Item = &'a StateKey>,
+) -> Result<SessionDiff> {
+    let left_annotator = AptosValueAnnotator::new(left);
+    let right_annotator = AptosValueAnnotator::new(right);
+
+    let mut diff = SessionDiff::new();
+    for key in keys {
+        let left_bytes = left.get_state_value_bytes(key)?;
+        let right_bytes = right.get_state_value_bytes(key)?;
+        if left_bytes == right_bytes {
+            continue;
+        }
+        let key_str = HumanReadable(key).to_string();
+
+        match key.inner() {
+            StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+                AccessPath::Resource(struct_tag) => {
+                    diff.insert(key_str, EntryDiff {
+                        left: left_bytes
+                            .map(|bytes| decode_resource(&left_annotator, &struct_tag, &bytes))
+                            .transpose()?,
+                        right: right_bytes
+                            .map(|bytes| decode_resource(&right_annotator, &struct_tag, &bytes))
+                            .transpose()?,
+                    });
+                },
+                AccessPath::ResourceGroup(_) => {
+                    let left_group = decode_group(left_bytes.as_deref())?;
+                    let right_group = decode_group(right_bytes.as_deref())?;
+
+                    let members: BTreeSet<_> =
+                        left_group.keys().chain(right_group.keys()).collect();
+                    for member in members {
+                        let left_member = left_group.get(member);
+                        let right_member = right_group.get(member);
+                        if left_member == right_member {
+                            continue;
+                        }
+                        diff.insert(
+                            format!("{}/{}", key_str, member.to_canonical_string()),
+                            EntryDiff {
+                                left: left_member
+                                    .map(|bytes| decode_resource(&left_annotator, member, bytes))
+                                    .transpose()?,
+                                right: right_member
+                                    .map(|bytes| decode_resource(&right_annotator, member, bytes))
+                                    .transpose()?,
+                            },
+                        );
+                    }
+                },
+                AccessPath::Code(_) => {
+                    let module_hash =
+                        |bytes: &[u8]| json!(HashValue::sha3_256_of(bytes).to_hex_literal());
+                    diff.insert(key_str, EntryDiff {
+                        left: left_bytes.map(|bytes| module_hash(&bytes)),
+                        right: right_bytes.map(|bytes| module_hash(&bytes)),
+                    });
+                },
+            },
+            _ => {
+                diff.insert(key_str, EntryDiff {
+                    left: left_bytes.map(|bytes| json!(hex::encode(bytes))),
+                    right: right_bytes.map(|bytes| json!(hex::encode(bytes))),
+                });
+            },
+        }
+    }
+
+    Ok(diff)
+}
+
+fn decode_resource<S: StateView>(
+    annotator: &AptosValueAnnotator<'_, S>,
+    struct_tag: &StructTag,
+    bytes: &[u8],
+) -> Result<serde_json::Value> {
+    let annotated = AnnotatedMoveValue::Struct(annotator.view_resource(struct_tag, bytes)?);
+    aptos_api_types::MoveValue::try_from(annotated)?.json()
+}
+
+fn decode_group(bytes: Option<&[u8]>) -> Result<BTreeMap<StructTag, Vec<u8>>> {
+    Ok(match bytes {
+        Some(bytes) => bcs::from_bytes(bytes)?,
+        None => BTreeMap::new(),
+    })
+}
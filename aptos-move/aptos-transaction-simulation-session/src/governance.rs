@@ -0,0 +1,322 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for simulating governance proposals.
+//!
+//! Normally, governance scripts needs to be approved through on-chain governance
+//! before they could be executed. This process involves setting up various states
+//! (e.g., staking pool, delegated voter), which can be quite complex.
+//!
+//! The simulation bypasses these challenges by patching `aptos_governance::resolve` and
+//! `aptos_governance::resolve_multi_step_proposal` with mock versions, which hand out the
+//! signer of the requested address without checking the votes, thus allowing the governance
+//! process to be skipped altogether.
+//!
+//! In other words, this simulation is intended for checking whether a governance
+//! proposal will execute successfully, assuming it gets approved, not whether the
+//! governance framework itself is working as intended.
+
+use anyhow::{anyhow, Context, Result};
+use aptos_crypto::HashValue;
+use aptos_transaction_simulation::SimulationStateStore;
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::{ApprovedExecutionHashes, GasScheduleV2, OnChainConfig},
+    write_set::WriteSet,
+};
+use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::SessionId, AptosVM};
+use aptos_vm_environment::environment::AptosEnvironment;
+use aptos_vm_types::{
+    module_and_script_storage::AsAptosCodeStorage, module_write_set::ModuleWriteSet,
+    storage::change_set_configs::ChangeSetConfigs,
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, FunctionDefinition, FunctionHandle, FunctionHandleIndex, IdentifierIndex,
+        Signature, SignatureIndex, SignatureToken, Visibility,
+    },
+    CompiledModule,
+};
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+    value::MoveValue,
+};
+use move_vm_runtime::module_traversal::{TraversalContext, TraversalStorage};
+use move_vm_types::gas::UnmeteredGasMeter;
+
+/// Proposal id passed to governance scripts, which the patched resolve functions ignore.
+pub const DUMMY_PROPOSAL_ID: u64 = u64::MAX;
+
+/// Abort code of the patched `resolve_multi_step_proposal` when the last step of a proposal
+/// still has a next execution hash.
+pub const MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK: u64 = 0xDEADBEEF;
+
+/***************************************************************************************************
+ * Compiled Module Helpers
+ *
+ **************************************************************************************************/
+fn find_function_def_by_name<'a>(
+    m: &'a mut CompiledModule,
+    name: &IdentStr,
+) -> Option<&'a mut FunctionDefinition> {
+    for (idx, func_def) in m.function_defs.iter().enumerate() {
+        let func_handle = m.function_handle_at(func_def.function);
+        let func_name = m.identifier_at(func_handle.name);
+        if name == func_name {
+            return Some(&mut m.function_defs[idx]);
+        }
+    }
+    None
+}
+
+fn get_or_add<T: PartialEq>(pool: &mut Vec<T>, val: T) -> usize {
+    match pool.iter().position(|elem| elem == &val) {
+        Some(idx) => idx,
+        None => {
+            let idx = pool.len();
+            pool.push(val);
+            idx
+        },
+    }
+}
+
+fn get_or_add_ident(m: &mut CompiledModule, ident: Identifier) -> IdentifierIndex {
+    IdentifierIndex::new(get_or_add(&mut m.identifiers, ident) as u16)
+}
+
+fn get_or_add_signature(m: &mut CompiledModule, sig: Vec<SignatureToken>) -> SignatureIndex {
+    SignatureIndex::new(get_or_add(&mut m.signatures, Signature(sig)) as u16)
+}
+
+fn find_function_handle_by_name(
+    m: &CompiledModule,
+    addr: AccountAddress,
+    module_name: &IdentStr,
+    func_name: &IdentStr,
+) -> Option<FunctionHandleIndex> {
+    for (idx, func_handle) in m.function_handles().iter().enumerate() {
+        let module_handle = m.module_handle_at(func_handle.module);
+        if m.address_identifier_at(module_handle.address) == &addr
+            && m.identifier_at(module_handle.name) == module_name
+            && m.identifier_at(func_handle.name) == func_name
+        {
+            return Some(FunctionHandleIndex(idx as u16));
+        }
+    }
+    None
+}
+
+fn add_simple_native_function(
+    m: &mut CompiledModule,
+    func_name: Identifier,
+    params: Vec<SignatureToken>,
+    returns: Vec<SignatureToken>,
+) -> Result<FunctionHandleIndex> {
+    if let Some(func_handle_idx) =
+        find_function_handle_by_name(m, *m.self_addr(), m.self_name(), &func_name)
+    {
+        return Ok(func_handle_idx);
+    }
+
+    let name = get_or_add_ident(m, func_name);
+    let parameters = get_or_add_signature(m, params);
+    let return_ = get_or_add_signature(m, returns);
+    let func_handle = FunctionHandle {
+        module: m.self_handle_idx(),
+        name,
+        parameters,
+        return_,
+        type_parameters: vec![],
+        access_specifiers: None,
+        attributes: vec![],
+    };
+    let func_handle_idx = FunctionHandleIndex(m.function_handles.len() as u16);
+    m.function_handles.push(func_handle);
+
+    let func_def = FunctionDefinition {
+        function: func_handle_idx,
+        visibility: Visibility::Private,
+        is_entry: false,
+        acquires_global_resources: vec![],
+        code: None,
+    };
+    m.function_defs.push(func_def);
+
+    Ok(func_handle_idx)
+}
+
+/***************************************************************************************************
+ * Patches
+ *
+ **************************************************************************************************/
+fn aptos_governance_module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::ONE,
+        Identifier::new("aptos_governance").unwrap(),
+    )
+}
+
+/// Helper to load a module from the state view, deserialize it, modify it with
+/// the provided callback, reserialize it and finally write it back.
+fn patch_module<F>(
+    state_view: &impl SimulationStateStore,
+    module_id: &ModuleId,
+    modify_module: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut CompiledModule) -> Result<()>,
+{
+    let mut m = state_view
+        .get_module(module_id)?
+        .ok_or_else(|| anyhow!("module {} does not exist", module_id))?;
+
+    modify_module(&mut m)?;
+
+    // Sanity check to ensure the correctness of the check
+    move_bytecode_verifier::verify_module(&m).map_err(|err| {
+        anyhow!(
+            "patched module failed to verify -- check if the patch is correct: {}",
+            err
+        )
+    })?;
+
+    state_view.add_module(&m)?;
+
+    Ok(())
+}
+
+/// Patches `aptos_framework::aptos_governance::resolve` and `resolve_multi_step_proposal` so
+/// that they return the requested signer directly, skipping the governance process altogether.
+///
+/// If `forbid_next_execution_hash` is set, the patched `resolve_multi_step_proposal` aborts with
+/// [`MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK`] when it is given a next execution hash, as the last
+/// step of a proposal must not have one.
+///
+/// The patches have to be redone before every script, as a script may upgrade the framework.
+pub fn patch_aptos_governance(
+    state_view: &impl SimulationStateStore,
+    forbid_next_execution_hash: bool,
+) -> Result<()> {
+    use Bytecode::*;
+
+    patch_module(state_view, &aptos_governance_module_id(), |m| {
+        // Inject `native fun create_signer`.
+        let create_signer_handle_idx = add_simple_native_function(
+            m,
+            Identifier::new("create_signer").unwrap(),
+            vec![SignatureToken::Address],
+            vec![SignatureToken::Signer],
+        )?;
+        let sig_u8_idx = get_or_add_signature(m, vec![SignatureToken::U8]);
+
+        // Both functions take the proposal id and the signer address as their first parameters.
+        for func_name in ["resolve", "resolve_multi_step_proposal"] {
+            let func_def = find_function_def_by_name(m, IdentStr::new(func_name)?)
+                .ok_or_else(|| anyhow!("failed to locate `fun {}`", func_name))?;
+            func_def.acquires_global_resources = vec![];
+            let code = func_def
+                .code
+                .as_mut()
+                .ok_or_else(|| anyhow!("`fun {}` must have a Move-defined body", func_name))?;
+
+            code.code.clear();
+            if forbid_next_execution_hash && func_name == "resolve_multi_step_proposal" {
+                // If it is needed to forbid a next execution hash, inject additional Move
+                // code at the beginning that aborts with a magic number if the vector
+                // representing the hash is not empty.
+                //
+                //     if (!vector::is_empty(&next_execution_hash)) {
+                //         abort MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK;
+                //     }
+                //
+                // The magic number can later be checked in Rust to determine if such violation
+                // has happened.
+                code.code.extend([
+                    ImmBorrowLoc(2),
+                    VecLen(sig_u8_idx),
+                    LdU64(0),
+                    Eq,
+                    BrTrue(7),
+                    LdU64(MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK),
+                    Abort,
+                ]);
+            }
+            // Replace the original logic with `create_signer(signer_address)`, bypassing
+            // the governance process.
+            code.code
+                .extend([MoveLoc(1), Call(create_signer_handle_idx), Ret]);
+        }
+
+        Ok(())
+    })
+}
+
+/// Adds the hash of the script to the list of approved hashes, so to enable the
+/// alternative (higher) execution limits.
+pub fn add_script_execution_hash(
+    state_view: &impl SimulationStateStore,
+    hash: HashValue,
+) -> Result<()> {
+    let entry = (DUMMY_PROPOSAL_ID, hash.to_vec());
+
+    state_view.modify_on_chain_config(|approved_hashes: &mut ApprovedExecutionHashes| {
+        if !approved_hashes.entries.contains(&entry) {
+            approved_hashes.entries.push(entry);
+        }
+        Ok(())
+    })
+}
+
+/***************************************************************************************************
+ * Reconfiguration
+ *
+ **************************************************************************************************/
+/// Runs `aptos_governance::force_end_epoch`, and returns its write set without applying it.
+///
+/// Ending the epoch applies the config changes governance scripts buffer for the next epoch.
+pub fn end_epoch_write_set(state_view: &impl SimulationStateStore) -> Result<WriteSet> {
+    let env = AptosEnvironment::new_with_injected_create_signer_for_gov_sim(&state_view);
+    let vm = AptosVM::new(&env, &state_view);
+    let resolver = state_view.as_move_resolver();
+    let module_storage = state_view.as_aptos_code_storage(&env);
+
+    let gas_schedule =
+        GasScheduleV2::fetch_config(&state_view).context("failed to fetch gas schedule v2")?;
+    let gas_feature_version = gas_schedule.feature_version;
+
+    let change_set_configs =
+        ChangeSetConfigs::unlimited_at_gas_feature_version(gas_feature_version);
+
+    let traversal_storage = TraversalStorage::new();
+    let mut sess = vm.new_session(&resolver, SessionId::void(), None);
+    sess.execute_function_bypass_visibility(
+        &aptos_governance_module_id(),
+        IdentStr::new("force_end_epoch").unwrap(),
+        vec![],
+        vec![MoveValue::Signer(AccountAddress::ONE)
+            .simple_serialize()
+            .unwrap()],
+        &mut UnmeteredGasMeter,
+        &mut TraversalContext::new(&traversal_storage),
+        &module_storage,
+    )?;
+    let mut change_set = sess.finish(&change_set_configs, &module_storage)?;
+
+    change_set.try_materialize_aggregator_v1_delta_set(&resolver)?;
+    let (write_set, _events) = change_set
+        .try_combine_into_storage_change_set(ModuleWriteSet::empty())
+        .expect("Failed to convert to storage ChangeSet")
+        .into_inner();
+
+    Ok(write_set)
+}
+
+/// Force-ends the epoch, so that buffered configuration changes get applied.
+pub fn force_end_epoch(state_view: &impl SimulationStateStore) -> Result<()> {
+    let write_set = end_epoch_write_set(state_view)?;
+    state_view.apply_write_set(&write_set)?;
+
+    Ok(())
+}
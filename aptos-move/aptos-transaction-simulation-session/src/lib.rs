@@ -3,8 +3,11 @@
 
 mod config;
 mod delta;
+mod diff;
+pub mod governance;
 mod session;
 mod state_store;
 mod txn_output;
 
-pub use session::Session;
+pub use diff::{EntryDiff, SessionDiff};
+pub use session::{PublishOptions, Session};
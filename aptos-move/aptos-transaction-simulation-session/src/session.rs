@@ -4,26 +4,47 @@
 use crate::{
     config::{BaseState, Config},
     delta::{load_delta, save_delta},
-    txn_output::{save_events, save_write_set},
+    diff::{diff_states, SessionDiff},
+    governance,
+    txn_output::{save_events, save_gas_profile, save_write_set},
 };
 use anyhow::Result;
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::HashValue;
+use aptos_framework::{
+    natives::code::{PackageMetadata, PackageRegistry},
+    BuildOptions, BuiltPackage,
+};
+use aptos_gas_profiling::GasProfiler;
 use aptos_resource_viewer::{AnnotatedMoveValue, AptosValueAnnotator};
 use aptos_rest_client::{AptosBaseUrl, Client};
 use aptos_transaction_simulation::{
-    DeltaStateStore, EitherStateView, EmptyStateView, SimulationStateStore, GENESIS_CHANGE_SET_HEAD,
+    Account, DeltaStateStore, EitherStateView, EmptyStateView, SimulationStateStore,
+    GENESIS_CHANGE_SET_HEAD,
 };
 use aptos_types::{
+    access_path::Path as AccessPath,
     account_address::{create_derived_object_address, AccountAddress},
+    chain_id::ChainId,
     fee_statement::FeeStatement,
-    state_store::{state_key::StateKey, TStateView},
-    transaction::{SignedTransaction, TransactionOutput, TransactionPayload, TransactionStatus},
+    on_chain_config::{GasScheduleV2, OnChainConfig},
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        TStateView,
+    },
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, Script, SignedTransaction,
+        TransactionArgument, TransactionExecutableRef, TransactionOutput, TransactionPayload,
+        TransactionStatus,
+    },
     vm_status::VMStatus,
+    write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
-use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
+use aptos_vm::{data_cache::AsMoveResolver, AptosSimulationVM, AptosVM};
 use aptos_vm_environment::environment::AptosEnvironment;
 use aptos_vm_logging::log_schema::AdapterLogSchema;
-use aptos_vm_types::module_and_script_storage::AsAptosCodeStorage;
+use aptos_vm_types::{module_and_script_storage::AsAptosCodeStorage, output::VMOutput};
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
@@ -32,7 +53,7 @@ use move_core_types::{
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -40,6 +61,15 @@ use url::Url;
 
 type SessionStateStore = DeltaStateStore<EitherStateView<EmptyStateView, DebuggerStateView>>;
 
+/// Directory in a session where its checkpoints are stored.
+const CHECKPOINTS_DIR: &str = "checkpoints";
+
+/// Balance of the accounts sending governance scripts, in Octa.
+const GOVERNANCE_SENDER_BALANCE: u64 = 100 * 100_000_000;
+
+/// Maximum gas units a governance script may use.
+const GOVERNANCE_SCRIPT_MAX_GAS: u64 = 2_000_000;
+
 /// Formats an account address for display.
 /// Truncates the address if it's more than 4 digits.
 fn format_address(address: &AccountAddress) -> String {
@@ -58,6 +88,62 @@ fn format_module_id(module_id: &ModuleId) -> String {
     format!("{}::{}", format_address(address), name)
 }
 
+/// Creates the state view of the base state of a session.
+fn base_state_view(base: &BaseState) -> Result<EitherStateView<EmptyStateView, DebuggerStateView>> {
+    Ok(match base {
+        BaseState::Empty => EitherStateView::Left(EmptyStateView),
+        BaseState::Remote {
+            node_url,
+            network_version,
+            api_key,
+        } => {
+            let client = remote_client(node_url, api_key)?;
+            let debugger = DebuggerStateView::new(
+                Arc::new(RestDebuggerInterface::new(client)),
+                *network_version,
+            );
+            EitherStateView::Right(debugger)
+        },
+    })
+}
+
+/// Creates a client of the remote network of a session.
+fn remote_client(node_url: &Url, api_key: &Option<String>) -> Result<Client> {
+    let mut builder = Client::builder(AptosBaseUrl::Custom(node_url.clone()));
+    if let Some(api_key) = api_key {
+        builder = builder.api_key(api_key)?;
+    }
+    Ok(builder.build())
+}
+
+/// Loads the config and state stored in a session or checkpoint directory.
+fn load_state(path: &Path) -> Result<(Config, SessionStateStore)> {
+    let config = Config::load_from_file(&path.join("config.json"))?;
+    let base = base_state_view(&config.base)?;
+    let delta = load_delta(&path.join("delta.json"))?;
+
+    Ok((
+        config,
+        DeltaStateStore::new_with_base_and_delta(base, delta),
+    ))
+}
+
+/// Checkpoint names are used as directory names.
+fn validate_checkpoint_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        || name.starts_with('.')
+    {
+        anyhow::bail!(
+            "Invalid checkpoint name {:?} -- only letters, digits, '-', '_' and '.' are allowed, and it cannot start with '.'.",
+            name
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 enum ViewResult {
@@ -92,6 +178,40 @@ enum Summary {
         group_type: String,
         group_value: Option<serde_json::Value>,
     },
+    Checkpoint {
+        name: String,
+    },
+    Restore {
+        name: String,
+    },
+    Fork {
+        session: PathBuf,
+        checkpoint: Option<String>,
+    },
+}
+
+/// Options for publishing a package to a session.
+#[derive(Debug, Clone)]
+pub struct PublishOptions {
+    /// Options to build the package with.
+    pub build_options: BuildOptions,
+    /// Maximum gas units the publish transaction may use.
+    pub max_gas_amount: u64,
+    /// Gas unit price of the publish transaction, in Octa.
+    pub gas_unit_price: u64,
+    /// Writes the package to the state directly, skipping upgrade policy and compatibility checks.
+    pub skip_compatibility_checks: bool,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            build_options: BuildOptions::default(),
+            max_gas_amount: 2_000_000,
+            gas_unit_price: 100,
+            skip_compatibility_checks: false,
+        }
+    }
 }
 
 /// A session for simulating transactions, with data being persisted to a directory, allowing the session
@@ -105,6 +225,11 @@ pub struct Session {
 }
 
 impl Session {
+    /// Returns the number of operations the session has performed.
+    pub fn ops(&self) -> u64 {
+        self.config.ops
+    }
+
     /// Returns a reference to the underlying state store.
     pub fn state_store(&self) -> &(impl SimulationStateStore + use<>) {
         &self.state_store
@@ -197,39 +322,197 @@ impl Session {
     /// Loads a previously stored session from disk.
     pub fn load(session_path: impl AsRef<Path>) -> Result<Self> {
         let session_path = session_path.as_ref().to_path_buf();
-        let config = Config::load_from_file(&session_path.join("config.json"))?;
+        let (config, state_store) = load_state(&session_path)?;
 
-        let base = match &config.base {
-            BaseState::Empty => EitherStateView::Left(EmptyStateView),
-            BaseState::Remote {
-                node_url,
-                network_version,
-                api_key,
-            } => {
-                let mut builder = Client::builder(AptosBaseUrl::Custom(node_url.clone()));
-                if let Some(api_key) = api_key {
-                    builder = builder.api_key(api_key)?;
-                }
-                let client = builder.build();
+        Ok(Self {
+            config,
+            path: session_path,
+            state_store,
+        })
+    }
 
-                let debugger = DebuggerStateView::new(
-                    Arc::new(RestDebuggerInterface::new(client)),
-                    *network_version,
-                );
-                EitherStateView::Right(debugger)
-            },
+    /// Enables or disables gas profiling for the transactions the session executes from now on.
+    ///
+    /// Only transactions run with [`Session::execute_transaction`] are profiled, not packages
+    /// published with [`Session::publish_package`] or governance scripts.
+    pub fn set_profile_gas(&mut self, enabled: bool) -> Result<()> {
+        self.config.profile_gas = enabled;
+        self.config.save_to_file(&self.path.join("config.json"))
+    }
+
+    /// Saves the current state of the session as a named checkpoint.
+    ///
+    /// Checkpoints are stored under `checkpoints/<name>` in the session directory, and can later be
+    /// forked from or compared against. Saving a checkpoint under an existing name replaces it.
+    pub fn checkpoint(&mut self, name: &str) -> Result<()> {
+        validate_checkpoint_name(name)?;
+
+        let checkpoint_path = self.checkpoint_path(name);
+        std::fs::create_dir_all(&checkpoint_path)?;
+        self.config
+            .save_to_file(&checkpoint_path.join("config.json"))?;
+        save_delta(
+            &checkpoint_path.join("delta.json"),
+            &self.state_store.delta(),
+        )?;
+
+        let summary = Summary::Checkpoint {
+            name: name.to_string(),
         };
+        let summary_path = self
+            .path
+            .join(format!("[{}] checkpoint {}", self.config.ops, name))
+            .join("summary.json");
+        std::fs::create_dir_all(summary_path.parent().unwrap())?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
 
-        let delta = load_delta(&session_path.join("delta.json"))?;
-        let state_store = DeltaStateStore::new_with_base_and_delta(base, delta);
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+
+        Ok(())
+    }
+
+    /// Restores the state of the session to a checkpoint.
+    ///
+    /// The outputs of the operations performed since the checkpoint are kept, and the operation
+    /// counter keeps increasing.
+    pub fn restore(&mut self, checkpoint: &str) -> Result<()> {
+        let (_, state_store) = self.state_at(Some(checkpoint))?;
+        self.state_store = state_store;
+
+        let summary = Summary::Restore {
+            name: checkpoint.to_string(),
+        };
+        let summary_path = self
+            .path
+            .join(format!("[{}] restore {}", self.config.ops, checkpoint))
+            .join("summary.json");
+        std::fs::create_dir_all(summary_path.parent().unwrap())?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+        save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+
+        Ok(())
+    }
+
+    /// Lists the names of the checkpoints of the session.
+    pub fn checkpoints(&self) -> Result<Vec<String>> {
+        let checkpoints_path = self.path.join(CHECKPOINTS_DIR);
+        if !checkpoints_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = vec![];
+        for entry in std::fs::read_dir(checkpoints_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// Creates a new session at `fork_path`, starting from a checkpoint of this session, or from
+    /// its current state if no checkpoint is given.
+    ///
+    /// The fork shares the base state of this session, but is otherwise independent of it.
+    pub fn fork(&self, checkpoint: Option<&str>, fork_path: impl AsRef<Path>) -> Result<Self> {
+        let fork_path = fork_path.as_ref().to_path_buf();
+
+        std::fs::create_dir_all(&fork_path)?;
+
+        if fork_path.read_dir()?.next().is_some() {
+            anyhow::bail!(
+                "Cannot fork session to {} -- directory is not empty.",
+                fork_path.display()
+            );
+        }
+
+        let (mut config, state_store) = self.state_at(checkpoint)?;
+
+        let summary = Summary::Fork {
+            session: self.path.clone(),
+            checkpoint: checkpoint.map(str::to_string),
+        };
+        let summary_path = fork_path
+            .join(format!("[{}] fork", config.ops))
+            .join("summary.json");
+        std::fs::create_dir_all(summary_path.parent().unwrap())?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+
+        config.ops += 1;
+        config.save_to_file(&fork_path.join("config.json"))?;
+        save_delta(&fork_path.join("delta.json"), &state_store.delta())?;
 
         Ok(Self {
             config,
-            path: session_path,
+            path: fork_path,
             state_store,
         })
     }
 
+    /// Compares a checkpoint of this session, or its current state, with a checkpoint or the
+    /// current state of another session, resource by resource.
+    ///
+    /// Both sides need to share the same base state, as only the entries either side changed on
+    /// top of the base state are compared.
+    pub fn diff(
+        &self,
+        checkpoint: Option<&str>,
+        other: &Session,
+        other_checkpoint: Option<&str>,
+    ) -> Result<SessionDiff> {
+        let (config, state_store) = self.state_at(checkpoint)?;
+        let (other_config, other_state_store) = other.state_at(other_checkpoint)?;
+
+        if config.base != other_config.base {
+            anyhow::bail!(
+                "Cannot diff sessions {} and {} -- they do not share the same base state.",
+                self.path.display(),
+                other.path.display()
+            );
+        }
+
+        let delta = state_store.delta();
+        let other_delta = other_state_store.delta();
+        let keys: BTreeSet<_> = delta.keys().chain(other_delta.keys()).collect();
+
+        diff_states(&state_store, &other_state_store, keys)
+    }
+
+    fn checkpoint_path(&self, name: &str) -> PathBuf {
+        self.path.join(CHECKPOINTS_DIR).join(name)
+    }
+
+    /// Loads the config and state of a checkpoint, or a copy of the current ones.
+    fn state_at(&self, checkpoint: Option<&str>) -> Result<(Config, SessionStateStore)> {
+        match checkpoint {
+            Some(name) => {
+                validate_checkpoint_name(name)?;
+                let checkpoint_path = self.checkpoint_path(name);
+                if !checkpoint_path.exists() {
+                    anyhow::bail!(
+                        "Checkpoint {} does not exist in session {}.",
+                        name,
+                        self.path.display()
+                    );
+                }
+                load_state(&checkpoint_path)
+            },
+            None => Ok((
+                self.config.clone(),
+                DeltaStateStore::new_with_base_and_delta(
+                    base_state_view(&self.config.base)?,
+                    self.state_store.delta(),
+                ),
+            )),
+        }
+    }
+
     /// Funds an account with APT.
     ///
     /// This counts as a session operation but is not a real transaction, as it modifies the
@@ -334,6 +617,7 @@ impl Session {
     /// After execution, selected parts of the transaction output get saved to a dedicated directory for inspection:
     /// - Write set changes
     /// - Emitted events
+    /// - If gas profiling is enabled for the session, the gas report and the folded flamegraph stacks
     pub fn execute_transaction(
         &mut self,
         txn: SignedTransaction,
@@ -345,12 +629,42 @@ impl Session {
         let resolver = self.state_store.as_move_resolver();
         let code_storage = self.state_store.as_aptos_code_storage(&env);
 
-        let (vm_status, vm_output) =
-            vm.execute_user_transaction(&resolver, &code_storage, &txn, &log_context);
+        let (vm_status, vm_output, gas_log) = if self.config.profile_gas {
+            match vm.execute_user_transaction_with_modified_gas_meter(
+                &resolver,
+                &code_storage,
+                &txn,
+                &log_context,
+                |gas_meter| match txn.executable_ref() {
+                    Ok(TransactionExecutableRef::EntryFunction(entry_func)) => {
+                        GasProfiler::new_function(
+                            gas_meter,
+                            entry_func.module().clone(),
+                            entry_func.function().to_owned(),
+                            entry_func.ty_args().to_vec(),
+                        )
+                    },
+                    _ => GasProfiler::new_script(gas_meter),
+                },
+            ) {
+                Ok((vm_status, vm_output, gas_profiler)) => {
+                    (vm_status, vm_output, Some(gas_profiler.finish()))
+                },
+                // Discarded like in the unprofiled path, there is nothing to profile
+                Err(vm_status) => {
+                    let vm_output = VMOutput::empty_with_status(TransactionStatus::Discard(
+                        vm_status.status_code(),
+                    ));
+                    (vm_status, vm_output, None)
+                },
+            }
+        } else {
+            let (vm_status, vm_output) =
+                vm.execute_user_transaction(&resolver, &code_storage, &txn, &log_context);
+            (vm_status, vm_output, None)
+        };
         let txn_output = vm_output.try_materialize_into_transaction_output(&resolver)?;
 
-        self.state_store.apply_write_set(txn_output.write_set())?;
-
         let name = match &txn.payload() {
             TransactionPayload::EntryFunction(entry_function) => {
                 format!(
@@ -364,10 +678,225 @@ impl Session {
             TransactionPayload::ModuleBundle(_) => unreachable!(),
             _ => "unknown".to_string(), // Payload and other future variants
         };
+        let output_path =
+            self.apply_transaction_output(&format!("execute {}", name), &txn_output)?;
 
-        let output_path = self
-            .path
-            .join(format!("[{}] execute {}", self.config.ops, name));
+        if let Some(gas_log) = gas_log {
+            save_gas_profile(&output_path, &gas_log, &name)?;
+        }
+
+        Ok((vm_status, txn_output))
+    }
+
+    /// Executes a script of a governance proposal as if the proposal had passed, and updates the
+    /// session state.
+    ///
+    /// `aptos_governance::resolve` and `resolve_multi_step_proposal` are patched in the session
+    /// state to hand out the requested signer without checking votes, see [`crate::governance`].
+    /// The script is sent by a freshly funded account, with a dummy proposal id as its only
+    /// argument. If `last_step` is set, the script fails if it still has a next execution hash.
+    ///
+    /// Config changes made by the script are buffered until the epoch ends, see
+    /// [`Session::end_epoch`].
+    pub fn execute_governance_script(
+        &mut self,
+        script: Vec<u8>,
+        last_step: bool,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        // The patches are redone for every script, as the previous one may have upgraded the
+        // framework.
+        governance::patch_aptos_governance(&self.state_store, last_step)?;
+        governance::add_script_execution_hash(&self.state_store, HashValue::sha3_256_of(&script))?;
+
+        let sender = self.state_store.store_and_fund_account(
+            Account::new(),
+            GOVERNANCE_SENDER_BALANCE,
+            0,
+        )?;
+        let gas_schedule: GasScheduleV2 = self.state_store.get_on_chain_config()?;
+        let gas_unit_price = gas_schedule
+            .entries
+            .iter()
+            .find(|(name, _)| name == "txn.min_price_per_gas_unit")
+            .map(|(_, price)| *price)
+            .unwrap_or(100);
+        let txn = sender
+            .account()
+            .transaction()
+            .script(Script::new(script, vec![], vec![TransactionArgument::U64(
+                governance::DUMMY_PROPOSAL_ID,
+            )]))
+            .chain_id(self.state_store.get_chain_id()?)
+            .sequence_number(0)
+            .gas_unit_price(gas_unit_price)
+            .max_gas_amount(GOVERNANCE_SCRIPT_MAX_GAS)
+            .ttl(u64::MAX)
+            .sign();
+
+        let env = AptosEnvironment::new_with_injected_create_signer_for_gov_sim(&self.state_store);
+        let vm = AptosVM::new(&env, &self.state_store);
+        let log_context = AdapterLogSchema::new(self.state_store.id(), 0);
+
+        let resolver = self.state_store.as_move_resolver();
+        let code_storage = self.state_store.as_aptos_code_storage(&env);
+
+        let (vm_status, vm_output) =
+            vm.execute_user_transaction(&resolver, &code_storage, &txn, &log_context);
+        let txn_output = vm_output.try_materialize_into_transaction_output(&resolver)?;
+
+        self.apply_transaction_output("execute governance script", &txn_output)?;
+
+        Ok((vm_status, txn_output))
+    }
+
+    /// Ends the current epoch, like the reconfiguration after a governance proposal resolves, so
+    /// that buffered config changes get applied.
+    ///
+    /// This counts as a session operation but is not a real transaction, as it modifies the
+    /// storage state directly.
+    pub fn end_epoch(&mut self) -> Result<()> {
+        let write_set = governance::end_epoch_write_set(&self.state_store)?;
+        self.state_store.apply_write_set(&write_set)?;
+
+        let output_path = self.path.join(format!("[{}] end epoch", self.config.ops));
+        std::fs::create_dir_all(&output_path)?;
+        save_write_set(
+            &self.state_store,
+            &output_path.join("write_set.json"),
+            &write_set,
+        )?;
+
+        self.config.ops += 1;
+
+        self.config.save_to_file(&self.path.join("config.json"))?;
+        save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+
+        Ok(())
+    }
+
+    /// Builds a Move package and publishes it to the session state.
+    ///
+    /// The package is published with a `0x1::code::publish_package_txn` transaction sent by the
+    /// address of its modules, which is simulated without signature or authentication key checks,
+    /// so packages of accounts forked from a live network can be published without their keys.
+    /// The publisher still pays for gas, so it has to hold enough APT for `options.max_gas_amount`
+    /// at `options.gas_unit_price`, see [`Session::fund_account`]. Otherwise the transaction is
+    /// discarded.
+    ///
+    /// If `options.skip_compatibility_checks` is set, the modules and the package registry are
+    /// written to the state directly instead, bypassing upgrade policy and compatibility checks.
+    /// This allows dry-running incompatible upgrades, but `init_module` is not run for new modules.
+    pub fn publish_package(
+        &mut self,
+        package_path: impl AsRef<Path>,
+        named_addresses: BTreeMap<String, AccountAddress>,
+        options: PublishOptions,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        let mut build_options = options.build_options;
+        build_options.named_addresses.extend(named_addresses);
+        let package = BuiltPackage::build(package_path.as_ref().to_path_buf(), build_options)?;
+
+        let mut publishers = package.modules().map(|module| *module.self_addr());
+        let publisher = match publishers.next() {
+            Some(publisher) => publisher,
+            None => anyhow::bail!("Package {} has no modules to publish.", package.name()),
+        };
+        if publishers.any(|address| address != publisher) {
+            anyhow::bail!(
+                "Cannot publish package {} -- its modules are not all at the same address.",
+                package.name()
+            );
+        }
+
+        let code = package.extract_code();
+        let metadata = package.extract_metadata()?;
+
+        let (vm_status, txn_output) = if options.skip_compatibility_checks {
+            let write_set = self.package_write_set(publisher, &package, metadata, code)?;
+            (
+                VMStatus::Executed,
+                TransactionOutput::new_success_with_write_set(write_set),
+            )
+        } else {
+            let chain_id: ChainId = self.state_store.get_on_chain_config()?;
+            let raw_txn = RawTransaction::new(
+                publisher,
+                self.get_sequence_number(publisher)?,
+                aptos_stdlib::code_publish_package_txn(bcs::to_bytes(&metadata)?, code),
+                options.max_gas_amount,
+                options.gas_unit_price,
+                // Sessions have no notion of wall clock time, so the transaction never expires
+                u64::MAX,
+                chain_id,
+            );
+            let txn = SignedTransaction::new_single_sender(
+                raw_txn,
+                AccountAuthenticator::NoAccountAuthenticator,
+            );
+
+            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &self.state_store)
+        };
+
+        self.apply_transaction_output(&format!("publish {}", package.name()), &txn_output)?;
+
+        Ok((vm_status, txn_output))
+    }
+
+    /// Creates the write set publishing a package without going through `0x1::code`, replacing the
+    /// package of the same name in the registry of the publisher.
+    fn package_write_set(
+        &self,
+        publisher: AccountAddress,
+        package: &BuiltPackage,
+        mut metadata: PackageMetadata,
+        code: Vec<Vec<u8>>,
+    ) -> Result<WriteSet> {
+        let write_op = |state_key: &StateKey, bytes: Vec<u8>| -> Result<WriteOp> {
+            Ok(match self.state_store.get_state_value_bytes(state_key)? {
+                Some(_) => WriteOp::legacy_modification(bytes.into()),
+                None => WriteOp::legacy_creation(bytes.into()),
+            })
+        };
+
+        let registry_key = StateKey::resource(&publisher, &PackageRegistry::struct_tag())?;
+        let mut registry = match self.state_store.get_state_value_bytes(&registry_key)? {
+            Some(bytes) => bcs::from_bytes::<PackageRegistry>(&bytes)?,
+            None => PackageRegistry { packages: vec![] },
+        };
+        match registry
+            .packages
+            .iter_mut()
+            .find(|existing| existing.name == metadata.name)
+        {
+            Some(existing) => {
+                metadata.upgrade_number = existing.upgrade_number + 1;
+                *existing = metadata;
+            },
+            None => registry.packages.push(metadata),
+        }
+
+        let mut write_ops = vec![];
+        for (module, bytes) in package.modules().zip(code) {
+            let state_key = StateKey::module(&publisher, module.self_name());
+            let op = write_op(&state_key, bytes)?;
+            write_ops.push((state_key, op));
+        }
+        let op = write_op(&registry_key, bcs::to_bytes(&registry)?)?;
+        write_ops.push((registry_key, op));
+
+        WriteSetMut::new(write_ops).freeze()
+    }
+
+    /// Applies the output of a transaction to the session state, and saves it to a dedicated
+    /// directory for inspection.
+    fn apply_transaction_output(
+        &mut self,
+        name: &str,
+        txn_output: &TransactionOutput,
+    ) -> Result<PathBuf> {
+        self.state_store.apply_write_set(txn_output.write_set())?;
+
+        let output_path = self.path.join(format!("[{}] {}", self.config.ops, name));
         std::fs::create_dir_all(&output_path)?;
 
         let summary = Summary::ExecuteTransaction {
@@ -389,7 +918,22 @@ impl Session {
         self.config.save_to_file(&self.path.join("config.json"))?;
         save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
 
-        Ok((vm_status, txn_output))
+        Ok(output_path)
+    }
+
+    /// Simulates a transaction against the session state, without applying its output.
+    ///
+    /// Like the simulation endpoint of a node, this skips authentication key checks, and the
+    /// transaction must not carry a valid signature.
+    pub fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        if txn.verify_signature().is_ok() {
+            anyhow::bail!("Simulated transactions must not have a valid signature.");
+        }
+
+        Ok(AptosSimulationVM::create_vm_and_simulate_signed_transaction(txn, &self.state_store))
     }
 
     /// Executes a view function and returns the output values.
@@ -453,6 +997,65 @@ impl Session {
         res
     }
 
+    /// Returns the resources of an account by type, with the members of resource groups listed
+    /// individually like in the node REST API. Unlike [`Session::view_resource`], this does not
+    /// record an operation.
+    ///
+    /// For a session forked from a remote network, the resources in the base state are fetched
+    /// from the network, so this has to be called from a blocking thread of a Tokio runtime.
+    pub fn account_resources(
+        &self,
+        address: AccountAddress,
+    ) -> Result<BTreeMap<StructTag, Vec<u8>>> {
+        let mut resources = match &self.config.base {
+            BaseState::Empty => BTreeMap::new(),
+            BaseState::Remote {
+                node_url,
+                network_version,
+                api_key,
+            } => {
+                // The base state is the one before the network version, see `DebuggerStateView`.
+                let client = remote_client(node_url, api_key)?;
+                tokio::runtime::Handle::current()
+                    .block_on(
+                        client.get_account_resources_at_version_bcs(address, network_version - 1),
+                    )?
+                    .into_inner()
+            },
+        };
+
+        let annotator = AptosValueAnnotator::new(&self.state_store);
+        for (state_key, state_value) in self.state_store.delta() {
+            let StateKeyInner::AccessPath(access_path) = state_key.inner() else {
+                continue;
+            };
+            if access_path.address != address {
+                continue;
+            }
+            match (access_path.get_path(), state_value) {
+                (AccessPath::Resource(struct_tag), Some(state_value)) => {
+                    resources.insert(struct_tag, state_value.bytes().to_vec());
+                },
+                (AccessPath::Resource(struct_tag), None) => {
+                    resources.remove(&struct_tag);
+                },
+                (AccessPath::ResourceGroup(group_tag), state_value) => {
+                    resources.retain(|struct_tag, _| {
+                        annotator.view_resource_group_member(struct_tag).as_ref()
+                            != Some(&group_tag)
+                    });
+                    if let Some(state_value) = state_value {
+                        let group: BTreeMap<StructTag, Vec<u8>> =
+                            bcs::from_bytes(state_value.bytes())?;
+                        resources.extend(group);
+                    }
+                },
+                _ => (),
+            }
+        }
+        Ok(resources)
+    }
+
     /// Views a Move resource.
     pub fn view_resource(
         &mut self,
@@ -594,3 +1197,151 @@ async fn test_init_then_load_session_remote() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_checkpoint_fork_and_diff() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let session_path = temp_dir.path().join("session");
+    let fork_path = temp_dir.path().join("fork");
+
+    let mut session = Session::init(&session_path)?;
+    session.checkpoint("genesis")?;
+    assert_eq!(session.checkpoints()?, vec!["genesis".to_string()]);
+    assert!(session.checkpoint("../escape").is_err());
+
+    let account = AccountAddress::from_hex_literal("0xcafe")?;
+    session.fund_account(account, 100)?;
+
+    // The funded store only exists after the checkpoint
+    let diff = session.diff(Some("genesis"), &session, None)?;
+    assert!(!diff.is_empty());
+    assert!(diff
+        .values()
+        .any(|entry| entry.left.is_none() && entry.right.is_some()));
+
+    // A fork from the checkpoint starts out identical to it, and independent of the session
+    let fork = session.fork(Some("genesis"), &fork_path)?;
+    assert!(session.diff(Some("genesis"), &fork, None)?.is_empty());
+    assert_eq!(fork.diff(None, &session, None)?, diff);
+
+    let fork_loaded = Session::load(&fork_path)?;
+    assert_eq!(fork.config, fork_loaded.config);
+    assert!(session.fork(None, &fork_path).is_err());
+
+    // Restoring the checkpoint undoes the funding
+    session.restore("genesis")?;
+    assert!(session.diff(Some("genesis"), &session, None)?.is_empty());
+    assert!(session.restore("missing").is_err());
+
+    Ok(())
+}
+
+#[cfg(test)]
+fn write_test_package(package_path: &Path) -> Result<()> {
+    std::fs::create_dir_all(package_path.join("sources"))?;
+    std::fs::write(
+        package_path.join("Move.toml"),
+        "[package]\nname = \"Hello\"\nversion = \"0.0.1\"\n\n[addresses]\nhello = \"0xcafe\"\n",
+    )?;
+    std::fs::write(
+        package_path.join("sources").join("hello.move"),
+        "module hello::hello { public fun one(): u64 { 1 } }\n",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_publish_package() -> Result<()> {
+    use aptos_types::transaction::ExecutionStatus;
+    use move_core_types::identifier::IdentStr;
+
+    let temp_dir = tempfile::tempdir()?;
+    let package_path = temp_dir.path().join("package");
+    write_test_package(&package_path)?;
+
+    let mut session = Session::init(temp_dir.path().join("session"))?;
+    let publisher = AccountAddress::from_hex_literal("0xcafe")?;
+    let module_key = StateKey::module(&publisher, IdentStr::new("hello")?);
+    let registry_key = StateKey::resource(&publisher, &PackageRegistry::struct_tag())?;
+
+    // The publisher pays for gas, so the publish transaction of an unfunded account is discarded
+    let (_, txn_output) =
+        session.publish_package(&package_path, BTreeMap::new(), PublishOptions::default())?;
+    assert!(txn_output.status().is_discarded());
+    assert!(session
+        .state_store
+        .get_state_value_bytes(&module_key)?
+        .is_none());
+
+    session.fund_account(publisher, 10 * 100_000_000)?;
+    let (_, txn_output) =
+        session.publish_package(&package_path, BTreeMap::new(), PublishOptions::default())?;
+    assert_eq!(
+        txn_output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    assert!(session
+        .state_store
+        .get_state_value_bytes(&module_key)?
+        .is_some());
+
+    // Writing the package directly counts as an upgrade of the published one
+    let (vm_status, _) =
+        session.publish_package(&package_path, BTreeMap::new(), PublishOptions {
+            skip_compatibility_checks: true,
+            ..PublishOptions::default()
+        })?;
+    assert_eq!(vm_status, VMStatus::Executed);
+    let registry: PackageRegistry = bcs::from_bytes(
+        &session
+            .state_store
+            .get_state_value_bytes(&registry_key)?
+            .unwrap(),
+    )?;
+    assert_eq!(registry.packages.len(), 1);
+    assert_eq!(registry.packages[0].upgrade_number, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_profile_gas() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let session_path = temp_dir.path().join("session");
+    let mut session = Session::init(&session_path)?;
+    session.set_profile_gas(true)?;
+
+    let sender = session
+        .state_store
+        .store_and_fund_account(Account::new(), 100_000_000, 0)?;
+    let transfer = |sequence_number| {
+        sender
+            .account()
+            .transaction()
+            .payload(aptos_stdlib::aptos_account_transfer(
+                AccountAddress::from_hex_literal("0xcafe").unwrap(),
+                100,
+            ))
+            .chain_id(session.state_store.get_chain_id().unwrap())
+            .sequence_number(sequence_number)
+            .gas_unit_price(100)
+            .sign()
+    };
+    let (txn, discarded_txn) = (transfer(0), transfer(5));
+
+    let (_, txn_output) = session.execute_transaction(txn)?;
+    assert!(matches!(txn_output.status(), TransactionStatus::Keep(_)));
+    let output_path = session_path.join("[0] execute 0x1::aptos_account::transfer");
+    assert!(output_path.join("gas_report").is_dir());
+    assert!(output_path.join("exec_io.folded").is_file());
+    assert!(output_path.join("storage.folded").is_file());
+
+    // A discarded transaction is reported like without profiling, instead of failing
+    let (_, txn_output) = session.execute_transaction(discarded_txn)?;
+    assert!(txn_output.status().is_discarded());
+    let output_path = session_path.join("[1] execute 0x1::aptos_account::transfer");
+    assert!(output_path.join("summary.json").is_file());
+    assert!(!output_path.join("gas_report").exists());
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 
 use crate::state_store::HumanReadable;
 use anyhow::{bail, Result};
+use aptos_gas_profiling::TransactionGasLog;
 use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_types::{
     access_path::Path as AccessPath,
@@ -131,3 +132,25 @@ pub fn save_events(
 
     Ok(())
 }
+
+/// Saves the gas profile of a transaction to a directory.
+///
+/// This includes an HTML report under `gas_report`, and the folded stacks its flamegraphs are
+/// rendered from, so they can be fed to other flamegraph tools or compared between transactions.
+pub fn save_gas_profile(output_path: &Path, gas_log: &TransactionGasLog, name: &str) -> Result<()> {
+    gas_log.generate_html_report(
+        output_path.join("gas_report"),
+        format!("Gas Report - {}", name),
+    )?;
+
+    std::fs::write(
+        output_path.join("exec_io.folded"),
+        gas_log.exec_io.to_folded_stack_lines().join("\n"),
+    )?;
+    std::fs::write(
+        output_path.join("storage.folded"),
+        gas_log.storage.to_folded_stack_lines().join("\n"),
+    )?;
+
+    Ok(())
+}
@@ -22,7 +22,9 @@ use anyhow::{anyhow, Context};
 use aptos_admin_service::AdminService;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{
+    merge_node_config, transaction_filter_type::TransactionFilters, NodeConfig, PersistableConfig,
+};
 use aptos_framework::ReleaseBundle;
 use aptos_genesis::builder::GenesisConfiguration;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
@@ -701,6 +703,10 @@ pub fn setup_environment_and_start_node(
 
     admin_service.set_aptos_db(db_rw.clone().into());
 
+    // Create the transaction filters, so that they can be updated through the admin service
+    let transaction_filters = TransactionFilters::new(&node_config);
+    admin_service.set_transaction_filters(transaction_filters.clone());
+
     // Set the Aptos VM configurations
     utils::set_aptos_vm_configurations(&node_config);
 
@@ -787,6 +793,7 @@ pub fn setup_environment_and_start_node(
         update_receiver,
         api_port_tx,
         indexer_grpc_port_tx,
+        transaction_filters.api.clone(),
     )?;
 
     // Set mempool client sender in order to enable the Mempool API in the admin service
@@ -802,6 +809,7 @@ pub fn setup_environment_and_start_node(
             mempool_listener,
             mempool_client_receiver,
             peers_and_metadata,
+            transaction_filters.mempool.clone(),
         );
 
     // Create the DKG runtime and get the VTxn pool
@@ -830,6 +838,7 @@ pub fn setup_environment_and_start_node(
             consensus_to_mempool_sender.clone(),
             db_rw.clone(),
             consensus_observer_reconfig_subscription,
+            transaction_filters.consensus.clone(),
         );
 
     // Create the consensus runtime (if enabled)
@@ -843,6 +852,7 @@ pub fn setup_environment_and_start_node(
         vtxn_pool,
         consensus_publisher.clone(),
         &mut admin_service,
+        transaction_filters.consensus,
     );
 
     Ok(AptosHandle {
@@ -4,7 +4,7 @@
 use crate::{bootstrap_api, indexer, mpsc::Receiver, network::ApplicationNetworkInterfaces};
 use aptos_admin_service::AdminService;
 use aptos_build_info::build_information;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{transaction_filter_type::SharedFilter, NodeConfig};
 use aptos_consensus::{
     consensus_observer::publisher::consensus_publisher::ConsensusPublisher,
     network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
@@ -56,6 +56,7 @@ pub fn bootstrap_api_and_indexer(
     update_receiver: Option<WatchReceiver<(Instant, Version)>>,
     api_port_tx: Option<oneshot::Sender<u16>>,
     indexer_grpc_port_tx: Option<oneshot::Sender<u16>>,
+    api_transaction_filter: SharedFilter,
 ) -> anyhow::Result<(
     Receiver<MempoolClientRequest>,
     Option<Runtime>,
@@ -105,6 +106,7 @@ pub fn bootstrap_api_and_indexer(
             mempool_client_sender.clone(),
             indexer_reader.clone(),
             api_port_tx,
+            api_transaction_filter,
         )?)
     } else {
         None
@@ -149,6 +151,7 @@ pub fn start_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    consensus_transaction_filter: SharedFilter,
 ) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
     let instant = Instant::now();
 
@@ -165,6 +168,7 @@ pub fn start_consensus_runtime(
         reconfig_subscription,
         vtxn_pool,
         consensus_publisher,
+        consensus_transaction_filter,
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
 
@@ -180,6 +184,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
     mempool_listener: MempoolNotificationListener,
     mempool_client_receiver: Receiver<MempoolClientRequest>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_transaction_filter: SharedFilter,
 ) -> (Runtime, Sender<QuorumStoreRequest>) {
     // Create a communication channel between consensus and mempool
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
@@ -197,6 +202,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
         mempool_listener,
         mempool_reconfig_subscription,
         peers_and_metadata,
+        mempool_transaction_filter,
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
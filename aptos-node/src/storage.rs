@@ -2,13 +2,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
+use aptos_backup_cli::{
+    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+    metadata::cache::MetadataCacheOpt,
+    storage::{
+        command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+        local_fs::LocalFs,
+        s3::{config::S3Config, S3},
+        BackupStorage,
+    },
+    utils::{GlobalRestoreOptions, RestoreRunMode},
+};
 use aptos_backup_service::start_backup_service;
-use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
-use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
+use aptos_config::{
+    config::{
+        BackupStorageConfig, BootstrappingMode, NodeConfig, BUFFERED_STATE_TARGET_ITEMS,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    },
+    utils::get_genesis_txn,
+};
+use aptos_db::{
+    fast_sync_storage_wrapper::FastSyncStorageWrapper, get_restore_handler::GetRestoreHandler,
+    AptosDB,
+};
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_indexer_grpc_table_info::internal_indexer_db_service::InternalIndexerDBService;
-use aptos_logger::{debug, info};
+use aptos_logger::{debug, info, warn};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_types::{
     ledger_info::{set_waypoint_version, LedgerInfoWithSignatures},
@@ -17,7 +37,7 @@ use aptos_types::{
 };
 use aptos_vm::aptos_vm::AptosVMBlockExecutor;
 use either::Either;
-use std::{fs, path::Path, sync::Arc, time::Instant};
+use std::{collections::HashMap, fs, path::Path, sync::Arc, time::Instant};
 use tokio::{
     runtime::Runtime,
     sync::watch::{channel, Receiver as WatchReceiver},
@@ -131,6 +151,126 @@ pub(crate) fn bootstrap_db(
     Ok((aptos_db, db_rw, None))
 }
 
+/// The file marking a restore from backup in progress, in the storage directory
+pub(crate) const RESTORE_IN_PROGRESS_FILE: &str = "restore_from_backup_in_progress";
+
+/// Restores the database from the configured backup if the node bootstraps from one:
+/// the epoch ending ledger infos, the latest state snapshot and the transactions after it.
+/// A previously interrupted restore is resumed. Otherwise, a database that already holds
+/// transactions is left as is, and state sync takes over from there.
+pub(crate) fn maybe_restore_from_backup(node_config: &NodeConfig) -> Result<()> {
+    if node_config.state_sync.state_sync_driver.bootstrapping_mode
+        != BootstrappingMode::RestoreFromBackup
+    {
+        return Ok(());
+    }
+    let backup_restore_config =
+        node_config.storage.backup_restore.as_ref().ok_or_else(|| {
+            anyhow!("storage.backup_restore must be set to restore from a backup")
+        })?;
+
+    let aptos_db = Arc::new(
+        AptosDB::open_kv_only(
+            node_config.storage.get_dir_paths(),
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+            node_config.storage.rocksdb_configs,
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            node_config.storage.max_num_nodes_per_lru_cache_shard,
+            None, /* internal indexer db */
+        )
+        .map_err(|err| anyhow!("DB failed to open for restore {}", err))?,
+    );
+    let restore_handler = aptos_db.get_restore_handler();
+    let restore_in_progress_file = node_config.storage.dir().join(RESTORE_IN_PROGRESS_FILE);
+    if restore_in_progress_file.exists() {
+        info!("Resuming the interrupted restore from backup.");
+    } else if restore_handler.get_next_expected_transaction_version()? > 0 {
+        info!("The DB already holds transactions, skipping the restore from backup.");
+        return Ok(());
+    } else {
+        fs::write(&restore_in_progress_file, b"")?;
+    }
+
+    // The epoch ending ledger infos in the backup are checked against the waypoints
+    let waypoint = node_config.base.waypoint.waypoint();
+    let genesis_waypoint = node_config
+        .execution
+        .genesis_waypoint
+        .as_ref()
+        .unwrap_or(&node_config.base.waypoint)
+        .genesis_waypoint();
+    let trusted_waypoints = [genesis_waypoint, waypoint]
+        .into_iter()
+        .map(|waypoint| (waypoint.version(), waypoint))
+        .collect::<HashMap<_, _>>();
+    let global_opt = GlobalRestoreOptions {
+        target_version: Version::MAX,
+        trusted_waypoints: Arc::new(trusted_waypoints),
+        run_mode: Arc::new(RestoreRunMode::Restore { restore_handler }),
+        concurrent_downloads: backup_restore_config
+            .concurrent_downloads
+            .unwrap_or_else(num_cpus::get),
+        replay_concurrency_level: num_cpus::get(),
+    };
+    let opt = RestoreCoordinatorOpt {
+        metadata_cache_opt: MetadataCacheOpt::new(
+            backup_restore_config.metadata_cache_dir.as_ref(),
+        ),
+        replay_all: false,
+        ledger_history_start_version: None,
+        skip_epoch_endings: false,
+    };
+
+    info!(
+        "Restoring the DB from backup storage {:?}.",
+        backup_restore_config.backup_storage
+    );
+    let instant = Instant::now();
+    let runtime = aptos_runtimes::spawn_named_runtime("backup-rst".into(), None);
+    runtime.block_on(async {
+        let storage: Arc<dyn BackupStorage> = match &backup_restore_config.backup_storage {
+            BackupStorageConfig::LocalFs { dir } => Arc::new(LocalFs::new(dir.clone())),
+            BackupStorageConfig::CommandAdapter { config } => Arc::new(CommandAdapter::new(
+                CommandAdapterConfig::load_from_file(config).await?,
+            )),
+            BackupStorageConfig::S3 { config } => {
+                Arc::new(S3::new(S3Config::load_from_file(config).await?)?)
+            },
+        };
+        RestoreCoordinator::new(opt, global_opt, storage)
+            .run()
+            .await
+    })?;
+
+    // Waypoints are only checked for the epochs restored, so make sure the
+    // restored ledger agrees with the waypoint if it already reaches it.
+    let synced_version = aptos_db
+        .get_synced_version()?
+        .ok_or_else(|| anyhow!("Nothing was restored from the backup"))?;
+    if waypoint.version() <= synced_version {
+        let ledger_info = aptos_db.get_epoch_ending_ledger_info(waypoint.version())?;
+        waypoint.verify(ledger_info.ledger_info())?;
+    } else {
+        // State sync has to fetch the rest, and only checks the waypoint once it gets there
+        warn!(
+            "The backup only restored the DB up to version {}, before the waypoint at version {}. \
+            State sync will catch up from there, a more recent backup would save it the work.",
+            synced_version,
+            waypoint.version()
+        );
+    }
+    fs::remove_file(&restore_in_progress_file)?;
+
+    info!(
+        "Restored the DB from backup up to version {} in {} ms",
+        synced_version,
+        instant.elapsed().as_millis()
+    );
+    Ok(())
+}
+
 /// Creates a RocksDb checkpoint for the consensus_db, state_sync_db,
 /// ledger_db and state_merkle_db and saves it to the checkpoint_path.
 /// Also, changes the working directory to run the node on the new path,
@@ -186,6 +326,9 @@ pub fn initialize_database_and_checkpoints(
         create_rocksdb_checkpoint_and_change_working_dir(node_config, working_dir);
     }
 
+    // If required, restore the database from a backup
+    maybe_restore_from_backup(node_config)?;
+
     // Open the database
     let instant = Instant::now();
     let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver) =
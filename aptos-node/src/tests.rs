@@ -1,15 +1,39 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{create_single_node_test_config, network};
-use aptos_config::config::{NodeConfig, WaypointConfig};
+use crate::{
+    create_single_node_test_config, network,
+    storage::{maybe_restore_from_backup, RESTORE_IN_PROGRESS_FILE},
+};
+use aptos_backup_cli::{
+    backup_types::{
+        epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+    },
+    storage::{local_fs::LocalFs, BackupStorage},
+    utils::{backup_service_client::BackupServiceClient, GlobalBackupOpt},
+};
+use aptos_backup_service::start_backup_service;
+use aptos_config::{
+    config::{
+        BackupRestoreConfig, BackupStorageConfig, BootstrappingMode, NodeConfig, WaypointConfig,
+    },
+    utils::get_available_port,
+};
+use aptos_db::AptosDB;
 use aptos_event_notifications::EventSubscriptionService;
+use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl_inner;
 use aptos_infallible::RwLock;
 use aptos_storage_interface::{DbReader, DbReaderWriter, DbWriter};
 use aptos_temppath::TempPath;
 use aptos_types::{chain_id::ChainId, waypoint::Waypoint};
 use rand::SeedableRng;
-use std::{fs, sync::Arc};
+use std::{
+    fs,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
 
 /// A mock database implementing DbReader and DbWriter
 pub struct MockDatabase;
@@ -114,3 +138,105 @@ fn test_create_single_node_test_config() {
             .bootstrapping_mode
     );
 }
+
+#[test]
+fn test_restore_from_local_backup() {
+    // Create a DB with a few epochs and back it all up to a local directory
+    let src_db_dir = TempPath::new();
+    let src_db = test_execution_with_storage_impl_inner(false, src_db_dir.path());
+    let latest_version = src_db.get_synced_version().unwrap().unwrap();
+    let port = get_available_port();
+    let runtime = start_backup_service(
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        src_db.clone(),
+    );
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let storage: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let global_opt = GlobalBackupOpt {
+        max_chunk_size: 1024 * 1024,
+        concurrent_data_requests: 2,
+    };
+    runtime.block_on(async {
+        EpochEndingBackupController::new(
+            EpochEndingBackupOpt {
+                start_epoch: 0,
+                end_epoch: 3,
+            },
+            global_opt.clone(),
+            client.clone(),
+            storage.clone(),
+        )
+        .run()
+        .await
+        .unwrap();
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt { epoch: 2 },
+            global_opt.clone(),
+            client.clone(),
+            storage.clone(),
+        )
+        .run()
+        .await
+        .unwrap();
+        TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: 0,
+                num_transactions: latest_version as usize + 1,
+            },
+            global_opt,
+            client,
+            storage,
+        )
+        .run()
+        .await
+        .unwrap();
+    });
+
+    // Create a node config restoring from the backup, trusting the genesis waypoint
+    let genesis_ledger_info = src_db.get_epoch_ending_ledger_info(0).unwrap();
+    let waypoint = Waypoint::new_epoch_boundary(genesis_ledger_info.ledger_info()).unwrap();
+    let temp_path = TempPath::new();
+    temp_path.create_as_dir().unwrap();
+    let mut node_config = NodeConfig::default();
+    node_config.set_data_dir(temp_path.path().to_path_buf());
+    node_config.base.waypoint = WaypointConfig::FromConfig(waypoint);
+    node_config.state_sync.state_sync_driver.bootstrapping_mode =
+        BootstrappingMode::RestoreFromBackup;
+    node_config.storage.backup_restore = Some(BackupRestoreConfig {
+        backup_storage: BackupStorageConfig::LocalFs {
+            dir: backup_dir.path().to_path_buf(),
+        },
+        concurrent_downloads: Some(2),
+        metadata_cache_dir: Some(temp_path.path().join("metadata_cache")),
+    });
+
+    // Restore the DB and verify it holds the same ledger as the original one
+    maybe_restore_from_backup(&node_config).unwrap();
+    assert!(!node_config
+        .storage
+        .dir()
+        .join(RESTORE_IN_PROGRESS_FILE)
+        .exists());
+    let db = AptosDB::new_readonly_for_test(node_config.storage.dir());
+    assert_eq!(db.get_synced_version().unwrap(), Some(latest_version));
+    assert_eq!(
+        db.get_accumulator_root_hash(latest_version).unwrap(),
+        src_db.get_accumulator_root_hash(latest_version).unwrap()
+    );
+    drop(db);
+
+    // A DB that already holds transactions is left as is, without reading the backup
+    node_config.storage.backup_restore = Some(BackupRestoreConfig {
+        backup_storage: BackupStorageConfig::LocalFs {
+            dir: temp_path.path().join("missing_backup"),
+        },
+        concurrent_downloads: None,
+        metadata_cache_dir: None,
+    });
+    maybe_restore_from_backup(&node_config).unwrap();
+}
@@ -68,6 +68,8 @@ pub struct ApiConfig {
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum number of view functions that can be sent with the Batch view API
+    pub max_view_function_batch_size: usize,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -82,6 +84,8 @@ pub struct ApiConfig {
     pub periodic_gas_estimation_ms: Option<u64>,
     /// Configuration to filter simulation requests.
     pub simulation_filter: Filter,
+    /// Configuration to filter transaction submissions. Block based matchers are not supported.
+    pub transaction_submission_filter: Filter,
     /// Configuration to filter view function requests.
     pub view_filter: ViewFilter,
     /// Periodically log stats for view function and simulate transaction usage
@@ -92,12 +96,30 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the transaction and event streaming APIs
+    #[serde(default = "default_disabled")]
+    pub transaction_stream_enabled: bool,
+    /// The number of transaction and event streams that can be open at any given time.
+    pub transaction_stream_max_active_connections: usize,
+    /// Maximum number of transactions a stream reads from storage at once
+    ///
+    /// A stream only reads the next batch once the client has consumed the previous one,
+    /// so this also bounds how much a slow client can hold in memory.
+    pub transaction_stream_batch_size: u16,
+    /// The interval at which a stream that has caught up polls storage for new transactions.
+    pub transaction_stream_poll_interval_ms: u64,
+    /// The interval at which streams send a keep-alive comment
+    ///
+    /// A disconnected client is only noticed when something is sent to it, so this bounds how
+    /// long a client whose filters match nothing holds on to a stream after disconnecting.
+    pub transaction_stream_keep_alive_interval_ms: u64,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+pub const DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE: usize = 50;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
@@ -135,16 +157,23 @@ impl Default for ApiConfig {
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_view_function_batch_size: DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
             periodic_gas_estimation_ms: Some(30_000),
             simulation_filter: Filter::default(),
+            transaction_submission_filter: Filter::default(),
             view_filter: ViewFilter::default(),
             periodic_function_stats_sec: Some(60),
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            transaction_stream_enabled: default_disabled(),
+            transaction_stream_max_active_connections: 100,
+            transaction_stream_batch_size: DEFAULT_MAX_PAGE_SIZE,
+            transaction_stream_poll_interval_ms: 100,
+            transaction_stream_keep_alive_interval_ms: 10_000,
         }
     }
 }
@@ -194,14 +223,52 @@ impl ConfigSanitizer for ApiConfig {
             ));
         }
 
-        // We don't support Block ID based simulation filters.
-        for rule in api_config.simulation_filter.rules() {
-            if let Matcher::BlockId(_) = rule.matcher() {
-                return Err(Error::ConfigSanitizerFailed(
-                    sanitizer_name,
-                    "Block ID based simulation filters are not supported!".into(),
-                ));
-            }
+        // Validate the view function batch size
+        if api_config.max_view_function_batch_size == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "max_view_function_batch_size must be greater than 0!".into(),
+            ));
+        }
+
+        // Validate the transaction stream batch size
+        if api_config.transaction_stream_enabled && api_config.transaction_stream_batch_size == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "transaction_stream_batch_size must be greater than 0!".into(),
+            ));
+        }
+
+        // Validate the transaction stream keep-alive interval
+        if api_config.transaction_stream_enabled
+            && api_config.transaction_stream_keep_alive_interval_ms == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "transaction_stream_keep_alive_interval_ms must be greater than 0!".into(),
+            ));
+        }
+
+        // We don't support Block ID based simulation filters, even nested in other matchers.
+        if api_config
+            .simulation_filter
+            .contains_matcher(&|matcher| matches!(matcher, Matcher::BlockId(_)))
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block ID based simulation filters are not supported!".into(),
+            ));
+        }
+
+        // Submitted transactions are not part of a block, so block based filters can't apply
+        if api_config
+            .transaction_submission_filter
+            .contains_matcher(&Matcher::is_block_matcher)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block based transaction submission filters are not supported!".into(),
+            ));
         }
 
         // Sanitize the gas estimation config
@@ -256,6 +323,7 @@ impl ViewFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aptos_crypto::HashValue;
 
     #[test]
     fn test_sanitize_disabled_api() {
@@ -319,4 +387,79 @@ mod tests {
                 .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_invalid_view_function_batch_size() {
+        // Create a node config with an empty view function batch size
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                max_view_function_batch_size: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // the view function batch size is invalid.
+        let error = ApiConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_simulation_filter_block_id() {
+        // Create a node config with a simulation filter that nests a block ID matcher
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                simulation_filter: Filter::empty().add_not_filter(
+                    false,
+                    Matcher::MatchesAnyOf(vec![Matcher::BlockId(HashValue::random())]),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // block ID based simulation filters are not supported.
+        let error = ApiConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Epoch based simulation filters are evaluated against the latest ledger info
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                simulation_filter: Filter::empty()
+                    .add_not_filter(false, Matcher::BlockEpochGreaterThan(10)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ApiConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_transaction_submission_filter_block_matchers() {
+        // Create a node config with a submission filter that nests a block epoch matcher
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                transaction_submission_filter: Filter::empty().add_matches_all_of_filter(
+                    false,
+                    vec![
+                        Matcher::Sender(AccountAddress::ONE),
+                        Matcher::Not(Box::new(Matcher::BlockEpochGreaterThan(10))),
+                    ],
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // submitted transactions are not part of a block.
+        let error = ApiConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
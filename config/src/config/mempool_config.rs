@@ -3,8 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{
-    config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+    config_optimizer::ConfigOptimizer,
+    config_sanitizer::ConfigSanitizer,
+    node_config_loader::NodeType,
+    transaction_filter_type::{Filter, Matcher},
+    Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
 use aptos_types::chain_id::ChainId;
@@ -50,6 +53,9 @@ pub struct MempoolConfig {
     pub default_failovers: usize,
     /// Whether or not to enable intelligent peer prioritization
     pub enable_intelligent_peer_prioritization: bool,
+    /// Whether or not to persist accepted transactions to an on-disk journal, so that they are
+    /// restored (and revalidated) when the node restarts.
+    pub enable_journal: bool,
     /// The maximum number of broadcasts sent to a single peer that are pending a response ACK at any point.
     pub max_broadcasts_per_peer: usize,
     /// Maximum number of inbound network messages to the Mempool application
@@ -102,6 +108,9 @@ pub struct MempoolConfig {
     pub enable_max_load_balancing_at_any_load: bool,
     /// Maximum number of orderless transactions allowed in the Mempool per user
     pub orderless_txn_capacity_per_user: usize,
+    /// Filter applied to transactions before they are admitted into the Mempool, both from
+    /// clients and from peers. Block based matchers are not supported.
+    pub transaction_filter: Filter,
 }
 
 impl Default for MempoolConfig {
@@ -121,6 +130,7 @@ impl Default for MempoolConfig {
             capacity_per_user: 100,
             default_failovers: 1,
             enable_intelligent_peer_prioritization: true,
+            enable_journal: false,
             shared_mempool_peer_update_interval_ms: 1_000,
             shared_mempool_priority_update_interval_secs: 600, // 10 minutes (frequent reprioritization is expensive)
             shared_mempool_failover_delay_ms: 500,
@@ -167,17 +177,32 @@ impl Default for MempoolConfig {
             ],
             enable_max_load_balancing_at_any_load: false,
             orderless_txn_capacity_per_user: 1000,
+            transaction_filter: Filter::empty(),
         }
     }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+
+        // Transactions are admitted outside of blocks, so block based filters can't apply
+        if node_config
+            .mempool
+            .transaction_filter
+            .contains_matcher(&Matcher::is_block_matcher)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block based mempool transaction filters are not supported!".into(),
+            ));
+        }
+
+        Ok(()) // TODO: add more reasonable verifications
     }
 }
 
@@ -349,4 +374,22 @@ mod tests {
             local_max_broadcasts_per_peer
         );
     }
+
+    #[test]
+    fn test_sanitize_block_based_transaction_filter() {
+        // Create a node config with a mempool filter that negates a block matcher
+        let mut node_config = NodeConfig::default();
+        node_config.mempool.transaction_filter =
+            Filter::empty().add_not_filter(false, Matcher::BlockTimeStampGreaterThan(1_000));
+
+        // Sanitize the config and verify that it fails because
+        // transactions are admitted outside of blocks.
+        let error = MempoolConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Sanitize a config with a transaction based filter and verify that it succeeds
+        node_config.mempool.transaction_filter =
+            Filter::empty().add_not_filter(false, Matcher::GasUnitPriceGreaterThan(100));
+        MempoolConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
 }
@@ -44,6 +44,7 @@ mod utils;
 pub use admin_service_config::*;
 pub use api_config::*;
 pub use base_config::*;
+pub use config_sanitizer::ConfigSanitizer;
 pub use consensus_config::*;
 pub use consensus_observer_config::*;
 pub use dag_consensus_config::*;
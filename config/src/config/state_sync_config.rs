@@ -44,6 +44,9 @@ pub enum BootstrappingMode {
     ExecuteTransactionsFromGenesis,
     /// Executes transactions or applies outputs from genesis (whichever is faster)
     ExecuteOrApplyFromGenesis,
+    /// Restores the latest state snapshot and transactions from the backup configured in
+    /// `storage.backup_restore`, then executes transactions or applies outputs from there
+    RestoreFromBackup,
 }
 
 impl BootstrappingMode {
@@ -57,6 +60,7 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
         }
     }
 
@@ -488,6 +492,16 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that a backup is configured for nodes restoring from one
+        if state_sync_driver_config.bootstrapping_mode == BootstrappingMode::RestoreFromBackup
+            && node_config.storage.backup_restore.is_none()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "storage.backup_restore must be set to restore from a backup!".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -793,6 +807,28 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_restore_from_backup_missing_storage() {
+        // Create a node config that restores from a backup
+        // without specifying the backup storage.
+        let node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_mode: BootstrappingMode::RestoreFromBackup,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
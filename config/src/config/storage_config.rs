@@ -103,6 +103,31 @@ impl ShardedDbPathConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackupRestoreConfig {
+    /// The storage holding the backups
+    pub backup_storage: BackupStorageConfig,
+    /// Number of concurrent downloads from the backup storage. Defaults to the number of CPUs.
+    #[serde(default)]
+    pub concurrent_downloads: Option<usize>,
+    /// Directory to cache the backup metadata in. Defaults to a temporary directory.
+    #[serde(default)]
+    pub metadata_cache_dir: Option<PathBuf>,
+}
+
+/// The backup storages supported by the backup-cli, see `aptos_backup_cli::storage`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupStorageConfig {
+    /// Backups in a directory on the local filesystem
+    LocalFs { dir: PathBuf },
+    /// Backups accessed through the commands in a command adapter config file
+    CommandAdapter { config: PathBuf },
+    /// Backups in an S3 compatible object store, accessed as described in an S3 config file
+    S3 { config: PathBuf },
+}
+
 /// Port selected RocksDB options for tuning underlying rocksdb instance of AptosDB.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
@@ -175,6 +200,9 @@ impl Default for RocksdbConfigs {
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
     pub backup_service_address: SocketAddr,
+    /// The backup to restore the database from when bootstrapping with
+    /// `BootstrappingMode::RestoreFromBackup`
+    pub backup_restore: Option<BackupRestoreConfig>,
     /// Top level directory to store the RocksDB
     pub dir: PathBuf,
     /// Storage pruning configuration
@@ -339,6 +367,7 @@ impl Default for StorageConfig {
     fn default() -> StorageConfig {
         StorageConfig {
             backup_service_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 6186),
+            backup_restore: None,
             dir: PathBuf::from("db"),
             // The prune window must at least out live a RPC request because its sub requests are
             // to return a consistent view of the DB at exactly same version. Considering a few
@@ -1,12 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::config::NodeConfig;
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{SignedTransaction, TransactionExecutableRef},
+    transaction::{
+        authenticator::{AccountAuthenticator, AnyPublicKey},
+        SignedTransaction, TransactionExecutableRef,
+    },
 };
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Matcher {
@@ -21,21 +27,84 @@ pub enum Matcher {
     BlockEpochGreaterThan(u64), // Matches transactions in blocks with epochs greater than the specified value
     BlockEpochLessThan(u64), // Matches transactions in blocks with epochs less than the specified value
     MatchesAllOf(Vec<Matcher>), // Matches transactions that satisfy all the provided conditions (i.e., logical AND)
+    MatchesAnyOf(Vec<Matcher>), // Matches transactions that satisfy any of the provided conditions (i.e., logical OR)
+    Not(Box<Matcher>),          // Matches transactions that do not satisfy the provided condition
+    PayloadType(PayloadType),   // Matches transactions with a specific type of payload
+    MultisigAddress(AccountAddress), // Matches multisig transactions executed by a specific multisig account
+    ScriptHash(HashValue), // Matches script transactions whose code has a specific SHA3-256 hash
+    FeePayer(AccountAddress), // Matches transactions whose gas is paid by a specific fee payer
+    SecondarySigner(AccountAddress), // Matches transactions with a specific secondary signer
+    PublicKey(AnyPublicKey), // Matches transactions signed (by any signer) with a specific public key
+    AuthenticatorType(AuthenticatorType), // Matches transactions with any signer using a specific type of authenticator
+    GasUnitPriceGreaterThan(u64), // Matches transactions with gas unit prices greater than the specified value
+    GasUnitPriceLessThan(u64), // Matches transactions with gas unit prices less than the specified value
+}
+
+/// The type of payload of a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PayloadType {
+    Script,
+    EntryFunction,
+    Multisig,
+}
+
+/// The type of authenticator used by a transaction signer
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuthenticatorType {
+    Ed25519,
+    MultiEd25519,
+    SingleKey,
+    MultiKey,
+    NoAccountAuthenticator,
+    Abstraction,
+    Keyless, // Single or multi key authenticators that contain a keyless or federated keyless key
+}
+
+/// The block that transactions are being filtered for, if any
+struct BlockInfo {
+    id: HashValue,
+    epoch: u64,
+    timestamp: u64,
 }
 
 impl Matcher {
-    fn matches(
-        &self,
-        block_id: HashValue,
-        block_epoch: u64,
-        block_timestamp: u64,
-        txn: &SignedTransaction,
-    ) -> bool {
-        match self {
+    /// Returns true iff the matcher depends on the block the transaction is in
+    pub fn is_block_matcher(&self) -> bool {
+        matches!(
+            self,
+            Matcher::BlockId(_)
+                | Matcher::BlockTimeStampGreaterThan(_)
+                | Matcher::BlockTimeStampLessThan(_)
+                | Matcher::BlockEpochGreaterThan(_)
+                | Matcher::BlockEpochLessThan(_)
+        )
+    }
+
+    /// Returns true iff the matcher, or any matcher nested in it, satisfies the predicate
+    pub fn contains(&self, predicate: &dyn Fn(&Matcher) -> bool) -> bool {
+        predicate(self)
+            || match self {
+                Matcher::MatchesAllOf(matchers) | Matcher::MatchesAnyOf(matchers) => {
+                    matchers.iter().any(|matcher| matcher.contains(predicate))
+                },
+                Matcher::Not(matcher) => matcher.contains(predicate),
+                _ => false,
+            }
+    }
+
+    /// Returns whether the matcher matches the transaction, or `None` if that depends on a block
+    /// and the transaction is not evaluated as part of one (e.g., on mempool admission). This
+    /// way, negating a block matcher does not match every transaction outside of blocks.
+    fn matches(&self, block: Option<&BlockInfo>, txn: &SignedTransaction) -> Option<bool> {
+        let matches = match self {
             Matcher::All => true,
-            Matcher::BlockId(id) => block_id == *id,
-            Matcher::BlockTimeStampGreaterThan(timestamp) => block_timestamp > *timestamp,
-            Matcher::BlockTimeStampLessThan(timestamp) => block_timestamp < *timestamp,
+            Matcher::BlockId(id) => return block.map(|block| block.id == *id),
+            Matcher::BlockTimeStampGreaterThan(timestamp) => {
+                return block.map(|block| block.timestamp > *timestamp)
+            },
+            Matcher::BlockTimeStampLessThan(timestamp) => {
+                return block.map(|block| block.timestamp < *timestamp)
+            },
             Matcher::TransactionId(id) => txn.committed_hash() == *id,
             Matcher::Sender(sender) => txn.sender() == *sender,
             Matcher::ModuleAddress(address) => match txn.payload().executable_ref() {
@@ -58,12 +127,137 @@ impl Matcher {
                     _ => false,
                 }
             },
-            Matcher::BlockEpochGreaterThan(epoch) => block_epoch > *epoch,
-            Matcher::BlockEpochLessThan(epoch) => block_epoch < *epoch,
-            Matcher::MatchesAllOf(matchers) => matchers
+            Matcher::BlockEpochGreaterThan(epoch) => {
+                return block.map(|block| block.epoch > *epoch)
+            },
+            Matcher::BlockEpochLessThan(epoch) => return block.map(|block| block.epoch < *epoch),
+            Matcher::MatchesAllOf(matchers) => {
+                // A single mismatch decides, even if other matchers can't be evaluated
+                let mut matches = Some(true);
+                for matcher in matchers {
+                    match matcher.matches(block, txn) {
+                        Some(false) => return Some(false),
+                        None => matches = None,
+                        Some(true) => {},
+                    }
+                }
+                return matches;
+            },
+            Matcher::MatchesAnyOf(matchers) => {
+                // A single match decides, even if other matchers can't be evaluated
+                let mut matches = Some(false);
+                for matcher in matchers {
+                    match matcher.matches(block, txn) {
+                        Some(true) => return Some(true),
+                        None => matches = None,
+                        Some(false) => {},
+                    }
+                }
+                return matches;
+            },
+            Matcher::Not(matcher) => return matcher.matches(block, txn).map(|matches| !matches),
+            Matcher::PayloadType(payload_type) => {
+                if txn.payload().is_multisig() {
+                    return *payload_type == PayloadType::Multisig;
+                }
+                match txn.payload().executable_ref() {
+                    Ok(TransactionExecutableRef::Script(_)) => *payload_type == PayloadType::Script,
+                    Ok(TransactionExecutableRef::EntryFunction(_)) => {
+                        *payload_type == PayloadType::EntryFunction
+                    },
+                    _ => false,
+                }
+            },
+            Matcher::MultisigAddress(address) => txn.multisig_address() == Some(*address),
+            Matcher::ScriptHash(hash) => match txn.payload().executable_ref() {
+                Ok(TransactionExecutableRef::Script(script)) => {
+                    HashValue::sha3_256_of(script.code()) == *hash
+                },
+                _ => false,
+            },
+            Matcher::FeePayer(address) => {
+                txn.authenticator_ref().fee_payer_address() == Some(*address)
+            },
+            Matcher::SecondarySigner(address) => txn
+                .authenticator_ref()
+                .secondary_signer_addresses()
+                .contains(address),
+            Matcher::PublicKey(public_key) => txn
+                .authenticator_ref()
+                .all_signers()
                 .iter()
-                .all(|matcher| matcher.matches(block_id, block_epoch, block_timestamp, txn)),
-        }
+                .any(|signer| has_public_key(signer, public_key)),
+            Matcher::AuthenticatorType(authenticator_type) => txn
+                .authenticator_ref()
+                .all_signers()
+                .iter()
+                .any(|signer| has_authenticator_type(signer, *authenticator_type)),
+            Matcher::GasUnitPriceGreaterThan(price) => txn.gas_unit_price() > *price,
+            Matcher::GasUnitPriceLessThan(price) => txn.gas_unit_price() < *price,
+        };
+        Some(matches)
+    }
+}
+
+/// Returns true iff the signer uses the given public key (or holds it in a set of keys)
+fn has_public_key(signer: &AccountAuthenticator, public_key: &AnyPublicKey) -> bool {
+    match signer {
+        AccountAuthenticator::Ed25519 {
+            public_key: signer_key,
+            ..
+        } => matches!(public_key, AnyPublicKey::Ed25519 { public_key } if public_key == signer_key),
+        AccountAuthenticator::MultiEd25519 {
+            public_key: signer_keys,
+            ..
+        } => match public_key {
+            AnyPublicKey::Ed25519 { public_key } => signer_keys.public_keys().contains(public_key),
+            _ => false,
+        },
+        AccountAuthenticator::SingleKey { authenticator } => {
+            authenticator.public_key() == public_key
+        },
+        AccountAuthenticator::MultiKey { authenticator } => authenticator
+            .public_keys()
+            .public_keys()
+            .contains(public_key),
+        AccountAuthenticator::NoAccountAuthenticator | AccountAuthenticator::Abstraction { .. } => {
+            false
+        },
+    }
+}
+
+/// Returns true iff the signer uses the given type of authenticator
+fn has_authenticator_type(
+    signer: &AccountAuthenticator,
+    authenticator_type: AuthenticatorType,
+) -> bool {
+    let is_keyless = |public_key: &AnyPublicKey| {
+        matches!(
+            public_key,
+            AnyPublicKey::Keyless { .. } | AnyPublicKey::FederatedKeyless { .. }
+        )
+    };
+    match (signer, authenticator_type) {
+        (AccountAuthenticator::Ed25519 { .. }, AuthenticatorType::Ed25519)
+        | (AccountAuthenticator::MultiEd25519 { .. }, AuthenticatorType::MultiEd25519)
+        | (AccountAuthenticator::SingleKey { .. }, AuthenticatorType::SingleKey)
+        | (AccountAuthenticator::MultiKey { .. }, AuthenticatorType::MultiKey)
+        | (
+            AccountAuthenticator::NoAccountAuthenticator,
+            AuthenticatorType::NoAccountAuthenticator,
+        )
+        | (AccountAuthenticator::Abstraction { .. }, AuthenticatorType::Abstraction) => true,
+        (AccountAuthenticator::SingleKey { authenticator }, AuthenticatorType::Keyless) => {
+            is_keyless(authenticator.public_key())
+        },
+        (AccountAuthenticator::MultiKey { authenticator }, AuthenticatorType::Keyless) => {
+            authenticator
+                .public_keys()
+                .public_keys()
+                .iter()
+                .any(is_keyless)
+        },
+        _ => false,
     }
 }
 
@@ -89,23 +283,17 @@ enum EvalResult {
 }
 
 impl Rule {
-    fn eval(
-        &self,
-        block_id: HashValue,
-        block_epoch: u64,
-        block_timestamp: u64,
-        txn: &SignedTransaction,
-    ) -> EvalResult {
+    fn eval(&self, block: Option<&BlockInfo>, txn: &SignedTransaction) -> EvalResult {
         match self {
             Rule::Allow(matcher) => {
-                if matcher.matches(block_id, block_epoch, block_timestamp, txn) {
+                if matcher.matches(block, txn) == Some(true) {
                     EvalResult::Allow
                 } else {
                     EvalResult::NoMatch
                 }
             },
             Rule::Deny(matcher) => {
-                if matcher.matches(block_id, block_epoch, block_timestamp, txn) {
+                if matcher.matches(block, txn) == Some(true) {
                     EvalResult::Deny
                 } else {
                     EvalResult::NoMatch
@@ -149,6 +337,24 @@ impl Rule {
 /// - Transactions that call the entry function test::check or test::new from the module with
 ///   address 0000000000000000000000000000000000000000000000000000000000000002.
 /// All other transactions are denied.
+///
+/// Matchers can also be combined to block specific patterns, for example:
+///             rules:
+///                 - Deny:
+///                     MatchesAnyOf:
+///                         - FeePayer: "0x6c1e7ef9e2a5b9a7c0a2d0e0b8f1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9"
+///                         - PayloadType: Multisig
+///                 - Deny:
+///                     MatchesAllOf:
+///                         - AuthenticatorType: Keyless
+///                         - Not:
+///                             GasUnitPriceGreaterThan: 150
+/// This filter denies transactions paid for by the given fee payer, multisig transactions, and
+/// keyless transactions with a gas unit price of at most 150. All other transactions are allowed.
+///
+/// Block matchers (e.g., `BlockId` or `BlockEpochGreaterThan`) can only be evaluated for
+/// transactions that are filtered as part of a block. Elsewhere, rules that depend on them do not
+/// apply, and the mempool and API submission filters reject them, see the config sanitizers.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Filter {
     rules: Vec<Rule>,
@@ -233,10 +439,77 @@ impl Filter {
         self.add_match_rule(allow, matcher)
     }
 
+    pub fn add_matches_any_of_filter(self, allow: bool, matchers: Vec<Matcher>) -> Self {
+        let matcher = Matcher::MatchesAnyOf(matchers);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_not_filter(self, allow: bool, matcher: Matcher) -> Self {
+        let matcher = Matcher::Not(Box::new(matcher));
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_payload_type_filter(self, allow: bool, payload_type: PayloadType) -> Self {
+        let matcher = Matcher::PayloadType(payload_type);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_multisig_address_filter(self, allow: bool, address: AccountAddress) -> Self {
+        let matcher = Matcher::MultisigAddress(address);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_script_hash_filter(self, allow: bool, hash: HashValue) -> Self {
+        let matcher = Matcher::ScriptHash(hash);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_fee_payer_filter(self, allow: bool, address: AccountAddress) -> Self {
+        let matcher = Matcher::FeePayer(address);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_secondary_signer_filter(self, allow: bool, address: AccountAddress) -> Self {
+        let matcher = Matcher::SecondarySigner(address);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_public_key_filter(self, allow: bool, public_key: AnyPublicKey) -> Self {
+        let matcher = Matcher::PublicKey(public_key);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_authenticator_type_filter(
+        self,
+        allow: bool,
+        authenticator_type: AuthenticatorType,
+    ) -> Self {
+        let matcher = Matcher::AuthenticatorType(authenticator_type);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_gas_unit_price_greater_than_filter(self, allow: bool, price: u64) -> Self {
+        let matcher = Matcher::GasUnitPriceGreaterThan(price);
+        self.add_match_rule(allow, matcher)
+    }
+
+    pub fn add_gas_unit_price_less_than_filter(self, allow: bool, price: u64) -> Self {
+        let matcher = Matcher::GasUnitPriceLessThan(price);
+        self.add_match_rule(allow, matcher)
+    }
+
     pub fn rules(&self) -> &[Rule] {
         &self.rules
     }
 
+    /// Returns true iff any matcher of the filter, including nested ones, satisfies the predicate
+    pub fn contains_matcher(&self, predicate: &dyn Fn(&Matcher) -> bool) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.matcher().contains(predicate))
+    }
+
+    /// Returns true iff the filter allows the transaction in the given block
     pub fn allows(
         &self,
         block_id: HashValue,
@@ -244,10 +517,25 @@ impl Filter {
         block_timestamp: u64,
         txn: &SignedTransaction,
     ) -> bool {
+        let block = BlockInfo {
+            id: block_id,
+            epoch: block_epoch,
+            timestamp: block_timestamp,
+        };
+        self.evaluate(Some(&block), txn)
+    }
+
+    /// Returns true iff the filter allows the transaction outside of a block (e.g., on
+    /// submission or mempool admission). Rules that depend on block matchers do not apply here.
+    pub fn allows_transaction(&self, txn: &SignedTransaction) -> bool {
+        self.evaluate(None, txn)
+    }
+
+    fn evaluate(&self, block: Option<&BlockInfo>, txn: &SignedTransaction) -> bool {
         for rule in &self.rules {
             // Rules are evaluated in the order and the first rule that matches is used. If no rule
             // matches, the transaction is allowed.
-            match rule.eval(block_id, block_epoch, block_timestamp, txn) {
+            match rule.eval(block, txn) {
                 EvalResult::Allow => return true,
                 EvalResult::Deny => return false,
                 EvalResult::NoMatch => continue,
@@ -256,3 +544,52 @@ impl Filter {
         true
     }
 }
+
+/// A handle to a filter that can be replaced at runtime (e.g., through the admin service).
+/// Clones of the handle share the same filter, so updates are seen by all of them.
+#[derive(Clone, Debug, Default)]
+pub struct SharedFilter(Arc<ArcSwap<Filter>>);
+
+impl SharedFilter {
+    pub fn new(filter: Filter) -> Self {
+        Self(Arc::new(ArcSwap::from_pointee(filter)))
+    }
+
+    /// Returns the current filter
+    pub fn load(&self) -> Arc<Filter> {
+        self.0.load_full()
+    }
+
+    /// Replaces the filter for all holders of the handle
+    pub fn store(&self, filter: Filter) {
+        self.0.store(Arc::new(filter));
+    }
+}
+
+impl From<Filter> for SharedFilter {
+    fn from(filter: Filter) -> Self {
+        Self::new(filter)
+    }
+}
+
+/// The transaction filters of the node, shared between the components applying them and the
+/// admin service that can update them.
+#[derive(Clone, Debug, Default)]
+pub struct TransactionFilters {
+    /// Applied to the transactions of blocks before execution
+    pub consensus: SharedFilter,
+    /// Applied to transactions before they are admitted into mempool
+    pub mempool: SharedFilter,
+    /// Applied to transactions submitted through the REST API
+    pub api: SharedFilter,
+}
+
+impl TransactionFilters {
+    pub fn new(node_config: &NodeConfig) -> Self {
+        Self {
+            consensus: node_config.execution.transaction_filter.clone().into(),
+            mempool: node_config.mempool.transaction_filter.clone().into(),
+            api: node_config.api.transaction_submission_filter.clone().into(),
+        }
+    }
+}
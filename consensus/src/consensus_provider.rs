@@ -26,7 +26,7 @@ use crate::{
 };
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::aptos_channel::Receiver;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{transaction_filter_type::SharedFilter, NodeConfig};
 use aptos_consensus_notifications::ConsensusNotificationSender;
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_executor::block_executor::BlockExecutor;
@@ -54,6 +54,7 @@ pub fn start_consensus(
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    transaction_filter: SharedFilter,
 ) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
@@ -69,7 +70,7 @@ pub fn start_consensus(
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
-        TransactionFilter::new(node_config.execution.transaction_filter.clone()),
+        TransactionFilter::new(transaction_filter),
         node_config.consensus.enable_pre_commit,
     );
 
@@ -138,6 +139,7 @@ pub fn start_consensus_observer(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    transaction_filter: SharedFilter,
 ) {
     // Create the (dummy) consensus network client
     let (self_sender, _self_receiver) =
@@ -162,7 +164,7 @@ pub fn start_consensus_observer(
             txn_notifier,
             state_sync_notifier,
             consensus_observer_runtime.handle(),
-            TransactionFilter::new(node_config.execution.transaction_filter.clone()),
+            TransactionFilter::new(transaction_filter),
             node_config.consensus.enable_pre_commit,
         );
 
@@ -1,17 +1,19 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::transaction_filter_type::Filter;
+use aptos_config::config::transaction_filter_type::SharedFilter;
 use aptos_crypto::HashValue;
 use aptos_types::transaction::SignedTransaction;
 
 pub struct TransactionFilter {
-    filter: Filter,
+    filter: SharedFilter,
 }
 
 impl TransactionFilter {
-    pub(crate) fn new(filter: Filter) -> Self {
-        Self { filter }
+    pub(crate) fn new(filter: impl Into<SharedFilter>) -> Self {
+        Self {
+            filter: filter.into(),
+        }
     }
 
     pub fn filter(
@@ -21,16 +23,17 @@ impl TransactionFilter {
         block_timestamp: u64,
         txns: Vec<SignedTransaction>,
     ) -> Vec<SignedTransaction> {
+        // Load the filter once, so that the whole block is filtered with the same rules even if
+        // the filter gets updated concurrently
+        let filter = self.filter.load();
+
         // Special case for no filter to avoid unnecessary iteration through all transactions in the default case
-        if self.filter.is_empty() {
+        if filter.is_empty() {
             return txns;
         }
 
         txns.into_iter()
-            .filter(|txn| {
-                self.filter
-                    .allows(block_id, block_epoch, block_timestamp, txn)
-            })
+            .filter(|txn| filter.allows(block_id, block_epoch, block_timestamp, txn))
             .collect()
     }
 }
@@ -38,14 +41,17 @@ impl TransactionFilter {
 #[cfg(test)]
 mod test {
     use crate::transaction_filter::TransactionFilter;
-    use aptos_config::config::transaction_filter_type::{Filter, Matcher};
+    use aptos_config::config::transaction_filter_type::{
+        AuthenticatorType, Filter, Matcher, PayloadType, SharedFilter,
+    };
     use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
     use aptos_types::{
         chain_id::ChainId,
         move_utils::MemberId,
         transaction::{
-            EntryFunction, RawTransaction, SignedTransaction, TransactionExecutableRef,
-            TransactionPayload,
+            authenticator::{AccountAuthenticator, AnyPublicKey},
+            EntryFunction, Multisig, MultisigTransactionPayload, RawTransaction, Script,
+            SignedTransaction, TransactionExecutableRef, TransactionPayload,
         },
     };
     use move_core_types::account_address::AccountAddress;
@@ -122,6 +128,69 @@ mod test {
         }
     }
 
+    fn create_entry_function(function: MemberId) -> EntryFunction {
+        let MemberId {
+            module_id,
+            member_id: function_id,
+        } = function;
+        EntryFunction::new(module_id, function_id, vec![], vec![])
+    }
+
+    fn create_raw_transaction(payload: TransactionPayload, gas_unit_price: u64) -> RawTransaction {
+        RawTransaction::new(
+            AccountAddress::random(),
+            0,
+            payload,
+            0,
+            gas_unit_price,
+            0,
+            ChainId::new(10),
+        )
+    }
+
+    fn create_signed_transaction_with_payload(
+        payload: TransactionPayload,
+        gas_unit_price: u64,
+    ) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate(&mut rand::thread_rng());
+        let raw_transaction = create_raw_transaction(payload, gas_unit_price);
+        SignedTransaction::new(
+            raw_transaction.clone(),
+            private_key.public_key(),
+            private_key.sign(&raw_transaction).unwrap(),
+        )
+    }
+
+    fn create_fee_payer_transaction(
+        secondary_signer_address: AccountAddress,
+        fee_payer_address: AccountAddress,
+    ) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate(&mut rand::thread_rng());
+        let payload = TransactionPayload::EntryFunction(create_entry_function(
+            str::parse("0x1::test::fee").unwrap(),
+        ));
+        let raw_transaction = create_raw_transaction(payload, 0);
+        let authenticator = AccountAuthenticator::ed25519(
+            private_key.public_key(),
+            private_key.sign(&raw_transaction).unwrap(),
+        );
+        SignedTransaction::new_fee_payer(
+            raw_transaction,
+            authenticator.clone(),
+            vec![secondary_signer_address],
+            vec![authenticator.clone()],
+            fee_payer_address,
+            authenticator,
+        )
+    }
+
+    fn get_public_key(txn: &SignedTransaction) -> AnyPublicKey {
+        match txn.authenticator_ref().sender() {
+            AccountAuthenticator::Ed25519 { public_key, .. } => AnyPublicKey::ed25519(public_key),
+            _ => panic!("Unexpected transaction authenticator"),
+        }
+    }
+
     #[test]
     fn test_empty_filter() {
         // Create an empty filter
@@ -619,4 +688,245 @@ mod test {
         // Verify that only the first transaction is denied
         assert_eq!(filtered_txns, txns[1..].to_vec());
     }
+
+    #[test]
+    fn test_matches_any_of_filter() {
+        // Create a filter that denies transactions from any of two senders (txn 0 and txn 1)
+        let (block_id, txns) = get_block_id_and_transactions();
+        let matchers = vec![
+            Matcher::Sender(txns[0].sender()),
+            Matcher::Sender(txns[1].sender()),
+        ];
+        let filter =
+            TransactionFilter::new(Filter::empty().add_matches_any_of_filter(false, matchers));
+
+        // Verify that it returns all transactions except the ones from the specified senders
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[2..].to_vec());
+    }
+
+    #[test]
+    fn test_not_filter() {
+        // Create a filter that denies transactions that are not from a specific sender (txn 0)
+        let (block_id, txns) = get_block_id_and_transactions();
+        let filter = TransactionFilter::new(
+            Filter::empty().add_not_filter(false, Matcher::Sender(txns[0].sender())),
+        );
+
+        // Verify that it only returns the transaction from the specified sender
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..1].to_vec());
+    }
+
+    #[test]
+    fn test_payload_type_and_script_hash_filter() {
+        // Create a script, an entry function and a multisig transaction
+        let code = vec![1, 2, 3];
+        let entry_function = create_entry_function(str::parse("0x1::test::check").unwrap());
+        let txns = vec![
+            create_signed_transaction_with_payload(
+                TransactionPayload::Script(Script::new(code.clone(), vec![], vec![])),
+                0,
+            ),
+            create_signed_transaction_with_payload(
+                TransactionPayload::EntryFunction(entry_function.clone()),
+                0,
+            ),
+            create_signed_transaction_with_payload(
+                TransactionPayload::Multisig(Multisig {
+                    multisig_address: AccountAddress::ONE,
+                    transaction_payload: Some(MultisigTransactionPayload::EntryFunction(
+                        entry_function,
+                    )),
+                }),
+                0,
+            ),
+        ];
+        let block_id = HashValue::random();
+
+        // Verify that each payload type only matches its own transaction
+        for (idx, payload_type) in [
+            PayloadType::Script,
+            PayloadType::EntryFunction,
+            PayloadType::Multisig,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let filter = TransactionFilter::new(
+                Filter::empty()
+                    .add_payload_type_filter(true, payload_type)
+                    .add_all_filter(false),
+            );
+            let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+            assert_eq!(filtered_txns, vec![txns[idx].clone()]);
+        }
+
+        // Verify that the multisig address only matches the multisig transaction
+        let filter = TransactionFilter::new(
+            Filter::empty().add_multisig_address_filter(false, AccountAddress::ONE),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..2].to_vec());
+
+        // Verify that the script hash only matches the script transaction
+        let filter = TransactionFilter::new(
+            Filter::empty().add_script_hash_filter(false, HashValue::sha3_256_of(&code)),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..].to_vec());
+    }
+
+    #[test]
+    fn test_fee_payer_and_secondary_signer_filter() {
+        // Create a regular transaction and two fee payer transactions
+        let (block_id, mut txns) = get_block_id_and_transactions();
+        txns.truncate(1);
+        let fee_payer = AccountAddress::random();
+        let secondary_signer = AccountAddress::random();
+        txns.push(create_fee_payer_transaction(secondary_signer, fee_payer));
+        txns.push(create_fee_payer_transaction(
+            AccountAddress::random(),
+            AccountAddress::random(),
+        ));
+
+        // Verify that the fee payer filter only denies the transaction with the fee payer
+        let filter = TransactionFilter::new(Filter::empty().add_fee_payer_filter(false, fee_payer));
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![txns[0].clone(), txns[2].clone()]);
+
+        // Verify that the secondary signer filter only denies the transaction with the signer
+        let filter = TransactionFilter::new(
+            Filter::empty().add_secondary_signer_filter(false, secondary_signer),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![txns[0].clone(), txns[2].clone()]);
+    }
+
+    #[test]
+    fn test_public_key_and_authenticator_type_filter() {
+        // Create transactions signed with different keys
+        let txns: Vec<_> = (0..3)
+            .map(|_| {
+                create_signed_transaction_with_payload(
+                    TransactionPayload::EntryFunction(create_entry_function(
+                        str::parse("0x1::test::check").unwrap(),
+                    )),
+                    0,
+                )
+            })
+            .collect();
+        let block_id = HashValue::random();
+
+        // Verify that the public key filter only denies the transaction signed with the key
+        let filter = TransactionFilter::new(
+            Filter::empty().add_public_key_filter(false, get_public_key(&txns[1])),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![txns[0].clone(), txns[2].clone()]);
+
+        // Verify that the authenticator type filter matches all the Ed25519 transactions
+        let filter = TransactionFilter::new(
+            Filter::empty().add_authenticator_type_filter(false, AuthenticatorType::Ed25519),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![]);
+
+        // Verify that the authenticator type filter doesn't match other authenticators
+        let filter = TransactionFilter::new(
+            Filter::empty().add_authenticator_type_filter(false, AuthenticatorType::Keyless),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns);
+    }
+
+    #[test]
+    fn test_gas_unit_price_filter() {
+        // Create transactions with increasing gas unit prices
+        let txns: Vec<_> = [99, 100, 101]
+            .into_iter()
+            .map(|gas_unit_price| {
+                create_signed_transaction_with_payload(
+                    TransactionPayload::EntryFunction(create_entry_function(
+                        str::parse("0x1::test::check").unwrap(),
+                    )),
+                    gas_unit_price,
+                )
+            })
+            .collect();
+        let block_id = HashValue::random();
+
+        // Verify that it only returns transactions with gas unit prices in the range
+        let filter = TransactionFilter::new(
+            Filter::empty()
+                .add_gas_unit_price_greater_than_filter(false, 100)
+                .add_gas_unit_price_less_than_filter(false, 100),
+        );
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..2].to_vec());
+    }
+
+    #[test]
+    fn test_block_matchers_outside_block() {
+        // Create a filter that denies transactions in blocks with epoch greater than 10
+        let (_, txns) = get_block_id_and_transactions();
+        let filter = Filter::empty()
+            .add_block_epoch_greater_than_filter(false, 10)
+            .add_sender_filter(false, txns[0].sender());
+
+        // Verify that block matchers never match outside of a block
+        let allowed_txns: Vec<_> = txns
+            .iter()
+            .filter(|txn| filter.allows_transaction(txn))
+            .cloned()
+            .collect();
+        assert_eq!(allowed_txns, txns[1..].to_vec());
+
+        // Verify that rules depending on block matchers don't apply outside of a block, even
+        // when negated, unless the other matchers decide on their own
+        let filter = Filter::empty()
+            .add_not_filter(false, Matcher::BlockEpochGreaterThan(10))
+            .add_matches_all_of_filter(false, vec![
+                Matcher::Sender(txns[0].sender()),
+                Matcher::Not(Box::new(Matcher::BlockId(HashValue::random()))),
+            ])
+            .add_matches_any_of_filter(false, vec![
+                Matcher::BlockTimeStampLessThan(1000),
+                Matcher::Sender(txns[1].sender()),
+            ]);
+        let allowed_txns: Vec<_> = txns
+            .iter()
+            .filter(|txn| filter.allows_transaction(txn))
+            .cloned()
+            .collect();
+        let expected_txns: Vec<_> = txns
+            .iter()
+            .filter(|txn| txn.sender() != txns[1].sender())
+            .cloned()
+            .collect();
+        assert_eq!(allowed_txns, expected_txns);
+
+        // Verify that the negated block matcher still applies to blocks
+        let (block_id, txns) = get_block_id_and_transactions();
+        let filter = TransactionFilter::new(
+            Filter::empty().add_not_filter(false, Matcher::BlockEpochGreaterThan(10)),
+        );
+        assert!(filter.filter(block_id, 10, 0, txns.clone()).is_empty());
+        assert_eq!(filter.filter(block_id, 11, 0, txns.clone()), txns);
+    }
+
+    #[test]
+    fn test_shared_filter_update() {
+        // Create a transaction filter from a shared filter that allows all transactions
+        let (block_id, txns) = get_block_id_and_transactions();
+        let shared_filter = SharedFilter::new(Filter::empty());
+        let filter = TransactionFilter::new(shared_filter.clone());
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns);
+
+        // Update the shared filter and verify that the transaction filter uses it
+        shared_filter.store(Filter::empty().add_sender_filter(false, txns[0].sender()));
+        let filtered_txns = filter.filter(block_id, 0, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..].to_vec());
+    }
 }
@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::{AuthenticationConfig, NodeConfig};
+use aptos_config::config::{
+    transaction_filter_type::TransactionFilters, AuthenticationConfig, NodeConfig,
+};
 use aptos_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
 };
@@ -28,6 +30,7 @@ use tokio::runtime::Runtime;
 
 mod consensus;
 mod mempool;
+mod transaction_filter;
 
 #[derive(Default)]
 pub struct Context {
@@ -37,6 +40,7 @@ pub struct Context {
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
+    transaction_filters: RwLock<Option<TransactionFilters>>,
 }
 
 impl Context {
@@ -56,6 +60,10 @@ impl Context {
     fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         *self.mempool_client_sender.write() = Some(mempool_client_sender);
     }
+
+    fn set_transaction_filters(&self, transaction_filters: TransactionFilters) {
+        *self.transaction_filters.write() = Some(transaction_filters);
+    }
 }
 
 pub struct AdminService {
@@ -119,6 +127,10 @@ impl AdminService {
             .set_mempool_client_sender(mempool_client_sender)
     }
 
+    pub fn set_transaction_filters(&self, transaction_filters: TransactionFilters) {
+        self.context.set_transaction_filters(transaction_filters)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -237,6 +249,36 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/transaction-filters") => {
+                let transaction_filters = context.transaction_filters.read().clone();
+                if let Some(transaction_filters) = transaction_filters {
+                    transaction_filter::handle_get_transaction_filters_request(
+                        req,
+                        transaction_filters,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Transaction filters are not available.",
+                    ))
+                }
+            },
+            (hyper::Method::PUT, "/transaction-filters") => {
+                let transaction_filters = context.transaction_filters.read().clone();
+                if let Some(transaction_filters) = transaction_filters {
+                    transaction_filter::handle_update_transaction_filters_request(
+                        req,
+                        transaction_filters,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Transaction filters are not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,183 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::transaction_filter_type::{Filter, Matcher, TransactionFilters};
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// The transaction filters of the node, in the same format as in the node config. On updates,
+/// the filters that are not provided are left unchanged.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct TransactionFilterConfigs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    consensus: Option<Filter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mempool: Option<Filter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api: Option<Filter>,
+}
+
+pub async fn handle_get_transaction_filters_request(
+    _req: Request<Body>,
+    transaction_filters: TransactionFilters,
+) -> hyper::Result<Response<Body>> {
+    let configs = TransactionFilterConfigs {
+        consensus: Some(transaction_filters.consensus.load().as_ref().clone()),
+        mempool: Some(transaction_filters.mempool.load().as_ref().clone()),
+        api: Some(transaction_filters.api.load().as_ref().clone()),
+    };
+
+    match serde_yaml::to_string(&configs) {
+        Ok(configs) => {
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/x-yaml"))];
+            Ok(reply_with(headers, configs))
+        },
+        Err(e) => {
+            info!("Failed to serialize transaction filters: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+/// Replaces the given transaction filters at runtime. The update is not persisted, i.e., the
+/// filters from the node config are used again after a restart. Like in the node config, block
+/// based matchers are only supported by the consensus filter.
+pub async fn handle_update_transaction_filters_request(
+    req: Request<Body>,
+    transaction_filters: TransactionFilters,
+) -> hyper::Result<Response<Body>> {
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+    let configs: TransactionFilterConfigs = match serde_yaml::from_slice(&body) {
+        Ok(configs) => configs,
+        Err(e) => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse transaction filters: {e}"),
+            ))
+        },
+    };
+
+    for (name, filter) in [("mempool", &configs.mempool), ("api", &configs.api)] {
+        if filter
+            .as_ref()
+            .is_some_and(|filter| filter.contains_matcher(&Matcher::is_block_matcher))
+        {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Block based matchers are not supported by the {name} transaction filter."),
+            ));
+        }
+    }
+
+    for (name, filter, shared_filter) in [
+        (
+            "consensus",
+            configs.consensus,
+            &transaction_filters.consensus,
+        ),
+        ("mempool", configs.mempool, &transaction_filters.mempool),
+        ("api", configs.api, &transaction_filters.api),
+    ] {
+        if let Some(filter) = filter {
+            info!("Updating the {name} transaction filter: {filter:?}");
+            shared_filter.store(filter);
+        }
+    }
+
+    Ok(reply_with_status(
+        StatusCode::OK,
+        "Transaction filters updated.",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{AdminService, Context};
+    use aptos_types::account_address::AccountAddress;
+    use hyper::Method;
+    use std::sync::Arc;
+
+    async fn send_request(
+        context: &Arc<Context>,
+        method: Method,
+        body: &str,
+    ) -> (StatusCode, String) {
+        let req = Request::builder()
+            .method(method)
+            .uri("/transaction-filters")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = AdminService::serve_requests(context.clone(), req, true)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_get_and_update_transaction_filters() {
+        // The filters are not available until the node sets them
+        let context = Arc::new(Context::default());
+        let (status, _) = send_request(&context, Method::GET, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let consensus_filter = Filter::empty().add_sender_filter(false, AccountAddress::ONE);
+        let transaction_filters = TransactionFilters::default();
+        transaction_filters
+            .consensus
+            .store(consensus_filter.clone());
+        context.set_transaction_filters(transaction_filters.clone());
+
+        let (status, body) = send_request(&context, Method::GET, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let configs: TransactionFilterConfigs = serde_yaml::from_str(&body).unwrap();
+        assert_eq!(configs.consensus, Some(consensus_filter.clone()));
+        assert_eq!(configs.mempool, Some(Filter::empty()));
+        assert_eq!(configs.api, Some(Filter::empty()));
+
+        // Only the provided filters are replaced
+        let mempool_filter = Filter::empty().add_gas_unit_price_less_than_filter(false, 100);
+        let update = TransactionFilterConfigs {
+            mempool: Some(mempool_filter.clone()),
+            ..Default::default()
+        };
+        let (status, _) = send_request(
+            &context,
+            Method::PUT,
+            &serde_yaml::to_string(&update).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(*transaction_filters.mempool.load(), mempool_filter);
+        assert_eq!(*transaction_filters.consensus.load(), consensus_filter);
+        assert!(transaction_filters.api.load().is_empty());
+
+        // Invalid updates are rejected without changing anything
+        let (status, _) = send_request(&context, Method::PUT, "unknown: 1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let update = TransactionFilterConfigs {
+            consensus: Some(Filter::empty()),
+            api: Some(Filter::empty().add_not_filter(false, Matcher::BlockEpochGreaterThan(10))),
+            ..Default::default()
+        };
+        let (status, _) = send_request(
+            &context,
+            Method::PUT,
+            &serde_yaml::to_string(&update).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(*transaction_filters.consensus.load(), consensus_filter);
+        assert!(transaction_filters.api.load().is_empty());
+    }
+}
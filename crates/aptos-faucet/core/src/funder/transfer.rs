@@ -16,7 +16,7 @@ use anyhow::{Context, Result};
 use aptos_logger::info;
 use aptos_sdk::{
     crypto::{ed25519::Ed25519PrivateKey, PrivateKey},
-    rest_client::{AptosBaseUrl, Client},
+    rest_client::{AptosBaseUrl, Client, RetryPolicy},
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{
         account_address::AccountAddress,
@@ -157,7 +157,8 @@ impl TransferFunder {
     /// the entire time because it uses cookies, ensuring we're talking to the same
     /// node behind the LB every time.
     pub fn get_api_client(&self) -> Client {
-        let mut builder = Client::builder(AptosBaseUrl::Custom(self.node_url.clone()));
+        let mut builder = Client::builder(AptosBaseUrl::Custom(self.node_url.clone()))
+            .retry_policy(RetryPolicy::exponential());
 
         if let Some(api_key) = self.node_api_key.clone() {
             builder = builder.api_key(&api_key).expect("Failed to set API key");
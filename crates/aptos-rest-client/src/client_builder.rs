@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    endpoints::{EndpointSelection, Endpoints, RetryPolicy},
     get_version_path_with_base, Client, DEFAULT_VERSION_PATH_BASE, X_APTOS_SDK_HEADER_VALUE,
 };
 use anyhow::Result;
@@ -10,7 +11,7 @@ use reqwest::{
     header::{self, HeaderMap, HeaderName, HeaderValue},
     Client as ReqwestClient, ClientBuilder as ReqwestClientBuilder,
 };
-use std::{env, str::FromStr, time::Duration};
+use std::{env, str::FromStr, sync::Arc, time::Duration};
 use url::Url;
 
 pub enum AptosBaseUrl {
@@ -35,6 +36,9 @@ pub struct ClientBuilder {
     reqwest_builder: ReqwestClientBuilder,
     version_path_base: String,
     base_url: Url,
+    fallback_urls: Vec<Url>,
+    endpoint_selection: EndpointSelection,
+    retry_policy: RetryPolicy,
     timeout: Duration,
     headers: HeaderMap,
 }
@@ -50,6 +54,9 @@ impl ClientBuilder {
         let mut client_builder = Self {
             reqwest_builder: ReqwestClient::builder(),
             base_url: aptos_base_url.to_url(),
+            fallback_urls: vec![],
+            endpoint_selection: EndpointSelection::default(),
+            retry_policy: RetryPolicy::default(),
             version_path_base: DEFAULT_VERSION_PATH_BASE.to_string(),
             timeout: Duration::from_secs(10), // Default to 10 seconds
             headers,
@@ -66,6 +73,26 @@ impl ClientBuilder {
         self
     }
 
+    /// Adds an endpoint of the same network, used when the base URL fails
+    ///
+    /// Fallback URLs have the same path as the base URL, e.g. `https://other-node.xyz/v1`.
+    /// Requests fail over to them without a retry policy, which only adds retries once every
+    /// endpoint failed.
+    pub fn fallback_url(mut self, fallback_url: Url) -> Self {
+        self.fallback_urls.push(fallback_url);
+        self
+    }
+
+    pub fn endpoint_selection(mut self, endpoint_selection: EndpointSelection) -> Self {
+        self.endpoint_selection = endpoint_selection;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
@@ -94,6 +121,8 @@ impl ClientBuilder {
 
     pub fn build(self) -> Client {
         let version_path_base = get_version_path_with_base(self.base_url.clone());
+        let mut urls = vec![self.base_url.clone()];
+        urls.extend(self.fallback_urls);
 
         Client {
             inner: self
@@ -105,6 +134,8 @@ impl ClientBuilder {
                 .unwrap(),
             base_url: self.base_url,
             version_path_base,
+            endpoints: Arc::new(Endpoints::new(urls, self.endpoint_selection)),
+            retry_policy: self.retry_policy,
         }
     }
 }
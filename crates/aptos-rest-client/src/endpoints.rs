@@ -0,0 +1,275 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Failover between several endpoints of the same network, and retrying of failed requests.
+//!
+//! Every request is first sent to the endpoint picked by the [`EndpointSelection`], and moves on
+//! to the next endpoint when it can't connect or gets a retriable response. Endpoints failing
+//! repeatedly are put aside for a while, and only used once no healthy endpoint is left.
+
+use aptos_infallible::Mutex;
+use aptos_retrier::ExponentWithLimitDelay;
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// Consecutive failures after which an endpoint is considered unhealthy
+const UNHEALTHY_AFTER_FAILURES: u32 = 3;
+/// How long an unhealthy endpoint is put aside
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+/// Weight of the latest request in the moving average of an endpoint's latency
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// How the client picks the endpoint to send a request to
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EndpointSelection {
+    /// Use the endpoints in the order they were given, moving on when one is unhealthy
+    #[default]
+    Failover,
+    /// Prefer the healthy endpoint with the lowest recent latency
+    LowestLatency,
+    /// Rotate over the healthy endpoints
+    RoundRobin,
+}
+
+/// When and how often failed requests are retried
+///
+/// Idempotent requests are retried on server errors and timeouts, while any request is retried
+/// on `429 Too Many Requests` and when it can't connect, as the node did not process it then.
+/// A `Retry-After` given in seconds replaces the backoff delay, up to `max_retry_after`.
+///
+/// A request failing that way is first sent once to each of the other endpoints, whatever the
+/// policy, and only retried once all of them failed.
+///
+/// The default doesn't retry, see [`RetryPolicy::exponential`] to retry.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Retries after every endpoint was tried once, 0 disables retrying
+    pub max_retries: usize,
+    /// Delay before the first retry, growing exponentially for later retries
+    pub initial_delay: Duration,
+    /// Limit of the delay between retries
+    pub max_delay: Duration,
+    /// Limit of the total time spent retrying a request
+    pub max_elapsed: Duration,
+    /// Limit of a delay requested by the server with `Retry-After`
+    pub max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy sending every request once to each endpoint at most
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::exponential()
+        }
+    }
+
+    /// A policy retrying a request 3 times, with delays growing from 200ms up to 5s
+    pub fn exponential() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            max_elapsed: Duration::from_secs(60),
+            max_retry_after: Duration::from_secs(30),
+        }
+    }
+
+    /// The delays between the attempts of a request
+    pub(crate) fn delays(&self) -> impl Iterator<Item = Duration> {
+        ExponentWithLimitDelay::new(
+            self.initial_delay.as_millis() as u64,
+            self.max_delay.as_millis() as u64,
+            self.max_elapsed.as_millis() as u64,
+        )
+        .take(self.max_retries)
+    }
+
+    /// The delay requested by a rate limited or unavailable server, if any
+    pub(crate) fn retry_after(&self, response: &reqwest::Response) -> Option<Duration> {
+        if !matches!(
+            response.status(),
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            return None;
+        }
+        // Only the delay-seconds form is supported, HTTP dates fall back to the backoff
+        let seconds: u64 = response
+            .headers()
+            .get(RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(Duration::from_secs(seconds).min(self.max_retry_after))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// What the client observed of an endpoint
+#[derive(Clone, Debug)]
+pub struct EndpointHealth {
+    pub url: Url,
+    /// Moving average of the latency of requests, if any was sent
+    pub latency: Option<Duration>,
+    pub requests: u64,
+    pub errors: u64,
+    pub consecutive_failures: u32,
+    /// Whether the endpoint is put aside after failing repeatedly
+    pub healthy: bool,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    latency: Option<Duration>,
+    requests: u64,
+    errors: u64,
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl HealthState {
+    fn is_healthy(&self, now: Instant) -> bool {
+        !matches!(self.unhealthy_until, Some(until) if until > now)
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        self.requests += 1;
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING)
+            },
+            None => latency,
+        });
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: Url,
+    /// The endpoint as a metrics label, without any path or credentials
+    label: String,
+    health: Mutex<HealthState>,
+}
+
+/// The endpoints of a client and what is known of their health
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    endpoints: Vec<Endpoint>,
+    selection: EndpointSelection,
+    next: AtomicUsize,
+}
+
+impl Endpoints {
+    pub(crate) fn new(urls: Vec<Url>, selection: EndpointSelection) -> Self {
+        Self {
+            endpoints: urls
+                .into_iter()
+                .map(|url| Endpoint {
+                    label: url.origin().ascii_serialization(),
+                    url,
+                    health: Mutex::new(HealthState::default()),
+                })
+                .collect(),
+            selection,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The endpoint requests are built against, and the other endpoints are substituted for
+    pub(crate) fn primary(&self) -> &Url {
+        &self.endpoints[0].url
+    }
+
+    pub(crate) fn label(&self, index: usize) -> &str {
+        &self.endpoints[index].label
+    }
+
+    /// The order to try the endpoints in for a request, healthy endpoints first
+    pub(crate) fn attempt_order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..self.endpoints.len())
+            .partition(|index| self.endpoints[*index].health.lock().is_healthy(now));
+
+        match self.selection {
+            EndpointSelection::Failover => {},
+            EndpointSelection::LowestLatency => {
+                // Endpoints without a measured latency are tried first, so they get one
+                healthy.sort_by_key(|index| {
+                    self.endpoints[*index]
+                        .health
+                        .lock()
+                        .latency
+                        .unwrap_or_default()
+                });
+            },
+            EndpointSelection::RoundRobin => {
+                if !healthy.is_empty() {
+                    let start = self.next.fetch_add(1, Ordering::Relaxed) % healthy.len();
+                    healthy.rotate_left(start);
+                }
+            },
+        }
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    /// Moves a URL built against the primary endpoint to another endpoint
+    pub(crate) fn rebase(&self, index: usize, url: &Url) -> Result<Url, url::ParseError> {
+        if index == 0 {
+            return Ok(url.clone());
+        }
+        let primary = self.primary().as_str().trim_end_matches('/');
+        let endpoint = self.endpoints[index].url.as_str().trim_end_matches('/');
+        match url.as_str().strip_prefix(primary) {
+            Some(path) => Url::parse(&format!("{}{}", endpoint, path)),
+            // URLs of other hosts, such as keyless services, are left alone
+            None => Ok(url.clone()),
+        }
+    }
+
+    pub(crate) fn record_success(&self, index: usize, latency: Duration) {
+        let mut health = self.endpoints[index].health.lock();
+        health.record_latency(latency);
+        health.consecutive_failures = 0;
+        health.unhealthy_until = None;
+    }
+
+    pub(crate) fn record_failure(&self, index: usize, latency: Duration) {
+        let mut health = self.endpoints[index].health.lock();
+        health.record_latency(latency);
+        health.errors += 1;
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= UNHEALTHY_AFTER_FAILURES {
+            health.unhealthy_until = Some(Instant::now() + UNHEALTHY_COOLDOWN);
+        }
+    }
+
+    pub(crate) fn health(&self) -> Vec<EndpointHealth> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let health = endpoint.health.lock();
+                EndpointHealth {
+                    url: endpoint.url.clone(),
+                    latency: health.latency,
+                    requests: health.requests,
+                    errors: health.errors,
+                    consecutive_failures: health.consecutive_failures,
+                    healthy: health.is_healthy(now),
+                }
+            })
+            .collect()
+    }
+}
@@ -12,11 +12,23 @@ pub use faucet::FaucetClient;
 pub mod response;
 pub use response::Response;
 pub mod client_builder;
+pub mod endpoints;
+mod metrics;
 pub mod state;
+#[cfg(test)]
+mod tests;
 pub mod types;
 
-pub use crate::client_builder::{AptosBaseUrl, ClientBuilder};
-use crate::{aptos::AptosVersion, error::RestError};
+use crate::{
+    aptos::AptosVersion,
+    endpoints::Endpoints,
+    error::RestError,
+    metrics::{REQUEST_ERRORS, REQUEST_LATENCY, REQUEST_RETRIES},
+};
+pub use crate::{
+    client_builder::{AptosBaseUrl, ClientBuilder},
+    endpoints::{EndpointHealth, EndpointSelection, RetryPolicy},
+};
 use anyhow::{anyhow, Result};
 pub use aptos_api_types::{
     self, IndexResponseBcs, MoveModuleBytecode, PendingTransaction, Transaction,
@@ -26,7 +38,7 @@ use aptos_api_types::{
     mime_types::{BCS, BCS_SIGNED_TRANSACTION, BCS_VIEW_FUNCTION, JSON},
     AptosError, AptosErrorCode, BcsBlock, Block, GasEstimation, HexEncodedBytes, IndexResponse,
     MoveModuleId, TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult,
-    UserTransaction, VersionedEvent, ViewFunction, ViewRequest,
+    UserTransaction, VersionedEvent, ViewBatchRequest, ViewBatchResult, ViewFunction, ViewRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -46,12 +58,12 @@ use move_core_types::{
 };
 use reqwest::{
     header::{ACCEPT, CONTENT_TYPE},
-    Client as ReqwestClient, StatusCode,
+    Client as ReqwestClient, Method, RequestBuilder, StatusCode,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 pub use state::State;
-use std::{collections::BTreeMap, future::Future, str::FromStr, time::Duration};
+use std::{collections::BTreeMap, future::Future, str::FromStr, sync::Arc, time::Duration};
 use tokio::time::Instant;
 pub use types::{deserialize_from_prefixed_hex_string, Account, Resource};
 use url::Url;
@@ -83,6 +95,9 @@ pub struct Client {
     inner: ReqwestClient,
     base_url: Url,
     version_path_base: String,
+    /// The base URL and its fallbacks, shared by clones of the client
+    endpoints: Arc<Endpoints>,
+    retry_policy: RetryPolicy,
 }
 
 // TODO: Dedupe the pepper/prover request/response types with the ones defined in the service.
@@ -153,6 +168,11 @@ impl Client {
         Ok(self)
     }
 
+    /// The health of the endpoints, as observed by this client and its clones
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.endpoints.health()
+    }
+
     pub fn build_path(&self, path: &str) -> AptosResult<Url> {
         Ok(self.base_url.join(&self.version_path_base)?.join(path)?)
     }
@@ -331,7 +351,7 @@ impl Client {
             address.to_hex(),
             asset_type
         ))?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -426,11 +446,39 @@ impl Client {
         }
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, JSON)
-            .body(request)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, JSON)
+                    .body(request),
+            )
+            .await?;
+
+        self.json(response).await
+    }
+
+    /// Executes a batch of view functions against the same ledger version.
+    ///
+    /// Each call succeeds or fails on its own; failures are reported in the matching
+    /// [`ViewBatchResult`] rather than failing the whole request.
+    pub async fn view_batch(
+        &self,
+        request: &ViewBatchRequest,
+        version: Option<u64>,
+    ) -> AptosResult<Response<Vec<ViewBatchResult>>> {
+        let request = serde_json::to_string(request)?;
+        let mut url = self.build_path("view/batch")?;
+        if let Some(version) = version {
+            url.set_query(Some(format!("ledger_version={}", version).as_str()));
+        }
+
+        let response = self
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, JSON)
+                    .body(request),
+            )
             .await?;
 
         self.json(response).await
@@ -448,12 +496,13 @@ impl Client {
         }
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_VIEW_FUNCTION)
-            .header(ACCEPT, BCS)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_VIEW_FUNCTION)
+                    .header(ACCEPT, BCS)
+                    .body(txn_payload),
+            )
             .await?;
 
         let response = self.check_and_parse_bcs_response(response).await?;
@@ -472,12 +521,13 @@ impl Client {
         }
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_VIEW_FUNCTION)
-            .header(ACCEPT, JSON)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_VIEW_FUNCTION)
+                    .header(ACCEPT, JSON)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.json(response).await
@@ -491,11 +541,12 @@ impl Client {
         let url = self.build_path("transactions/simulate")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.json(response).await
@@ -515,11 +566,12 @@ impl Client {
         ))?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.json(response).await
@@ -533,12 +585,13 @@ impl Client {
         let url = self.build_path("transactions/simulate")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .header(ACCEPT, BCS)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .header(ACCEPT, BCS)
+                    .body(txn_payload),
+            )
             .await?;
 
         let response = self.check_and_parse_bcs_response(response).await?;
@@ -558,12 +611,13 @@ impl Client {
         ))?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .header(ACCEPT, BCS)
-            .body(txn_payload)
-            .send()
+            .send_idempotent(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .header(ACCEPT, BCS)
+                    .body(txn_payload),
+            )
             .await?;
 
         let response = self.check_and_parse_bcs_response(response).await?;
@@ -578,11 +632,12 @@ impl Client {
         let url = self.build_path("transactions")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.json::<PendingTransaction>(response).await
@@ -596,11 +651,12 @@ impl Client {
         let url = self.build_path("transactions")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.check_response(response).await?;
@@ -612,12 +668,13 @@ impl Client {
         let url = self.build_path("transactions")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .header(ACCEPT, BCS)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .header(ACCEPT, BCS)
+                    .body(txn_payload),
+            )
             .await?;
 
         let response = self.check_and_parse_bcs_response(response).await?;
@@ -632,11 +689,12 @@ impl Client {
         let url = self.build_path("transactions/batch")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .body(txn_payload),
+            )
             .await?;
         self.json(response).await
     }
@@ -649,12 +707,13 @@ impl Client {
         let url = self.build_path("transactions/batch")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
-            .header(ACCEPT, BCS)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+                    .header(ACCEPT, BCS)
+                    .body(txn_payload),
+            )
             .await?;
 
         let response = self.check_and_parse_bcs_response(response).await?;
@@ -994,7 +1053,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
@@ -1031,7 +1090,7 @@ impl Client {
         hash: HashValue,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_hash/{}", hash.to_hex_literal()))?;
-        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        let response = self.send(self.inner.get(url).header(ACCEPT, BCS)).await?;
         Ok(response)
     }
 
@@ -1040,7 +1099,7 @@ impl Client {
         hash: HashValue,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_hash/{}", hash.to_hex_literal()))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send(self.inner.get(url)).await?)
     }
 
     pub async fn get_transaction_by_version(
@@ -1065,7 +1124,7 @@ impl Client {
         version: u64,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_version/{}", version))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send(self.inner.get(url)).await?)
     }
 
     pub async fn get_account_ordered_transactions(
@@ -1085,7 +1144,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
@@ -1184,9 +1243,7 @@ impl Client {
         ))?;
 
         let response = self
-            .inner
-            .get(url)
-            .send()
+            .send(self.inner.get(url))
             .await
             .map_err(anyhow::Error::from)?;
         self.json(response).await
@@ -1268,7 +1325,7 @@ impl Client {
             version
         ))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1360,7 +1417,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         self.json(response).await
     }
 
@@ -1474,7 +1531,9 @@ impl Client {
             "key": json!(key),
         });
 
-        let response = self.inner.post(url).json(&data).send().await?;
+        let response = self
+            .send_idempotent(self.inner.post(url).json(&data))
+            .await?;
         self.json(response).await
     }
 
@@ -1496,7 +1555,9 @@ impl Client {
             "key": json!(key),
         });
 
-        let response = self.inner.post(url).json(&data).send().await?;
+        let response = self
+            .send_idempotent(self.inner.post(url).json(&data))
+            .await?;
         self.json(response).await
     }
 
@@ -1577,7 +1638,7 @@ impl Client {
 
     pub async fn get_account(&self, address: AccountAddress) -> AptosResult<Response<Account>> {
         let url = self.build_path(&format!("accounts/{}", address.to_hex()))?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1615,14 +1676,14 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         let response = self.check_and_parse_bcs_response(response).await?;
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
     pub async fn estimate_gas_price(&self) -> AptosResult<Response<GasEstimation>> {
         let url = self.build_path("estimate_gas_price")?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1633,7 +1694,7 @@ impl Client {
             .append_pair("name", &name)
             .append_pair("actions", &actions)
             .finish();
-        let response = self.inner.get(url.clone()).send().await?;
+        let response = self.send(self.inner.get(url.clone())).await?;
 
         if !response.status().is_success() {
             Err(parse_error(response).await)
@@ -1645,6 +1706,108 @@ impl Client {
         }
     }
 
+    /// Sends a request, failing over between endpoints and then retrying as the retry policy allows
+    ///
+    /// Only `GET` and `HEAD` requests are retried after a server error, see [Self::send_idempotent]
+    /// for other requests that can be repeated safely.
+    async fn send(&self, request: RequestBuilder) -> AptosResult<reqwest::Response> {
+        let request = request.build()?;
+        let idempotent = matches!(*request.method(), Method::GET | Method::HEAD);
+        self.execute(request, idempotent).await
+    }
+
+    /// Sends a request that can be repeated safely, such as a view function or a simulation
+    async fn send_idempotent(&self, request: RequestBuilder) -> AptosResult<reqwest::Response> {
+        self.execute(request.build()?, true).await
+    }
+
+    async fn execute(
+        &self,
+        request: reqwest::Request,
+        idempotent: bool,
+    ) -> AptosResult<reqwest::Response> {
+        let order = self.endpoints.attempt_order();
+        let mut delays = self.retry_policy.delays();
+        let mut attempt = 0;
+        loop {
+            let index = order[attempt % order.len()];
+            let endpoint = self.endpoints.label(index);
+            let mut attempt_request = request
+                .try_clone()
+                .ok_or_else(|| anyhow!("Requests with a streamed body can't be sent"))?;
+            *attempt_request.url_mut() = self.endpoints.rebase(index, request.url())?;
+
+            let start = Instant::now();
+            let result = self.inner.execute(attempt_request).await;
+            let latency = start.elapsed();
+
+            // Requests that were rate limited or could not connect were not processed, so they
+            // can be sent again even if they are not idempotent
+            let (failure, retriable, retry_after) = match &result {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => (
+                    Some("rate_limited"),
+                    true,
+                    self.retry_policy.retry_after(response),
+                ),
+                Ok(response) if response.status().is_server_error() => (
+                    Some("server_error"),
+                    idempotent,
+                    self.retry_policy.retry_after(response),
+                ),
+                Ok(_) => (None, false, None),
+                Err(err) if err.is_connect() => (Some("connect"), true, None),
+                Err(err) if err.is_timeout() => (Some("timeout"), idempotent, None),
+                Err(_) => (Some("request"), false, None),
+            };
+            match failure {
+                None => {
+                    self.endpoints.record_success(index, latency);
+                    REQUEST_LATENCY
+                        .with_label_values(&[endpoint, "success"])
+                        .observe(latency.as_secs_f64());
+                },
+                Some(kind) => {
+                    self.endpoints.record_failure(index, latency);
+                    REQUEST_LATENCY
+                        .with_label_values(&[endpoint, "error"])
+                        .observe(latency.as_secs_f64());
+                    REQUEST_ERRORS.with_label_values(&[endpoint, kind]).inc();
+                },
+            }
+
+            // Every endpoint is tried once before the retry policy applies, so that a request
+            // fails over to the fallbacks even when it isn't retried
+            let backoff = match (retriable, attempt + 1 < order.len()) {
+                (false, _) => return Ok(result?),
+                (true, true) => Duration::ZERO,
+                (true, false) => match delays.next() {
+                    Some(backoff) => backoff,
+                    None => return Ok(result?),
+                },
+            };
+            attempt += 1;
+            let next_index = order[attempt % order.len()];
+            // Failing over to another endpoint needs no delay, a rate limit only applies to the
+            // endpoint that asked for it
+            let delay = match retry_after {
+                _ if next_index != index => Duration::ZERO,
+                Some(retry_after) => retry_after,
+                None => backoff,
+            };
+            debug!(
+                "Request to {} failed ({}), retrying on {} in {}ms",
+                endpoint,
+                failure.unwrap_or_default(),
+                self.endpoints.label(next_index),
+                delay.as_millis()
+            );
+            REQUEST_RETRIES
+                .with_label_values(&[self.endpoints.label(next_index)])
+                .inc();
+            tokio::time::sleep(delay).await;
+        }
+    }
+
     async fn check_response(
         &self,
         response: reqwest::Response,
@@ -1670,10 +1833,7 @@ impl Client {
     pub async fn health_check(&self, seconds: u64) -> AptosResult<()> {
         let url = self.build_path("-/healthy")?;
         let response = self
-            .inner
-            .get(url)
-            .query(&[("duration_secs", seconds)])
-            .send()
+            .send(self.inner.get(url).query(&[("duration_secs", seconds)]))
             .await?;
 
         if !response.status().is_success() {
@@ -1684,11 +1844,11 @@ impl Client {
     }
 
     async fn get<T: DeserializeOwned>(&self, url: Url) -> AptosResult<Response<T>> {
-        self.json(self.inner.get(url).send().await?).await
+        self.json(self.send(self.inner.get(url)).await?).await
     }
 
     async fn get_bcs(&self, url: Url) -> AptosResult<Response<bytes::Bytes>> {
-        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        let response = self.send(self.inner.get(url).header(ACCEPT, BCS)).await?;
         self.check_and_parse_bcs_response(response).await
     }
 
@@ -1726,11 +1886,7 @@ impl Client {
         data: serde_json::Value,
     ) -> AptosResult<T> {
         let response = self
-            .inner
-            .post(url)
-            .header(ACCEPT, JSON)
-            .json(&data)
-            .send()
+            .send(self.inner.post(url).header(ACCEPT, JSON).json(&data))
             .await?;
         if !response.status().is_success() {
             Err(parse_error(response).await)
@@ -1745,11 +1901,7 @@ impl Client {
         data: serde_json::Value,
     ) -> AptosResult<Response<bytes::Bytes>> {
         let response = self
-            .inner
-            .post(url)
-            .header(ACCEPT, BCS)
-            .json(&data)
-            .send()
+            .send_idempotent(self.inner.post(url).header(ACCEPT, BCS).json(&data))
             .await?;
         self.check_and_parse_bcs_response(response).await
     }
@@ -1769,7 +1921,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         self.check_and_parse_bcs_response(response).await
     }
 
@@ -1874,7 +2026,7 @@ impl Client {
                 ledger_version,
                 &cursor,
             )?;
-            let raw_response = self.inner.get(url).send().await?;
+            let raw_response = self.send(self.inner.get(url)).await?;
             let response: Response<Vec<T>> = self.json(raw_response).await?;
             cursor.clone_from(&response.state().cursor);
             if cursor.is_none() {
@@ -1960,8 +2112,13 @@ impl From<(ReqwestClient, Url)> for Client {
     fn from((inner, base_url): (ReqwestClient, Url)) -> Self {
         Client {
             inner,
+            endpoints: Arc::new(Endpoints::new(
+                vec![base_url.clone()],
+                EndpointSelection::default(),
+            )),
             base_url,
             version_path_base: DEFAULT_VERSION_PATH_BASE.to_string(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
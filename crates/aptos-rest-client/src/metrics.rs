@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounterVec,
+};
+use once_cell::sync::Lazy;
+
+/// Latency of requests by endpoint and result, including the ones that failed
+pub static REQUEST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_rest_client_request_latency_seconds",
+        "Latency of REST API requests by endpoint and result",
+        &["endpoint", "result"],
+        exponential_buckets(/*start=*/ 0.005, /*factor=*/ 2.0, /*count=*/ 14).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Failed requests by endpoint and kind of failure
+pub static REQUEST_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_rest_client_request_errors",
+        "REST API requests that failed, by endpoint and kind of failure",
+        &["endpoint", "kind"],
+    )
+    .unwrap()
+});
+
+/// Requests sent again after a failure, by the endpoint they were retried on
+pub static REQUEST_RETRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_rest_client_request_retries",
+        "REST API requests retried, by the endpoint they were retried on",
+        &["endpoint"],
+    )
+    .unwrap()
+});
@@ -0,0 +1,232 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    endpoints::{EndpointSelection, Endpoints, RetryPolicy},
+    AptosBaseUrl, Client,
+};
+use aptos_infallible::Mutex;
+use reqwest::{header::RETRY_AFTER, Method, StatusCode};
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use url::Url;
+use warp::{http::Response, hyper::Body, path::FullPath, Filter};
+
+/// The responses a fake node gives in order, the last one repeating, and the requests it got
+#[derive(Default)]
+struct FakeNode {
+    responses: Vec<(StatusCode, Option<&'static str>)>,
+    requests: Vec<(Method, String)>,
+}
+
+fn start_fake_node(
+    responses: Vec<(StatusCode, Option<&'static str>)>,
+) -> (SocketAddr, Arc<Mutex<FakeNode>>) {
+    let node = Arc::new(Mutex::new(FakeNode {
+        responses,
+        ..Default::default()
+    }));
+    let route = warp::method().and(warp::path::full()).map({
+        let node = node.clone();
+        move |method, path: FullPath| -> Response<Body> {
+            let mut node = node.lock();
+            node.requests.push((method, path.as_str().to_string()));
+            let index = std::cmp::min(node.requests.len(), node.responses.len()) - 1;
+            let (status, retry_after) = node.responses[index];
+            let mut response = Response::builder().status(status);
+            if let Some(retry_after) = retry_after {
+                response = response.header(RETRY_AFTER, retry_after);
+            }
+            response.body(Body::empty()).unwrap()
+        }
+    });
+    let (addr, server) =
+        warp::serve(route).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+    (addr, node)
+}
+
+/// An address nothing listens on, so that requests to it fail to connect
+fn closed_addr() -> SocketAddr {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+fn url(addr: SocketAddr) -> Url {
+    Url::parse(&format!("http://{}/v1", addr)).unwrap()
+}
+
+fn fast_retries() -> RetryPolicy {
+    RetryPolicy {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        ..RetryPolicy::exponential()
+    }
+}
+
+fn new_client(addr: SocketAddr, retry_policy: RetryPolicy) -> Client {
+    Client::builder(AptosBaseUrl::Custom(url(addr)))
+        .retry_policy(retry_policy)
+        .build()
+}
+
+#[tokio::test]
+async fn test_server_errors_retried_only_when_idempotent() {
+    let responses = vec![
+        (StatusCode::INTERNAL_SERVER_ERROR, None),
+        (StatusCode::OK, None),
+    ];
+
+    let (addr, node) = start_fake_node(responses.clone());
+    let client = new_client(addr, fast_retries());
+    let request = client.inner.get(client.build_path("-/healthy").unwrap());
+    assert_eq!(client.send(request).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(node.lock().requests.len(), 2);
+
+    let (addr, node) = start_fake_node(responses.clone());
+    let client = new_client(addr, fast_retries());
+    let request = client
+        .inner
+        .post(client.build_path("transactions").unwrap());
+    assert_eq!(
+        client.send(request).await.unwrap().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(node.lock().requests.len(), 1);
+
+    let (addr, node) = start_fake_node(responses.clone());
+    let client = new_client(addr, fast_retries());
+    let request = client.inner.post(client.build_path("view").unwrap());
+    assert_eq!(
+        client.send_idempotent(request).await.unwrap().status(),
+        StatusCode::OK
+    );
+    assert_eq!(node.lock().requests.len(), 2);
+
+    // Clients don't retry unless they opt in
+    let (addr, node) = start_fake_node(responses);
+    let client = Client::new(url(addr));
+    let request = client.inner.get(client.build_path("-/healthy").unwrap());
+    assert_eq!(
+        client.send(request).await.unwrap().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(node.lock().requests.len(), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limit_waits_for_retry_after() {
+    let (addr, node) = start_fake_node(vec![
+        (StatusCode::TOO_MANY_REQUESTS, Some("60")),
+        (StatusCode::OK, None),
+    ]);
+    let client = new_client(addr, RetryPolicy {
+        max_retry_after: Duration::from_secs(1),
+        ..fast_retries()
+    });
+
+    // Rate limited requests were not processed, so even a submission is retried
+    let start = Instant::now();
+    let request = client
+        .inner
+        .post(client.build_path("transactions").unwrap());
+    assert_eq!(client.send(request).await.unwrap().status(), StatusCode::OK);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_secs(1));
+    assert!(elapsed < Duration::from_secs(60));
+    assert_eq!(node.lock().requests.len(), 2);
+}
+
+#[tokio::test]
+async fn test_failover_on_connect_error() {
+    let (addr, node) = start_fake_node(vec![(StatusCode::OK, None)]);
+    let client = Client::builder(AptosBaseUrl::Custom(url(closed_addr())))
+        .fallback_url(url(addr))
+        .retry_policy(fast_retries())
+        .build();
+
+    let request = client
+        .inner
+        .post(client.build_path("transactions").unwrap());
+    assert_eq!(client.send(request).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(node.lock().requests, vec![(
+        Method::POST,
+        "/v1/transactions".to_string()
+    )]);
+
+    let health = client.endpoint_health();
+    assert_eq!(health[0].errors, 1);
+    assert_eq!(health[0].consecutive_failures, 1);
+    assert_eq!(health[1].errors, 0);
+    assert_eq!(health[1].requests, 1);
+}
+
+#[tokio::test]
+async fn test_failover_without_retries() {
+    let (primary_addr, primary) = start_fake_node(vec![(StatusCode::SERVICE_UNAVAILABLE, None)]);
+    let (fallback_addr, fallback) = start_fake_node(vec![(StatusCode::OK, None)]);
+    let client = Client::builder(AptosBaseUrl::Custom(url(primary_addr)))
+        .fallback_url(url(fallback_addr))
+        .build();
+
+    // Every endpoint is tried once, even though the default policy doesn't retry
+    let request = client.inner.get(client.build_path("-/healthy").unwrap());
+    assert_eq!(client.send(request).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(primary.lock().requests.len(), 1);
+    assert_eq!(fallback.lock().requests.len(), 1);
+}
+
+#[test]
+fn test_rebase() {
+    let endpoints = Endpoints::new(
+        vec![
+            Url::parse("http://localhost:8080/v1").unwrap(),
+            Url::parse("https://fullnode.example.com/api/v1/").unwrap(),
+        ],
+        EndpointSelection::Failover,
+    );
+    let url = Url::parse("http://localhost:8080/v1/accounts/0x1/resources?limit=10").unwrap();
+
+    assert_eq!(endpoints.rebase(0, &url).unwrap(), url);
+    assert_eq!(
+        endpoints.rebase(1, &url).unwrap().as_str(),
+        "https://fullnode.example.com/api/v1/accounts/0x1/resources?limit=10"
+    );
+
+    // Other hosts are left alone
+    let other = Url::parse("https://prover.example.com/v0/prove").unwrap();
+    assert_eq!(endpoints.rebase(1, &other).unwrap(), other);
+}
+
+#[test]
+fn test_unhealthy_after_repeated_failures() {
+    let endpoints = Endpoints::new(
+        vec![
+            Url::parse("http://localhost:8080/v1").unwrap(),
+            Url::parse("http://localhost:8081/v1").unwrap(),
+        ],
+        EndpointSelection::Failover,
+    );
+    let latency = Duration::from_millis(10);
+
+    endpoints.record_failure(0, latency);
+    endpoints.record_failure(0, latency);
+    assert!(endpoints.health()[0].healthy);
+    assert_eq!(endpoints.attempt_order(), vec![0, 1]);
+
+    // The third failure in a row puts the endpoint aside
+    endpoints.record_failure(0, latency);
+    let health = endpoints.health();
+    assert!(!health[0].healthy);
+    assert_eq!(health[0].consecutive_failures, 3);
+    assert_eq!(endpoints.attempt_order(), vec![1, 0]);
+
+    endpoints.record_success(0, latency);
+    assert!(endpoints.health()[0].healthy);
+    assert_eq!(endpoints.attempt_order(), vec![0, 1]);
+}
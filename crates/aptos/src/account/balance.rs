@@ -17,7 +17,7 @@ use serde::Serialize;
 
 /// Show the account's balance of different coins
 ///
-/// TODO: Fungible assets
+/// For fungible assets that are not paired with a coin, use `movement fa balance`
 #[derive(Debug, Parser)]
 pub struct Balance {
     /// Address of the account you want to list resources/modules/balance for
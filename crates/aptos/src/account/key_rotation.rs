@@ -304,6 +304,9 @@ impl CliCommand<RotateSummary> for RotateKey {
             account: Some(current_address),
             private_key: new_private_key,
             derivation_path: new_derivation_path,
+            encrypted_private_key: None,
+            external_signer: None,
+            account_keys: None,
             ..self.txn_options.profile_options.profile()?
         };
 
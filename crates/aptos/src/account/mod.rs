@@ -56,8 +56,11 @@ pub enum MultisigAccountTool {
     Execute(multisig_account::Execute),
     ExecuteReject(multisig_account::ExecuteReject),
     ExecuteWithPayload(multisig_account::ExecuteWithPayload),
+    ListPending(multisig_account::ListPending),
     Reject(multisig_account::Reject),
+    Show(multisig_account::Show),
     VerifyProposal(multisig_account::VerifyProposal),
+    Watch(multisig_account::Watch),
 }
 
 impl MultisigAccountTool {
@@ -69,8 +72,11 @@ impl MultisigAccountTool {
             MultisigAccountTool::Execute(tool) => tool.execute_serialized().await,
             MultisigAccountTool::ExecuteReject(tool) => tool.execute_serialized().await,
             MultisigAccountTool::ExecuteWithPayload(tool) => tool.execute_serialized().await,
+            MultisigAccountTool::ListPending(tool) => tool.execute_serialized().await,
             MultisigAccountTool::Reject(tool) => tool.execute_serialized().await,
+            MultisigAccountTool::Show(tool) => tool.execute_serialized().await,
             MultisigAccountTool::VerifyProposal(tool) => tool.execute_serialized().await,
+            MultisigAccountTool::Watch(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -1,30 +1,43 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::{
-    types::{
-        CliCommand, CliError, CliTypedResult, EntryFunctionArguments, MultisigAccount,
-        MultisigAccountWithSequenceNumber, TransactionOptions, TransactionSummary,
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliTypedResult, EntryFunctionArguments, MultisigAccount,
+            MultisigAccountWithSequenceNumber, ProfileOptions, RestOptions, TransactionOptions,
+            TransactionSummary,
+        },
+        utils::{view_function, view_json_number, view_json_option_str},
     },
-    utils::view_json_option_str,
+    move_tool::CachedPackageRegistry,
 };
-use aptos_api_types::ViewFunction;
+use aptos_api_types::{MoveFunction, MoveStructTag, MoveType, ViewFunction};
 use aptos_cached_packages::aptos_stdlib;
 use aptos_crypto::HashValue;
 use aptos_rest_client::{
     aptos_api_types::{HexEncodedBytes, WriteResource, WriteSetChange},
-    Transaction,
+    Client, Transaction,
 };
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{Multisig, MultisigTransactionPayload, TransactionPayload},
+    transaction::{EntryFunction, Multisig, MultisigTransactionPayload, TransactionPayload},
 };
 use async_trait::async_trait;
 use bcs::to_bytes;
 use clap::Parser;
-use move_core_types::{ident_str, language_storage::ModuleId};
+use move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, TypeTag},
+    u256::U256,
+};
 use serde::Serialize;
 use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use url::Url;
 
 /// Create a new multisig account (v2) on-chain.
 ///
@@ -368,3 +381,725 @@ impl CliCommand<TransactionSummary> for ExecuteReject {
             .map(|inner| inner.into())
     }
 }
+
+/// A multisig transaction proposal, with its payload decoded and its votes tallied
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MultisigProposal {
+    pub sequence_number: u64,
+    pub creator: AccountAddress,
+    pub creation_time_secs: u64,
+    /// The proposed entry function call, if the full payload is stored on-chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<DecodedEntryFunction>,
+    /// The hex encoded payload, if it is stored on-chain but couldn't be decoded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_payload: Option<String>,
+    /// The hash of the payload, if only the hash is stored on-chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_hash: Option<String>,
+    pub approvals: Vec<AccountAddress>,
+    pub rejections: Vec<AccountAddress>,
+    /// Owners that have not voted yet
+    pub pending_owners: Vec<AccountAddress>,
+    pub num_signatures_required: u64,
+    /// Number of approvals still missing before the proposal can be executed
+    pub approvals_needed: u64,
+    /// Whether the proposal is next in line and has enough approvals to be executed
+    pub can_be_executed: bool,
+}
+
+/// An entry function call stored in a multisig proposal
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DecodedEntryFunction {
+    /// Name of the on-chain package containing the called module
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    /// Arguments decoded with the function's ABI, or hex encoded if they couldn't be decoded
+    pub arguments: Vec<serde_json::Value>,
+}
+
+/// List the pending transaction proposals of a multisig account
+///
+/// Shows the decoded payload, the votes and the number of approvals still needed for every
+/// proposal that has not been executed or removed yet.
+#[derive(Debug, Parser)]
+pub struct ListPending {
+    #[clap(flatten)]
+    pub(crate) multisig_account: MultisigAccount,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<MultisigProposal>> for ListPending {
+    fn command_name(&self) -> &'static str {
+        "ListPendingMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<MultisigProposal>> {
+        let url = self.rest_options.url(&self.profile_options)?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let multisig_address = self.multisig_account.multisig_address;
+        let mut reader = ProposalReader::new(&client, url, multisig_address).await?;
+
+        let pending = reader
+            .view("get_pending_transactions", vec![multisig_address.to_vec()])
+            .await?;
+        let pending = pending.as_array().ok_or_else(|| {
+            CliError::UnexpectedError("Pending transactions are not a list".to_string())
+        })?;
+
+        // Pending proposals are returned in order, starting right after the last resolved one.
+        // Both are read at the same ledger version, so no proposal is resolved in between.
+        let first_sequence_number = view_json_number::<u64>(
+            &reader
+                .view("last_resolved_sequence_number", vec![
+                    multisig_address.to_vec()
+                ])
+                .await?,
+        )? + 1;
+        let mut proposals = vec![];
+        for (index, transaction) in pending.iter().enumerate() {
+            proposals.push(
+                reader
+                    .proposal(first_sequence_number + index as u64, transaction)
+                    .await?,
+            );
+        }
+        Ok(proposals)
+    }
+}
+
+/// Show a transaction proposal of a multisig account
+///
+/// Shows the decoded payload, who has approved or rejected it and how many approvals are still
+/// needed before it can be executed.
+#[derive(Debug, Parser)]
+pub struct Show {
+    #[clap(flatten)]
+    pub(crate) multisig_account_with_sequence_number: MultisigAccountWithSequenceNumber,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<MultisigProposal> for Show {
+    fn command_name(&self) -> &'static str {
+        "ShowMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigProposal> {
+        let url = self.rest_options.url(&self.profile_options)?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let MultisigAccountWithSequenceNumber {
+            multisig_account,
+            sequence_number,
+        } = self.multisig_account_with_sequence_number;
+        ProposalReader::new(&client, url, multisig_account.multisig_address)
+            .await?
+            .fetch(sequence_number)
+            .await
+    }
+}
+
+/// Watch the votes on a transaction proposal of a multisig account
+///
+/// Prints every change to the votes until the proposal is executed or removed, and then
+/// returns the last state of the proposal.
+#[derive(Debug, Parser)]
+pub struct Watch {
+    #[clap(flatten)]
+    pub(crate) multisig_account_with_sequence_number: MultisigAccountWithSequenceNumber,
+    /// Stop watching as soon as the proposal has enough approvals to be executed
+    #[clap(long)]
+    pub(crate) wait_executable: bool,
+    /// Seconds between checks of the proposal
+    #[clap(long, default_value_t = 5)]
+    pub(crate) poll_interval_secs: u64,
+    /// Give up after this many seconds, waits forever if not set
+    #[clap(long)]
+    pub(crate) timeout_secs: Option<u64>,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<MultisigProposal> for Watch {
+    fn command_name(&self) -> &'static str {
+        "WatchMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigProposal> {
+        let url = self.rest_options.url(&self.profile_options)?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let MultisigAccountWithSequenceNumber {
+            multisig_account,
+            sequence_number,
+        } = self.multisig_account_with_sequence_number;
+        let multisig_address = multisig_account.multisig_address;
+        let mut reader = ProposalReader::new(&client, url, multisig_address).await?;
+        let start = Instant::now();
+
+        let mut last: Option<MultisigProposal> = None;
+        loop {
+            let resolved = view_json_number::<u64>(
+                &reader
+                    .view("last_resolved_sequence_number", vec![
+                        multisig_address.to_vec()
+                    ])
+                    .await?,
+            )? >= sequence_number;
+            if resolved {
+                eprintln!("Proposal {} has been resolved", sequence_number);
+                return last.ok_or_else(|| {
+                    CliError::CommandArgumentError(format!(
+                        "Proposal {} was already resolved",
+                        sequence_number
+                    ))
+                });
+            }
+
+            let proposal = reader.fetch(sequence_number).await?;
+            if last.as_ref() != Some(&proposal) {
+                eprintln!(
+                    "Proposal {}: {} approvals, {} rejections, {} more approvals needed",
+                    sequence_number,
+                    proposal.approvals.len(),
+                    proposal.rejections.len(),
+                    proposal.approvals_needed,
+                );
+            }
+            if self.wait_executable && proposal.can_be_executed {
+                return Ok(proposal);
+            }
+            last = Some(proposal);
+
+            if let Some(timeout_secs) = self.timeout_secs {
+                if start.elapsed() >= Duration::from_secs(timeout_secs) {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Timed out after {} seconds watching proposal {}",
+                        timeout_secs, sequence_number
+                    )));
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(self.poll_interval_secs)).await;
+            reader.refresh().await?;
+        }
+    }
+}
+
+/// Calls a `0x1::multisig_account` view function returning a single value
+async fn multisig_view(
+    client: &Client,
+    function: &str,
+    args: Vec<Vec<u8>>,
+    version: u64,
+) -> CliTypedResult<serde_json::Value> {
+    view_function(
+        client,
+        AccountAddress::ONE,
+        "multisig_account",
+        function,
+        vec![],
+        args,
+        Some(version),
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| {
+        CliError::UnexpectedError(format!("View function {} returned nothing", function))
+    })
+}
+
+/// Reads proposals of one multisig account, caching the ABIs used to decode their payloads
+///
+/// All reads are made at the same ledger version, so that the owners, the votes and the
+/// pending proposals are consistent with each other.
+struct ProposalReader<'a> {
+    client: &'a Client,
+    /// Node URL, used to read the package registries of called modules
+    url: Url,
+    multisig_address: AccountAddress,
+    /// Ledger version that all reads are made at
+    version: u64,
+    owners: Vec<AccountAddress>,
+    num_signatures_required: u64,
+    /// ABIs of called functions, with the name of the package containing their module
+    functions: BTreeMap<(ModuleId, String), (Option<String>, MoveFunction)>,
+}
+
+impl<'a> ProposalReader<'a> {
+    async fn new(
+        client: &'a Client,
+        url: Url,
+        multisig_address: AccountAddress,
+    ) -> CliTypedResult<Self> {
+        let mut reader = Self {
+            client,
+            url,
+            multisig_address,
+            version: 0,
+            owners: vec![],
+            num_signatures_required: 0,
+            functions: BTreeMap::new(),
+        };
+        reader.refresh().await?;
+        Ok(reader)
+    }
+
+    /// Moves to the latest ledger version, reading the owners again as they may have changed
+    async fn refresh(&mut self) -> CliTypedResult<()> {
+        self.version = self
+            .client
+            .get_ledger_information()
+            .await?
+            .into_inner()
+            .version;
+        let args = vec![self.multisig_address.to_vec()];
+        let owners = self.view("owners", args.clone()).await?;
+        self.owners = serde_json::from_value(owners)
+            .map_err(|err| CliError::UnableToParse("multisig owners", err.to_string()))?;
+        self.num_signatures_required =
+            view_json_number(&self.view("num_signatures_required", args).await?)?;
+        Ok(())
+    }
+
+    async fn view(&self, function: &str, args: Vec<Vec<u8>>) -> CliTypedResult<serde_json::Value> {
+        multisig_view(self.client, function, args, self.version).await
+    }
+
+    async fn fetch(&mut self, sequence_number: u64) -> CliTypedResult<MultisigProposal> {
+        let transaction = self
+            .view("get_transaction", vec![
+                self.multisig_address.to_vec(),
+                bcs::to_bytes(&sequence_number)?,
+            ])
+            .await?;
+        self.proposal(sequence_number, &transaction).await
+    }
+
+    /// Builds a proposal from the JSON of a `0x1::multisig_account::MultisigTransaction`
+    async fn proposal(
+        &mut self,
+        sequence_number: u64,
+        transaction: &serde_json::Value,
+    ) -> CliTypedResult<MultisigProposal> {
+        let creator = serde_json::from_value(transaction["creator"].clone())
+            .map_err(|err| CliError::UnableToParse("proposal creator", err.to_string()))?;
+        let creation_time_secs = view_json_number(&transaction["creation_time_secs"])?;
+
+        // Votes are a `SimpleMap<address, bool>` of approvals and rejections. Votes of removed
+        // owners are kept on-chain but no longer count, so they are left out.
+        let mut approvals = vec![];
+        let mut rejections = vec![];
+        for vote in transaction["votes"]["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let owner: AccountAddress = serde_json::from_value(vote["key"].clone())
+                .map_err(|err| CliError::UnableToParse("proposal vote", err.to_string()))?;
+            if !self.owners.contains(&owner) {
+                continue;
+            }
+            match vote["value"].as_bool() {
+                Some(true) => approvals.push(owner),
+                Some(false) => rejections.push(owner),
+                None => {
+                    return Err(CliError::UnableToParse(
+                        "proposal vote",
+                        vote["value"].to_string(),
+                    ))
+                },
+            }
+        }
+        let pending_owners = self
+            .owners
+            .iter()
+            .filter(|owner| !approvals.contains(owner) && !rejections.contains(owner))
+            .copied()
+            .collect();
+
+        // A payload that can't be decoded, e.g. calling a module that is no longer on-chain,
+        // doesn't keep the rest of the proposal from being shown
+        let (payload, raw_payload) = match view_json_option_str(&transaction["payload"])? {
+            Some(payload) => {
+                let payload: HexEncodedBytes = payload.parse()?;
+                match self.decode_payload(payload.inner()).await {
+                    Ok(decoded) => (Some(decoded), None),
+                    Err(err) => {
+                        eprintln!(
+                            "Unable to decode the payload of proposal {}: {}",
+                            sequence_number, err
+                        );
+                        (None, Some(payload.to_string()))
+                    },
+                }
+            },
+            None => (None, None),
+        };
+        let payload_hash = view_json_option_str(&transaction["payload_hash"])?;
+
+        let can_be_executed = view_json_value_bool(
+            &self
+                .view("can_be_executed", vec![
+                    self.multisig_address.to_vec(),
+                    bcs::to_bytes(&sequence_number)?,
+                ])
+                .await?,
+        )?;
+
+        Ok(MultisigProposal {
+            sequence_number,
+            creator,
+            creation_time_secs,
+            payload,
+            raw_payload,
+            payload_hash,
+            approvals_needed: self
+                .num_signatures_required
+                .saturating_sub(approvals.len() as u64),
+            approvals,
+            rejections,
+            pending_owners,
+            num_signatures_required: self.num_signatures_required,
+            can_be_executed,
+        })
+    }
+
+    /// Decodes a stored payload into an entry function call, using the ABI of the function
+    /// from the on-chain package of its module
+    async fn decode_payload(&mut self, payload: &[u8]) -> CliTypedResult<DecodedEntryFunction> {
+        let MultisigTransactionPayload::EntryFunction(entry_function) =
+            bcs::from_bytes::<MultisigTransactionPayload>(payload)?;
+        let (package, function) = self.function_abi(&entry_function).await?;
+
+        // Signer parameters are not part of the payload
+        let params = function.params.iter().filter(|param| !param.is_signer());
+        let arguments = params
+            .zip(entry_function.args())
+            .map(|(param, arg)| {
+                let param = substitute_type_params(param, entry_function.ty_args());
+                decode_argument(&param, arg)
+                    .unwrap_or_else(|_| json!(HexEncodedBytes::from(arg.clone()).to_string()))
+            })
+            .collect();
+
+        Ok(DecodedEntryFunction {
+            package,
+            function: format!(
+                "{}::{}::{}",
+                entry_function.module().address().to_hex_literal(),
+                entry_function.module().name(),
+                entry_function.function()
+            ),
+            type_arguments: entry_function
+                .ty_args()
+                .iter()
+                .map(|ty_arg| ty_arg.to_canonical_string())
+                .collect(),
+            arguments,
+        })
+    }
+
+    async fn function_abi(
+        &mut self,
+        entry_function: &EntryFunction,
+    ) -> CliTypedResult<(Option<String>, MoveFunction)> {
+        let module = entry_function.module();
+        let key = (module.clone(), entry_function.function().to_string());
+        if let Some(abi) = self.functions.get(&key) {
+            return Ok(abi.clone());
+        }
+
+        let registry =
+            CachedPackageRegistry::create(self.url.clone(), *module.address(), false).await?;
+        let package = registry
+            .package_name_of_module(module.name().as_str())
+            .map(|name| name.to_string());
+        let abi = self
+            .client
+            .get_account_module(*module.address(), module.name().as_str())
+            .await?
+            .into_inner()
+            .try_parse_abi()?
+            .abi
+            .ok_or_else(|| {
+                CliError::UnexpectedError(format!("Unable to parse the ABI of module {}", module))
+            })?;
+        let function = abi
+            .exposed_functions
+            .into_iter()
+            .find(|function| function.name.as_str() == entry_function.function().as_str())
+            .ok_or_else(|| {
+                CliError::UnexpectedError(format!(
+                    "Function {}::{} not found on-chain",
+                    module,
+                    entry_function.function()
+                ))
+            })?;
+
+        self.functions
+            .insert(key, (package.clone(), function.clone()));
+        Ok((package, function))
+    }
+}
+
+fn view_json_value_bool(value: &serde_json::Value) -> CliTypedResult<bool> {
+    value
+        .as_bool()
+        .ok_or_else(|| CliError::UnexpectedError(format!("JSON value is not a bool: {}", value)))
+}
+
+/// Replaces the generic type parameters of a parameter type with the call's type arguments
+fn substitute_type_params(typ: &MoveType, ty_args: &[TypeTag]) -> MoveType {
+    match typ {
+        MoveType::GenericTypeParam { index } => ty_args
+            .get(*index as usize)
+            .map(MoveType::from)
+            .unwrap_or_else(|| typ.clone()),
+        MoveType::Vector { items } => MoveType::Vector {
+            items: Box::new(substitute_type_params(items, ty_args)),
+        },
+        MoveType::Struct(tag) => {
+            let mut tag = tag.clone();
+            tag.generic_type_params = tag
+                .generic_type_params
+                .iter()
+                .map(|param| substitute_type_params(param, ty_args))
+                .collect();
+            MoveType::Struct(tag)
+        },
+        _ => typ.clone(),
+    }
+}
+
+/// Decodes a BCS encoded entry function argument into the JSON the node API would show for it
+fn decode_argument(typ: &MoveType, bytes: &[u8]) -> anyhow::Result<serde_json::Value> {
+    let mut reader = ArgumentReader { bytes };
+    let value = reader.read(typ)?;
+    anyhow::ensure!(reader.bytes.is_empty(), "Trailing bytes in argument");
+    Ok(value)
+}
+
+fn is_std_struct(tag: &MoveStructTag, module: &str, name: &str) -> bool {
+    *tag.address.inner() == AccountAddress::ONE
+        && tag.module.as_str() == module
+        && tag.name.as_str() == name
+}
+
+struct ArgumentReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl ArgumentReader<'_> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&[u8]> {
+        anyhow::ensure!(self.bytes.len() >= len, "Argument is too short");
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_uleb128(&mut self) -> anyhow::Result<usize> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value as usize);
+            }
+        }
+        anyhow::bail!("Invalid ULEB128 length")
+    }
+
+    fn read(&mut self, typ: &MoveType) -> anyhow::Result<serde_json::Value> {
+        Ok(match typ {
+            MoveType::Bool => json!(self.take(1)?[0] != 0),
+            MoveType::U8 => json!(self.take(1)?[0]),
+            MoveType::U16 => json!(u16::from_le_bytes(self.take(2)?.try_into()?)),
+            MoveType::U32 => json!(u32::from_le_bytes(self.take(4)?.try_into()?)),
+            MoveType::U64 => json!(u64::from_le_bytes(self.take(8)?.try_into()?).to_string()),
+            MoveType::U128 => json!(u128::from_le_bytes(self.take(16)?.try_into()?).to_string()),
+            MoveType::U256 => json!(U256::from_le_bytes(&self.take(32)?.try_into()?).to_string()),
+            MoveType::Address => {
+                json!(AccountAddress::from_bytes(self.take(32)?)?.to_hex_literal())
+            },
+            MoveType::Vector { items } if **items == MoveType::U8 => {
+                let len = self.read_uleb128()?;
+                json!(HexEncodedBytes::from(self.take(len)?.to_vec()).to_string())
+            },
+            MoveType::Vector { items } => {
+                let len = self.read_uleb128()?;
+                let values = (0..len)
+                    .map(|_| self.read(items))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                json!(values)
+            },
+            MoveType::Struct(tag) if is_std_struct(tag, "string", "String") => {
+                let len = self.read_uleb128()?;
+                json!(std::str::from_utf8(self.take(len)?)?)
+            },
+            MoveType::Struct(tag) if is_std_struct(tag, "object", "Object") => {
+                json!({ "inner": AccountAddress::from_bytes(self.take(32)?)?.to_hex_literal() })
+            },
+            MoveType::Struct(tag) if is_std_struct(tag, "option", "Option") => {
+                let inner = tag
+                    .generic_type_params
+                    .first()
+                    .ok_or_else(|| anyhow::anyhow!("Option without a type parameter"))?;
+                let len = self.read_uleb128()?;
+                let values = (0..len)
+                    .map(|_| self.read(inner))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                json!({ "vec": values })
+            },
+            _ => anyhow::bail!("Unsupported argument type {}", typ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn struct_type(tag: &str, generic_type_params: Vec<MoveType>) -> MoveType {
+        let mut tag = MoveStructTag::from_str(tag).unwrap();
+        tag.generic_type_params = generic_type_params;
+        MoveType::Struct(tag)
+    }
+
+    fn vector(items: MoveType) -> MoveType {
+        MoveType::Vector {
+            items: Box::new(items),
+        }
+    }
+
+    #[test]
+    fn test_decode_vector_arguments() {
+        let bytes = bcs::to_bytes(&vec![1u64, 2, 3]).unwrap();
+        assert_eq!(
+            decode_argument(&vector(MoveType::U64), &bytes).unwrap(),
+            json!(["1", "2", "3"])
+        );
+
+        // Bytes are shown as hex, like the node API does
+        let bytes = bcs::to_bytes(&vec![vec![1u8, 2], vec![]]).unwrap();
+        assert_eq!(
+            decode_argument(&vector(vector(MoveType::U8)), &bytes).unwrap(),
+            json!(["0x0102", "0x"])
+        );
+
+        let addresses = vec![
+            AccountAddress::ONE,
+            AccountAddress::from_hex_literal("0xcafe").unwrap(),
+        ];
+        let bytes = bcs::to_bytes(&addresses).unwrap();
+        assert_eq!(
+            decode_argument(&vector(MoveType::Address), &bytes).unwrap(),
+            json!(["0x1", "0xcafe"])
+        );
+    }
+
+    #[test]
+    fn test_decode_struct_arguments() {
+        let string = struct_type("0x1::string::String", vec![]);
+        let bytes = bcs::to_bytes("multisig").unwrap();
+        assert_eq!(decode_argument(&string, &bytes).unwrap(), json!("multisig"));
+        assert!(decode_argument(&string, &bcs::to_bytes(&vec![0xFFu8]).unwrap()).is_err());
+
+        let option = struct_type("0x1::option::Option", vec![MoveType::U128]);
+        let bytes = bcs::to_bytes(&Some(5u128)).unwrap();
+        assert_eq!(
+            decode_argument(&option, &bytes).unwrap(),
+            json!({ "vec": ["5"] })
+        );
+        let bytes = bcs::to_bytes(&None::<u128>).unwrap();
+        assert_eq!(
+            decode_argument(&option, &bytes).unwrap(),
+            json!({ "vec": [] })
+        );
+
+        let option = struct_type("0x1::option::Option", vec![string]);
+        let bytes = bcs::to_bytes(&Some("name")).unwrap();
+        assert_eq!(
+            decode_argument(&option, &bytes).unwrap(),
+            json!({ "vec": ["name"] })
+        );
+
+        let object = struct_type("0x1::object::Object", vec![struct_type(
+            "0x1::fungible_asset::Metadata",
+            vec![],
+        )]);
+        let bytes = bcs::to_bytes(&AccountAddress::from_hex_literal("0xa").unwrap()).unwrap();
+        assert_eq!(
+            decode_argument(&object, &bytes).unwrap(),
+            json!({ "inner": "0xa" })
+        );
+
+        // Other structs can't be passed to entry functions, so they aren't decoded
+        let other = struct_type("0x1::fungible_asset::Metadata", vec![]);
+        assert!(decode_argument(&other, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_arguments() {
+        let bytes = bcs::to_bytes(&1u64).unwrap();
+        assert!(decode_argument(&MoveType::U128, &bytes).is_err());
+        assert!(decode_argument(&MoveType::U32, &bytes).is_err());
+
+        // The length of a vector is longer than its content
+        let mut bytes = bcs::to_bytes(&vec![1u64, 2]).unwrap();
+        bytes.truncate(bytes.len() - 1);
+        assert!(decode_argument(&vector(MoveType::U64), &bytes).is_err());
+    }
+
+    #[test]
+    fn test_substitute_type_params() {
+        let ty_args = vec![
+            TypeTag::U64,
+            TypeTag::from_str("0x1::fungible_asset::Metadata").unwrap(),
+        ];
+        let t0 = MoveType::GenericTypeParam { index: 0 };
+        let t1 = MoveType::GenericTypeParam { index: 1 };
+
+        assert_eq!(
+            substitute_type_params(&vector(t0.clone()), &ty_args),
+            vector(MoveType::U64)
+        );
+        assert_eq!(
+            substitute_type_params(
+                &struct_type("0x1::object::Object", vec![t1.clone()]),
+                &ty_args
+            ),
+            struct_type("0x1::object::Object", vec![struct_type(
+                "0x1::fungible_asset::Metadata",
+                vec![]
+            )])
+        );
+        assert_eq!(
+            substitute_type_params(
+                &struct_type("0x1::option::Option", vec![vector(t0)]),
+                &ty_args
+            ),
+            struct_type("0x1::option::Option", vec![vector(MoveType::U64)])
+        );
+
+        // Parameters without a type argument are left alone
+        let t2 = MoveType::GenericTypeParam { index: 2 };
+        assert_eq!(substitute_type_params(&t2, &ty_args), t2);
+        assert_eq!(
+            substitute_type_params(&MoveType::Bool, &ty_args),
+            MoveType::Bool
+        );
+    }
+}
@@ -0,0 +1,628 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keys of single-key and multi-key accounts
+//!
+//! Unlike legacy Ed25519 accounts, these accounts are authenticated by `AnyPublicKey`s, so a
+//! profile can sign with a Secp256k1 ECDSA key, or with a threshold of several keys of mixed
+//! types. Keys are stored with their AIP-80 prefix, which tells their type apart, either in
+//! plaintext or encrypted with the passphrase of the profile like legacy Ed25519 keys.
+//!
+//! Secp256r1 ECDSA keys can be part of a multi-key account, but the CLI can't sign with them, as
+//! accounts only accept Secp256r1 signatures wrapped in WebAuthn assertions from a passkey.
+
+use crate::common::{
+    keystore::{read_passphrase_of, EncryptedPrivateKey},
+    types::{CliError, CliTypedResult, KeyType},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    hash::CryptoHash,
+    secp256k1_ecdsa, secp256r1_ecdsa, PrivateKey, SigningKey, ValidCryptoMaterial,
+    ValidCryptoMaterialStringExt,
+};
+use aptos_keygen::KeyGen;
+use aptos_types::transaction::{
+    authenticator::{
+        AccountAuthenticator, AnyPublicKey, AnySignature, AuthenticationKey, MultiKey,
+        MultiKeyAuthenticator, SingleKeyAuthenticator,
+    },
+    RawTransaction, SignedTransaction,
+};
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// A private key that can sign for single-key and multi-key accounts
+#[derive(Debug)]
+pub enum AnyPrivateKey {
+    Ed25519(Ed25519PrivateKey),
+    Secp256k1Ecdsa(secp256k1_ecdsa::PrivateKey),
+}
+
+impl AnyPrivateKey {
+    /// Parses a private key with an AIP-80 prefix, or a plain hex key of the given type
+    pub fn from_encoded_string(key_type: Option<KeyType>, encoded: &str) -> CliTypedResult<Self> {
+        let encoded = encoded.trim();
+        let key_type = if encoded.starts_with(Ed25519PrivateKey::AIP_80_PREFIX) {
+            KeyType::Ed25519
+        } else if encoded.starts_with(secp256k1_ecdsa::PrivateKey::AIP_80_PREFIX) {
+            KeyType::Secp256k1Ecdsa
+        } else if encoded.starts_with(secp256r1_ecdsa::PrivateKey::AIP_80_PREFIX) {
+            KeyType::Secp256r1Ecdsa
+        } else {
+            key_type.ok_or_else(|| {
+                CliError::UnableToParse(
+                    "private key",
+                    "the key has no AIP-80 prefix, add --key-type".to_string(),
+                )
+            })?
+        };
+
+        let parse_error = |err: aptos_crypto::CryptoMaterialError| {
+            CliError::UnableToParse("private key", err.to_string())
+        };
+        match key_type {
+            KeyType::Ed25519 => Ed25519PrivateKey::from_encoded_string(encoded)
+                .map(AnyPrivateKey::Ed25519)
+                .map_err(parse_error),
+            KeyType::Secp256k1Ecdsa => secp256k1_ecdsa::PrivateKey::from_encoded_string(encoded)
+                .map(AnyPrivateKey::Secp256k1Ecdsa)
+                .map_err(parse_error),
+            key_type => Err(CliError::CommandArgumentError(format!(
+                "Transactions can't be signed with {} keys, use one of [ed25519, secp256k1-ecdsa]",
+                key_type
+            ))),
+        }
+    }
+
+    /// Generates a new private key of the given type
+    pub fn generate(key_type: KeyType, keygen: &mut KeyGen) -> CliTypedResult<Self> {
+        match key_type {
+            KeyType::Ed25519 => Ok(AnyPrivateKey::Ed25519(
+                keygen.generate_ed25519_private_key(),
+            )),
+            KeyType::Secp256k1Ecdsa => Ok(AnyPrivateKey::Secp256k1Ecdsa(
+                keygen.generate_secp256k1_ecdsa_private_key(),
+            )),
+            key_type => Err(CliError::CommandArgumentError(format!(
+                "Transactions can't be signed with {} keys, use one of [ed25519, secp256k1-ecdsa]",
+                key_type
+            ))),
+        }
+    }
+
+    /// Encodes the private key with its AIP-80 prefix
+    pub fn to_aip_80_string(&self) -> CliTypedResult<String> {
+        match self {
+            AnyPrivateKey::Ed25519(key) => key.to_aip_80_string(),
+            AnyPrivateKey::Secp256k1Ecdsa(key) => key.to_aip_80_string(),
+        }
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+
+    pub fn public_key(&self) -> AnyPublicKey {
+        match self {
+            AnyPrivateKey::Ed25519(key) => AnyPublicKey::ed25519(key.public_key()),
+            AnyPrivateKey::Secp256k1Ecdsa(key) => AnyPublicKey::secp256k1_ecdsa(key.public_key()),
+        }
+    }
+
+    /// Signs a raw transaction, or the data of a multi-agent or fee payer transaction
+    pub fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> CliTypedResult<AnySignature> {
+        let sign_error =
+            |err: aptos_crypto::CryptoMaterialError| CliError::UnexpectedError(err.to_string());
+        match self {
+            AnyPrivateKey::Ed25519(key) => SigningKey::sign(key, message)
+                .map(AnySignature::ed25519)
+                .map_err(sign_error),
+            AnyPrivateKey::Secp256k1Ecdsa(key) => SigningKey::sign(key, message)
+                .map(AnySignature::secp256k1_ecdsa)
+                .map_err(sign_error),
+        }
+    }
+}
+
+impl Serialize for AnyPrivateKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(
+            &self
+                .to_aip_80_string()
+                .map_err(|err| S::Error::custom(err.to_string()))?,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyPrivateKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        AnyPrivateKey::from_encoded_string(None, &encoded)
+            .map_err(|err| D::Error::custom(err.to_string()))
+    }
+}
+
+/// Parses a public key of a single-key or multi-key account with its AIP-80 prefix
+pub fn parse_any_public_key(encoded: &str) -> CliTypedResult<AnyPublicKey> {
+    let encoded = encoded.trim();
+    let parse_error = |err: aptos_crypto::CryptoMaterialError| {
+        CliError::UnableToParse("public key", err.to_string())
+    };
+    if encoded.starts_with(Ed25519PublicKey::AIP_80_PREFIX) {
+        Ed25519PublicKey::from_encoded_string(encoded)
+            .map(AnyPublicKey::ed25519)
+            .map_err(parse_error)
+    } else if encoded.starts_with(secp256k1_ecdsa::PublicKey::AIP_80_PREFIX) {
+        secp256k1_ecdsa::PublicKey::from_encoded_string(encoded)
+            .map(AnyPublicKey::secp256k1_ecdsa)
+            .map_err(parse_error)
+    } else if encoded.starts_with(secp256r1_ecdsa::PublicKey::AIP_80_PREFIX) {
+        secp256r1_ecdsa::PublicKey::from_encoded_string(encoded)
+            .map(AnyPublicKey::secp256r1_ecdsa)
+            .map_err(parse_error)
+    } else {
+        Err(CliError::UnableToParse(
+            "public key",
+            format!(
+                "{} has no AIP-80 prefix, expected one of [{}, {}, {}]",
+                encoded,
+                Ed25519PublicKey::AIP_80_PREFIX,
+                secp256k1_ecdsa::PublicKey::AIP_80_PREFIX,
+                secp256r1_ecdsa::PublicKey::AIP_80_PREFIX
+            ),
+        ))
+    }
+}
+
+/// Encodes a public key of a single-key or multi-key account with its AIP-80 prefix
+pub fn encode_any_public_key(public_key: &AnyPublicKey) -> CliTypedResult<String> {
+    match public_key {
+        AnyPublicKey::Ed25519 { public_key } => public_key.to_aip_80_string(),
+        AnyPublicKey::Secp256k1Ecdsa { public_key } => public_key.to_aip_80_string(),
+        AnyPublicKey::Secp256r1Ecdsa { public_key } => public_key.to_aip_80_string(),
+        _ => {
+            return Err(CliError::UnexpectedError(
+                "Keyless public keys can't be stored in a profile".to_string(),
+            ))
+        },
+    }
+    .map_err(|err| CliError::UnexpectedError(err.to_string()))
+}
+
+fn serialize_public_key<S: Serializer>(
+    public_key: &AnyPublicKey,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    encode_any_public_key(public_key)
+        .map_err(|err| S::Error::custom(err.to_string()))?
+        .serialize(serializer)
+}
+
+fn deserialize_public_key<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AnyPublicKey, D::Error> {
+    parse_any_public_key(&String::deserialize(deserializer)?)
+        .map_err(|err| D::Error::custom(err.to_string()))
+}
+
+fn serialize_public_keys<S: Serializer>(
+    public_keys: &[AnyPublicKey],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    public_keys
+        .iter()
+        .map(encode_any_public_key)
+        .collect::<CliTypedResult<Vec<_>>>()
+        .map_err(|err| S::Error::custom(err.to_string()))?
+        .serialize(serializer)
+}
+
+fn deserialize_public_keys<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<AnyPublicKey>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|encoded| parse_any_public_key(encoded))
+        .collect::<CliTypedResult<_>>()
+        .map_err(|err| D::Error::custom(err.to_string()))
+}
+
+/// A private key of [AccountKeys], in plaintext or encrypted with the passphrase of the profile
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StoredPrivateKey {
+    Plaintext(AnyPrivateKey),
+    Encrypted {
+        /// Public key of the private key, so the account is known without the passphrase
+        #[serde(
+            serialize_with = "serialize_public_key",
+            deserialize_with = "deserialize_public_key"
+        )]
+        public_key: AnyPublicKey,
+        /// The private key with its AIP-80 prefix, encrypted
+        #[serde(flatten)]
+        private_key: EncryptedPrivateKey,
+    },
+}
+
+impl StoredPrivateKey {
+    pub fn public_key(&self) -> AnyPublicKey {
+        match self {
+            StoredPrivateKey::Plaintext(private_key) => private_key.public_key(),
+            StoredPrivateKey::Encrypted { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, StoredPrivateKey::Encrypted { .. })
+    }
+
+    fn encrypt(self, passphrase: &str) -> CliTypedResult<Self> {
+        match self {
+            StoredPrivateKey::Plaintext(private_key) => Ok(StoredPrivateKey::Encrypted {
+                public_key: private_key.public_key(),
+                private_key: EncryptedPrivateKey::seal(
+                    private_key.to_aip_80_string()?.as_bytes(),
+                    passphrase,
+                )?,
+            }),
+            encrypted => Ok(encrypted),
+        }
+    }
+
+    fn decrypt(self, passphrase: &str) -> CliTypedResult<Self> {
+        match self {
+            StoredPrivateKey::Encrypted {
+                public_key,
+                private_key,
+            } => {
+                let encoded = String::from_utf8(private_key.open(passphrase)?).map_err(|err| {
+                    CliError::UnableToParse("keystore private key", err.to_string())
+                })?;
+                let private_key = AnyPrivateKey::from_encoded_string(None, &encoded)?;
+                if private_key.public_key() != public_key {
+                    return Err(CliError::UnableToParse(
+                        "keystore private key",
+                        "the private key does not match its public key".to_string(),
+                    ));
+                }
+                Ok(StoredPrivateKey::Plaintext(private_key))
+            },
+            plaintext => Ok(plaintext),
+        }
+    }
+
+    fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> CliTypedResult<AnySignature> {
+        match self {
+            StoredPrivateKey::Plaintext(private_key) => private_key.sign(message),
+            StoredPrivateKey::Encrypted { .. } => Err(CliError::UnexpectedError(
+                "The private key has to be decrypted before signing".to_string(),
+            )),
+        }
+    }
+}
+
+/// Keys of a single-key or multi-key account, as stored in a profile
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccountKeys {
+    /// An account authenticated by a single key of any supported type
+    SingleKey { private_key: StoredPrivateKey },
+    /// An account authenticated by a threshold of its keys
+    MultiKey {
+        /// All public keys of the account, in the order of the account's authentication key
+        #[serde(
+            serialize_with = "serialize_public_keys",
+            deserialize_with = "deserialize_public_keys"
+        )]
+        public_keys: Vec<AnyPublicKey>,
+        signatures_required: u8,
+        /// Private keys held by the profile, by index of their public key
+        private_keys: BTreeMap<u8, StoredPrivateKey>,
+    },
+}
+
+impl AccountKeys {
+    /// Builds the keys of a multi-key account, checking that the private keys belong to the
+    /// account and are enough to sign
+    pub fn multi_key(
+        public_keys: Vec<AnyPublicKey>,
+        signatures_required: u8,
+        private_keys: Vec<AnyPrivateKey>,
+    ) -> CliTypedResult<Self> {
+        MultiKey::new(public_keys.clone(), signatures_required)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+
+        let mut indexed_private_keys = BTreeMap::new();
+        for private_key in private_keys {
+            let public_key = private_key.public_key();
+            let index = public_keys
+                .iter()
+                .position(|key| *key == public_key)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(format!(
+                        "Private key of {} is not one of the public keys of the account",
+                        encode_any_public_key(&public_key).unwrap_or_default()
+                    ))
+                })?;
+            indexed_private_keys.insert(index as u8, StoredPrivateKey::Plaintext(private_key));
+        }
+        if indexed_private_keys.len() < signatures_required as usize {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} signatures are required, but only {} private keys were given",
+                signatures_required,
+                indexed_private_keys.len()
+            )));
+        }
+
+        Ok(AccountKeys::MultiKey {
+            public_keys,
+            signatures_required,
+            private_keys: indexed_private_keys,
+        })
+    }
+
+    /// Builds the keys of a single-key account
+    pub fn single_key(private_key: AnyPrivateKey) -> Self {
+        AccountKeys::SingleKey {
+            private_key: StoredPrivateKey::Plaintext(private_key),
+        }
+    }
+
+    /// Whether any private key is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        match self {
+            AccountKeys::SingleKey { private_key } => private_key.is_encrypted(),
+            AccountKeys::MultiKey { private_keys, .. } => {
+                private_keys.values().any(StoredPrivateKey::is_encrypted)
+            },
+        }
+    }
+
+    /// Encrypts all private keys with the passphrase
+    pub fn encrypt(self, passphrase: &str) -> CliTypedResult<Self> {
+        self.map_private_keys(|private_key| private_key.encrypt(passphrase))
+    }
+
+    /// Decrypts all private keys with the passphrase
+    pub fn decrypt(self, passphrase: &str) -> CliTypedResult<Self> {
+        self.map_private_keys(|private_key| private_key.decrypt(passphrase))
+    }
+
+    /// Decrypts the private keys of a profile with the passphrase from
+    /// [KEYSTORE_PASSPHRASE_ENV](crate::common::keystore::KEYSTORE_PASSPHRASE_ENV), or prompts for
+    /// it once for all keys
+    pub fn unlock(self, profile: &str) -> CliTypedResult<Self> {
+        if !self.is_encrypted() {
+            return Ok(self);
+        }
+        self.decrypt(&read_passphrase_of(profile)?)
+    }
+
+    fn map_private_keys(
+        self,
+        f: impl Fn(StoredPrivateKey) -> CliTypedResult<StoredPrivateKey>,
+    ) -> CliTypedResult<Self> {
+        Ok(match self {
+            AccountKeys::SingleKey { private_key } => AccountKeys::SingleKey {
+                private_key: f(private_key)?,
+            },
+            AccountKeys::MultiKey {
+                public_keys,
+                signatures_required,
+                private_keys,
+            } => AccountKeys::MultiKey {
+                public_keys,
+                signatures_required,
+                private_keys: private_keys
+                    .into_iter()
+                    .map(|(index, private_key)| Ok((index, f(private_key)?)))
+                    .collect::<CliTypedResult<_>>()?,
+            },
+        })
+    }
+
+    /// The public keys of the account
+    pub fn public_keys(&self) -> Vec<AnyPublicKey> {
+        match self {
+            AccountKeys::SingleKey { private_key } => vec![private_key.public_key()],
+            AccountKeys::MultiKey { public_keys, .. } => public_keys.clone(),
+        }
+    }
+
+    /// The authentication key, from which the address of a new account is derived
+    pub fn authentication_key(&self) -> CliTypedResult<AuthenticationKey> {
+        match self {
+            AccountKeys::SingleKey { private_key } => {
+                Ok(AuthenticationKey::any_key(private_key.public_key()))
+            },
+            AccountKeys::MultiKey {
+                public_keys,
+                signatures_required,
+                ..
+            } => Ok(AuthenticationKey::multi_key(multi_key(
+                public_keys,
+                *signatures_required,
+            )?)),
+        }
+    }
+
+    /// Signs a raw transaction with a single-key or multi-key authenticator, the keys have to be
+    /// unlocked first
+    pub fn sign_transaction(&self, raw_txn: RawTransaction) -> CliTypedResult<SignedTransaction> {
+        let authenticator = self.authenticator(&raw_txn)?;
+        Ok(SignedTransaction::new_single_sender(raw_txn, authenticator))
+    }
+
+    /// Signs a raw transaction, or the data of a multi-agent or fee payer transaction, into the
+    /// authenticator of this account, the keys have to be unlocked first
+    pub fn authenticator<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> CliTypedResult<AccountAuthenticator> {
+        Ok(match self {
+            AccountKeys::SingleKey { private_key } => AccountAuthenticator::single_key(
+                SingleKeyAuthenticator::new(private_key.public_key(), private_key.sign(message)?),
+            ),
+            AccountKeys::MultiKey {
+                public_keys,
+                signatures_required,
+                private_keys,
+            } => {
+                // Signatures have to be in the order of their public keys
+                let signatures = private_keys
+                    .iter()
+                    .take(*signatures_required as usize)
+                    .map(|(index, private_key)| Ok((*index, private_key.sign(message)?)))
+                    .collect::<CliTypedResult<Vec<_>>>()?;
+                let authenticator = MultiKeyAuthenticator::new(
+                    multi_key(public_keys, *signatures_required)?,
+                    signatures,
+                )
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+                AccountAuthenticator::multi_key(authenticator)
+            },
+        })
+    }
+}
+
+fn multi_key(public_keys: &[AnyPublicKey], signatures_required: u8) -> CliTypedResult<MultiKey> {
+    MultiKey::new(public_keys.to_vec(), signatures_required)
+        .map_err(|err| CliError::UnableToParse("multi-key account", err.to_string()))
+}
+
+/// The public parts of [AccountKeys], shown in profile summaries
+#[derive(Debug, Serialize)]
+pub struct AccountKeysSummary {
+    pub public_keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures_required: Option<u8>,
+    pub private_keys_encrypted: bool,
+}
+
+impl From<&AccountKeys> for AccountKeysSummary {
+    fn from(keys: &AccountKeys) -> Self {
+        AccountKeysSummary {
+            public_keys: keys
+                .public_keys()
+                .iter()
+                .filter_map(|key| encode_any_public_key(key).ok())
+                .collect(),
+            signatures_required: match keys {
+                AccountKeys::SingleKey { .. } => None,
+                AccountKeys::MultiKey {
+                    signatures_required,
+                    ..
+                } => Some(*signatures_required),
+            },
+            private_keys_encrypted: keys.is_encrypted(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::Uniform;
+    use aptos_types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{Script, TransactionPayload},
+    };
+
+    fn raw_transaction(sender: AccountAddress) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    #[test]
+    fn test_multi_key_profile_round_trip_and_signing() {
+        let mut rng = rand::thread_rng();
+        let ed25519_key = Ed25519PrivateKey::generate(&mut rng);
+        let secp256k1_key = secp256k1_ecdsa::PrivateKey::generate(&mut rng);
+        let secp256r1_key = secp256r1_ecdsa::PrivateKey::generate(&mut rng);
+        let public_keys = vec![
+            AnyPublicKey::ed25519(ed25519_key.public_key()),
+            AnyPublicKey::secp256r1_ecdsa(secp256r1_key.public_key()),
+            AnyPublicKey::secp256k1_ecdsa(secp256k1_key.public_key()),
+        ];
+        let keys = AccountKeys::multi_key(public_keys, 2, vec![
+            AnyPrivateKey::Secp256k1Ecdsa(secp256k1_key),
+            AnyPrivateKey::Ed25519(ed25519_key),
+        ])
+        .unwrap();
+
+        // The keys survive being saved to and loaded from the config
+        let yaml = serde_yaml::to_string(&keys).unwrap();
+        let keys: AccountKeys = serde_yaml::from_str(&yaml).unwrap();
+
+        let sender = keys.authentication_key().unwrap().account_address();
+        let signed_txn = keys.sign_transaction(raw_transaction(sender)).unwrap();
+        assert!(signed_txn.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn test_single_key_secp256k1_signing() {
+        let private_key = AnyPrivateKey::from_encoded_string(
+            None,
+            &secp256k1_ecdsa::PrivateKey::generate(&mut rand::thread_rng())
+                .to_aip_80_string()
+                .unwrap(),
+        )
+        .unwrap();
+        let keys = AccountKeys::single_key(private_key);
+
+        let sender = keys.authentication_key().unwrap().account_address();
+        let signed_txn = keys.sign_transaction(raw_transaction(sender)).unwrap();
+        assert!(signed_txn.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn test_encrypted_account_keys() {
+        let mut rng = rand::thread_rng();
+        let ed25519_key = Ed25519PrivateKey::generate(&mut rng);
+        let secp256k1_key = secp256k1_ecdsa::PrivateKey::generate(&mut rng);
+        let keys = AccountKeys::multi_key(
+            vec![
+                AnyPublicKey::secp256k1_ecdsa(secp256k1_key.public_key()),
+                AnyPublicKey::ed25519(ed25519_key.public_key()),
+            ],
+            2,
+            vec![
+                AnyPrivateKey::Secp256k1Ecdsa(secp256k1_key),
+                AnyPrivateKey::Ed25519(ed25519_key),
+            ],
+        )
+        .unwrap();
+        let sender = keys.authentication_key().unwrap().account_address();
+        let keys = keys.encrypt("passphrase").unwrap();
+        assert!(keys.is_encrypted());
+
+        // No private key is saved in plaintext, but the account is known without the passphrase
+        let yaml = serde_yaml::to_string(&keys).unwrap();
+        assert!(!yaml.contains(Ed25519PrivateKey::AIP_80_PREFIX));
+        assert!(!yaml.contains(secp256k1_ecdsa::PrivateKey::AIP_80_PREFIX));
+        let keys: AccountKeys = serde_yaml::from_str(&yaml).unwrap();
+        assert!(keys.is_encrypted());
+        assert_eq!(keys.authentication_key().unwrap().account_address(), sender);
+        assert!(keys.sign_transaction(raw_transaction(sender)).is_err());
+
+        let keys = keys.decrypt("passphrase").unwrap();
+        assert!(!keys.is_encrypted());
+        let signed_txn = keys.sign_transaction(raw_transaction(sender)).unwrap();
+        assert!(signed_txn.verify_signature().is_ok());
+
+        let keys = keys.encrypt("passphrase").unwrap();
+        let AccountKeys::MultiKey { private_keys, .. } = keys else {
+            unreachable!()
+        };
+        for private_key in private_keys.into_values() {
+            assert!(private_key.decrypt("wrong passphrase").is_err());
+        }
+    }
+}
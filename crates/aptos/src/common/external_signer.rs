@@ -0,0 +1,265 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing transactions with a program or daemon that holds the private key
+//!
+//! The signer receives a single JSON request, and answers with a single JSON response:
+//!
+//! ```text
+//! {"account": "0x...", "public_key": "0x...", "signing_message": "0x..."}
+//! {"signature": "0x..."}
+//! ```
+//!
+//! A signer that refuses to sign answers `{"error": "..."}` instead. The signing message is the
+//! BCS of the raw transaction, or of the multi-agent or fee payer transaction data when signing
+//! offline, with its domain separator, ready for Ed25519 signing.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+    traits::signing_message,
+    Signature, ValidCryptoMaterialStringExt,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{RawTransaction, SignedTransaction},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Where a profile sends its transactions to be signed
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExternalSigner {
+    /// A program that is run for every signature, with the request on stdin and the response
+    /// on stdout
+    Command {
+        program: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+    /// A Unix domain socket of a signing daemon, which gets the request as a line and closes
+    /// the connection after the response
+    Socket { path: PathBuf },
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    account: AccountAddress,
+    public_key: String,
+    signing_message: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: Option<String>,
+    error: Option<String>,
+}
+
+impl ExternalSigner {
+    /// Signs a raw transaction, checking the signature against the public key of the profile
+    pub fn sign_transaction(
+        &self,
+        public_key: Ed25519PublicKey,
+        raw_txn: RawTransaction,
+    ) -> CliTypedResult<SignedTransaction> {
+        let signature = self.sign_message(raw_txn.sender(), &public_key, &raw_txn)?;
+        Ok(SignedTransaction::new(raw_txn, public_key, signature))
+    }
+
+    /// Signs a raw transaction, or the data of a multi-agent or fee payer transaction, for an
+    /// account, checking the signature against the public key of the profile
+    pub fn sign_message<T: CryptoHash + Serialize>(
+        &self,
+        account: AccountAddress,
+        public_key: &Ed25519PublicKey,
+        message: &T,
+    ) -> CliTypedResult<Ed25519Signature> {
+        let message =
+            signing_message(message).map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let request = SignRequest {
+            account,
+            public_key: public_key
+                .to_encoded_string()
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            signing_message: format!("0x{}", hex::encode(&message)),
+        };
+        let mut request = serde_json::to_vec(&request)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        request.push(b'\n');
+
+        let response = match self {
+            ExternalSigner::Command { program, args } => {
+                Self::run_command(program, args, &request)?
+            },
+            ExternalSigner::Socket { path } => Self::call_socket(path, &request)?,
+        };
+        let response: SignResponse = serde_json::from_slice(&response)
+            .map_err(|err| CliError::UnableToParse("external signer response", err.to_string()))?;
+        let signature = match (response.signature, response.error) {
+            (_, Some(error)) => {
+                return Err(CliError::UnexpectedError(format!(
+                    "External signer refused to sign: {}",
+                    error
+                )))
+            },
+            (Some(signature), None) => Ed25519Signature::from_encoded_string(&signature)
+                .map_err(|err| CliError::UnableToParse("external signature", err.to_string()))?,
+            (None, None) => {
+                return Err(CliError::UnableToParse(
+                    "external signer response",
+                    "missing signature".to_string(),
+                ))
+            },
+        };
+
+        signature
+            .verify_arbitrary_msg(&message, public_key)
+            .map_err(|_| {
+                CliError::UnexpectedError(
+                    "External signer returned a signature that does not match the public key of the profile"
+                        .to_string(),
+                )
+            })?;
+        Ok(signature)
+    }
+
+    fn run_command(program: &str, args: &[String], request: &[u8]) -> CliTypedResult<Vec<u8>> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| CliError::IO(program.to_string(), err))?;
+
+        // Dropping stdin after writing closes it, so the signer sees the end of the request
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(request)
+            .map_err(|err| CliError::IO(program.to_string(), err))?;
+        let output = child
+            .wait_with_output()
+            .map_err(|err| CliError::IO(program.to_string(), err))?;
+        if !output.status.success() {
+            return Err(CliError::UnexpectedError(format!(
+                "External signer {} failed with {}",
+                program, output.status
+            )));
+        }
+        Ok(output.stdout)
+    }
+
+    #[cfg(unix)]
+    fn call_socket(path: &Path, request: &[u8]) -> CliTypedResult<Vec<u8>> {
+        use std::io::Read;
+
+        let io_error = |err| CliError::IO(path.display().to_string(), err);
+        let mut stream = std::os::unix::net::UnixStream::connect(path).map_err(io_error)?;
+        stream.write_all(request).map_err(io_error)?;
+        stream
+            .shutdown(std::net::Shutdown::Write)
+            .map_err(io_error)?;
+
+        let mut response = vec![];
+        stream.read_to_end(&mut response).map_err(io_error)?;
+        Ok(response)
+    }
+
+    #[cfg(not(unix))]
+    fn call_socket(_path: &Path, _request: &[u8]) -> CliTypedResult<Vec<u8>> {
+        Err(CliError::CommandArgumentError(
+            "Socket external signers are only supported on Unix".to_string(),
+        ))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey};
+    use aptos_keygen::KeyGen;
+    use aptos_types::{chain_id::ChainId, transaction::Script};
+    use std::{
+        io::{BufRead, BufReader},
+        os::unix::net::UnixListener,
+        thread,
+    };
+
+    fn raw_txn(sender: AccountAddress) -> RawTransaction {
+        RawTransaction::new_script(
+            sender,
+            0,
+            Script::new(vec![], vec![], vec![]),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    /// Serves a single request on a Unix socket like a signing daemon, signing with `key`
+    fn serve_one(
+        listener: UnixListener,
+        key: Ed25519PrivateKey,
+        raw_txn: RawTransaction,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            BufReader::new(&stream).read_line(&mut request).unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(
+                serde_json::from_value::<AccountAddress>(request["account"].clone()).unwrap(),
+                raw_txn.sender()
+            );
+            assert_eq!(
+                request["signing_message"].as_str().unwrap(),
+                format!("0x{}", hex::encode(signing_message(&raw_txn).unwrap()))
+            );
+
+            let signature = key.sign(&raw_txn).unwrap().to_encoded_string().unwrap();
+            (&stream)
+                .write_all(
+                    serde_json::json!({ "signature": signature })
+                        .to_string()
+                        .as_bytes(),
+                )
+                .unwrap();
+        })
+    }
+
+    #[test]
+    fn test_socket_signer() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let key = keygen.generate_ed25519_private_key();
+        let public_key = key.public_key();
+        let wrong_key = keygen.generate_ed25519_private_key();
+        let sender = AccountAddress::random();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signer.sock");
+        let signer = ExternalSigner::Socket { path: path.clone() };
+
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = serve_one(listener.try_clone().unwrap(), key, raw_txn(sender));
+        let signed_txn = signer
+            .sign_transaction(public_key.clone(), raw_txn(sender))
+            .unwrap();
+        server.join().unwrap();
+        assert!(signed_txn.verify_signature().is_ok());
+        assert_eq!(signed_txn.sender(), sender);
+
+        // A signature by another key than the profile's is rejected
+        let server = serve_one(listener, wrong_key, raw_txn(sender));
+        let result = signer.sign_transaction(public_key.clone(), raw_txn(sender));
+        server.join().unwrap();
+        assert!(matches!(result, Err(CliError::UnexpectedError(_))));
+    }
+}
@@ -5,6 +5,8 @@ use super::types::FaucetOptions;
 use crate::{
     account::key_rotation::lookup_address,
     common::{
+        keystore::{read_new_passphrase, EncryptedPrivateKey},
+        networks::{self, canonical_network_name, validate_network_name, NetworkRegistry},
         types::{
             account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
             ConfigSearchMode, EncodingOptions, HardwareWalletOptions, PrivateKeyInputOptions,
@@ -21,7 +23,7 @@ use aptos_ledger;
 use async_trait::async_trait;
 use clap::Parser;
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
@@ -63,6 +65,13 @@ pub struct InitTool {
     #[clap(flatten)]
     pub(crate) hardware_wallet_options: HardwareWalletOptions,
 
+    /// Whether to store the private key encrypted with a passphrase
+    ///
+    /// The passphrase is read from `MOVEMENT_KEYSTORE_PASSPHRASE`, or prompted for. Commands
+    /// signing with the profile ask for it again, or read it from the same variable.
+    #[clap(long, conflicts_with = "ledger")]
+    pub encrypt_private_key: bool,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -104,12 +113,14 @@ impl CliCommand<()> for InitTool {
         eprintln!("Configuring for profile {}", profile_name);
 
         // Choose a network
-        let network = if let Some(network) = self.network {
+        let registry = NetworkRegistry::load()?;
+        let network = if let Some(network) = self.network.clone() {
             eprintln!("Configuring for network {:?}", network);
             network
         } else {
             eprintln!(
-                "Choose network from [devnet, testnet, mainnet, local, custom | defaults to testnet]"
+                "Choose network from [{}, custom | defaults to testnet]",
+                registry.names().collect::<Vec<_>>().join(", ")
             );
             let input = read_line("network")?;
             let input = input.trim();
@@ -135,30 +146,20 @@ impl CliCommand<()> for InitTool {
         }
 
         // Ensure the config contains the network used
-        profile_config.network = Some(network);
+        profile_config.network = Some(network.clone());
 
         // Ensure that there is at least a REST URL set for the network
-        match network {
-            Network::Mainnet => {
-                profile_config.rest_url = Some("https://mainnet.movementnetwork.xyz".to_string());
-                profile_config.faucet_url = None;
-            },
-            Network::Testnet => {
-                profile_config.rest_url = Some("https://testnet.movementnetwork.xyz".to_string());
-                profile_config.faucet_url =
-                    Some("https://faucet.testnet.movementnetwork.xyz".to_string());
-            },
-            Network::Devnet => {
-                profile_config.rest_url = Some("https://devnet.movementnetwork.xyz".to_string());
-                profile_config.faucet_url =
-                    Some("https://faucet.devnet.movementnetwork.xyz".to_string());
-            },
-            Network::Local => {
-                profile_config.rest_url = Some("http://localhost:8080".to_string());
-                profile_config.faucet_url = Some("http://localhost:8081".to_string());
-            },
-            Network::Custom => self.custom_network(&mut profile_config)?,
-        }
+        let expected_chain_id = if network == Network::Custom {
+            self.custom_network(&mut profile_config)?;
+            None
+        } else {
+            let network_config = registry.resolve(&network)?;
+            profile_config.rest_url = Some(network_config.rest_url.clone());
+            profile_config
+                .faucet_url
+                .clone_from(&network_config.faucet_url);
+            network_config.chain_id
+        };
 
         // Check if any ledger flag is set
         let derivation_path = if let Some(deri_path) =
@@ -211,11 +212,11 @@ impl CliCommand<()> for InitTool {
                 eprintln!("Using command line argument for private key");
                 key
             } else {
-                eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
+                eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", if profile_config.private_key.is_some() || profile_config.encrypted_private_key.is_some() { "Redacted" } else { "None" });
                 let input = read_line("Private key")?;
                 let input = input.trim();
                 if input.is_empty() {
-                    if let Some(key) = profile_config.private_key {
+                    if let Some(key) = profile_config.unlock_private_key(Some(profile_name))? {
                         eprintln!("No key given, keeping existing key...");
                         key
                     } else {
@@ -267,12 +268,35 @@ impl CliCommand<()> for InitTool {
         .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?;
         let client = aptos_rest_client::Client::new(rest_url);
 
+        // Make sure the endpoint serves the network it was registered for
+        if let Some(expected_chain_id) = expected_chain_id {
+            let chain_id = client.get_ledger_information().await?.inner().chain_id;
+            if chain_id != expected_chain_id {
+                return Err(CliError::UnexpectedError(format!(
+                    "Network {} is registered with chain ID {}, but its REST endpoint reports chain ID {}",
+                    network, expected_chain_id, chain_id
+                )));
+            }
+        }
+
         // lookup the address from onchain instead of deriving it
         // if this is the rotated key, deriving it will outputs an incorrect address
         let derived_address = account_address_from_public_key(&public_key);
         let address = lookup_address(&client, derived_address, false).await?;
 
-        profile_config.private_key = private_key;
+        // The key is stored either in plaintext or encrypted, and replaces any external signer
+        // or single-key and multi-key account keys
+        profile_config.external_signer = None;
+        profile_config.account_keys = None;
+        if self.encrypt_private_key {
+            profile_config.private_key = None;
+            profile_config.encrypted_private_key = private_key
+                .map(|key| EncryptedPrivateKey::encrypt(&key, &read_new_passphrase()?))
+                .transpose()?;
+        } else {
+            profile_config.private_key = private_key;
+            profile_config.encrypted_private_key = None;
+        }
         profile_config.public_key = Some(public_key);
         profile_config.account = Some(address);
 
@@ -424,14 +448,18 @@ impl InitTool {
 
 /// A simplified list of all networks supported by the CLI
 ///
-/// Any command using this, will be simpler to setup as profiles
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+/// Any command using this, will be simpler to setup as profiles. Every network
+/// other than `custom` is resolved against the [NetworkRegistry], so networks
+/// added to the CLI config can be used by name.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Network {
     Mainnet,
     Testnet,
     Devnet,
     Local,
     Custom,
+    /// A network added under `networks` in the CLI config
+    Named(String),
 }
 
 impl Display for Network {
@@ -440,11 +468,12 @@ impl Display for Network {
             f,
             "{}",
             match self {
-                Network::Mainnet => "mainnet",
-                Network::Testnet => "testnet",
-                Network::Devnet => "devnet",
-                Network::Local => "local",
+                Network::Mainnet => networks::MAINNET,
+                Network::Testnet => networks::TESTNET,
+                Network::Devnet => networks::DEVNET,
+                Network::Local => networks::LOCAL,
                 Network::Custom => "custom",
+                Network::Named(name) => name,
             }
         )
     }
@@ -454,22 +483,47 @@ impl FromStr for Network {
     type Err = CliError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s.to_lowercase().trim() {
-            "mainnet" => Self::Mainnet,
-            "testnet" => Self::Testnet,
-            "devnet" => Self::Devnet,
-            "local" => Self::Local,
+        Ok(match canonical_network_name(s).as_str() {
+            networks::MAINNET => Self::Mainnet,
+            networks::TESTNET => Self::Testnet,
+            networks::DEVNET => Self::Devnet,
+            networks::LOCAL => Self::Local,
             "custom" => Self::Custom,
             str => {
-                return Err(CliError::CommandArgumentError(format!(
-                    "Invalid network {}.  Must be one of [devnet, testnet, mainnet, local, custom]",
-                    str
-                )));
+                validate_network_name(str).map_err(|_| {
+                    CliError::CommandArgumentError(format!(
+                        "Invalid network {}.  Must be one of [devnet, testnet, mainnet, local, custom] or a network from the CLI config",
+                        str
+                    ))
+                })?;
+                Self::Named(str.to_string())
             },
         })
     }
 }
 
+/// Built-in networks keep the serialized names of older CLI versions, so
+/// existing configs stay readable both ways
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Network::Mainnet => serializer.serialize_str("Mainnet"),
+            Network::Testnet => serializer.serialize_str("Testnet"),
+            Network::Devnet => serializer.serialize_str("Devnet"),
+            Network::Local => serializer.serialize_str("Local"),
+            Network::Custom => serializer.serialize_str("Custom"),
+            Network::Named(name) => serializer.serialize_str(name),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let network = String::deserialize(deserializer)?;
+        Network::from_str(&network).map_err(serde::de::Error::custom)
+    }
+}
+
 impl Default for Network {
     fn default() -> Self {
         Self::Testnet
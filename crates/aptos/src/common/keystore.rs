@@ -0,0 +1,192 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passphrase encrypted private keys for CLI profiles
+//!
+//! The key is derived from the passphrase with Argon2id, and the private key is sealed with
+//! AES-256-GCM, so a wrong passphrase or a tampered keystore fails to decrypt.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterial};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable the keystore passphrase is read from instead of prompting
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "MOVEMENT_KEYSTORE_PASSPHRASE";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// Parameters of the function deriving the encryption key from the passphrase
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum KdfParams {
+    Argon2id {
+        /// Memory cost in KiB
+        m_cost: u32,
+        /// Number of iterations
+        t_cost: u32,
+        /// Degree of parallelism
+        p_cost: u32,
+        /// Hex encoded salt
+        salt: String,
+    },
+}
+
+impl KdfParams {
+    fn derive_key(&self, passphrase: &str) -> CliTypedResult<[u8; KEY_LENGTH]> {
+        match self {
+            KdfParams::Argon2id {
+                m_cost,
+                t_cost,
+                p_cost,
+                salt,
+            } => {
+                let salt = hex::decode(salt)?;
+                let params = Params::new(*m_cost, *t_cost, *p_cost, Some(KEY_LENGTH))
+                    .map_err(|err| CliError::UnableToParse("keystore kdf", err.to_string()))?;
+                let mut key = [0u8; KEY_LENGTH];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+                Ok(key)
+            },
+        }
+    }
+}
+
+/// A private key encrypted with a passphrase, as stored in a profile
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EncryptedPrivateKey {
+    pub kdf: KdfParams,
+    /// Hex encoded AES-256-GCM nonce
+    pub nonce: String,
+    /// Hex encoded AES-256-GCM ciphertext of the private key, including the tag
+    pub ciphertext: String,
+}
+
+impl EncryptedPrivateKey {
+    /// Encrypts a private key with a fresh salt and nonce
+    pub fn encrypt(private_key: &Ed25519PrivateKey, passphrase: &str) -> CliTypedResult<Self> {
+        Self::seal(&private_key.to_bytes(), passphrase)
+    }
+
+    /// Decrypts the private key, failing if the passphrase is wrong
+    pub fn decrypt(&self, passphrase: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        let bytes = self.open(passphrase)?;
+        Ed25519PrivateKey::try_from(bytes.as_slice())
+            .map_err(|err| CliError::UnableToParse("keystore private key", err.to_string()))
+    }
+
+    /// Encrypts any secret with a fresh salt and nonce
+    pub(crate) fn seal(secret: &[u8], passphrase: &str) -> CliTypedResult<Self> {
+        let mut rng = rand::thread_rng();
+        let mut salt = [0u8; SALT_LENGTH];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+
+        let kdf = KdfParams::Argon2id {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+            salt: hex::encode(salt),
+        };
+        let cipher = Aes256Gcm::new(&kdf.derive_key(passphrase)?.into());
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), secret)
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to encrypt key: {}", err)))?;
+
+        Ok(Self {
+            kdf,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts a secret sealed with [EncryptedPrivateKey::seal], failing if the passphrase is
+    /// wrong
+    pub(crate) fn open(&self, passphrase: &str) -> CliTypedResult<Vec<u8>> {
+        let nonce = hex::decode(&self.nonce)?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(CliError::UnableToParse(
+                "keystore nonce",
+                format!("expected {} bytes, got {}", NONCE_LENGTH, nonce.len()),
+            ));
+        }
+        let ciphertext = hex::decode(&self.ciphertext)?;
+
+        let cipher = Aes256Gcm::new(&self.kdf.derive_key(passphrase)?.into());
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                CliError::CommandArgumentError(
+                    "Failed to decrypt the private key, the passphrase is wrong".to_string(),
+                )
+            })
+    }
+
+    /// Decrypts the private key of a profile with the passphrase from
+    /// [KEYSTORE_PASSPHRASE_ENV], or prompts for it
+    pub fn unlock(&self, profile: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        self.decrypt(&read_passphrase_of(profile)?)
+    }
+}
+
+/// Reads the passphrase of a profile from [KEYSTORE_PASSPHRASE_ENV], or prompts for it
+pub fn read_passphrase_of(profile: &str) -> CliTypedResult<String> {
+    match std::env::var(KEYSTORE_PASSPHRASE_ENV) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => read_passphrase(&format!("Enter the passphrase of profile {}: ", profile)),
+    }
+}
+
+/// Reads a new passphrase from [KEYSTORE_PASSPHRASE_ENV], or prompts for it twice
+pub fn read_new_passphrase() -> CliTypedResult<String> {
+    if let Ok(passphrase) = std::env::var(KEYSTORE_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = read_passphrase("Enter a passphrase to encrypt the private key: ")?;
+    if passphrase.is_empty() {
+        return Err(CliError::CommandArgumentError(
+            "The passphrase must not be empty".to_string(),
+        ));
+    }
+    if read_passphrase("Confirm the passphrase: ")? != passphrase {
+        return Err(CliError::CommandArgumentError(
+            "The passphrases do not match".to_string(),
+        ));
+    }
+    Ok(passphrase)
+}
+
+fn read_passphrase(prompt: &str) -> CliTypedResult<String> {
+    rpassword::prompt_password(prompt).map_err(|err| CliError::IO("passphrase".to_string(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{PrivateKey, Uniform};
+
+    #[test]
+    fn test_encrypted_private_key_round_trip() {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let encrypted = EncryptedPrivateKey::encrypt(&private_key, "passphrase").unwrap();
+
+        // The keystore survives being saved to and loaded from the config
+        let yaml = serde_yaml::to_string(&encrypted).unwrap();
+        let encrypted: EncryptedPrivateKey = serde_yaml::from_str(&yaml).unwrap();
+
+        let decrypted = encrypted.decrypt("passphrase").unwrap();
+        assert_eq!(decrypted.public_key(), private_key.public_key());
+        assert!(encrypted.decrypt("wrong passphrase").is_err());
+    }
+}
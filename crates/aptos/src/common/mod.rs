@@ -1,8 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod account_keys;
+pub mod external_signer;
 pub mod init;
+pub mod keystore;
 pub mod local_simulation;
+pub mod networks;
 pub mod transactions;
 pub mod types;
 pub mod utils;
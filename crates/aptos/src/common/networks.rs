@@ -0,0 +1,209 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    init::Network,
+    types::{CliConfig, CliError, CliTypedResult, ConfigSearchMode},
+};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const MAINNET: &str = "mainnet";
+pub const TESTNET: &str = "testnet";
+pub const DEVNET: &str = "devnet";
+pub const LOCAL: &str = "local";
+/// Another name of the local network
+pub const LOCALHOST: &str = "localhost";
+
+/// Endpoints of a network the CLI can connect to
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NetworkConfig {
+    /// URL for the REST endpoint of a fullnode
+    pub rest_url: String,
+    /// URL for the faucet endpoint, if the network has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub faucet_url: Option<String>,
+    /// URL for the indexer GraphQL endpoint, if the network has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexer_url: Option<String>,
+    /// Chain ID the REST endpoint is expected to report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u8>,
+}
+
+impl NetworkConfig {
+    pub fn rest_url(&self) -> CliTypedResult<Url> {
+        Url::parse(&self.rest_url)
+            .map_err(|err| CliError::UnableToParse("network rest_url", err.to_string()))
+    }
+
+    pub fn faucet_url(&self) -> CliTypedResult<Option<Url>> {
+        self.faucet_url
+            .as_deref()
+            .map(Url::parse)
+            .transpose()
+            .map_err(|err| CliError::UnableToParse("network faucet_url", err.to_string()))
+    }
+
+    pub fn indexer_url(&self) -> CliTypedResult<Option<Url>> {
+        self.indexer_url
+            .as_deref()
+            .map(Url::parse)
+            .transpose()
+            .map_err(|err| CliError::UnableToParse("network indexer_url", err.to_string()))
+    }
+
+    /// Ensures every configured URL parses
+    pub fn validate(&self) -> CliTypedResult<()> {
+        self.rest_url()?;
+        self.faucet_url()?;
+        self.indexer_url()?;
+        Ok(())
+    }
+}
+
+/// All networks the CLI knows by name
+///
+/// The built-in Movement networks can be overridden, and further networks
+/// added, under `networks` in `.movement/config.yaml`.
+#[derive(Clone, Debug)]
+pub struct NetworkRegistry {
+    networks: BTreeMap<String, NetworkConfig>,
+}
+
+impl Default for NetworkRegistry {
+    fn default() -> Self {
+        let network = |rest_url: &str,
+                       faucet_url: Option<&str>,
+                       indexer_url: Option<&str>,
+                       chain_id: Option<u8>| NetworkConfig {
+            rest_url: rest_url.to_string(),
+            faucet_url: faucet_url.map(str::to_string),
+            indexer_url: indexer_url.map(str::to_string),
+            chain_id,
+        };
+
+        let networks = BTreeMap::from([
+            (
+                MAINNET.to_string(),
+                network(
+                    "https://mainnet.movementnetwork.xyz",
+                    None,
+                    Some("https://indexer.mainnet.movementnetwork.xyz/v1/graphql"),
+                    Some(126),
+                ),
+            ),
+            (
+                TESTNET.to_string(),
+                network(
+                    "https://testnet.movementnetwork.xyz",
+                    Some("https://faucet.testnet.movementnetwork.xyz"),
+                    Some("https://indexer.testnet.movementnetwork.xyz/v1/graphql"),
+                    Some(250),
+                ),
+            ),
+            (
+                DEVNET.to_string(),
+                network(
+                    "https://devnet.movementnetwork.xyz",
+                    Some("https://faucet.devnet.movementnetwork.xyz"),
+                    None,
+                    None,
+                ),
+            ),
+            (
+                LOCAL.to_string(),
+                network(
+                    "http://localhost:8080",
+                    Some("http://localhost:8081"),
+                    Some("http://localhost:8090/v1/graphql"),
+                    Some(4),
+                ),
+            ),
+        ]);
+
+        Self { networks }
+    }
+}
+
+impl NetworkRegistry {
+    /// Loads the built-in networks, and the networks of the CLI config if there is one
+    pub fn load() -> CliTypedResult<Self> {
+        let mut registry = Self::default();
+        if CliConfig::config_exists(ConfigSearchMode::CurrentDirAndParents) {
+            let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+            registry.extend(config.networks.unwrap_or_default())?;
+        }
+        Ok(registry)
+    }
+
+    /// Adds networks, replacing any network of the same name
+    pub fn extend(&mut self, networks: BTreeMap<String, NetworkConfig>) -> CliTypedResult<()> {
+        for (name, network) in networks {
+            validate_network_name(&name)?;
+            network.validate()?;
+            self.networks.insert(canonical_network_name(&name), network);
+        }
+        Ok(())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.networks.keys().map(String::as_str)
+    }
+
+    pub fn networks(&self) -> &BTreeMap<String, NetworkConfig> {
+        &self.networks
+    }
+
+    /// Looks up a network by name
+    pub fn get(&self, name: &str) -> CliTypedResult<&NetworkConfig> {
+        self.networks.get(&canonical_network_name(name)).ok_or_else(|| {
+            CliError::CommandArgumentError(format!(
+                "Unknown network {}. Must be one of [{}], or add it under `networks` in .movement/config.yaml",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            ))
+        })
+    }
+
+    /// Looks up the endpoints of a profile network
+    ///
+    /// `custom` networks have no registry entry, their URLs live in the profile.
+    pub fn resolve(&self, network: &Network) -> CliTypedResult<&NetworkConfig> {
+        match network {
+            Network::Custom => Err(CliError::CommandArgumentError(
+                "A custom network has no registered endpoints, use --rest-url and --faucet-url"
+                    .to_string(),
+            )),
+            network => self.get(&network.to_string()),
+        }
+    }
+}
+
+/// The name a network is registered under, as names are case insensitive and
+/// `localhost` stands for the local network
+pub fn canonical_network_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    if name == LOCALHOST {
+        LOCAL.to_string()
+    } else {
+        name
+    }
+}
+
+/// Network names are used as YAML keys and on the command line, where anything
+/// containing `:` or `/` is taken for a URL
+pub fn validate_network_name(name: &str) -> CliTypedResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid || name.eq_ignore_ascii_case("custom") {
+        return Err(CliError::CommandArgumentError(format!(
+            "Invalid network name {}. Names may only contain letters, digits, '-' and '_', and cannot be 'custom'",
+            name
+        )));
+    }
+    Ok(())
+}
@@ -6,7 +6,7 @@ use crate::common::{
     types::{
         AccountType, CliConfig, CliError, CliTypedResult, ConfigSearchMode, EncodingOptions,
         ExtractEd25519PublicKey, GasOptions, PrivateKeyInputOptions, ProfileOptions, PromptOptions,
-        RestOptions, TransactionSummary, ACCEPTED_CLOCK_SKEW_US, US_IN_SECS,
+        RestOptions, TransactionSummary, ACCEPTED_CLOCK_SKEW_US, DEFAULT_PROFILE, US_IN_SECS,
     },
     utils::{get_account_with_state, get_sequence_number},
 };
@@ -73,8 +73,16 @@ impl TxnOptions {
             self.profile_options.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )? {
-            if profile.private_key.is_some() {
+            if let Some(account_keys) = profile.account_keys {
+                let profile_name = self
+                    .profile_options
+                    .profile_name()
+                    .unwrap_or(DEFAULT_PROFILE);
+                Ok(AccountType::AccountKeys(account_keys.unlock(profile_name)?))
+            } else if profile.private_key.is_some() || profile.encrypted_private_key.is_some() {
                 Ok(AccountType::Local)
+            } else if let Some(external_signer) = profile.external_signer {
+                Ok(AccountType::ExternalSigner(external_signer))
             } else {
                 Ok(AccountType::HardwareWallet)
             }
@@ -114,7 +122,7 @@ impl TxnOptions {
     }
 
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        self.get_address()
     }
 
     pub fn get_public_key(&self) -> CliTypedResult<Ed25519PublicKey> {
@@ -4,8 +4,12 @@
 use super::utils::{explorer_transaction_link, fund_account, strip_private_key_prefix};
 use crate::{
     common::{
+        account_keys::{AccountKeys, AccountKeysSummary},
+        external_signer::ExternalSigner,
         init::Network,
+        keystore::EncryptedPrivateKey,
         local_simulation,
+        networks::{NetworkConfig, NetworkRegistry},
         utils::{
             check_if_file_exists, create_dir_if_not_exist, deserialize_address_str,
             deserialize_material_with_prefix, dir_default_to_current, get_account_with_state,
@@ -24,7 +28,9 @@ use aptos_api_types::ViewFunction;
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     encoding_type::{EncodingError, EncodingType},
-    x25519, PrivateKey, ValidCryptoMaterialStringExt,
+    hash::CryptoHash,
+    traits::signing_message,
+    x25519, CryptoMaterialError, PrivateKey, SigningKey, ValidCryptoMaterialStringExt,
 };
 use aptos_framework::chunked_publish::{
     default_large_packages_module_address, CHUNK_SIZE_IN_BYTES,
@@ -36,7 +42,7 @@ use aptos_move_debugger::aptos_debugger::AptosDebugger;
 use aptos_rest_client::{
     aptos_api_types::{EntryFunctionId, HashValue, MoveType, ViewRequest},
     error::RestError,
-    AptosBaseUrl, Client, Transaction,
+    AptosBaseUrl, Client, RetryPolicy, Transaction,
 };
 use aptos_sdk::{
     transaction_builder::TransactionFactory,
@@ -45,8 +51,9 @@ use aptos_sdk::{
 use aptos_types::{
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload, Script,
-        SignedTransaction, TransactionArgument, TransactionPayload, TransactionStatus,
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        EntryFunction, MultisigTransactionPayload, RawTransaction, Script, SignedTransaction,
+        TransactionArgument, TransactionPayload, TransactionStatus,
     },
 };
 use aptos_vm_types::output::VMOutput;
@@ -255,6 +262,9 @@ pub struct CliConfig {
     /// Map of profile configs
     #[serde(skip_serializing_if = "Option::is_none")]
     pub profiles: Option<BTreeMap<String, ProfileConfig>>,
+    /// Map of networks added to the built-in ones, usable by name with `--network`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networks: Option<BTreeMap<String, NetworkConfig>>,
 }
 
 const CONFIG_FILE: &str = "config.yaml";
@@ -297,6 +307,31 @@ pub struct ProfileConfig {
     /// Derivation path index of the account on ledger
     #[serde(skip_serializing_if = "Option::is_none")]
     pub derivation_path: Option<String>,
+    /// Private key for commands, encrypted with a passphrase
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub encrypted_private_key: Option<EncryptedPrivateKey>,
+    /// Program or daemon that signs transactions for the public key of the profile
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub external_signer: Option<ExternalSigner>,
+    /// Keys of a single-key or multi-key account, used instead of the Ed25519 keys
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub account_keys: Option<AccountKeys>,
+}
+
+impl ProfileConfig {
+    /// Returns the private key of the profile, decrypting it if it is encrypted
+    pub fn unlock_private_key(
+        &self,
+        profile_name: Option<&str>,
+    ) -> CliTypedResult<Option<Ed25519PrivateKey>> {
+        match (&self.private_key, &self.encrypted_private_key) {
+            (Some(private_key), _) => Ok(Some(private_key.clone())),
+            (None, Some(encrypted_private_key)) => encrypted_private_key
+                .unlock(profile_name.unwrap_or(DEFAULT_PROFILE))
+                .map(Some),
+            (None, None) => Ok(None),
+        }
+    }
 }
 
 /// ProfileConfig but without the private parts
@@ -305,6 +340,11 @@ pub struct ProfileSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<Network>,
     pub has_private_key: bool,
+    pub private_key_encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_signer: Option<ExternalSigner>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_keys: Option<AccountKeysSummary>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_material_with_prefix",
@@ -325,8 +365,17 @@ pub struct ProfileSummary {
 impl From<&ProfileConfig> for ProfileSummary {
     fn from(config: &ProfileConfig) -> Self {
         ProfileSummary {
-            network: config.network,
-            has_private_key: config.private_key.is_some(),
+            network: config.network.clone(),
+            has_private_key: config.private_key.is_some()
+                || config.encrypted_private_key.is_some()
+                || config.account_keys.is_some(),
+            private_key_encrypted: config.encrypted_private_key.is_some()
+                || config
+                    .account_keys
+                    .as_ref()
+                    .is_some_and(AccountKeys::is_encrypted),
+            external_signer: config.external_signer.clone(),
+            account_keys: config.account_keys.as_ref().map(AccountKeysSummary::from),
             public_key: config.public_key.clone(),
             account: config.account,
             rest_url: config.rest_url.clone(),
@@ -339,6 +388,7 @@ impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
             profiles: Some(BTreeMap::new()),
+            networks: None,
         }
     }
 }
@@ -416,7 +466,13 @@ impl CliConfig {
 
     /// Saves the config to ./.movement/config.yaml
     pub fn save(&self) -> CliTypedResult<()> {
-        let aptos_folder = Self::aptos_folder(ConfigSearchMode::CurrentDir)?;
+        self.save_to(ConfigSearchMode::CurrentDir)
+    }
+
+    /// Saves the config to the folder `mode` finds, which is the one [Self::load] reads with
+    /// the same mode, or else to ./.movement/config.yaml
+    pub fn save_to(&self, mode: ConfigSearchMode) -> CliTypedResult<()> {
+        let aptos_folder = Self::aptos_folder(mode)?;
 
         // Create if it doesn't exist
         let no_dir = !aptos_folder.exists();
@@ -464,6 +520,10 @@ pub enum KeyType {
     X25519,
     /// A BLS12381 key for consensus
     Bls12381,
+    /// Secp256k1 ECDSA key used for signing as a single-key account
+    Secp256k1Ecdsa,
+    /// Secp256r1 ECDSA key, as used by passkeys through WebAuthn
+    Secp256r1Ecdsa,
 }
 
 impl Display for KeyType {
@@ -472,6 +532,8 @@ impl Display for KeyType {
             KeyType::Ed25519 => "ed25519",
             KeyType::X25519 => "x25519",
             KeyType::Bls12381 => "bls12381",
+            KeyType::Secp256k1Ecdsa => "secp256k1-ecdsa",
+            KeyType::Secp256r1Ecdsa => "secp256r1-ecdsa",
         };
         write!(f, "{}", str)
     }
@@ -485,7 +547,9 @@ impl FromStr for KeyType {
             "ed25519" => Ok(KeyType::Ed25519),
             "x25519" => Ok(KeyType::X25519),
             "bls12381" => Ok(KeyType::Bls12381),
-            _ => Err("Invalid key type: Must be one of [ed25519, x25519]"),
+            "secp256k1-ecdsa" => Ok(KeyType::Secp256k1Ecdsa),
+            "secp256r1-ecdsa" => Ok(KeyType::Secp256r1Ecdsa),
+            _ => Err("Invalid key type: Must be one of [ed25519, x25519, bls12381, secp256k1-ecdsa, secp256r1-ecdsa]"),
         }
     }
 }
@@ -772,6 +836,18 @@ impl HardwareWalletOptions {
     }
 }
 
+/// Loads the private key and account of a profile, decrypting the key if needed
+fn load_profile_private_key(
+    profile: &ProfileOptions,
+) -> CliTypedResult<Option<(Option<Ed25519PrivateKey>, Option<AccountAddress>)>> {
+    CliConfig::load_profile(
+        profile.profile_name(),
+        ConfigSearchMode::CurrentDirAndParents,
+    )?
+    .map(|p| Ok((p.unlock_private_key(profile.profile_name())?, p.account)))
+    .transpose()
+}
+
 #[derive(Debug, Default, Parser)]
 pub struct PrivateKeyInputOptions {
     /// Signing Ed25519 private key file path
@@ -887,17 +963,19 @@ impl PrivateKeyInputOptions {
             // If we use the CLI inputs, then we should derive or use the address from the input
             let address = account_address_from_public_key(&private_key.public_key());
             Ok(address)
-        } else if let Some((Some(public_key), maybe_config_address)) = CliConfig::load_profile(
+        } else if let Some(profile) = CliConfig::load_profile(
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| (p.public_key, p.account))
-        {
-            if let Some(address) = maybe_config_address {
-                Ok(address)
-            } else {
-                let address = account_address_from_public_key(&public_key);
-                Ok(address)
+        )? {
+            match (profile.account, profile.public_key, profile.account_keys) {
+                (Some(address), _, _) => Ok(address),
+                (None, Some(public_key), _) => Ok(account_address_from_public_key(&public_key)),
+                (None, None, Some(account_keys)) => {
+                    Ok(account_keys.authentication_key()?.account_address())
+                },
+                (None, None, None) => Err(CliError::CommandArgumentError(
+                    "One of ['--private-key', '--private-key-file'], or ['public_key'] must present in profile".to_string(),
+                )),
             }
         } else {
             Err(CliError::CommandArgumentError(
@@ -925,12 +1003,7 @@ impl PrivateKeyInputOptions {
                 let address = account_address_from_public_key(&key.public_key());
                 Ok((key, address))
             }
-        } else if let Some((Some(key), maybe_config_address)) = CliConfig::load_profile(
-            profile.profile_name(),
-            ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| (p.private_key, p.account))
-        {
+        } else if let Some((Some(key), maybe_config_address)) = load_profile_private_key(profile)? {
             match (maybe_address, maybe_config_address) {
                 (Some(address), _) => Ok((key, address)),
                 (_, Some(address)) => Ok((key, address)),
@@ -954,12 +1027,7 @@ impl PrivateKeyInputOptions {
     ) -> CliTypedResult<Ed25519PrivateKey> {
         if let Some(key) = self.extract_private_key_cli(encoding)? {
             Ok(key)
-        } else if let Some(Some(private_key)) = CliConfig::load_profile(
-            profile.profile_name(),
-            ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| p.private_key)
-        {
+        } else if let Some((Some(private_key), _)) = load_profile_private_key(profile)? {
             Ok(private_key)
         } else {
             Err(CliError::CommandArgumentError(
@@ -1093,6 +1161,12 @@ pub struct RestOptions {
     #[clap(long)]
     pub(crate) url: Option<reqwest::Url>,
 
+    /// URL to another fullnode on the same network, used when the main one fails
+    ///
+    /// Can be given several times, fallbacks are tried in the given order
+    #[clap(long = "fallback-url")]
+    pub(crate) fallback_urls: Vec<reqwest::Url>,
+
     /// Connection timeout in seconds, used for the REST endpoint of the fullnode
     #[clap(long, default_value_t = DEFAULT_EXPIRATION_SECS, alias = "connection-timeout-s")]
     pub connection_timeout_secs: u64,
@@ -1108,6 +1182,7 @@ impl Default for RestOptions {
     fn default() -> Self {
         Self {
             url: None,
+            fallback_urls: vec![],
             connection_timeout_secs: DEFAULT_EXPIRATION_SECS,
             node_api_key: None,
         }
@@ -1118,6 +1193,7 @@ impl RestOptions {
     pub fn new(url: Option<reqwest::Url>, connection_timeout_secs: Option<u64>) -> Self {
         RestOptions {
             url,
+            fallback_urls: vec![],
             connection_timeout_secs: connection_timeout_secs.unwrap_or(DEFAULT_EXPIRATION_SECS),
             node_api_key: None,
         }
@@ -1126,31 +1202,123 @@ impl RestOptions {
     /// Retrieve the URL from the profile or the command line
     pub fn url(&self, profile: &ProfileOptions) -> CliTypedResult<reqwest::Url> {
         if let Some(ref url) = self.url {
-            Ok(url.clone())
-        } else if let Some(Some(url)) = CliConfig::load_profile(
+            return Ok(url.clone());
+        }
+
+        let profile = CliConfig::load_profile(
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| p.rest_url)
-        {
-            reqwest::Url::parse(&url)
-                .map_err(|err| CliError::UnableToParse("Rest URL", err.to_string()))
-        } else {
-            Err(CliError::CommandArgumentError("No rest url given.  Please add --url or add a rest_url to the .movement/config.yaml for the current profile".to_string()))
+        )?;
+        match profile {
+            Some(ProfileConfig {
+                rest_url: Some(url),
+                ..
+            }) => reqwest::Url::parse(&url)
+                .map_err(|err| CliError::UnableToParse("Rest URL", err.to_string())),
+            // Profiles of a registered network can leave the URL to the registry
+            Some(ProfileConfig {
+                network: Some(network),
+                ..
+            }) if network != Network::Custom => {
+                NetworkRegistry::load()?.resolve(&network)?.rest_url()
+            },
+            _ => Err(CliError::CommandArgumentError("No rest url given.  Please add --url or add a rest_url to the .movement/config.yaml for the current profile".to_string())),
         }
     }
 
     pub fn client(&self, profile: &ProfileOptions) -> CliTypedResult<Client> {
         let mut client = Client::builder(AptosBaseUrl::Custom(self.url(profile)?))
             .timeout(Duration::from_secs(self.connection_timeout_secs))
+            .retry_policy(RetryPolicy::exponential())
             .header(aptos_api_types::X_APTOS_CLIENT, X_APTOS_CLIENT_VALUE)?;
         if let Some(node_api_key) = &self.node_api_key {
             client = client.api_key(node_api_key)?;
         }
+        for fallback_url in &self.fallback_urls {
+            client = client.fallback_url(fallback_url.clone());
+        }
         Ok(client.build())
     }
 }
 
+/// Options for querying the indexer GraphQL API
+#[derive(Debug, Default, Parser)]
+pub struct IndexerOptions {
+    /// URL of the indexer GraphQL API
+    ///
+    /// Defaults to the indexer of the network of the profile
+    #[clap(long)]
+    pub(crate) indexer_url: Option<reqwest::Url>,
+}
+
+impl IndexerOptions {
+    /// Retrieve the URL from the command line, or the network registry for the profile's network
+    pub fn url(&self, profile: &ProfileOptions) -> CliTypedResult<reqwest::Url> {
+        if let Some(ref url) = self.indexer_url {
+            return Ok(url.clone());
+        }
+
+        let network = CliConfig::load_profile(
+            profile.profile_name(),
+            ConfigSearchMode::CurrentDirAndParents,
+        )?
+        .and_then(|profile| profile.network)
+        .filter(|network| *network != Network::Custom);
+        let url = match network {
+            Some(network) => NetworkRegistry::load()?.resolve(&network)?.indexer_url()?,
+            None => None,
+        };
+        url.ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "No indexer url given.  Please add --indexer-url or use a profile of a network with an indexer".to_string(),
+            )
+        })
+    }
+
+    /// Runs a GraphQL query, returning the `data` of the response
+    pub async fn query<T: serde::de::DeserializeOwned>(
+        &self,
+        profile: &ProfileOptions,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> CliTypedResult<T> {
+        #[derive(Deserialize)]
+        struct GraphQlError {
+            message: String,
+        }
+
+        #[derive(Deserialize)]
+        struct GraphQlResponse<T> {
+            data: Option<T>,
+            #[serde(default)]
+            errors: Vec<GraphQlError>,
+        }
+
+        let url = self.url(profile)?;
+        let response: GraphQlResponse<T> = reqwest::Client::new()
+            .post(url.clone())
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| CliError::ApiError(format!("Indexer query to {} failed: {}", url, err)))?
+            .json()
+            .await
+            .map_err(|err| CliError::UnableToParse("indexer response", err.to_string()))?;
+
+        if !response.errors.is_empty() {
+            let messages: Vec<_> = response.errors.into_iter().map(|e| e.message).collect();
+            return Err(CliError::ApiError(format!(
+                "Indexer query failed: {}",
+                messages.join(", ")
+            )));
+        }
+        response
+            .data
+            .ok_or_else(|| CliError::UnexpectedError("Indexer response has no data".to_string()))
+    }
+}
+
 /// Options for optimization level
 #[derive(Debug, Clone, Parser)]
 pub enum OptimizationLevel {
@@ -1663,17 +1831,7 @@ impl FaucetOptions {
         match profile.faucet_url {
             Some(url) => reqwest::Url::parse(&url)
                 .map_err(|err| CliError::UnableToParse("config faucet_url", err.to_string())),
-            None => match profile.network {
-                Some(Network::Mainnet) => {
-                    Err(CliError::CommandArgumentError("There is no faucet for mainnet. Please create and fund the account by transferring funds from another account. If you are confident you want to use a faucet, set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile".to_string()))
-                },
-                Some(Network::Testnet) => {
-                    Err(CliError::CommandArgumentError(format!("To get testnet APT you must visit {}. If you are confident you want to use a faucet programmatically, set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile", get_mint_site_url(None))))
-                },
-                _ => {
-                    Err(CliError::CommandArgumentError("No faucet given. Please set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile".to_string()))
-                },
-            },
+            None => network_faucet_url(&NetworkRegistry::load()?, profile.network.as_ref()),
         }
     }
 
@@ -1696,6 +1854,27 @@ impl FaucetOptions {
     }
 }
 
+/// Looks up the faucet of a profile network in the [NetworkRegistry], so that
+/// faucets of the built-in networks can be overridden like those of any other
+pub(crate) fn network_faucet_url(
+    registry: &NetworkRegistry,
+    network: Option<&Network>,
+) -> CliTypedResult<reqwest::Url> {
+    let network = match network {
+        Some(network) if *network != Network::Custom => network,
+        _ => return Err(CliError::CommandArgumentError("No faucet given. Please set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile".to_string())),
+    };
+    if let Some(faucet_url) = registry.resolve(network)?.faucet_url()? {
+        return Ok(faucet_url);
+    }
+
+    match network {
+        Network::Mainnet => Err(CliError::CommandArgumentError("There is no faucet for mainnet. Please create and fund the account by transferring funds from another account. If you are confident you want to use a faucet, set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile".to_string())),
+        Network::Testnet => Err(CliError::CommandArgumentError(format!("To get testnet APT you must visit {}. If you are confident you want to use a faucet programmatically, set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile", get_mint_site_url(None)))),
+        network => Err(CliError::CommandArgumentError(format!("Network {} has no faucet. Please set --faucet-url or add a faucet URL to .movement/config.yaml for the current profile", network))),
+    }
+}
+
 /// Gas price options for manipulating how to prioritize transactions
 #[derive(Debug, Clone, Eq, Parser, PartialEq)]
 pub struct GasOptions {
@@ -1744,6 +1923,19 @@ impl Default for GasOptions {
 pub enum AccountType {
     Local,
     HardwareWallet,
+    ExternalSigner(ExternalSigner),
+    /// A single-key or multi-key account signing with the keys of the profile
+    AccountKeys(AccountKeys),
+}
+
+/// Gas, sequence number and chain parameters of a transaction about to be signed
+#[derive(Clone, Copy, Debug)]
+pub struct TransactionParameters {
+    pub chain_id: ChainId,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    pub max_gas: u64,
+    pub expiration_timestamp_secs: u64,
 }
 
 /// Common options for interacting with an account for a validator
@@ -1798,8 +1990,16 @@ impl TransactionOptions {
             self.profile_options.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )? {
-            if profile.private_key.is_some() {
+            if let Some(account_keys) = profile.account_keys {
+                let profile_name = self
+                    .profile_options
+                    .profile_name()
+                    .unwrap_or(DEFAULT_PROFILE);
+                Ok(AccountType::AccountKeys(account_keys.unlock(profile_name)?))
+            } else if profile.private_key.is_some() || profile.encrypted_private_key.is_some() {
                 Ok(AccountType::Local)
+            } else if let Some(external_signer) = profile.external_signer {
+                Ok(AccountType::ExternalSigner(external_signer))
             } else {
                 Ok(AccountType::HardwareWallet)
             }
@@ -1830,8 +2030,13 @@ impl TransactionOptions {
             )
     }
 
+    /// Retrieves the sender address, without needing the private key
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        self.private_key_options.extract_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.sender_account,
+        )
     }
 
     pub fn get_public_key(&self) -> CliTypedResult<Ed25519PublicKey> {
@@ -1868,8 +2073,98 @@ impl TransactionOptions {
         payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
         let client = self.rest_client()?;
-        let (sender_public_key, sender_address) = self.get_public_key_and_address()?;
+        let account_type = self.get_transaction_account_type()?;
+        let sender_address = self.sender_address()?;
+
+        let TransactionParameters {
+            chain_id,
+            sequence_number,
+            gas_unit_price,
+            max_gas,
+            ..
+        } = self
+            .transaction_parameters(&client, sender_address, &payload, |unsigned_transaction| {
+                // Single-key and multi-key accounts are simulated without an authenticator, as not
+                // every key type has a placeholder signature
+                Ok(match &account_type {
+                    AccountType::AccountKeys(_) => SignedTransaction::new_single_sender(
+                        unsigned_transaction,
+                        AccountAuthenticator::NoAccountAuthenticator,
+                    ),
+                    _ => SignedTransaction::new(
+                        unsigned_transaction,
+                        self.get_public_key_and_address()?.0,
+                        Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                    ),
+                })
+            })
+            .await?;
+
+        // Build a transaction
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .with_transaction_expiration_time(self.gas_options.expiration_secs);
+
+        // Sign it with the appropriate signer
+        let transaction = match account_type {
+            AccountType::Local => {
+                let (private_key, _) = self.get_key_and_address()?;
+                let sender_account =
+                    &mut LocalAccount::new(sender_address, private_key, sequence_number);
+                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload))
+            },
+            AccountType::HardwareWallet => {
+                let sender_account = &mut HardwareWalletAccount::new(
+                    sender_address,
+                    self.get_public_key_and_address()?.0,
+                    self.profile_options
+                        .derivation_path()
+                        .expect("derivative path is missing from profile")
+                        .unwrap(),
+                    HardwareWalletType::Ledger,
+                    sequence_number,
+                );
+                sender_account
+                    .sign_with_transaction_builder(transaction_factory.payload(payload))?
+            },
+            AccountType::ExternalSigner(external_signer) => {
+                let raw_txn = transaction_factory
+                    .payload(payload)
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build();
+                external_signer.sign_transaction(self.get_public_key_and_address()?.0, raw_txn)?
+            },
+            AccountType::AccountKeys(account_keys) => {
+                let raw_txn = transaction_factory
+                    .payload(payload)
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build();
+                account_keys.sign_transaction(raw_txn)?
+            },
+        };
+
+        self.submit_signed_transaction(&client, transaction).await
+    }
 
+    /// Looks up the sequence number and chain of the sender, and picks the gas unit price and
+    /// maximum gas of a transaction
+    ///
+    /// Unless set in the options, the gas unit price is estimated by the node, and the maximum gas
+    /// by simulating the transaction that `simulation_transaction` builds out of the unsigned
+    /// transaction. Estimated prices are confirmed with the user.
+    pub async fn transaction_parameters<F>(
+        &self,
+        client: &Client,
+        sender_address: AccountAddress,
+        payload: &TransactionPayload,
+        simulation_transaction: F,
+    ) -> CliTypedResult<TransactionParameters>
+    where
+        F: FnOnce(RawTransaction) -> CliTypedResult<SignedTransaction>,
+    {
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
         let ask_to_confirm_price;
@@ -1884,7 +2179,7 @@ impl TransactionOptions {
         };
 
         // Get sequence number for account
-        let (account, state) = get_account_with_state(&client, sender_address).await?;
+        let (account, state) = get_account_with_state(client, sender_address).await?;
         let sequence_number = account.sequence_number;
 
         // Retrieve local time, and ensure it's within an expected skew of the blockchain
@@ -1899,7 +2194,7 @@ impl TransactionOptions {
         if now_usecs < state.timestamp_usecs - ACCEPTED_CLOCK_SKEW_US {
             eprintln!("Local clock is is skewed from blockchain clock.  Clock is more than {} seconds behind the blockchain {}", ACCEPTED_CLOCK_SKEW_US, state.timestamp_usecs / US_IN_SECS );
         }
-        let expiration_time_secs = now + self.gas_options.expiration_secs;
+        let expiration_timestamp_secs = now + self.gas_options.expiration_secs;
 
         let chain_id = ChainId::new(state.chain_id);
         // TODO: Check auth key against current private key and provide a better message
@@ -1919,14 +2214,9 @@ impl TransactionOptions {
                 .payload(payload.clone())
                 .sender(sender_address)
                 .sequence_number(sequence_number)
-                .expiration_timestamp_secs(expiration_time_secs)
+                .expiration_timestamp_secs(expiration_timestamp_secs)
                 .build();
-
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_public_key.clone(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
+            let signed_transaction = simulation_transaction(unsigned_transaction)?;
 
             let txns = client
                 .simulate_with_gas_estimation(&signed_transaction, true, false)
@@ -1960,37 +2250,64 @@ impl TransactionOptions {
             adjusted_max_gas
         };
 
-        // Build a transaction
-        let transaction_factory = TransactionFactory::new(chain_id)
-            .with_gas_unit_price(gas_unit_price)
-            .with_max_gas_amount(max_gas)
-            .with_transaction_expiration_time(self.gas_options.expiration_secs);
+        Ok(TransactionParameters {
+            chain_id,
+            sequence_number,
+            gas_unit_price,
+            max_gas,
+            expiration_timestamp_secs,
+        })
+    }
 
-        // Sign it with the appropriate signer
-        let transaction = match self.get_transaction_account_type() {
-            Ok(AccountType::Local) => {
+    /// Signs a raw transaction, or the data of a multi-agent or fee payer transaction, with the
+    /// signer of the profile
+    pub fn sign_message<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> CliTypedResult<AccountAuthenticator> {
+        let sign_error = |err: CryptoMaterialError| CliError::UnexpectedError(err.to_string());
+        match self.get_transaction_account_type()? {
+            AccountType::Local => {
                 let (private_key, _) = self.get_key_and_address()?;
-                let sender_account =
-                    &mut LocalAccount::new(sender_address, private_key, sequence_number);
-                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload))
+                let signature = private_key.sign(message).map_err(sign_error)?;
+                Ok(AccountAuthenticator::ed25519(
+                    private_key.public_key(),
+                    signature,
+                ))
             },
-            Ok(AccountType::HardwareWallet) => {
-                let sender_account = &mut HardwareWalletAccount::new(
-                    sender_address,
-                    sender_public_key,
-                    self.profile_options
-                        .derivation_path()
-                        .expect("derivative path is missing from profile")
-                        .unwrap(),
+            AccountType::HardwareWallet => {
+                let (public_key, address) = self.get_public_key_and_address()?;
+                let derivation_path = self.profile_options.derivation_path()?.ok_or_else(|| {
+                    CliError::CommandArgumentError(
+                        "Derivation path is missing from profile".to_string(),
+                    )
+                })?;
+                let hardware_wallet = HardwareWalletAccount::new(
+                    address,
+                    public_key.clone(),
+                    derivation_path,
                     HardwareWalletType::Ledger,
-                    sequence_number,
+                    0,
                 );
-                sender_account
-                    .sign_with_transaction_builder(transaction_factory.payload(payload))?
+                let signature = hardware_wallet
+                    .sign_arbitrary_message(&signing_message(message).map_err(sign_error)?)?;
+                Ok(AccountAuthenticator::ed25519(public_key, signature))
             },
-            Err(err) => return Err(err),
-        };
+            AccountType::ExternalSigner(external_signer) => {
+                let (public_key, address) = self.get_public_key_and_address()?;
+                let signature = external_signer.sign_message(address, &public_key, message)?;
+                Ok(AccountAuthenticator::ed25519(public_key, signature))
+            },
+            AccountType::AccountKeys(account_keys) => account_keys.authenticator(message),
+        }
+    }
 
+    /// Submits a signed transaction, printing out a useful transaction link, and waits for it
+    pub async fn submit_signed_transaction(
+        &self,
+        client: &Client,
+        transaction: SignedTransaction,
+    ) -> CliTypedResult<Transaction> {
         // Submit the transaction, printing out a useful transaction link
         client
             .submit_bcs(&transaction)
@@ -2053,7 +2370,7 @@ impl TransactionOptions {
         const DEFAULT_GAS_UNIT_PRICE: u64 = 100;
         const DEFAULT_MAX_GAS: u64 = 2_000_000;
 
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let sender_address = self.sender_address()?;
         let gas_unit_price = self
             .gas_options
             .gas_unit_price
@@ -2081,9 +2398,14 @@ impl TransactionOptions {
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let raw_txn = transaction_factory
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build();
+        // Signed by whichever signer the profile uses, as the prologue checks the authenticator
+        let authenticator = self.sign_message(&raw_txn)?;
+        let transaction = SignedTransaction::new_single_sender(raw_txn, authenticator);
         let hash = transaction.committed_hash();
 
         let debugger = AptosDebugger::rest_client(client).unwrap();
@@ -19,7 +19,10 @@ use aptos_crypto::{
 };
 use aptos_keygen::KeyGen;
 use aptos_logger::{debug, Level};
-use aptos_rest_client::{aptos_api_types::HashValue, Account, Client, FaucetClient, State};
+use aptos_rest_client::{
+    aptos_api_types::{HashValue, ViewFunction},
+    Account, Client, FaucetClient, State,
+};
 use aptos_telemetry::service::telemetry_is_disabled;
 use aptos_types::{
     account_address::create_multisig_account_address,
@@ -28,7 +31,11 @@ use aptos_types::{
     transaction::{authenticator::AuthenticationKey, TransactionPayload},
 };
 use itertools::Itertools;
-use move_core_types::{account_address::AccountAddress, language_storage::CORE_CODE_ADDRESS};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag, CORE_CODE_ADDRESS},
+};
 use reqwest::Url;
 use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(unix)]
@@ -570,6 +577,44 @@ pub fn view_json_option_str(option_ref: &serde_json::Value) -> CliTypedResult<Op
     }
 }
 
+/// Runs a view function, returning its JSON encoded return values
+///
+/// Runs at the given ledger version, or at the latest one if none is given.
+pub async fn view_function(
+    client: &Client,
+    address: AccountAddress,
+    module: &str,
+    function: &str,
+    ty_args: Vec<TypeTag>,
+    args: Vec<Vec<u8>>,
+    version: Option<u64>,
+) -> CliTypedResult<Vec<serde_json::Value>> {
+    let view_function = ViewFunction {
+        module: ModuleId::new(address, Identifier::new(module)?),
+        function: Identifier::new(function)?,
+        ty_args,
+        args,
+    };
+    Ok(client
+        .view_bcs_with_json_response(&view_function, version)
+        .await?
+        .into_inner())
+}
+
+/// Convert a view function JSON number into a number.
+///
+/// Integers wider than 32 bits are returned as JSON strings, narrower ones as JSON numbers.
+pub fn view_json_number<T: FromStr>(number_ref: &serde_json::Value) -> CliTypedResult<T> {
+    let parsed = match number_ref {
+        serde_json::Value::String(number) => number.parse().ok(),
+        serde_json::Value::Number(number) => number.to_string().parse().ok(),
+        _ => None,
+    };
+    parsed.ok_or_else(|| {
+        CliError::UnexpectedError(format!("JSON value is not a number: {}", number_ref))
+    })
+}
+
 pub fn explorer_account_link(hash: AccountAddress, network: Option<Network>) -> String {
     // For now, default to what the browser is already on, though the link could be wrong
     if let Some(network) = network {
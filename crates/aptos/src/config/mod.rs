@@ -3,8 +3,12 @@
 
 use crate::{
     common::{
+        account_keys::{parse_any_public_key, AccountKeys, AnyPrivateKey},
+        external_signer::ExternalSigner,
+        keystore::{read_new_passphrase, EncryptedPrivateKey},
+        networks::{canonical_network_name, validate_network_name, NetworkConfig, NetworkRegistry},
         types::{
-            CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode,
+            CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode, KeyType,
             ProfileSummary, APTOS_FOLDER_GIT_IGNORE, CONFIG_FOLDER, GIT_IGNORE,
         },
         utils::{create_dir_if_not_exist, current_dir, read_from_file, write_to_user_only_file},
@@ -13,7 +17,9 @@ use crate::{
     Tool,
 };
 use aptos_cli_common::generate_cli_completions;
-use aptos_crypto::ValidCryptoMaterialStringExt;
+use aptos_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
+use aptos_keygen::KeyGen;
+use aptos_types::{account_address::AccountAddress, transaction::authenticator::AnyPublicKey};
 use async_trait::async_trait;
 use clap::{Parser, ValueEnum};
 use clap_complete::Shell;
@@ -33,16 +39,28 @@ pub enum ConfigTool {
     ShowPrivateKey(ShowPrivateKey),
     RenameProfile(RenameProfile),
     DeleteProfile(DeleteProfile),
+    ShowNetworks(ShowNetworks),
+    AddNetwork(AddNetwork),
+    EncryptPrivateKey(EncryptPrivateKey),
+    SetExternalSigner(SetExternalSigner),
+    SetSingleKey(SetSingleKey),
+    SetMultiKey(SetMultiKey),
 }
 
 impl ConfigTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            ConfigTool::AddNetwork(tool) => tool.execute_serialized().await,
             ConfigTool::DeleteProfile(tool) => tool.execute_serialized().await,
+            ConfigTool::EncryptPrivateKey(tool) => tool.execute_serialized().await,
             ConfigTool::GenerateShellCompletions(tool) => tool.execute_serialized_success().await,
             ConfigTool::RenameProfile(tool) => tool.execute_serialized().await,
+            ConfigTool::SetExternalSigner(tool) => tool.execute_serialized().await,
             ConfigTool::SetGlobalConfig(tool) => tool.execute_serialized().await,
+            ConfigTool::SetMultiKey(tool) => tool.execute_serialized().await,
+            ConfigTool::SetSingleKey(tool) => tool.execute_serialized().await,
             ConfigTool::ShowGlobalConfig(tool) => tool.execute_serialized().await,
+            ConfigTool::ShowNetworks(tool) => tool.execute_serialized().await,
             ConfigTool::ShowPrivateKey(tool) => tool.execute_serialized().await,
             ConfigTool::ShowProfiles(tool) => tool.execute_serialized().await,
         }
@@ -138,7 +156,7 @@ impl CliCommand<String> for ShowPrivateKey {
 
         if let Some(profiles) = &config.profiles {
             if let Some(profile) = profiles.get(&self.profile.clone()) {
-                if let Some(private_key) = &profile.private_key {
+                if let Some(private_key) = profile.unlock_private_key(Some(&self.profile))? {
                     Ok(private_key.to_aip_80_string()?)
                 } else {
                     Err(CliError::CommandArgumentError(format!(
@@ -160,6 +178,295 @@ impl CliCommand<String> for ShowPrivateKey {
     }
 }
 
+/// Encrypt the private key of a profile with a passphrase
+///
+/// This encrypts the Ed25519 private key of the profile, or the private keys of its single-key or
+/// multi-key account. The passphrase is read from `MOVEMENT_KEYSTORE_PASSPHRASE`, or prompted for.
+/// Commands signing with the profile ask for it again, or read it from the same variable.
+#[derive(Parser, Debug)]
+pub struct EncryptPrivateKey {
+    /// Which profile's private key to encrypt
+    #[clap(long)]
+    profile: String,
+}
+
+#[async_trait]
+impl CliCommand<String> for EncryptPrivateKey {
+    fn command_name(&self) -> &'static str {
+        "EncryptPrivateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+        let profile = config
+            .profiles
+            .as_mut()
+            .and_then(|profiles| profiles.get_mut(&self.profile))
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Profile {} does not exist", self.profile))
+            })?;
+
+        match (profile.private_key.take(), profile.account_keys.take()) {
+            (Some(private_key), _) => {
+                profile.encrypted_private_key = Some(EncryptedPrivateKey::encrypt(
+                    &private_key,
+                    &read_new_passphrase()?,
+                )?);
+            },
+            (None, Some(account_keys)) if !account_keys.is_encrypted() => {
+                profile.account_keys = Some(account_keys.encrypt(&read_new_passphrase()?)?);
+            },
+            _ => {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} does not have a plaintext private key",
+                    self.profile
+                )))
+            },
+        }
+        config.save()?;
+
+        Ok(format!(
+            "Encrypted the private key of profile {}",
+            self.profile
+        ))
+    }
+}
+
+/// Sign the transactions of a profile with an external signer
+///
+/// The signer is either a program, run for every transaction with a JSON request on stdin and
+/// the JSON response on stdout, or a Unix domain socket of a signing daemon. Any private key of
+/// the profile is removed.
+#[derive(Parser, Debug)]
+pub struct SetExternalSigner {
+    /// Which profile to sign for
+    #[clap(long)]
+    profile: String,
+
+    /// Program to run for every signature
+    #[clap(long, conflicts_with = "socket", required_unless_present = "socket")]
+    program: Option<String>,
+
+    /// Argument to pass to the program, can be repeated
+    #[clap(long = "arg", requires = "program", allow_hyphen_values = true)]
+    args: Vec<String>,
+
+    /// Unix domain socket of a signing daemon
+    #[clap(long, value_parser)]
+    socket: Option<PathBuf>,
+
+    /// Public key of the signer, if the profile does not have it yet
+    #[clap(long, value_parser = Ed25519PublicKey::from_encoded_string)]
+    public_key: Option<Ed25519PublicKey>,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for SetExternalSigner {
+    fn command_name(&self) -> &'static str {
+        "SetExternalSigner"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+        let profile = config
+            .profiles
+            .as_mut()
+            .and_then(|profiles| profiles.get_mut(&self.profile))
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Profile {} does not exist", self.profile))
+            })?;
+
+        if let Some(public_key) = self.public_key {
+            profile.public_key = Some(public_key);
+        }
+        if profile.public_key.is_none() {
+            return Err(CliError::CommandArgumentError(
+                "The profile has no public key, add --public-key".to_string(),
+            ));
+        }
+
+        profile.external_signer = Some(match (self.program, self.socket) {
+            (Some(program), _) => ExternalSigner::Command {
+                program,
+                args: self.args,
+            },
+            (None, Some(path)) => ExternalSigner::Socket { path },
+            (None, None) => unreachable!("clap requires --program or --socket"),
+        });
+        profile.private_key = None;
+        profile.encrypted_private_key = None;
+        profile.derivation_path = None;
+        profile.account_keys = None;
+
+        let summary = ProfileSummary::from(&*profile);
+        config.save()?;
+        Ok(summary)
+    }
+}
+
+/// Replaces the signing keys of a profile with the keys of a single-key or multi-key account
+fn set_account_keys(
+    profile_name: &str,
+    account_keys: AccountKeys,
+    account: Option<AccountAddress>,
+    encrypt_private_key: bool,
+) -> CliTypedResult<ProfileSummary> {
+    let mut config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+    let profile = config
+        .profiles
+        .as_mut()
+        .and_then(|profiles| profiles.get_mut(profile_name))
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} does not exist", profile_name))
+        })?;
+
+    let account = match account {
+        Some(account) => account,
+        None => account_keys.authentication_key()?.account_address(),
+    };
+    let account_keys = if encrypt_private_key {
+        account_keys.encrypt(&read_new_passphrase()?)?
+    } else {
+        account_keys
+    };
+    profile.account = Some(account);
+    profile.account_keys = Some(account_keys);
+    profile.private_key = None;
+    profile.public_key = None;
+    profile.encrypted_private_key = None;
+    profile.external_signer = None;
+    profile.derivation_path = None;
+
+    let summary = ProfileSummary::from(&*profile);
+    config.save()?;
+    Ok(summary)
+}
+
+/// Sign the transactions of a profile as a single-key account
+///
+/// Single-key accounts can sign with Secp256k1 ECDSA keys, as well as Ed25519 keys under a
+/// different address than legacy accounts. A new key is generated unless one is given, and the
+/// address of the profile is derived from the key unless `--account` is given.
+#[derive(Parser, Debug)]
+pub struct SetSingleKey {
+    /// Which profile to set the key of
+    #[clap(long)]
+    profile: String,
+
+    /// Type of the key to generate, or of a given key without an AIP-80 prefix
+    #[clap(long, default_value_t = KeyType::Secp256k1Ecdsa)]
+    key_type: KeyType,
+
+    /// Private key, either with an AIP-80 prefix such as `secp256k1-priv-` or hex encoded
+    #[clap(long, group = "private_key_input")]
+    private_key: Option<String>,
+
+    /// File containing the private key
+    #[clap(long, group = "private_key_input", value_parser)]
+    private_key_file: Option<PathBuf>,
+
+    /// Address of the account, for an account whose authentication key was rotated
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    account: Option<AccountAddress>,
+
+    /// Whether to store the private keys encrypted with a passphrase
+    ///
+    /// The passphrase is read from `MOVEMENT_KEYSTORE_PASSPHRASE`, or prompted for. Commands
+    /// signing with the profile ask for it again, or read it from the same variable.
+    #[clap(long)]
+    encrypt_private_key: bool,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for SetSingleKey {
+    fn command_name(&self) -> &'static str {
+        "SetSingleKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let encoded = match (self.private_key, self.private_key_file) {
+            (Some(private_key), _) => Some(private_key),
+            (None, Some(file)) => Some(
+                String::from_utf8(read_from_file(&file)?)
+                    .map_err(|err| CliError::UnableToParse("private key file", err.to_string()))?,
+            ),
+            (None, None) => None,
+        };
+        let private_key = match encoded {
+            Some(encoded) => AnyPrivateKey::from_encoded_string(Some(self.key_type), &encoded)?,
+            None => AnyPrivateKey::generate(self.key_type, &mut KeyGen::from_os_rng())?,
+        };
+
+        set_account_keys(
+            &self.profile,
+            AccountKeys::single_key(private_key),
+            self.account,
+            self.encrypt_private_key,
+        )
+    }
+}
+
+/// Sign the transactions of a profile as a multi-key account
+///
+/// A multi-key account is authenticated by a threshold of its public keys, which can be of mixed
+/// types. The profile signs with the given private keys, which have to reach the threshold on
+/// their own. The address of the profile is derived from the public keys unless `--account` is
+/// given.
+#[derive(Parser, Debug)]
+pub struct SetMultiKey {
+    /// Which profile to set the keys of
+    #[clap(long)]
+    profile: String,
+
+    /// Public key of the account with its AIP-80 prefix, repeated in the order of the account's
+    /// keys
+    #[clap(long = "public-key", required = true, value_parser = parse_any_public_key)]
+    public_keys: Vec<AnyPublicKey>,
+
+    /// Number of signatures required to authenticate the account
+    #[clap(long)]
+    signatures_required: u8,
+
+    /// Private key with an AIP-80 prefix for one of the public keys, can be repeated
+    #[clap(long = "private-key", required = true)]
+    private_keys: Vec<String>,
+
+    /// Address of the account, for an account whose authentication key was rotated
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    account: Option<AccountAddress>,
+
+    /// Whether to store the private keys encrypted with a passphrase
+    ///
+    /// The passphrase is read from `MOVEMENT_KEYSTORE_PASSPHRASE`, or prompted for. Commands
+    /// signing with the profile ask for it again, or read it from the same variable.
+    #[clap(long)]
+    encrypt_private_key: bool,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for SetMultiKey {
+    fn command_name(&self) -> &'static str {
+        "SetMultiKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let private_keys = self
+            .private_keys
+            .iter()
+            .map(|encoded| AnyPrivateKey::from_encoded_string(None, encoded))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        let account_keys =
+            AccountKeys::multi_key(self.public_keys, self.signatures_required, private_keys)?;
+
+        set_account_keys(
+            &self.profile,
+            account_keys,
+            self.account,
+            self.encrypt_private_key,
+        )
+    }
+}
+
 /// Shows the current profiles available
 ///
 /// This will only show public information and will not show
@@ -198,6 +505,85 @@ impl CliCommand<BTreeMap<String, ProfileSummary>> for ShowProfiles {
     }
 }
 
+/// Shows the networks available to `--network`
+///
+/// This includes the built-in networks and the ones added to the CLI config
+#[derive(Parser, Debug)]
+pub struct ShowNetworks {}
+
+#[async_trait]
+impl CliCommand<BTreeMap<String, NetworkConfig>> for ShowNetworks {
+    fn command_name(&self) -> &'static str {
+        "ShowNetworks"
+    }
+
+    async fn execute(self) -> CliTypedResult<BTreeMap<String, NetworkConfig>> {
+        Ok(NetworkRegistry::load()?.networks().clone())
+    }
+}
+
+/// Add a named network to the CLI config
+///
+/// The network can then be used by name with `--network`, e.g. in
+/// `movement init`, `movement move replay` and `movement move sim init`.
+/// Adding a network with the name of a built-in one overrides it.
+#[derive(Parser, Debug)]
+pub struct AddNetwork {
+    /// Name of the network
+    #[clap(long)]
+    name: String,
+
+    /// URL for the REST endpoint of a fullnode on the network
+    #[clap(long)]
+    rest_url: reqwest::Url,
+
+    /// URL for the faucet endpoint of the network
+    #[clap(long)]
+    faucet_url: Option<reqwest::Url>,
+
+    /// URL for the indexer GraphQL endpoint of the network
+    ///
+    /// Used by the commands querying the indexer, unless they are given `--indexer-url`
+    #[clap(long)]
+    indexer_url: Option<reqwest::Url>,
+
+    /// Chain ID the REST endpoint has to report
+    #[clap(long)]
+    chain_id: Option<u8>,
+}
+
+#[async_trait]
+impl CliCommand<String> for AddNetwork {
+    fn command_name(&self) -> &'static str {
+        "AddNetwork"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        validate_network_name(&self.name)?;
+        let name = canonical_network_name(&self.name);
+
+        // Networks are looked up in the nearest config, so they are added to it as well
+        let mode = ConfigSearchMode::CurrentDirAndParents;
+        let mut config = if CliConfig::config_exists(mode) {
+            CliConfig::load(mode)?
+        } else {
+            CliConfig::default()
+        };
+        config
+            .networks
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.clone(), NetworkConfig {
+                rest_url: self.rest_url.to_string(),
+                faucet_url: self.faucet_url.map(|url| url.to_string()),
+                indexer_url: self.indexer_url.map(|url| url.to_string()),
+                chain_id: self.chain_id,
+            });
+        config.save_to(mode)?;
+
+        Ok(format!("Added network {}", name))
+    }
+}
+
 /// Delete the specified profile.
 #[derive(Parser, Debug)]
 pub struct DeleteProfile {
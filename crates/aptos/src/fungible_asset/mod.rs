@@ -0,0 +1,476 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, IndexerOptions, ProfileOptions,
+        RestOptions, TransactionOptions, TransactionSummary,
+    },
+    utils::{view_function, view_json_number, view_json_option_str},
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{EntryFunction, TransactionPayload},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use move_core_types::{
+    ident_str,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::str::FromStr;
+
+/// Tool for interacting with fungible assets
+///
+/// Fungible assets (FA) are identified by the address of their metadata object.
+/// Transfers, mints and burns go through the primary fungible stores of the accounts.
+#[derive(Debug, Subcommand)]
+pub enum FungibleAssetTool {
+    Balance(Balance),
+    Burn(Burn),
+    ListStores(ListStores),
+    Metadata(ShowMetadata),
+    Mint(Mint),
+    Transfer(Transfer),
+}
+
+impl FungibleAssetTool {
+    pub async fn execute(self) -> CliResult {
+        use FungibleAssetTool::*;
+        match self {
+            Balance(tool) => tool.execute_serialized().await,
+            Burn(tool) => tool.execute_serialized().await,
+            ListStores(tool) => tool.execute_serialized().await,
+            Metadata(tool) => tool.execute_serialized().await,
+            Mint(tool) => tool.execute_serialized().await,
+            Transfer(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The type tag of `0x1::fungible_asset::Metadata`
+fn metadata_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("fungible_asset").into(),
+        name: ident_str!("Metadata").into(),
+        type_args: vec![],
+    }))
+}
+
+/// Show the balance of a fungible asset in an account's primary store
+#[derive(Debug, Parser)]
+pub struct Balance {
+    /// Address of the account, defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Address of the metadata object of the fungible asset
+    #[clap(long)]
+    pub(crate) metadata: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FungibleAssetBalance {
+    pub account: AccountAddress,
+    pub metadata: AccountAddress,
+    pub balance: u64,
+    pub frozen: bool,
+}
+
+#[async_trait]
+impl CliCommand<FungibleAssetBalance> for Balance {
+    fn command_name(&self) -> &'static str {
+        "FungibleAssetBalance"
+    }
+
+    async fn execute(self) -> CliTypedResult<FungibleAssetBalance> {
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let client = self.rest_options.client(&self.profile_options)?;
+        let args = vec![account.to_vec(), self.metadata.to_vec()];
+
+        let balance = view_function(
+            &client,
+            AccountAddress::ONE,
+            "primary_fungible_store",
+            "balance",
+            vec![metadata_type()],
+            args.clone(),
+            None,
+        )
+        .await?;
+        let frozen = view_function(
+            &client,
+            AccountAddress::ONE,
+            "primary_fungible_store",
+            "is_frozen",
+            vec![metadata_type()],
+            args,
+            None,
+        )
+        .await?;
+
+        Ok(FungibleAssetBalance {
+            account,
+            metadata: self.metadata,
+            balance: view_json_number(&balance[0])?,
+            frozen: serde_json::from_value(frozen[0].clone())
+                .map_err(|err| CliError::UnableToParse("frozen status", err.to_string()))?,
+        })
+    }
+}
+
+/// Show the metadata and supply of a fungible asset
+#[derive(Debug, Parser)]
+pub struct ShowMetadata {
+    /// Address of the metadata object of the fungible asset
+    #[clap(long)]
+    pub(crate) metadata: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FungibleAssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon_uri: String,
+    pub project_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FungibleAssetSummary {
+    pub address: AccountAddress,
+    #[serde(flatten)]
+    pub metadata: FungibleAssetMetadata,
+    /// Current supply, if it is tracked
+    pub supply: Option<u128>,
+    /// Maximum supply, if it is limited
+    pub maximum: Option<u128>,
+}
+
+#[async_trait]
+impl CliCommand<FungibleAssetSummary> for ShowMetadata {
+    fn command_name(&self) -> &'static str {
+        "FungibleAssetMetadata"
+    }
+
+    async fn execute(self) -> CliTypedResult<FungibleAssetSummary> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let view_metadata = |function: &'static str| {
+            view_function(
+                &client,
+                AccountAddress::ONE,
+                "fungible_asset",
+                function,
+                vec![metadata_type()],
+                vec![self.metadata.to_vec()],
+                None,
+            )
+        };
+
+        let metadata = view_metadata("metadata").await?;
+        let supply = view_metadata("supply").await?;
+        let maximum = view_metadata("maximum").await?;
+
+        let parse_supply = |value: &serde_json::Value| {
+            view_json_option_str(value)?
+                .map(|supply| view_json_number(&json!(supply)))
+                .transpose()
+        };
+
+        Ok(FungibleAssetSummary {
+            address: self.metadata,
+            metadata: serde_json::from_value(metadata[0].clone())
+                .map_err(|err| CliError::UnableToParse("metadata", err.to_string()))?,
+            supply: parse_supply(&supply[0])?,
+            maximum: parse_supply(&maximum[0])?,
+        })
+    }
+}
+
+/// List the fungible stores owned by an account
+///
+/// Stores are looked up through the indexer, so this covers secondary stores as well as
+/// primary stores, but may lag behind the chain.
+#[derive(Debug, Parser)]
+pub struct ListStores {
+    /// Address of the account, defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) indexer_options: IndexerOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FungibleStore {
+    /// Address of the store, or the coin type for coins that are not migrated yet
+    #[serde(rename(deserialize = "storage_id"))]
+    pub store: String,
+    /// Address of the metadata object, or the coin type for coins
+    #[serde(rename(deserialize = "asset_type"))]
+    pub metadata: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    pub amount: serde_json::Value,
+    pub is_primary: bool,
+    pub is_frozen: bool,
+    pub token_standard: String,
+}
+
+const LIST_STORES_QUERY: &str = r#"
+query FungibleStores($owner: String) {
+  current_fungible_asset_balances(
+    where: { owner_address: { _eq: $owner } }
+    order_by: { asset_type: asc }
+  ) {
+    storage_id
+    asset_type
+    amount
+    is_primary
+    is_frozen
+    token_standard
+    metadata {
+      symbol
+      decimals
+    }
+  }
+}
+"#;
+
+#[async_trait]
+impl CliCommand<Vec<FungibleStore>> for ListStores {
+    fn command_name(&self) -> &'static str {
+        "ListFungibleStores"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<FungibleStore>> {
+        #[derive(Deserialize)]
+        struct StoreMetadata {
+            symbol: String,
+            decimals: u8,
+        }
+
+        #[derive(Deserialize)]
+        struct IndexedStore {
+            #[serde(flatten)]
+            store: FungibleStore,
+            metadata: Option<StoreMetadata>,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            current_fungible_asset_balances: Vec<IndexedStore>,
+        }
+
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let response: Response = self
+            .indexer_options
+            .query(
+                &self.profile_options,
+                LIST_STORES_QUERY,
+                json!({ "owner": account.to_standard_string() }),
+            )
+            .await?;
+
+        Ok(response
+            .current_fungible_asset_balances
+            .into_iter()
+            .map(|indexed| {
+                let mut store = indexed.store;
+                if let Some(metadata) = indexed.metadata {
+                    store.symbol = Some(metadata.symbol);
+                    store.decimals = Some(metadata.decimals);
+                }
+                store
+            })
+            .collect())
+    }
+}
+
+/// Transfer a fungible asset to another account
+///
+/// The asset is moved between the primary stores of the sender and the receiver, creating the
+/// receiver's store if needed.
+#[derive(Debug, Parser)]
+pub struct Transfer {
+    /// Address of the metadata object of the fungible asset
+    #[clap(long)]
+    pub(crate) metadata: AccountAddress,
+
+    /// Address of the receiving account
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) to: AccountAddress,
+
+    /// Amount to transfer, in the smallest unit of the asset
+    #[clap(long)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Transfer {
+    fn command_name(&self) -> &'static str {
+        "TransferFungibleAsset"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                AccountAddress::ONE,
+                ident_str!("primary_fungible_store").to_owned(),
+            ),
+            ident_str!("transfer").to_owned(),
+            vec![metadata_type()],
+            vec![
+                bcs::to_bytes(&self.metadata)?,
+                bcs::to_bytes(&self.to)?,
+                bcs::to_bytes(&self.amount)?,
+            ],
+        ));
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+/// Options to pick the module managing a fungible asset
+#[derive(Debug, Parser)]
+pub struct ManagerOptions {
+    /// Address of the metadata object of the fungible asset
+    #[clap(long)]
+    pub(crate) metadata: AccountAddress,
+
+    /// Module managing the fungible asset e.g. `0x42::managed_fungible_asset`
+    ///
+    /// The module has to provide `mint_to_primary_stores` and `burn_from_primary_stores` entry
+    /// functions like the `managed_fungible_asset` example, taking the metadata object, a vector
+    /// of accounts and a vector of amounts.
+    #[clap(long, value_parser = parse_module_id)]
+    pub(crate) manager: ModuleId,
+}
+
+fn parse_module_id(module: &str) -> CliTypedResult<ModuleId> {
+    ModuleId::from_str(module)
+        .map_err(|err| CliError::UnableToParse("manager module", err.to_string()))
+}
+
+impl ManagerOptions {
+    fn payload(
+        &self,
+        function: &'static str,
+        account: AccountAddress,
+        amount: u64,
+    ) -> CliTypedResult<TransactionPayload> {
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            self.manager.clone(),
+            Identifier::new(function)?,
+            vec![],
+            vec![
+                bcs::to_bytes(&self.metadata)?,
+                bcs::to_bytes(&vec![account])?,
+                bcs::to_bytes(&vec![amount])?,
+            ],
+        )))
+    }
+}
+
+/// Mint a managed fungible asset into an account's primary store
+///
+/// The sender has to be the admin of the managing module.
+#[derive(Debug, Parser)]
+pub struct Mint {
+    #[clap(flatten)]
+    pub(crate) manager_options: ManagerOptions,
+
+    /// Address of the account to mint to
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) to: AccountAddress,
+
+    /// Amount to mint, in the smallest unit of the asset
+    #[clap(long)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Mint {
+    fn command_name(&self) -> &'static str {
+        "MintFungibleAsset"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload =
+            self.manager_options
+                .payload("mint_to_primary_stores", self.to, self.amount)?;
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+/// Burn a managed fungible asset from an account's primary store
+///
+/// The sender has to be the admin of the managing module.
+#[derive(Debug, Parser)]
+pub struct Burn {
+    #[clap(flatten)]
+    pub(crate) manager_options: ManagerOptions,
+
+    /// Address of the account to burn from
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) from: AccountAddress,
+
+    /// Amount to burn, in the smallest unit of the asset
+    #[clap(long)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Burn {
+    fn command_name(&self) -> &'static str {
+        "BurnFungibleAsset"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload =
+            self.manager_options
+                .payload("burn_from_primary_stores", self.from, self.amount)?;
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
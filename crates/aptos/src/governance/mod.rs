@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod delegation_pool;
+pub mod simulate;
 pub mod utils;
 
 #[cfg(feature = "no-upload-proposal")]
@@ -69,6 +70,7 @@ pub enum GovernanceTool {
     ShowProposal(ViewProposal),
     ListProposals(ListProposals),
     VerifyProposal(VerifyProposal),
+    SimulateProposal(simulate::SimulateProposal),
     ExecuteProposal(ExecuteProposal),
     GenerateUpgradeProposal(GenerateUpgradeProposal),
     ApproveExecutionHash(ApproveExecutionHash),
@@ -87,6 +89,7 @@ impl GovernanceTool {
             ShowProposal(tool) => tool.execute_serialized().await,
             ListProposals(tool) => tool.execute_serialized().await,
             VerifyProposal(tool) => tool.execute_serialized().await,
+            SimulateProposal(tool) => tool.execute_serialized().await,
             ApproveExecutionHash(tool) => tool.execute_serialized().await,
             DelegationPool(tool) => tool.execute().await,
         }
@@ -0,0 +1,460 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::{
+        CliCommand, CliError, CliTypedResult, ProfileOptions, PromptOptions, RestOptions,
+    },
+    governance::{compile_in_temp_dir, get_proposal, Proposal, VotingForum},
+    move_tool::FrameworkPackageArgs,
+};
+use aptos_crypto::HashValue;
+use aptos_transaction_simulation_session::{
+    governance::MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK, Session,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::{
+        ApprovedExecutionHashes, FeatureFlag, Features, GasScheduleV2, OnChainConfig,
+    },
+    transaction::{ExecutionStatus, TransactionStatus},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+/// Name of the session checkpoint holding the state before the proposal.
+const BEFORE_CHECKPOINT: &str = "before";
+
+/// Simulate a governance proposal against the current network state
+///
+/// The proposal scripts are executed in order on a fork of the network state as if the proposal
+/// had passed, followed by the reconfiguration that applies buffered config changes. No votes are
+/// needed and nothing is submitted to the network.
+///
+/// The resulting feature flag, gas schedule and on-chain config changes are reported, along with
+/// the status of every script.
+#[derive(Parser)]
+pub struct SimulateProposal {
+    /// The id of the onchain proposal
+    ///
+    /// If provided, the first script must match the execution hash of the proposal.
+    #[clap(long)]
+    pub(crate) proposal_id: Option<u64>,
+
+    /// Paths to the scripts of the proposal, in execution order
+    ///
+    /// Files ending in `.mv` are treated as compiled scripts, anything else is compiled as a
+    /// Move script against the framework.
+    #[clap(long, num_args = 1.., required = true, value_parser)]
+    pub(crate) script_paths: Vec<PathBuf>,
+
+    /// Directory to keep the simulation session in
+    ///
+    /// The session can be inspected further with `movement move sim`. If not provided, a
+    /// temporary directory is used and removed afterwards.
+    #[clap(long, value_parser)]
+    pub(crate) session_path: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) framework_package_args: FrameworkPackageArgs,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<ProposalSimulation> for SimulateProposal {
+    fn command_name(&self) -> &'static str {
+        "SimulateProposal"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProposalSimulation> {
+        let scripts = self
+            .script_paths
+            .iter()
+            .map(|path| self.load_script(path))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+
+        let client = self.rest_options.client(&self.profile)?;
+        if let Some(proposal_id) = self.proposal_id {
+            let forum = client
+                .get_account_resource_bcs::<VotingForum>(
+                    AccountAddress::ONE,
+                    "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
+                )
+                .await?
+                .into_inner();
+            let proposal: Proposal = get_proposal(&client, forum.table_handle.0, proposal_id)
+                .await?
+                .into();
+
+            let computed_hash = scripts[0].1.to_hex();
+            if computed_hash != proposal.execution_hash {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Hash of {} ({}) does not match the execution hash of proposal {} ({})",
+                    self.script_paths[0].display(),
+                    computed_hash,
+                    proposal_id,
+                    proposal.execution_hash
+                )));
+            }
+        }
+
+        let url = self.rest_options.url(&self.profile)?;
+        let version = client.get_ledger_information().await?.inner().version;
+
+        let temp_dir;
+        let session_path = match &self.session_path {
+            Some(path) => path.clone(),
+            None => {
+                temp_dir = TempDir::new().map_err(|err| {
+                    CliError::IO("Failed to create temporary directory".to_string(), err)
+                })?;
+                temp_dir.path().to_path_buf()
+            },
+        };
+        let mut session = Session::init_with_remote_state(
+            &session_path,
+            url,
+            version,
+            self.rest_options.node_api_key.clone(),
+        )?;
+
+        let features_before = fetch_config::<Features>(&session)?;
+        let gas_schedule_before = fetch_config::<GasScheduleV2>(&session)?;
+        session.checkpoint(BEFORE_CHECKPOINT)?;
+
+        let mut steps = vec![];
+        let mut success = true;
+        for (idx, (path, (script, hash))) in self.script_paths.iter().zip(scripts).enumerate() {
+            let last_step = idx + 1 == self.script_paths.len();
+            let (_, output) = session.execute_governance_script(script, last_step)?;
+
+            let (succeeded, status) = match output.status() {
+                TransactionStatus::Keep(ExecutionStatus::Success) => (true, "Success".to_string()),
+                TransactionStatus::Keep(ExecutionStatus::MoveAbort { code, .. })
+                    if *code == MAGIC_FAILED_NEXT_EXECUTION_HASH_CHECK =>
+                {
+                    (
+                        false,
+                        "The last script has a non-zero next execution hash".to_string(),
+                    )
+                },
+                status => (false, format!("{:?}", status)),
+            };
+            steps.push(ProposalSimulationStep {
+                script: path.clone(),
+                execution_hash: hash.to_hex(),
+                status,
+                gas_used: output.gas_used(),
+            });
+
+            // Later scripts depend on the earlier ones, so there is no point in running them
+            if !succeeded {
+                success = false;
+                break;
+            }
+        }
+
+        if success {
+            session.end_epoch()?;
+        }
+
+        let features_after = fetch_config::<Features>(&session)?;
+        let gas_schedule_after = fetch_config::<GasScheduleV2>(&session)?;
+
+        let (features_enabled, features_disabled) =
+            feature_changes(&features_before, &features_after);
+
+        Ok(ProposalSimulation {
+            success,
+            version,
+            steps,
+            features_enabled,
+            features_disabled,
+            gas_schedule_changes: gas_schedule_changes(&gas_schedule_before, &gas_schedule_after),
+            config_changes: config_changes(&session)?,
+            session_path: self.session_path,
+        })
+    }
+}
+
+impl SimulateProposal {
+    fn load_script(&self, path: &Path) -> CliTypedResult<(Vec<u8>, HashValue)> {
+        if path.extension().is_some_and(|ext| ext == "mv") {
+            let bytes = std::fs::read(path)
+                .map_err(|err| CliError::IO(format!("Unable to read {}", path.display()), err))?;
+            let hash = HashValue::sha3_256_of(&bytes);
+            return Ok((bytes, hash));
+        }
+
+        let script_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "script".to_string());
+        compile_in_temp_dir(
+            &script_name,
+            path,
+            &self.framework_package_args,
+            self.prompt_options,
+            None,
+            None,
+            None,
+        )
+    }
+}
+
+fn fetch_config<T: OnChainConfig>(session: &Session) -> CliTypedResult<T> {
+    T::fetch_config(session.state_store()).ok_or_else(|| {
+        CliError::UnexpectedError(format!(
+            "Failed to fetch on-chain config {}",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+/// Lists the feature flags enabled and disabled between two feature sets, by name where known.
+fn feature_changes(before: &Features, after: &Features) -> (Vec<String>, Vec<String>) {
+    let flags = |features: &Features| -> BTreeSet<usize> {
+        features
+            .features
+            .iter()
+            .enumerate()
+            .flat_map(|(byte_idx, byte)| {
+                (0..8)
+                    .filter(move |bit_idx| byte & (1 << bit_idx) != 0)
+                    .map(move |bit_idx| byte_idx * 8 + bit_idx)
+            })
+            .collect()
+    };
+    let name = |idx: &usize| match FeatureFlag::from_repr(*idx) {
+        Some(flag) => format!("{:?}", flag),
+        None => idx.to_string(),
+    };
+
+    let before = flags(before);
+    let after = flags(after);
+    (
+        after.difference(&before).map(name).collect(),
+        before.difference(&after).map(name).collect(),
+    )
+}
+
+fn gas_schedule_changes(
+    before: &GasScheduleV2,
+    after: &GasScheduleV2,
+) -> BTreeMap<String, ValueChange> {
+    let before_entries: BTreeMap<_, _> = before.entries.iter().cloned().collect();
+    let after_entries: BTreeMap<_, _> = after.entries.iter().cloned().collect();
+
+    let mut changes: BTreeMap<_, _> = before_entries
+        .keys()
+        .chain(after_entries.keys())
+        .filter_map(|name| {
+            let change = ValueChange {
+                before: before_entries.get(name).copied(),
+                after: after_entries.get(name).copied(),
+            };
+            (change.before != change.after).then(|| (name.clone(), change))
+        })
+        .collect();
+    if before.feature_version != after.feature_version {
+        changes.insert("feature_version".to_string(), ValueChange {
+            before: Some(before.feature_version),
+            after: Some(after.feature_version),
+        });
+    }
+    changes
+}
+
+/// Diffs the resources of the framework account, leaving out the configs reported separately and
+/// the execution hashes approved for the simulation itself.
+fn config_changes(session: &Session) -> CliTypedResult<BTreeMap<String, ConfigChange>> {
+    let prefix = format!("resource::{}::", AccountAddress::ONE);
+    let excluded: Vec<_> = [
+        <Features as OnChainConfig>::struct_tag(),
+        <GasScheduleV2 as OnChainConfig>::struct_tag(),
+        <ApprovedExecutionHashes as OnChainConfig>::struct_tag(),
+    ]
+    .iter()
+    .map(|tag| tag.to_canonical_string())
+    .collect();
+
+    let diff = session.diff(Some(BEFORE_CHECKPOINT), session, None)?;
+    Ok(diff
+        .into_iter()
+        .filter(|(key, _)| {
+            key.starts_with(&prefix) && !excluded.iter().any(|tag| key.ends_with(tag.as_str()))
+        })
+        .map(|(key, entry)| {
+            (key, ConfigChange {
+                before: entry.left,
+                after: entry.right,
+            })
+        })
+        .collect())
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProposalSimulation {
+    pub success: bool,
+    /// The network version the simulation forked from
+    pub version: u64,
+    pub steps: Vec<ProposalSimulationStep>,
+    pub features_enabled: Vec<String>,
+    pub features_disabled: Vec<String>,
+    pub gas_schedule_changes: BTreeMap<String, ValueChange>,
+    pub config_changes: BTreeMap<String, ConfigChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProposalSimulationStep {
+    pub script: PathBuf,
+    pub execution_hash: String,
+    pub status: String,
+    pub gas_used: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ValueChange {
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigChange {
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::CliTestFramework;
+
+    fn gas_schedule(feature_version: u64, entries: &[(&str, u64)]) -> GasScheduleV2 {
+        GasScheduleV2 {
+            feature_version,
+            entries: entries
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_feature_changes() {
+        let before = Features {
+            features: vec![0b0000_0110],
+        };
+        let after = Features {
+            features: vec![0b0000_1100, 0b0000_0001],
+        };
+        let name = |idx: usize| format!("{:?}", FeatureFlag::from_repr(idx).unwrap());
+
+        let (enabled, disabled) = feature_changes(&before, &after);
+        assert_eq!(enabled, vec![name(3), name(8)]);
+        assert_eq!(disabled, vec![name(1)]);
+        assert_eq!(feature_changes(&after, &after), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_gas_schedule_changes() {
+        let before = gas_schedule(10, &[("txn.a", 1), ("txn.b", 2), ("txn.c", 3)]);
+        let after = gas_schedule(11, &[("txn.a", 1), ("txn.b", 4), ("txn.d", 5)]);
+
+        let changes: Vec<_> = gas_schedule_changes(&before, &after)
+            .into_iter()
+            .map(|(name, change)| (name, change.before, change.after))
+            .collect();
+        assert_eq!(changes, vec![
+            ("feature_version".to_string(), Some(10), Some(11)),
+            ("txn.b".to_string(), Some(2), Some(4)),
+            ("txn.c".to_string(), Some(3), None),
+            ("txn.d".to_string(), None, Some(5)),
+        ]);
+        assert!(gas_schedule_changes(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_simulate_feature_flag_proposal() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = Session::init(temp_dir.path().join("session")).unwrap();
+        let features_before = fetch_config::<Features>(&session).unwrap();
+        let gas_schedule_before = fetch_config::<GasScheduleV2>(&session).unwrap();
+        session.checkpoint(BEFORE_CHECKPOINT).unwrap();
+
+        // Deprecated flags have no effect, so enabling one can't break the reconfiguration
+        let flag = (0..features_before.features.len() * 8)
+            .filter_map(FeatureFlag::from_repr)
+            .find(|flag| {
+                format!("{:?}", flag).starts_with("_DEPRECATED")
+                    && !features_before.is_enabled(*flag)
+            })
+            .expect("Genesis should leave a deprecated feature flag disabled");
+        let script_path = temp_dir.path().join("enable_feature.move");
+        std::fs::write(
+            &script_path,
+            format!(
+                r#"script {{
+    use aptos_framework::aptos_governance;
+    use std::features;
+
+    fun main(proposal_id: u64) {{
+        let framework_signer = aptos_governance::resolve(proposal_id, @0x1);
+        features::change_feature_flags_for_next_epoch(&framework_signer, vector[{}], vector[]);
+    }}
+}}"#,
+                flag as u64
+            ),
+        )
+        .unwrap();
+        let (script, _) = compile_in_temp_dir(
+            "enable_feature",
+            &script_path,
+            &FrameworkPackageArgs {
+                framework_git_rev: None,
+                framework_local_dir: Some(CliTestFramework::aptos_framework_dir()),
+                skip_fetch_latest_git_deps: true,
+            },
+            PromptOptions::yes(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let (_, output) = session.execute_governance_script(script, true).unwrap();
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(ExecutionStatus::Success)
+        );
+
+        // The change is buffered until the epoch ends
+        let features = fetch_config::<Features>(&session).unwrap();
+        assert_eq!(
+            feature_changes(&features_before, &features),
+            (vec![], vec![])
+        );
+
+        session.end_epoch().unwrap();
+        let features_after = fetch_config::<Features>(&session).unwrap();
+        assert_eq!(
+            feature_changes(&features_before, &features_after),
+            (vec![format!("{:?}", flag)], vec![])
+        );
+        let gas_schedule_after = fetch_config::<GasScheduleV2>(&session).unwrap();
+        assert!(gas_schedule_changes(&gas_schedule_before, &gas_schedule_after).is_empty());
+    }
+}
@@ -6,14 +6,18 @@
 pub mod account;
 pub mod common;
 pub mod config;
+pub mod fungible_asset;
 pub mod genesis;
 pub mod governance;
 pub mod move_tool;
 pub mod node;
+pub mod object;
 pub mod op;
 pub mod stake;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test;
+pub mod token;
+pub mod transaction;
 pub mod update;
 pub mod workspace;
 
@@ -35,6 +39,8 @@ pub enum Tool {
     #[clap(subcommand)]
     Config(config::ConfigTool),
     #[clap(subcommand)]
+    Fa(fungible_asset::FungibleAssetTool),
+    #[clap(subcommand)]
     Genesis(genesis::GenesisTool),
     #[clap(subcommand)]
     Governance(governance::GovernanceTool),
@@ -49,8 +55,14 @@ pub enum Tool {
     #[clap(subcommand)]
     Node(node::NodeTool),
     #[clap(subcommand)]
+    Object(object::ObjectTool),
+    #[clap(subcommand)]
     Stake(stake::StakeTool),
     #[clap(subcommand)]
+    Token(token::TokenTool),
+    #[clap(subcommand)]
+    Tx(transaction::TransactionTool),
+    #[clap(subcommand)]
     Update(update::UpdateTool),
     #[clap(subcommand, hide(true))]
     Workspace(WorkspaceCommand),
@@ -62,6 +74,7 @@ impl Tool {
         match self {
             Account(tool) => tool.execute().await,
             Config(tool) => tool.execute().await,
+            Fa(tool) => tool.execute().await,
             Genesis(tool) => tool.execute().await,
             Governance(tool) => tool.execute().await,
             Info(tool) => tool.execute_serialized().await,
@@ -71,7 +84,10 @@ impl Tool {
             Move(tool) => tool.execute().await,
             Multisig(tool) => tool.execute().await,
             Node(tool) => tool.execute().await,
+            Object(tool) => tool.execute().await,
             Stake(tool) => tool.execute().await,
+            Token(tool) => tool.execute().await,
+            Tx(tool) => tool.execute().await,
             Update(tool) => tool.execute().await,
             Workspace(workspace) => workspace.execute_serialized_without_logger().await,
         }
@@ -5,6 +5,7 @@ use crate::{
     account::derive_resource_account::ResourceAccountSeed,
     common::{
         local_simulation,
+        networks::{self, validate_network_name, NetworkRegistry},
         transactions::TxnOptions,
         types::{
             load_account_arg, ArgWithTypeJSON, ChunkedPublishOption, CliConfig, CliError,
@@ -86,6 +87,7 @@ mod manifest;
 pub mod package_hooks;
 mod show;
 mod sim;
+mod sim_server;
 pub mod stored_package;
 
 const HELLO_BLOCKCHAIN_EXAMPLE: &str = include_str!(
@@ -760,7 +762,7 @@ impl CliCommand<&'static str> for DocumentPackage {
     }
 }
 
-#[derive(Parser)]
+#[derive(Debug, Parser)]
 pub struct IncludedArtifactsArgs {
     /// Artifacts to be generated when building the package
     ///
@@ -2247,16 +2249,35 @@ pub enum ReplayNetworkSelection {
     Mainnet,
     Testnet,
     Devnet,
+    /// A network from the [NetworkRegistry], such as one added to the CLI config
+    Named(String),
     RestEndpoint(String),
 }
 
+impl ReplayNetworkSelection {
+    /// Resolves the REST URL of the network through the [NetworkRegistry]
+    pub fn rest_url(&self) -> CliTypedResult<Url> {
+        let name = match self {
+            Self::Mainnet => networks::MAINNET,
+            Self::Testnet => networks::TESTNET,
+            Self::Devnet => networks::DEVNET,
+            Self::Named(name) => name.as_str(),
+            Self::RestEndpoint(url) => {
+                return Url::parse(url)
+                    .map_err(|_err| CliError::UnableToParse("url", url.to_string()))
+            },
+        };
+        NetworkRegistry::load()?.get(name)?.rest_url()
+    }
+}
+
 /// Replay a comitted transaction using a local VM.
 #[derive(Parser, Debug)]
 pub struct Replay {
     /// The network to replay on.
     ///
     /// Possible values:
-    ///     mainnet, testnet, <REST_ENDPOINT_URL>
+    ///     mainnet, testnet, devnet, <NETWORK_FROM_CLI_CONFIG>, <REST_ENDPOINT_URL>
     #[clap(long)]
     pub(crate) network: ReplayNetworkSelection,
 
@@ -2290,6 +2311,7 @@ impl FromStr for ReplayNetworkSelection {
             "mainnet" => Self::Mainnet,
             "testnet" => Self::Testnet,
             "devnet" => Self::Devnet,
+            _ if validate_network_name(s).is_ok() => Self::Named(s.to_owned()),
             _ => Self::RestEndpoint(s.to_owned()),
         })
     }
@@ -2302,26 +2324,14 @@ impl CliCommand<TransactionSummary> for Replay {
     }
 
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        use ReplayNetworkSelection::*;
-
         if self.profile_gas && self.benchmark {
             return Err(CliError::UnexpectedError(
                 "Cannot perform benchmarking and gas profiling at the same time.".to_string(),
             ));
         }
 
-        let rest_endpoint = match &self.network {
-            Mainnet => "https://mainnet.movementnetwork.xyz",
-            Testnet => "https://testnet.movementnetwork.xyz",
-            Devnet => "https://devnet.movementnetwork.xyz",
-            RestEndpoint(url) => url,
-        };
-
         // Build the client
-        let client = Client::builder(AptosBaseUrl::Custom(
-            Url::parse(rest_endpoint)
-                .map_err(|_err| CliError::UnableToParse("url", rest_endpoint.to_string()))?,
-        ));
+        let client = Client::builder(AptosBaseUrl::Custom(self.network.rest_url()?));
 
         // add the node API key if it is provided
         let client = if let Some(api_key) = self.node_api_key {
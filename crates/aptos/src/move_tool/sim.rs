@@ -3,35 +3,24 @@
 
 use crate::{
     common::types::{
-        CliCommand, CliError, CliResult, CliTypedResult, EntryFunctionArguments, TransactionSummary,
+        CliCommand, CliError, CliResult, CliTypedResult, EntryFunctionArguments,
+        MovePackageOptions, TransactionSummary,
     },
-    move_tool::ReplayNetworkSelection,
+    move_tool::{sim_server, IncludedArtifactsArgs, ReplayNetworkSelection},
 };
 use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
 use aptos_rest_client::{AptosBaseUrl, Client};
-use aptos_transaction_simulation_session::Session;
+use aptos_transaction_simulation_session::{PublishOptions, Session, SessionDiff};
 use aptos_types::transaction::{
     EntryFunction, RawTransaction, SignedTransaction, TransactionPayload,
 };
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
 use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
-use std::path::PathBuf;
-use url::Url;
-
-/// Convert ReplayNetworkSelection to a URL
-fn network_to_url(network: &ReplayNetworkSelection) -> Result<Url, crate::common::types::CliError> {
-    match network {
-        ReplayNetworkSelection::Mainnet => Url::parse("https://mainnet.aptoslabs.com")
-            .map_err(|e| crate::common::types::CliError::UnexpectedError(e.to_string())),
-        ReplayNetworkSelection::Testnet => Url::parse("https://testnet.aptoslabs.com")
-            .map_err(|e| crate::common::types::CliError::UnexpectedError(e.to_string())),
-        ReplayNetworkSelection::Devnet => Url::parse("https://devnet.aptoslabs.com")
-            .map_err(|e| crate::common::types::CliError::UnexpectedError(e.to_string())),
-        ReplayNetworkSelection::RestEndpoint(url_str) => Url::parse(url_str)
-            .map_err(|e| crate::common::types::CliError::UnexpectedError(e.to_string())),
-    }
-}
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
+};
 
 /// Initializes a new simulation session
 #[derive(Debug, Parser)]
@@ -41,6 +30,8 @@ pub struct Init {
     path: PathBuf,
 
     /// If specified, starts the simulation by forking from a remote network state.
+    ///
+    /// Either the name of a built-in network or one from the CLI config, or a REST endpoint URL.
     #[clap(long)]
     network: Option<ReplayNetworkSelection>,
 
@@ -57,6 +48,13 @@ pub struct Init {
     /// It is strongly recommended to specify an API key to avoid rate limiting.
     #[clap(long)]
     api_key: Option<String>,
+
+    /// Execute the transactions of the session under the gas profiler
+    ///
+    /// The gas report and the folded flamegraph stacks of each transaction are saved to its
+    /// output directory in the session.
+    #[clap(long)]
+    profile_gas: bool,
 }
 
 #[async_trait]
@@ -66,9 +64,9 @@ impl CliCommand<()> for Init {
     }
 
     async fn execute(self) -> CliTypedResult<()> {
-        match self.network {
+        let mut session = match self.network {
             Some(network) => {
-                let url = network_to_url(&network)?;
+                let url = network.rest_url()?;
                 let network_version = match self.network_version {
                     Some(txn_id) => txn_id,
                     None => {
@@ -77,11 +75,13 @@ impl CliCommand<()> for Init {
                     },
                 };
 
-                Session::init_with_remote_state(&self.path, url, network_version, self.api_key)?;
-            },
-            None => {
-                Session::init(&self.path)?;
+                Session::init_with_remote_state(&self.path, url, network_version, self.api_key)?
             },
+            None => Session::init(&self.path)?,
+        };
+
+        if self.profile_gas {
+            session.set_profile_gas(true)?;
         }
 
         Ok(())
@@ -401,14 +401,257 @@ impl CliCommand<TransactionSummary> for Run {
     }
 }
 
+/// Build a Move package and publish it to a simulation session
+///
+/// The package is published by the address of its modules, without needing its private key.
+/// The address still pays for gas, so fund it first with `sim fund`.
+#[derive(Debug, Parser)]
+pub struct Publish {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    #[clap(flatten)]
+    included_artifacts_args: IncludedArtifactsArgs,
+
+    #[clap(flatten)]
+    move_options: MovePackageOptions,
+
+    /// Write the package to the session state directly, skipping upgrade policy and
+    /// compatibility checks
+    ///
+    /// This allows dry-running incompatible upgrades of packages forked from a live network.
+    /// Note that `init_module` is not run for newly added modules.
+    #[clap(long)]
+    skip_compatibility_checks: bool,
+
+    /// Maximum gas units willing to pay
+    #[clap(long, default_value = "2000000")]
+    max_gas: u64,
+
+    /// Gas unit price in octas
+    #[clap(long, default_value = "100")]
+    gas_unit_price: u64,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Publish {
+    fn command_name(&self) -> &'static str {
+        "publish"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let mut session = Session::load(&self.session)?;
+
+        let build_options = self
+            .included_artifacts_args
+            .included_artifacts
+            .build_options(&self.move_options)?;
+        let (vm_status, txn_output) = session.publish_package(
+            self.move_options.get_package_path()?,
+            self.move_options.named_addresses(),
+            PublishOptions {
+                build_options,
+                max_gas_amount: self.max_gas,
+                gas_unit_price: self.gas_unit_price,
+                skip_compatibility_checks: self.skip_compatibility_checks,
+            },
+        )?;
+
+        let success = match txn_output.status() {
+            aptos_types::transaction::TransactionStatus::Keep(exec_status) => {
+                Some(exec_status.is_success())
+            },
+            aptos_types::transaction::TransactionStatus::Discard(_)
+            | aptos_types::transaction::TransactionStatus::Retry => None,
+        };
+
+        Ok(TransactionSummary {
+            transaction_hash: aptos_crypto::HashValue::zero().into(),
+            gas_used: Some(txn_output.gas_used()),
+            gas_unit_price: Some(self.gas_unit_price),
+            pending: None,
+            sender: None,
+            sequence_number: None,
+            success,
+            timestamp_us: None,
+            version: None,
+            vm_status: Some(vm_status.to_string()),
+        })
+    }
+}
+
+/// Save the current state of a session as a named checkpoint
+///
+/// A checkpoint can later be forked into a new session, or compared against with `diff`.
+#[derive(Debug, Parser)]
+pub struct Checkpoint {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Name of the checkpoint, replacing an existing checkpoint of the same name
+    #[clap(long)]
+    name: String,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for Checkpoint {
+    fn command_name(&self) -> &'static str {
+        "checkpoint"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let mut session = Session::load(&self.session)?;
+        session.checkpoint(&self.name)?;
+        Ok(session.checkpoints()?)
+    }
+}
+
+/// Fork a new session from a checkpoint, or from the current state, of a session
+///
+/// This allows trying out alternative transaction sequences from the same starting point.
+#[derive(Debug, Parser)]
+pub struct Fork {
+    /// Path to the stored session to fork from
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Checkpoint of the session to fork from
+    ///
+    /// If not specified, the current state of the session is used.
+    #[clap(long)]
+    checkpoint: Option<String>,
+
+    /// Path to the directory where the forked session will be stored
+    #[clap(long)]
+    path: PathBuf,
+
+    /// Execute the transactions of the forked session under the gas profiler
+    ///
+    /// Otherwise, the fork keeps the setting of the session or checkpoint it is forked from.
+    #[clap(long)]
+    profile_gas: bool,
+}
+
+#[async_trait]
+impl CliCommand<()> for Fork {
+    fn command_name(&self) -> &'static str {
+        "fork"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let session = Session::load(&self.session)?;
+        let mut fork = session.fork(self.checkpoint.as_deref(), &self.path)?;
+
+        if self.profile_gas {
+            fork.set_profile_gas(true)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare the state of two sessions or checkpoints, resource by resource
+///
+/// Every resource that differs is shown decoded on both sides, `left` being `--session` and
+/// `right` being `--other-session`. Both need to be forked from the same base state.
+#[derive(Debug, Parser)]
+pub struct Diff {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Checkpoint of `--session` to compare, instead of its current state
+    #[clap(long)]
+    checkpoint: Option<String>,
+
+    /// Path to the stored session to compare with
+    ///
+    /// If not specified, `--session` is compared with itself, e.g. a checkpoint with its current
+    /// state.
+    #[clap(long)]
+    other_session: Option<PathBuf>,
+
+    /// Checkpoint of the other session to compare, instead of its current state
+    #[clap(long)]
+    other_checkpoint: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<SessionDiff> for Diff {
+    fn command_name(&self) -> &'static str {
+        "diff"
+    }
+
+    async fn execute(self) -> CliTypedResult<SessionDiff> {
+        let session = Session::load(&self.session)?;
+        let other = match &self.other_session {
+            Some(other_session) => Session::load(other_session)?,
+            None => Session::load(&self.session)?,
+        };
+
+        Ok(session.diff(
+            self.checkpoint.as_deref(),
+            &other,
+            self.other_checkpoint.as_deref(),
+        )?)
+    }
+}
+
+/// Serve a simulation session through a subset of the node REST API
+///
+/// Supports ledger info, account and resource lookups, BCS transaction submission, lookup and
+/// simulation, and JSON or BCS view function requests under `/v1`. Submitted transactions are
+/// checked like on a node and executed right away, each one advancing the ledger version by one.
+/// The session can be controlled with
+/// `POST /sim/fund` (`{"account", "amount"}`), `POST /sim/checkpoint` (`{"name"}`) and
+/// `POST /sim/reset` (`{"checkpoint"}`).
+#[derive(Debug, Parser)]
+pub struct Serve {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Address to bind the server to
+    #[clap(long, default_value = "127.0.0.1")]
+    bind_to: Ipv4Addr,
+
+    /// Port to serve the REST API on
+    #[clap(long, default_value_t = 8080)]
+    port: u16,
+}
+
+#[async_trait]
+impl CliCommand<()> for Serve {
+    fn command_name(&self) -> &'static str {
+        "serve"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let session = Session::load(&self.session)?;
+        sim_server::run_server(
+            session,
+            SocketAddr::V4(SocketAddrV4::new(self.bind_to, self.port)),
+        )
+        .await
+        .map_err(|err| CliError::UnexpectedError(format!("Simulation server failed: {:#}", err)))
+    }
+}
+
 /// BETA: Commands for interacting with a local simulation session
 ///
 /// BETA: Subject to change
 #[derive(Subcommand)]
 pub enum Sim {
     Init(Init),
+    Checkpoint(Checkpoint),
+    Diff(Diff),
+    Fork(Fork),
     Fund(Fund),
+    Publish(Publish),
     Run(Run),
+    Serve(Serve),
     ViewResource(ViewResource),
     ViewResourceGroup(ViewResourceGroup),
 }
@@ -417,8 +660,13 @@ impl Sim {
     pub async fn execute(self) -> CliResult {
         match self {
             Sim::Init(init) => init.execute_serialized_success().await,
+            Sim::Checkpoint(checkpoint) => checkpoint.execute_serialized().await,
+            Sim::Diff(diff) => diff.execute_serialized().await,
+            Sim::Fork(fork) => fork.execute_serialized_success().await,
             Sim::Fund(fund) => fund.execute_serialized_success().await,
+            Sim::Publish(publish) => publish.execute_serialized().await,
             Sim::Run(run) => run.execute_serialized().await,
+            Sim::Serve(serve) => serve.execute_serialized_success().await,
             Sim::ViewResource(view_resource) => view_resource.execute_serialized().await,
             Sim::ViewResourceGroup(view_resource_group) => {
                 view_resource_group.execute_serialized().await
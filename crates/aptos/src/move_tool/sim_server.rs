@@ -0,0 +1,846 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A server exposing a simulation session through a subset of the node REST API, so clients and
+//! SDKs can run transactions against the session as they would against a node.
+//!
+//! Transactions are executed as soon as they are submitted, each one advancing the session by an
+//! operation, which is reported as the ledger version, so waiting for a transaction returns right
+//! away. Reads and view functions do not advance the session. Besides the node API under `/v1`,
+//! the session can be controlled through `/sim/fund`, `/sim/checkpoint` and `/sim/reset`.
+
+use anyhow::{anyhow, Result};
+use aptos_api_types::{AsConverter, MoveConverter, ViewFunction, ViewRequest, U64};
+use aptos_crypto::HashValue;
+use aptos_resource_viewer::{AnnotatedMoveValue, AptosValueAnnotator};
+use aptos_storage_interface::DbReader;
+use aptos_transaction_simulation_session::Session;
+use aptos_types::{
+    account_config::AccountResource,
+    chain_id::ChainId,
+    contract_event::ContractEvent,
+    on_chain_config::{ConfigurationResource, CurrentTimeMicroseconds, OnChainConfig},
+    state_store::{state_key::StateKey, StateView, TStateView},
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
+    vm_status::{StatusCode as VMStatusCode, VMStatus},
+};
+use aptos_vm::AptosVM;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use poem::{
+    get, handler,
+    http::StatusCode,
+    listener::TcpListener,
+    middleware::Tracing,
+    post,
+    web::{Data, Json, Path},
+    Endpoint, EndpointExt, IntoResponse, Request, Response, Route, Server,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+/// The state shared by all requests.
+struct ServerState {
+    session: Mutex<Session>,
+    /// Executed transactions by hash, in their REST API representation.
+    transactions: Mutex<HashMap<HashValue, serde_json::Value>>,
+}
+
+/// The API converter needs a database only for table infos and transaction auxiliary data,
+/// which a session does not have.
+struct NoDatabase;
+
+impl DbReader for NoDatabase {}
+
+fn converter<S: StateView>(state_view: &S) -> MoveConverter<'_, S> {
+    state_view.as_converter(Arc::new(NoDatabase), None)
+}
+
+/// Runs the server until it is shut down.
+pub async fn run_server(session: Session, address: SocketAddr) -> Result<()> {
+    eprintln!("Serving simulation session at http://{}/v1", address);
+    Server::new(TcpListener::bind(address))
+        .name("simulation-session")
+        .run(app(session))
+        .await?;
+    Ok(())
+}
+
+fn app(session: Session) -> impl Endpoint {
+    let state = Arc::new(ServerState {
+        session: Mutex::new(session),
+        transactions: Mutex::new(HashMap::new()),
+    });
+
+    Route::new()
+        .at("/v1", get(ledger_info))
+        .at("/v1/accounts/:address", get(account))
+        .at("/v1/accounts/:address/resources", get(account_resources))
+        .at(
+            "/v1/accounts/:address/resource/:resource_type",
+            get(account_resource),
+        )
+        .at("/v1/transactions", post(submit_transaction))
+        .at("/v1/transactions/by_hash/:hash", get(transaction_by_hash))
+        .at(
+            "/v1/transactions/wait_by_hash/:hash",
+            get(transaction_by_hash),
+        )
+        .at("/v1/transactions/simulate", post(simulate_transaction))
+        .at("/v1/view", post(view))
+        .at("/sim/fund", post(fund))
+        .at("/sim/checkpoint", post(checkpoint))
+        .at("/sim/reset", post(reset))
+        .data(state)
+        .with(Tracing)
+}
+
+/// An error in the shape of the errors of the node REST API.
+struct ApiError {
+    status: StatusCode,
+    error_code: &'static str,
+    message: String,
+    vm_error_code: Option<u64>,
+}
+
+impl ApiError {
+    fn bad_request(message: impl ToString) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error_code: "invalid_input",
+            message: message.to_string(),
+            vm_error_code: None,
+        }
+    }
+
+    /// A transaction failing validation, like the node rejects it on submission.
+    fn vm_error(status_code: VMStatusCode) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error_code: "vm_error",
+            message: format!(
+                "Invalid transaction: Type: {:?} Code: {:?}",
+                status_code.status_type(),
+                status_code
+            ),
+            vm_error_code: Some(status_code as u64),
+        }
+    }
+
+    fn not_found(error_code: &'static str, message: impl ToString) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            error_code,
+            message: message.to_string(),
+            vm_error_code: None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error_code: "internal_error",
+            message: format!("{:#}", error),
+            vm_error_code: None,
+        }
+    }
+}
+
+fn respond(result: Result<serde_json::Value, ApiError>, status: StatusCode) -> Response {
+    match result {
+        Ok(value) => Json(value).with_status(status).into_response(),
+        Err(error) => Json(json!({
+            "message": error.message,
+            "error_code": error.error_code,
+            "vm_error_code": error.vm_error_code,
+        }))
+        .with_status(error.status)
+        .into_response(),
+    }
+}
+
+/// Runs an operation on the session on a blocking thread, as the session may fetch state from a
+/// remote network synchronously.
+async fn with_session<T: Send + 'static>(
+    state: &Arc<ServerState>,
+    operation: impl FnOnce(&mut Session) -> Result<T, ApiError> + Send + 'static,
+) -> Result<T, ApiError> {
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut session = state
+            .session
+            .lock()
+            .map_err(|_| anyhow!("Session lock is poisoned"))?;
+        operation(&mut session)
+    })
+    .await
+    .map_err(|err| anyhow!("Session operation panicked: {}", err))?
+}
+
+fn parse_address(address: &str) -> Result<AccountAddress, ApiError> {
+    AccountAddress::from_str(address)
+        .map_err(|err| ApiError::bad_request(format!("Invalid address {}: {}", address, err)))
+}
+
+fn decode_struct(
+    state_view: &impl StateView,
+    struct_tag: &StructTag,
+    bytes: &[u8],
+) -> Result<serde_json::Value> {
+    let annotator = AptosValueAnnotator::new(state_view);
+    let annotated = AnnotatedMoveValue::Struct(annotator.view_resource(struct_tag, bytes)?);
+    aptos_api_types::MoveValue::try_from(annotated)?.json()
+}
+
+fn events_json(
+    state_view: &impl StateView,
+    events: &[ContractEvent],
+) -> Result<Vec<serde_json::Value>> {
+    events
+        .iter()
+        .map(|event| {
+            let data = match event.type_tag() {
+                TypeTag::Struct(struct_tag) => {
+                    decode_struct(state_view, struct_tag, event.event_data())?
+                },
+                _ => json!(hex::encode(event.event_data())),
+            };
+            let (guid, sequence_number) = match event {
+                ContractEvent::V1(event) => (
+                    json!({
+                        "creation_number": U64::from(event.key().get_creation_number()),
+                        "account_address": event.key().get_creator_address().to_hex_literal(),
+                    }),
+                    event.sequence_number(),
+                ),
+                ContractEvent::V2(_) => (
+                    json!({
+                        "creation_number": U64::from(0),
+                        "account_address": AccountAddress::ZERO.to_hex_literal(),
+                    }),
+                    0,
+                ),
+            };
+            Ok(json!({
+                "guid": guid,
+                "sequence_number": U64::from(sequence_number),
+                "type": event.type_tag().to_canonical_string(),
+                "data": data,
+            }))
+        })
+        .collect()
+}
+
+/// The fields of a transaction shared by its pending and executed representations.
+fn transaction_fields(txn: &SignedTransaction) -> serde_json::Map<String, serde_json::Value> {
+    let json = json!({
+        "hash": txn.committed_hash().to_hex_literal(),
+        "sender": txn.sender().to_hex_literal(),
+        "sequence_number": U64::from(txn.sequence_number()),
+        "max_gas_amount": U64::from(txn.max_gas_amount()),
+        "gas_unit_price": U64::from(txn.gas_unit_price()),
+        "expiration_timestamp_secs": U64::from(txn.expiration_timestamp_secs()),
+    });
+    match json {
+        serde_json::Value::Object(fields) => fields,
+        _ => unreachable!(),
+    }
+}
+
+fn executed_transaction_json(
+    state_view: &impl StateView,
+    txn: &SignedTransaction,
+    version: u64,
+    vm_status: &VMStatus,
+    output: &TransactionOutput,
+) -> Result<serde_json::Value> {
+    let success = matches!(output.status(), TransactionStatus::Keep(status) if status.is_success());
+    let vm_status = if success {
+        "Executed successfully".to_string()
+    } else {
+        format!("{:?}", vm_status)
+    };
+
+    let mut fields = transaction_fields(txn);
+    fields.insert("type".to_string(), json!("user_transaction"));
+    fields.insert("version".to_string(), json!(U64::from(version)));
+    fields.insert("success".to_string(), json!(success));
+    fields.insert("vm_status".to_string(), json!(vm_status));
+    fields.insert("gas_used".to_string(), json!(U64::from(output.gas_used())));
+    fields.insert(
+        "events".to_string(),
+        json!(events_json(state_view, output.events())?),
+    );
+    let converter = converter(state_view);
+    let changes: Vec<_> = output
+        .write_set()
+        .write_op_iter()
+        .filter_map(|(state_key, write_op)| {
+            converter
+                .try_into_write_set_changes(state_key.clone(), write_op.clone())
+                .ok()
+        })
+        .flatten()
+        .collect();
+    fields.insert("changes".to_string(), json!(changes));
+    Ok(serde_json::Value::Object(fields))
+}
+
+fn decode_transaction(body: &[u8]) -> Result<SignedTransaction, ApiError> {
+    bcs::from_bytes(body).map_err(|err| {
+        ApiError::bad_request(format!(
+            "Transactions have to be submitted as BCS encoded signed transactions: {}",
+            err
+        ))
+    })
+}
+
+#[derive(Serialize)]
+struct LedgerInfo {
+    chain_id: u8,
+    epoch: U64,
+    ledger_version: U64,
+    oldest_ledger_version: U64,
+    ledger_timestamp: U64,
+    node_role: &'static str,
+    oldest_block_height: U64,
+    block_height: U64,
+}
+
+#[handler]
+async fn ledger_info(state: Data<&Arc<ServerState>>) -> Response {
+    let result = with_session(&state, |session| {
+        let state_view = session.state_store();
+        let chain_id = ChainId::fetch_config(state_view)
+            .ok_or_else(|| anyhow!("Chain ID not found in session state"))?;
+        let epoch = ConfigurationResource::fetch_config(state_view)
+            .map(|configuration| configuration.epoch())
+            .unwrap_or_default();
+        let timestamp = CurrentTimeMicroseconds::fetch_config(state_view)
+            .map(|time| time.microseconds)
+            .unwrap_or_default();
+
+        Ok(json!(LedgerInfo {
+            chain_id: chain_id.id(),
+            epoch: epoch.into(),
+            ledger_version: session.ops().into(),
+            oldest_ledger_version: 0.into(),
+            ledger_timestamp: timestamp.into(),
+            node_role: "full_node",
+            oldest_block_height: 0.into(),
+            block_height: session.ops().into(),
+        }))
+    })
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[handler]
+async fn account(state: Data<&Arc<ServerState>>, Path(address): Path<String>) -> Response {
+    let result = async {
+        let address = parse_address(&address)?;
+        with_session(&state, move |session| {
+            let state_key = StateKey::resource(&address, &AccountResource::struct_tag())
+                .map_err(anyhow::Error::from)?;
+            let bytes = session
+                .state_store()
+                .get_state_value_bytes(&state_key)
+                .map_err(anyhow::Error::from)?
+                .ok_or_else(|| {
+                    ApiError::not_found(
+                        "account_not_found",
+                        format!("Account not found: {}", address),
+                    )
+                })?;
+            let account: AccountResource = bcs::from_bytes(&bytes).map_err(anyhow::Error::from)?;
+
+            Ok(json!({
+                "sequence_number": U64::from(account.sequence_number()),
+                "authentication_key": format!("0x{}", hex::encode(account.authentication_key())),
+            }))
+        })
+        .await
+    }
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[handler]
+async fn account_resources(
+    state: Data<&Arc<ServerState>>,
+    Path(address): Path<String>,
+) -> Response {
+    let result = async {
+        let address = parse_address(&address)?;
+        with_session(&state, move |session| {
+            let resources = session.account_resources(address)?;
+            let resources = converter(session.state_store()).try_into_resources(
+                resources
+                    .iter()
+                    .map(|(struct_tag, bytes)| (struct_tag.clone(), bytes.as_slice())),
+            )?;
+            Ok(json!(resources))
+        })
+        .await
+    }
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[handler]
+async fn account_resource(
+    state: Data<&Arc<ServerState>>,
+    Path((address, resource_type)): Path<(String, String)>,
+) -> Response {
+    let result = async {
+        let address = parse_address(&address)?;
+        let struct_tag = StructTag::from_str(&resource_type).map_err(|err| {
+            ApiError::bad_request(format!("Invalid resource type {}: {}", resource_type, err))
+        })?;
+        with_session(&state, move |session| {
+            let state_view = session.state_store();
+            let state_key =
+                StateKey::resource(&address, &struct_tag).map_err(anyhow::Error::from)?;
+            let bytes = state_view
+                .get_state_value_bytes(&state_key)
+                .map_err(anyhow::Error::from)?
+                .ok_or_else(|| {
+                    ApiError::not_found(
+                        "resource_not_found",
+                        format!(
+                            "Resource {} not found at {}",
+                            struct_tag.to_canonical_string(),
+                            address
+                        ),
+                    )
+                })?;
+
+            Ok(json!({
+                "type": struct_tag.to_canonical_string(),
+                "data": decode_struct(state_view, &struct_tag, &bytes)?,
+            }))
+        })
+        .await
+    }
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[handler]
+async fn submit_transaction(state: Data<&Arc<ServerState>>, body: Vec<u8>) -> Response {
+    let result = async {
+        // The session executes transactions without authenticating them, so check the signature
+        // and chain id like the node does before accepting a transaction.
+        let txn = decode_transaction(&body)?
+            .check_signature()
+            .map_err(|_| ApiError::vm_error(VMStatusCode::INVALID_SIGNATURE))?
+            .into_inner();
+        let executed = with_session(&state, move |session| {
+            let chain_id = ChainId::fetch_config(session.state_store())
+                .ok_or_else(|| anyhow!("Chain ID not found in session state"))?;
+            if txn.chain_id() != chain_id {
+                return Err(ApiError::vm_error(VMStatusCode::BAD_CHAIN_ID));
+            }
+
+            let version = session.ops();
+            let (vm_status, output) = session.execute_transaction(txn.clone())?;
+            if let TransactionStatus::Discard(status_code) = output.status() {
+                return Err(ApiError::vm_error(*status_code));
+            }
+            let executed = executed_transaction_json(
+                session.state_store(),
+                &txn,
+                version,
+                &vm_status,
+                &output,
+            )?;
+            Ok((txn, executed))
+        })
+        .await;
+
+        let (txn, executed) = executed?;
+        state
+            .transactions
+            .lock()
+            .map_err(|_| anyhow!("Transaction lock is poisoned"))?
+            .insert(txn.committed_hash(), executed);
+
+        let mut pending = transaction_fields(&txn);
+        pending.insert("type".to_string(), json!("pending_transaction"));
+        Ok(serde_json::Value::Object(pending))
+    }
+    .await;
+    respond(result, StatusCode::ACCEPTED)
+}
+
+#[handler]
+async fn transaction_by_hash(state: Data<&Arc<ServerState>>, Path(hash): Path<String>) -> Response {
+    let result = (|| {
+        let hash = HashValue::from_str(hash.trim_start_matches("0x"))
+            .map_err(|err| ApiError::bad_request(format!("Invalid hash {}: {}", hash, err)))?;
+        state
+            .transactions
+            .lock()
+            .map_err(|_| anyhow!("Transaction lock is poisoned"))?
+            .get(&hash)
+            .cloned()
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "transaction_not_found",
+                    format!("Transaction not found: {}", hash.to_hex_literal()),
+                )
+            })
+    })();
+    respond(result, StatusCode::OK)
+}
+
+#[handler]
+async fn simulate_transaction(state: Data<&Arc<ServerState>>, body: Vec<u8>) -> Response {
+    let result = async {
+        let txn = decode_transaction(&body)?;
+        with_session(&state, move |session| {
+            let (vm_status, output) = session
+                .simulate_transaction(&txn)
+                .map_err(ApiError::bad_request)?;
+            let simulated = executed_transaction_json(
+                session.state_store(),
+                &txn,
+                session.ops(),
+                &vm_status,
+                &output,
+            )?;
+            Ok(json!([simulated]))
+        })
+        .await
+    }
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+/// Executes a view function requested in JSON or BCS like on a node, returning the values in JSON.
+/// Views are read only, they are not recorded as session operations.
+#[handler]
+async fn view(state: Data<&Arc<ServerState>>, request: &Request, body: Vec<u8>) -> Response {
+    let is_json = request
+        .content_type()
+        .is_some_and(|content_type| content_type.starts_with("application/json"));
+    let result = with_session(&state, move |session| {
+        let state_view = session.state_store();
+        let converter = converter(state_view);
+        let view_function = if is_json {
+            let request: ViewRequest = serde_json::from_slice(&body).map_err(|err| {
+                ApiError::bad_request(format!("Invalid view function request: {}", err))
+            })?;
+            converter
+                .convert_view_function(request)
+                .map_err(ApiError::bad_request)?
+        } else {
+            bcs::from_bytes::<ViewFunction>(&body).map_err(|err| {
+                ApiError::bad_request(format!(
+                    "View functions have to be requested as JSON or BCS encoded view functions: {}",
+                    err
+                ))
+            })?
+        };
+
+        let output = AptosVM::execute_view_function(
+            state_view,
+            view_function.module.clone(),
+            view_function.function.clone(),
+            view_function.ty_args.clone(),
+            view_function.args.clone(),
+            u64::MAX,
+        );
+        let values = output.values.map_err(ApiError::bad_request)?;
+        let return_types = converter
+            .function_return_types(&view_function)
+            .and_then(|types| {
+                types
+                    .iter()
+                    .map(TypeTag::try_from)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .map_err(ApiError::bad_request)?;
+        let values = values
+            .iter()
+            .zip(&return_types)
+            .map(|(value, ty)| converter.try_into_move_value(ty, value))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(json!(values))
+    })
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+struct FundRequest {
+    account: AccountAddress,
+    amount: u64,
+}
+
+#[handler]
+async fn fund(state: Data<&Arc<ServerState>>, Json(request): Json<FundRequest>) -> Response {
+    let result = with_session(&state, move |session| {
+        session.fund_account(request.account, request.amount)?;
+        Ok(json!({ "account": request.account, "amount": U64::from(request.amount) }))
+    })
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+struct CheckpointRequest {
+    name: String,
+}
+
+#[handler]
+async fn checkpoint(
+    state: Data<&Arc<ServerState>>,
+    Json(request): Json<CheckpointRequest>,
+) -> Response {
+    let result = with_session(&state, move |session| {
+        session
+            .checkpoint(&request.name)
+            .map_err(ApiError::bad_request)?;
+        Ok(json!({ "checkpoints": session.checkpoints()? }))
+    })
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+struct ResetRequest {
+    checkpoint: String,
+}
+
+#[handler]
+async fn reset(state: Data<&Arc<ServerState>>, Json(request): Json<ResetRequest>) -> Response {
+    let result = with_session(&state, move |session| {
+        session
+            .restore(&request.checkpoint)
+            .map_err(ApiError::bad_request)?;
+        Ok(json!({ "checkpoint": request.checkpoint }))
+    })
+    .await;
+    respond(result, StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::PrivateKey;
+    use aptos_keygen::KeyGen;
+    use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+    use aptos_types::transaction::authenticator::AuthenticationKey;
+    use move_core_types::{ident_str, language_storage::ModuleId};
+    use poem::http::{Method, Uri};
+    use std::path::Path as FsPath;
+
+    const BCS_TRANSACTION: &str = "application/x.aptos.signed_transaction+bcs";
+    const BCS_VIEW_FUNCTION: &str = "application/x.aptos.view_function+bcs";
+
+    /// A local session with a funded account.
+    fn new_session(session_path: &FsPath) -> (Session, LocalAccount, ChainId) {
+        let mut session = Session::init(session_path).unwrap();
+        let private_key = KeyGen::from_seed([0; 32]).generate_ed25519_private_key();
+        let public_key = private_key.public_key();
+        let address = AuthenticationKey::ed25519(&public_key).account_address();
+        session
+            .create_and_fund_account(address, public_key, 1_000_000_000)
+            .unwrap();
+        let chain_id = ChainId::fetch_config(session.state_store()).unwrap();
+        (
+            session,
+            LocalAccount::new(address, private_key, 0),
+            chain_id,
+        )
+    }
+
+    async fn call(
+        app: &impl Endpoint,
+        method: Method,
+        uri: &str,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(Uri::from_str(uri).unwrap())
+            .content_type(content_type)
+            .body(body);
+        let response = app.get_response(request).await;
+        let status = response.status();
+        (status, response.into_body().into_json().await.unwrap())
+    }
+
+    async fn get(app: &impl Endpoint, uri: &str) -> (StatusCode, serde_json::Value) {
+        call(app, Method::GET, uri, "application/json", vec![]).await
+    }
+
+    async fn ledger_version(app: &impl Endpoint) -> serde_json::Value {
+        get(app, "/v1").await.1["ledger_version"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (session, account, chain_id) = new_session(temp_dir.path());
+        let app = app(session);
+        let receiver = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let factory = TransactionFactory::new(chain_id).with_gas_unit_price(100);
+
+        let txn = account.sign_with_transaction_builder(factory.account_transfer(receiver, 100));
+        let (status, pending) = call(
+            &app,
+            Method::POST,
+            "/v1/transactions",
+            BCS_TRANSACTION,
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(pending["type"], "pending_transaction");
+
+        let hash = pending["hash"].as_str().unwrap();
+        let (status, executed) =
+            get(&app, &format!("/v1/transactions/wait_by_hash/{}", hash)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(executed["success"], true);
+        let changes = executed["changes"].as_array().unwrap();
+        assert!(changes
+            .iter()
+            .any(|change| change["data"]["type"] == "0x1::account::Account"));
+
+        // Transactions the node would reject are not executed
+        let version = ledger_version(&app).await;
+        let other_chain = TransactionFactory::new(ChainId::new(chain_id.id() + 1));
+        let txn =
+            account.sign_with_transaction_builder(other_chain.account_transfer(receiver, 100));
+        let (status, error) = call(
+            &app,
+            Method::POST,
+            "/v1/transactions",
+            BCS_TRANSACTION,
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error_code"], "vm_error");
+        assert_eq!(error["vm_error_code"], VMStatusCode::BAD_CHAIN_ID as u64);
+
+        let other_key = KeyGen::from_seed([1; 32]).generate_ed25519_private_key();
+        let txn = factory
+            .account_transfer(receiver, 100)
+            .sender(account.address())
+            .sequence_number(1)
+            .build()
+            .sign(&other_key, account.public_key().clone())
+            .unwrap()
+            .into_inner();
+        let (status, error) = call(
+            &app,
+            Method::POST,
+            "/v1/transactions",
+            BCS_TRANSACTION,
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error["vm_error_code"],
+            VMStatusCode::INVALID_SIGNATURE as u64
+        );
+        assert_eq!(ledger_version(&app).await, version);
+    }
+
+    #[tokio::test]
+    async fn test_view() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (session, account, _) = new_session(temp_dir.path());
+        let app = app(session);
+        let version = ledger_version(&app).await;
+
+        let request = json!({
+            "function": "0x1::account::exists_at",
+            "type_arguments": [],
+            "arguments": [account.address().to_hex_literal()],
+        });
+        let (status, values) = call(
+            &app,
+            Method::POST,
+            "/v1/view",
+            "application/json",
+            serde_json::to_vec(&request).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(values, json!([true]));
+
+        let request = ViewFunction {
+            module: ModuleId::new(AccountAddress::ONE, ident_str!("account").to_owned()),
+            function: ident_str!("get_sequence_number").to_owned(),
+            ty_args: vec![],
+            args: vec![bcs::to_bytes(&account.address()).unwrap()],
+        };
+        let (status, values) = call(
+            &app,
+            Method::POST,
+            "/v1/view",
+            BCS_VIEW_FUNCTION,
+            bcs::to_bytes(&request).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(values, json!(["0"]));
+
+        // Views are not recorded as session operations
+        assert_eq!(ledger_version(&app).await, version);
+        for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+            let name = entry.unwrap().file_name();
+            assert!(!name.to_string_lossy().contains("view"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_account_reads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (session, account, _) = new_session(temp_dir.path());
+        let app = app(session);
+        let address = account.address().to_hex_literal();
+
+        let (status, info) = get(&app, &format!("/v1/accounts/{}", address)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(info["sequence_number"], "0");
+
+        let (status, resources) = get(&app, &format!("/v1/accounts/{}/resources", address)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(resources
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|resource| resource["type"] == "0x1::account::Account"));
+
+        let (status, resource) = get(
+            &app,
+            &format!("/v1/accounts/{}/resource/0x1::account::Account", address),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(resource["data"]["sequence_number"], "0");
+
+        let (status, error) = get(&app, "/v1/accounts/0xcafe").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["error_code"], "account_not_found");
+    }
+}
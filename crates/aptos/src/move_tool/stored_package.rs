@@ -93,6 +93,16 @@ impl CachedPackageRegistry {
         bail!("module `{}` not found", name)
     }
 
+    /// Finds the name of the package containing the given module, if any.
+    pub fn package_name_of_module(&self, module_name: impl AsRef<str>) -> Option<&str> {
+        let module_name = module_name.as_ref();
+        self.inner
+            .packages
+            .iter()
+            .find(|package| package.modules.iter().any(|m| m.name == module_name))
+            .map(|package| package.name.as_str())
+    }
+
     /// Finds the metadata for the given package in the registry by its unique name.
     pub async fn get_package(
         &self,
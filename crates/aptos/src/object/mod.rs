@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
+        TransactionOptions, TransactionSummary,
+    },
+    utils::view_function,
+};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use move_core_types::{
+    ident_str,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::Serialize;
+
+/// Tool for inspecting and transferring objects
+///
+/// This works with any object, including fungible asset metadata and Token v2 NFTs.
+#[derive(Debug, Subcommand)]
+pub enum ObjectTool {
+    Show(Show),
+    Transfer(Transfer),
+}
+
+impl ObjectTool {
+    pub async fn execute(self) -> CliResult {
+        use ObjectTool::*;
+        match self {
+            Show(tool) => tool.execute_serialized().await,
+            Transfer(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The type tag of `0x1::object::ObjectCore`, which every object has
+pub(crate) fn object_core_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("object").into(),
+        name: ident_str!("ObjectCore").into(),
+        type_args: vec![],
+    }))
+}
+
+/// Looks up the current owner of an object
+pub(crate) async fn object_owner(
+    client: &aptos_rest_client::Client,
+    object: AccountAddress,
+) -> CliTypedResult<AccountAddress> {
+    let owner = view_function(
+        client,
+        AccountAddress::ONE,
+        "object",
+        "owner",
+        vec![object_core_type()],
+        vec![object.to_vec()],
+        None,
+    )
+    .await?;
+    serde_json::from_value(owner[0].clone())
+        .map_err(|err| CliError::UnableToParse("object owner", err.to_string()))
+}
+
+/// Show the owner, transfer status and resources of an object
+#[derive(Debug, Parser)]
+pub struct Show {
+    /// Address of the object
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) object: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObjectSummary {
+    pub address: AccountAddress,
+    pub owner: AccountAddress,
+    /// Whether the object was burnt by its owner
+    pub burnt: bool,
+    /// Whether the object can be transferred with `0x1::object::transfer`
+    pub transferable: bool,
+    /// Types of the resources stored at the object
+    pub resources: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<ObjectSummary> for Show {
+    fn command_name(&self) -> &'static str {
+        "ShowObject"
+    }
+
+    async fn execute(self) -> CliTypedResult<ObjectSummary> {
+        let client = &self.rest_options.client(&self.profile_options)?;
+        let object = self.object;
+        let view_flag = |function: &'static str| async move {
+            let flag = view_function(
+                client,
+                AccountAddress::ONE,
+                "object",
+                function,
+                vec![object_core_type()],
+                vec![object.to_vec()],
+                None,
+            )
+            .await?;
+            serde_json::from_value::<bool>(flag[0].clone())
+                .map_err(|err| CliError::UnableToParse("object status", err.to_string()))
+        };
+
+        let owner = object_owner(client, object).await?;
+        let burnt = view_flag("is_burnt").await?;
+        let untransferable = view_flag("is_untransferable").await?;
+        let resources = client.get_account_resources(object).await?.into_inner();
+
+        // Whether ungated transfers are allowed is only exposed through the `ObjectCore` resource
+        let core_type = object_core_type().to_canonical_string();
+        let allow_ungated_transfer = resources
+            .iter()
+            .find(|resource| resource.resource_type.to_canonical_string() == core_type)
+            .and_then(|core| core.data["allow_ungated_transfer"].as_bool())
+            .ok_or_else(|| CliError::UnexpectedError(format!("{} is not an object", object)))?;
+
+        Ok(ObjectSummary {
+            address: object,
+            owner,
+            burnt,
+            transferable: allow_ungated_transfer && !untransferable,
+            resources: resources
+                .into_iter()
+                .map(|resource| resource.resource_type.to_canonical_string())
+                .collect(),
+        })
+    }
+}
+
+/// Transfer an object to another account
+///
+/// The sender has to be the direct owner of the object.
+#[derive(Debug, Parser)]
+pub struct Transfer {
+    /// Address of the object
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) object: AccountAddress,
+
+    /// Address of the new owner
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) to: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Transfer {
+    fn command_name(&self) -> &'static str {
+        "TransferObject"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::object_transfer_call(self.object, self.to))
+            .await
+            .map(TransactionSummary::from)
+    }
+}
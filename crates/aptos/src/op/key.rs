@@ -16,12 +16,13 @@ use crate::{
 };
 use aptos_config::config::{Peer, PeerRole};
 use aptos_crypto::{
-    bls12381, ed25519, ed25519::Ed25519PrivateKey, encoding_type::EncodingType, x25519, PrivateKey,
-    ValidCryptoMaterial,
+    bls12381, ed25519, ed25519::Ed25519PrivateKey, encoding_type::EncodingType, secp256k1_ecdsa,
+    secp256r1_ecdsa, x25519, PrivateKey, ValidCryptoMaterial,
 };
 use aptos_genesis::config::HostAndPort;
-use aptos_types::account_address::{
-    create_multisig_account_address, from_identity_public_key, AccountAddress,
+use aptos_types::{
+    account_address::{create_multisig_account_address, from_identity_public_key, AccountAddress},
+    transaction::authenticator::{AnyPublicKey, AuthenticationKey},
 };
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -174,7 +175,7 @@ impl NetworkKeyInputOptions {
 /// key encoded with the `encoding`.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, secp256k1-ecdsa, secp256r1-ecdsa]
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
     /// Vanity prefix that resultant account address should start with, e.g. 0xaceface or d00d. Each
@@ -261,10 +262,37 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
                 let private_key = keygen.generate_bls12381_private_key();
                 self.save_params.save_bls_key(&private_key, "bls12381")
             },
+            KeyType::Secp256k1Ecdsa => {
+                let private_key = keygen.generate_secp256k1_ecdsa_private_key();
+                let public_key = AnyPublicKey::secp256k1_ecdsa(private_key.public_key());
+                let mut result_map = self.save_params.save_key(&private_key, "secp256k1-ecdsa")?;
+                insert_single_key_address(&mut result_map, public_key);
+                Ok(result_map)
+            },
+            KeyType::Secp256r1Ecdsa => {
+                let private_key = keygen.generate_secp256r1_ecdsa_private_key();
+                let public_key = AnyPublicKey::secp256r1_ecdsa(private_key.public_key());
+                let mut result_map = self.save_params.save_key(&private_key, "secp256r1-ecdsa")?;
+                insert_single_key_address(&mut result_map, public_key);
+                Ok(result_map)
+            },
         }
     }
 }
 
+/// Adds the address of the single-key account of a public key to a CLI result
+fn insert_single_key_address(
+    result_map: &mut HashMap<&'static str, PathBuf>,
+    public_key: AnyPublicKey,
+) {
+    let account_address = AuthenticationKey::any_key(public_key).account_address();
+    // Store account address in a PathBuf so it can be displayed in CLI result.
+    result_map.insert(
+        "Account Address:",
+        PathBuf::from(account_address.to_hex_literal()),
+    );
+}
+
 impl GenerateKey {
     /// A test friendly typed key generation for x25519 keys.
     pub async fn generate_x25519(
@@ -320,7 +348,7 @@ impl GenerateKey {
 /// ```
 #[derive(Debug, Parser)]
 pub struct ExtractPublicKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, secp256k1-ecdsa, secp256r1-ecdsa]
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
     #[clap(flatten)]
@@ -388,6 +416,36 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for ExtractPublicKey {
                     )?,
                 ]
             },
+            KeyType::Secp256k1Ecdsa => {
+                let key = self
+                    .save_params
+                    .encoding_options
+                    .encoding
+                    .decode_key::<secp256k1_ecdsa::PrivateKey>(
+                        "secp256k1-ecdsa private key",
+                        private_key_bytes,
+                    )?;
+                vec![self.save_params.save_material(
+                    &key.public_key(),
+                    "secp256k1-ecdsa public key",
+                    PUBLIC_KEY_EXTENSION,
+                )?]
+            },
+            KeyType::Secp256r1Ecdsa => {
+                let key = self
+                    .save_params
+                    .encoding_options
+                    .encoding
+                    .decode_key::<secp256r1_ecdsa::PrivateKey>(
+                        "secp256r1-ecdsa private key",
+                        private_key_bytes,
+                    )?;
+                vec![self.save_params.save_material(
+                    &key.public_key(),
+                    "secp256r1-ecdsa public key",
+                    PUBLIC_KEY_EXTENSION,
+                )?]
+            },
         };
         Ok(HashMap::from_iter(files))
     }
@@ -9,21 +9,27 @@ use crate::{
             LookupAddress, NewAuthKeyOptions, NewProfileOptions, RotateKey, RotateSummary,
         },
         list::{ListAccount, ListQuery},
+        multisig_account::{self, MultisigProposal},
         transfer::{TransferCoins, TransferSummary},
     },
     common::{
+        external_signer::ExternalSigner,
         init::{InitTool, Network},
+        transactions::TxnOptions,
         types::{
             account_address_from_public_key, AccountAddressWrapper, ArgWithTypeVec,
-            AuthenticationKeyInputOptions, ChunkedPublishOption, CliError, CliTypedResult,
-            EncodingOptions, EntryFunctionArguments, FaucetOptions, GasOptions, KeyType,
-            LargePackagesModuleOption, MoveManifestAccountWrapper, MovePackageOptions,
-            OptionalPoolAddressArgs, OverrideSizeCheckOption, PoolAddressArgs,
-            PrivateKeyInputOptions, PromptOptions, PublicKeyInputOptions, RestOptions, RngArgs,
-            SaveFile, ScriptFunctionArguments, TransactionOptions, TransactionSummary, TypeArgVec,
+            AuthenticationKeyInputOptions, ChunkedPublishOption, CliConfig, CliError,
+            CliTypedResult, ConfigSearchMode, EncodingOptions, EntryFunctionArguments,
+            FaucetOptions, GasOptions, KeyType, LargePackagesModuleOption,
+            MoveManifestAccountWrapper, MovePackageOptions, MultisigAccount,
+            MultisigAccountWithSequenceNumber, OptionalPoolAddressArgs, OverrideSizeCheckOption,
+            PoolAddressArgs, PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions,
+            PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, ScriptFunctionArguments,
+            TransactionOptions, TransactionSummary, TypeArgVec,
         },
         utils::write_to_file,
     },
+    fungible_asset::{self, FungibleAssetBalance, FungibleAssetSummary, ManagerOptions},
     governance::{
         CompileScriptFunction, ProposalSubmissionSummary, SubmitProposal, SubmitProposalArgs,
         SubmitVote, SubmitVoteArgs, VerifyProposal, VerifyProposalResponse,
@@ -40,13 +46,16 @@ use crate::{
         UpdateConsensusKey, UpdateValidatorNetworkAddresses, ValidatorConfig,
         ValidatorConsensusKeyArgs, ValidatorNetworkAddressesArgs,
     },
+    object::{self, ObjectSummary},
     op::key::{ExtractPeer, GenerateKey, NetworkKeyInputOptions, SaveKey},
     stake::{
         AddStake, IncreaseLockup, InitializeStakeOwner, SetDelegatedVoter, SetOperator,
         UnlockStake, WithdrawStake,
     },
+    token::{self, TokenSummary},
     CliCommand,
 };
+use aptos_cached_packages::aptos_stdlib;
 use aptos_config::config::Peer;
 use aptos_crypto::{
     bls12381,
@@ -553,6 +562,7 @@ impl CliTestFramework {
             skip_faucet: false,
             ledger: false,
             hardware_wallet_options: Default::default(),
+            encrypt_private_key: false,
         }
         .execute()
         .await
@@ -689,6 +699,339 @@ impl CliTestFramework {
         );
     }
 
+    pub async fn fa_balance(
+        &self,
+        index: usize,
+        metadata: AccountAddress,
+    ) -> CliTypedResult<FungibleAssetBalance> {
+        fungible_asset::Balance {
+            account: Some(self.account_id(index)),
+            metadata,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn fa_metadata(
+        &self,
+        metadata: AccountAddress,
+    ) -> CliTypedResult<FungibleAssetSummary> {
+        fungible_asset::ShowMetadata {
+            metadata,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn fa_transfer(
+        &self,
+        sender_index: usize,
+        receiver_index: usize,
+        metadata: AccountAddress,
+        amount: u64,
+    ) -> CliTypedResult<TransactionSummary> {
+        fungible_asset::Transfer {
+            metadata,
+            to: self.account_id(receiver_index),
+            amount,
+            txn_options: self.transaction_options(sender_index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn fa_mint(
+        &self,
+        admin_index: usize,
+        manager: ModuleId,
+        metadata: AccountAddress,
+        receiver_index: usize,
+        amount: u64,
+    ) -> CliTypedResult<TransactionSummary> {
+        fungible_asset::Mint {
+            manager_options: ManagerOptions { metadata, manager },
+            to: self.account_id(receiver_index),
+            amount,
+            txn_options: self.transaction_options(admin_index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn fa_burn(
+        &self,
+        admin_index: usize,
+        manager: ModuleId,
+        metadata: AccountAddress,
+        owner_index: usize,
+        amount: u64,
+    ) -> CliTypedResult<TransactionSummary> {
+        fungible_asset::Burn {
+            manager_options: ManagerOptions { metadata, manager },
+            from: self.account_id(owner_index),
+            amount,
+            txn_options: self.transaction_options(admin_index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn show_object(&self, object: AccountAddress) -> CliTypedResult<ObjectSummary> {
+        object::Show {
+            object,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn transfer_object(
+        &self,
+        owner_index: usize,
+        object: AccountAddress,
+        receiver_index: usize,
+    ) -> CliTypedResult<TransactionSummary> {
+        object::Transfer {
+            object,
+            to: self.account_id(receiver_index),
+            txn_options: self.transaction_options(owner_index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn create_token_collection(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> CliTypedResult<TransactionSummary> {
+        token::CreateCollection {
+            name: name.to_string(),
+            description: String::new(),
+            uri: String::new(),
+            max_supply: u64::MAX,
+            royalty_numerator: 0,
+            royalty_denominator: 1,
+            mutable: false,
+            burnable: false,
+            freezable: false,
+            txn_options: self.transaction_options(index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn mint_token(
+        &self,
+        index: usize,
+        collection: &str,
+        name: &str,
+    ) -> CliTypedResult<TransactionSummary> {
+        token::Mint {
+            collection: collection.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            uri: String::new(),
+            txn_options: self.transaction_options(index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn show_token(&self, token: AccountAddress) -> CliTypedResult<TokenSummary> {
+        token::Show {
+            token,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn transfer_token(
+        &self,
+        owner_index: usize,
+        token: AccountAddress,
+        receiver_index: usize,
+    ) -> CliTypedResult<TransactionSummary> {
+        token::Transfer {
+            token,
+            to: self.account_id(receiver_index),
+            txn_options: self.transaction_options(owner_index, None),
+        }
+        .execute()
+        .await
+    }
+
+    /// Creates a multisig account owned by the sender and the additional owners, returning its
+    /// address
+    pub async fn create_multisig_account(
+        &self,
+        index: usize,
+        additional_owner_indexes: Vec<usize>,
+        num_signatures_required: u64,
+    ) -> CliTypedResult<AccountAddress> {
+        let summary = multisig_account::Create {
+            additional_owners: additional_owner_indexes
+                .into_iter()
+                .map(|index| self.account_id(index))
+                .collect(),
+            num_signatures_required,
+            txn_options: self.transaction_options(index, None),
+        }
+        .execute()
+        .await?;
+        summary
+            .multisig_account
+            .map(|account| account.multisig_address)
+            .ok_or_else(|| CliError::UnexpectedError("Multisig account not created".to_string()))
+    }
+
+    pub async fn propose_multisig_transaction(
+        &self,
+        index: usize,
+        multisig_address: AccountAddress,
+        function_id: &str,
+        args: Vec<ArgWithType>,
+    ) -> CliTypedResult<TransactionSummary> {
+        multisig_account::CreateTransaction {
+            multisig_account: MultisigAccount { multisig_address },
+            txn_options: self.transaction_options(index, None),
+            entry_function_args: EntryFunctionArguments {
+                function_id: Some(MemberId::from_str(function_id)?),
+                arg_vec: ArgWithTypeVec { args },
+                type_arg_vec: TypeArgVec { type_args: vec![] },
+                json_file: None,
+            },
+            store_hash_only: false,
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn approve_multisig_transaction(
+        &self,
+        index: usize,
+        multisig_address: AccountAddress,
+        sequence_number: u64,
+    ) -> CliTypedResult<TransactionSummary> {
+        multisig_account::Approve {
+            multisig_account_with_sequence_number: MultisigAccountWithSequenceNumber {
+                multisig_account: MultisigAccount { multisig_address },
+                sequence_number,
+            },
+            txn_options: self.transaction_options(index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn execute_multisig_transaction(
+        &self,
+        index: usize,
+        multisig_address: AccountAddress,
+    ) -> CliTypedResult<TransactionSummary> {
+        multisig_account::Execute {
+            multisig_account: MultisigAccount { multisig_address },
+            txn_options: self.transaction_options(index, None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn list_pending_multisig_transactions(
+        &self,
+        multisig_address: AccountAddress,
+    ) -> CliTypedResult<Vec<MultisigProposal>> {
+        multisig_account::ListPending {
+            multisig_account: MultisigAccount { multisig_address },
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn show_multisig_transaction(
+        &self,
+        multisig_address: AccountAddress,
+        sequence_number: u64,
+    ) -> CliTypedResult<MultisigProposal> {
+        multisig_account::Show {
+            multisig_account_with_sequence_number: MultisigAccountWithSequenceNumber {
+                multisig_account: MultisigAccount { multisig_address },
+                sequence_number,
+            },
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    /// Saves a profile for an account that signs with an external signer, without its private key
+    pub fn save_external_signer_profile(
+        &self,
+        profile: &str,
+        index: usize,
+        external_signer: ExternalSigner,
+    ) -> CliTypedResult<()> {
+        let mut config = if CliConfig::config_exists(ConfigSearchMode::CurrentDir) {
+            CliConfig::load(ConfigSearchMode::CurrentDir)?
+        } else {
+            CliConfig::default()
+        };
+        config.profiles.get_or_insert_with(BTreeMap::new).insert(
+            profile.to_string(),
+            ProfileConfig {
+                public_key: Some(self.private_key(index).public_key()),
+                account: Some(self.account_id(index)),
+                rest_url: Some(self.endpoint.to_string()),
+                external_signer: Some(external_signer),
+                ..Default::default()
+            },
+        );
+        config.save()
+    }
+
+    /// Resolves the sender of a profile through the experimental transaction options
+    pub fn profile_sender_address(&self, profile: &str) -> CliTypedResult<AccountAddress> {
+        TxnOptions {
+            profile_options: ProfileOptions {
+                profile: Some(profile.to_string()),
+            },
+            ..Default::default()
+        }
+        .sender_address()
+    }
+
+    /// Simulates a coin transfer locally, signed with the signer of the profile
+    pub async fn simulate_transfer_locally(
+        &self,
+        profile: &str,
+        receiver_index: usize,
+        amount: u64,
+    ) -> CliTypedResult<TransactionSummary> {
+        TransactionOptions {
+            profile_options: ProfileOptions {
+                profile: Some(profile.to_string()),
+            },
+            rest_options: self.rest_options(),
+            prompt_options: PromptOptions::yes(),
+            ..Default::default()
+        }
+        .simulate_locally(aptos_stdlib::aptos_account_transfer(
+            self.account_id(receiver_index),
+            amount,
+        ))
+        .await
+    }
+
     async fn last_n_transactions_details(&self, count: u16) -> String {
         let result = aptos_rest_client::Client::new(self.endpoint.clone())
             .get_transactions(None, Some(count))
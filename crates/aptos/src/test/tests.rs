@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    move_tool::{ArgWithType, FunctionArgType},
+    common::{
+        init::Network,
+        networks::{NetworkConfig, NetworkRegistry},
+        types::network_faucet_url,
+    },
+    move_tool::{ArgWithType, FunctionArgType, ReplayNetworkSelection},
     CliResult, Tool,
 };
 use clap::Parser;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 /// In order to ensure that there aren't duplicate input arguments for untested CLI commands,
 /// we call help on every command to ensure it at least runs
@@ -29,6 +34,16 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "config", "set-global-config", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "config", "show-global-config"]).await;
     assert_cmd_not_panic(&["aptos", "config", "show-profiles"]).await;
+    assert_cmd_not_panic(&["aptos", "config", "show-networks", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "config", "add-network", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "fa"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "balance", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "burn", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "list-stores", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "metadata", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "mint", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "fa", "transfer", "--help"]).await;
 
     assert_cmd_not_panic(&["aptos", "genesis"]).await;
     assert_cmd_not_panic(&["aptos", "genesis", "generate-genesis", "--help"]).await;
@@ -100,6 +115,10 @@ async fn ensure_every_command_args_work() {
     ])
     .await;
 
+    assert_cmd_not_panic(&["aptos", "object"]).await;
+    assert_cmd_not_panic(&["aptos", "object", "show", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "object", "transfer", "--help"]).await;
+
     assert_cmd_not_panic(&["aptos", "stake"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "add-stake", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "increase-lockup", "--help"]).await;
@@ -108,6 +127,13 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "stake", "set-operator", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "unlock-stake", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "withdraw-stake", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "token"]).await;
+    assert_cmd_not_panic(&["aptos", "token", "create-collection", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "token", "list", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "token", "mint", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "token", "show", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "token", "transfer", "--help"]).await;
 }
 
 /// Ensure we can parse URLs for args
@@ -121,6 +147,113 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+/// Ensure networks resolve through the registry and keep their config names
+#[test]
+fn ensure_networks_resolve_through_registry() {
+    let mut registry = NetworkRegistry::default();
+    registry
+        .extend(BTreeMap::from([("bardock".to_string(), NetworkConfig {
+            rest_url: "https://bardock.example.com".to_string(),
+            faucet_url: None,
+            indexer_url: None,
+            chain_id: Some(250),
+        })]))
+        .unwrap();
+
+    let network = Network::from_str("Bardock").unwrap();
+    assert_eq!(network, Network::Named("bardock".to_string()));
+    assert_eq!(registry.resolve(&network).unwrap().chain_id, Some(250));
+    assert!(registry
+        .resolve(&Network::Mainnet)
+        .unwrap()
+        .rest_url
+        .contains("movementnetwork.xyz"));
+    assert!(registry.resolve(&Network::Custom).is_err());
+    assert!(registry.get("unknown").is_err());
+
+    // `localhost` is the local network, not a network of its own
+    assert_eq!(Network::from_str("localhost").unwrap(), Network::Local);
+    assert_eq!(
+        registry.get("localhost").unwrap(),
+        registry.resolve(&Network::Local).unwrap()
+    );
+    assert!(Network::from_str("https://example.com").is_err());
+
+    // Built-in networks keep the names older configs were written with
+    assert_eq!(
+        serde_yaml::to_string(&Network::Testnet).unwrap().trim(),
+        "Testnet"
+    );
+    assert_eq!(
+        serde_yaml::from_str::<Network>("Testnet").unwrap(),
+        Network::Testnet
+    );
+    assert_eq!(serde_yaml::from_str::<Network>("bardock").unwrap(), network);
+
+    assert!(matches!(
+        ReplayNetworkSelection::from_str("bardock").unwrap(),
+        ReplayNetworkSelection::Named(_)
+    ));
+    assert!(matches!(
+        ReplayNetworkSelection::from_str("http://localhost:8080").unwrap(),
+        ReplayNetworkSelection::RestEndpoint(_)
+    ));
+}
+
+/// Ensure faucets of built-in networks can be overridden in the registry too
+#[test]
+fn ensure_faucets_resolve_through_registry() {
+    let network = |faucet_url: Option<&str>| NetworkConfig {
+        rest_url: "https://fullnode.example.com".to_string(),
+        faucet_url: faucet_url.map(str::to_string),
+        indexer_url: None,
+        chain_id: None,
+    };
+    let mut registry = NetworkRegistry::default();
+    assert_eq!(
+        network_faucet_url(&registry, Some(&Network::Devnet))
+            .unwrap()
+            .as_str(),
+        "https://faucet.devnet.movementnetwork.xyz/"
+    );
+
+    registry
+        .extend(BTreeMap::from([
+            (
+                "devnet".to_string(),
+                network(Some("https://faucet.example.com")),
+            ),
+            ("testnet".to_string(), network(None)),
+            (
+                "mainnet".to_string(),
+                network(Some("https://mainnet-faucet.example.com")),
+            ),
+            (
+                "bardock".to_string(),
+                network(Some("https://bardock-faucet.example.com")),
+            ),
+        ]))
+        .unwrap();
+    for (network, faucet_url) in [
+        (Network::Devnet, "https://faucet.example.com/"),
+        (Network::Mainnet, "https://mainnet-faucet.example.com/"),
+        (
+            Network::Named("bardock".to_string()),
+            "https://bardock-faucet.example.com/",
+        ),
+    ] {
+        assert_eq!(
+            network_faucet_url(&registry, Some(&network))
+                .unwrap()
+                .as_str(),
+            faucet_url
+        );
+    }
+    assert!(network_faucet_url(&registry, Some(&Network::Testnet)).is_err());
+    assert!(network_faucet_url(&registry, Some(&Network::Custom)).is_err());
+    assert!(network_faucet_url(&registry, None).is_err());
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
@@ -0,0 +1,396 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliResult, CliTypedResult, IndexerOptions, ProfileOptions,
+            RestOptions, TransactionOptions, TransactionSummary,
+        },
+        utils::{view_function, view_json_number},
+    },
+    object::object_owner,
+};
+use aptos_cached_packages::{aptos_stdlib, aptos_token_objects_sdk_builder};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use move_core_types::{
+    ident_str,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+
+/// Address of the `aptos_token_objects` package
+const TOKEN_OBJECTS_ADDRESS: AccountAddress = AccountAddress::FOUR;
+
+/// Tool for working with Token v2 NFTs
+///
+/// Tokens are objects, created through the `0x4::aptos_token` module.
+#[derive(Debug, Subcommand)]
+pub enum TokenTool {
+    CreateCollection(CreateCollection),
+    List(List),
+    Mint(Mint),
+    Show(Show),
+    Transfer(Transfer),
+}
+
+impl TokenTool {
+    pub async fn execute(self) -> CliResult {
+        use TokenTool::*;
+        match self {
+            CreateCollection(tool) => tool.execute_serialized().await,
+            List(tool) => tool.execute_serialized().await,
+            Mint(tool) => tool.execute_serialized().await,
+            Show(tool) => tool.execute_serialized().await,
+            Transfer(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The type tag of `0x4::token::Token`
+fn token_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: TOKEN_OBJECTS_ADDRESS,
+        module: ident_str!("token").into(),
+        name: ident_str!("Token").into(),
+        type_args: vec![],
+    }))
+}
+
+fn parse_view_value<T: DeserializeOwned>(
+    value: serde_json::Value,
+    field: &'static str,
+) -> CliTypedResult<T> {
+    serde_json::from_value(value).map_err(|err| CliError::UnableToParse(field, err.to_string()))
+}
+
+/// Show the collection, metadata and owner of a token
+#[derive(Debug, Parser)]
+pub struct Show {
+    /// Address of the token object
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) token: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenSummary {
+    pub address: AccountAddress,
+    pub owner: AccountAddress,
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub name: String,
+    pub description: String,
+    pub uri: String,
+    /// Index of the token in its collection, 0 if the collection does not track supply
+    pub index: u64,
+}
+
+#[async_trait]
+impl CliCommand<TokenSummary> for Show {
+    fn command_name(&self) -> &'static str {
+        "ShowToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<TokenSummary> {
+        let client = &self.rest_options.client(&self.profile_options)?;
+        let token = self.token;
+        let view_token = |function: &'static str| async move {
+            let value = view_function(
+                client,
+                TOKEN_OBJECTS_ADDRESS,
+                "token",
+                function,
+                vec![token_type()],
+                vec![token.to_vec()],
+                None,
+            )
+            .await?;
+            Ok::<_, CliError>(value[0].clone())
+        };
+
+        Ok(TokenSummary {
+            address: token,
+            owner: object_owner(client, token).await?,
+            creator: parse_view_value(view_token("creator").await?, "token creator")?,
+            collection: parse_view_value(view_token("collection_name").await?, "token collection")?,
+            name: parse_view_value(view_token("name").await?, "token name")?,
+            description: parse_view_value(view_token("description").await?, "token description")?,
+            uri: parse_view_value(view_token("uri").await?, "token uri")?,
+            index: view_json_number(&view_token("index").await?)?,
+        })
+    }
+}
+
+/// List the tokens owned by an account
+///
+/// Tokens are looked up through the indexer, which may lag behind the chain.
+#[derive(Debug, Parser)]
+pub struct List {
+    /// Address of the account, defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) indexer_options: IndexerOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OwnedToken {
+    pub token: String,
+    pub collection: String,
+    pub name: String,
+    pub uri: String,
+    pub amount: serde_json::Value,
+}
+
+const LIST_TOKENS_QUERY: &str = r#"
+query OwnedTokens($owner: String) {
+  current_token_ownerships_v2(
+    where: { owner_address: { _eq: $owner }, amount: { _gt: "0" }, token_standard: { _eq: "v2" } }
+    order_by: { token_data_id: asc }
+  ) {
+    token_data_id
+    amount
+    current_token_data {
+      token_name
+      token_uri
+      current_collection {
+        collection_name
+      }
+    }
+  }
+}
+"#;
+
+#[async_trait]
+impl CliCommand<Vec<OwnedToken>> for List {
+    fn command_name(&self) -> &'static str {
+        "ListTokens"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<OwnedToken>> {
+        #[derive(Deserialize)]
+        struct Collection {
+            collection_name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct TokenData {
+            token_name: String,
+            token_uri: String,
+            current_collection: Option<Collection>,
+        }
+
+        #[derive(Deserialize)]
+        struct Ownership {
+            token_data_id: String,
+            amount: serde_json::Value,
+            current_token_data: Option<TokenData>,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            current_token_ownerships_v2: Vec<Ownership>,
+        }
+
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let response: Response = self
+            .indexer_options
+            .query(
+                &self.profile_options,
+                LIST_TOKENS_QUERY,
+                json!({ "owner": account.to_standard_string() }),
+            )
+            .await?;
+
+        Ok(response
+            .current_token_ownerships_v2
+            .into_iter()
+            .map(|ownership| {
+                let data = ownership.current_token_data;
+                OwnedToken {
+                    token: ownership.token_data_id,
+                    collection: data
+                        .as_ref()
+                        .and_then(|data| data.current_collection.as_ref())
+                        .map(|collection| collection.collection_name.clone())
+                        .unwrap_or_default(),
+                    name: data
+                        .as_ref()
+                        .map(|data| data.token_name.clone())
+                        .unwrap_or_default(),
+                    uri: data.map(|data| data.token_uri).unwrap_or_default(),
+                    amount: ownership.amount,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Transfer a token to another account
+///
+/// The sender has to be the direct owner of the token.
+#[derive(Debug, Parser)]
+pub struct Transfer {
+    /// Address of the token object
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) token: AccountAddress,
+
+    /// Address of the new owner
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) to: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Transfer {
+    fn command_name(&self) -> &'static str {
+        "TransferToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::object_transfer_call(self.token, self.to))
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+/// Create a collection with `0x4::aptos_token`
+///
+/// The collection is owned by the sender, who can then mint tokens into it.
+#[derive(Debug, Parser)]
+pub struct CreateCollection {
+    /// Name of the collection, unique per creator
+    #[clap(long)]
+    pub(crate) name: String,
+
+    /// Description of the collection
+    #[clap(long, default_value = "")]
+    pub(crate) description: String,
+
+    /// URI of the collection metadata
+    #[clap(long, default_value = "")]
+    pub(crate) uri: String,
+
+    /// Maximum number of tokens in the collection
+    #[clap(long, default_value_t = u64::MAX)]
+    pub(crate) max_supply: u64,
+
+    /// Numerator of the royalty paid to the creator on sales
+    #[clap(long, default_value_t = 0)]
+    pub(crate) royalty_numerator: u64,
+
+    /// Denominator of the royalty paid to the creator on sales
+    #[clap(long, default_value_t = 1)]
+    pub(crate) royalty_denominator: u64,
+
+    /// Allow the creator to change the collection and its tokens after creation
+    #[clap(long)]
+    pub(crate) mutable: bool,
+
+    /// Allow the creator to burn tokens of the collection
+    #[clap(long)]
+    pub(crate) burnable: bool,
+
+    /// Allow the creator to freeze the transfer of tokens of the collection
+    #[clap(long)]
+    pub(crate) freezable: bool,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for CreateCollection {
+    fn command_name(&self) -> &'static str {
+        "CreateCollection"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload = aptos_token_objects_sdk_builder::aptos_token_create_collection(
+            self.description.into_bytes(),
+            self.max_supply,
+            self.name.into_bytes(),
+            self.uri.into_bytes(),
+            self.mutable,
+            self.mutable,
+            self.mutable,
+            self.mutable,
+            self.mutable,
+            self.mutable,
+            self.mutable,
+            self.burnable,
+            self.freezable,
+            self.royalty_numerator,
+            self.royalty_denominator,
+        );
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+/// Mint a token into a collection created with `0x4::aptos_token`
+///
+/// The sender has to be the creator of the collection, and receives the token.
+#[derive(Debug, Parser)]
+pub struct Mint {
+    /// Name of the collection to mint into
+    #[clap(long)]
+    pub(crate) collection: String,
+
+    /// Name of the token
+    #[clap(long)]
+    pub(crate) name: String,
+
+    /// Description of the token
+    #[clap(long, default_value = "")]
+    pub(crate) description: String,
+
+    /// URI of the token metadata
+    #[clap(long, default_value = "")]
+    pub(crate) uri: String,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Mint {
+    fn command_name(&self) -> &'static str {
+        "MintToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload = aptos_token_objects_sdk_builder::aptos_token_mint(
+            self.collection.into_bytes(),
+            self.description.into_bytes(),
+            self.name.into_bytes(),
+            self.uri.into_bytes(),
+            vec![],
+            vec![],
+            vec![],
+        );
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
@@ -0,0 +1,632 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing transactions offline, e.g. with a cold wallet on an air-gapped machine
+//!
+//! Signing is split in three steps, which pass the transaction along in a file:
+//!
+//! 1. `tx build` looks up the sequence number, chain id and gas of the transaction on a machine
+//!    connected to the network, and writes the unsigned transaction.
+//! 2. `tx sign` signs the transaction with a profile, without connecting to the network. Every
+//!    signer of a multi-agent or fee payer transaction signs it, either in turn on the same file,
+//!    or on their own copy of the file.
+//! 3. `tx submit` combines the signatures of one or more copies of the file, and submits the
+//!    transaction.
+//!
+//! The file is the BCS of an [`OfflineTransaction`]. It starts with the version of the format,
+//! followed by the chain id and expiration of the transaction, which are repeated from the raw
+//! transaction so they can be checked before parsing the rest of the file. Signatures are
+//! stored by the address of their signer, as the `AccountAuthenticator` of that signer over the
+//! raw transaction, or over the `RawTransactionWithData` of a multi-agent or fee payer
+//! transaction.
+
+use crate::common::{
+    types::{
+        load_account_arg, CliCommand, CliError, CliResult, CliTypedResult, EntryFunctionArguments,
+        TransactionOptions, TransactionParameters, TransactionSummary,
+    },
+    utils::{check_if_file_exists, prompt_yes_with_override, read_from_file, write_to_file},
+};
+use aptos_crypto::HashValue;
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, RawTransactionWithData,
+        SignedTransaction, TransactionPayload,
+    },
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the offline transaction file format, bumped on incompatible changes
+pub const OFFLINE_TRANSACTION_FORMAT_VERSION: u8 = 1;
+
+/// Tool for building, signing and submitting transactions in separate steps
+///
+/// This allows signing transactions offline, e.g. with a cold wallet on an air-gapped machine.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Build(Build),
+    Sign(Sign),
+    Submit(Submit),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        use TransactionTool::*;
+        match self {
+            Build(tool) => tool.execute_serialized().await,
+            Sign(tool) => tool.execute_serialized().await,
+            Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// A transaction being signed offline, as stored in the files passed between the steps
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OfflineTransaction {
+    /// Version of the file format, see [`OFFLINE_TRANSACTION_FORMAT_VERSION`]
+    pub format_version: u8,
+    pub chain_id: ChainId,
+    pub expiration_timestamp_secs: u64,
+    pub raw_txn: RawTransaction,
+    /// Other accounts signing a multi-agent transaction, in order
+    pub secondary_signer_addresses: Vec<AccountAddress>,
+    /// The account paying for gas instead of the sender, if any
+    pub fee_payer_address: Option<AccountAddress>,
+    /// Signatures collected so far, by signer
+    pub signatures: BTreeMap<AccountAddress, AccountAuthenticator>,
+}
+
+impl OfflineTransaction {
+    pub fn new(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: Option<AccountAddress>,
+    ) -> Self {
+        Self {
+            format_version: OFFLINE_TRANSACTION_FORMAT_VERSION,
+            chain_id: raw_txn.chain_id(),
+            expiration_timestamp_secs: raw_txn.expiration_timestamp_secs(),
+            raw_txn,
+            secondary_signer_addresses,
+            fee_payer_address,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    pub fn read(path: &Path) -> CliTypedResult<Self> {
+        Self::from_bytes(&read_from_file(path)?)
+    }
+
+    /// Decodes a file, checking its format version before the rest of it, so that a file of
+    /// another version is reported as such instead of as a corrupt file
+    pub fn from_bytes(bytes: &[u8]) -> CliTypedResult<Self> {
+        let parse_error =
+            |message: String| CliError::UnableToParse("offline transaction file", message);
+        // The version is a single byte, as BCS encodes a u8 as is
+        match bytes.first() {
+            Some(&OFFLINE_TRANSACTION_FORMAT_VERSION) => {},
+            Some(format_version) => {
+                return Err(parse_error(format!(
+                    "unsupported format version {}, expected {}, the file was written by another version of the CLI",
+                    format_version, OFFLINE_TRANSACTION_FORMAT_VERSION
+                )))
+            },
+            None => return Err(parse_error("the file is empty".to_string())),
+        }
+
+        let transaction: Self =
+            bcs::from_bytes(bytes).map_err(|err| parse_error(err.to_string()))?;
+        if transaction.chain_id != transaction.raw_txn.chain_id()
+            || transaction.expiration_timestamp_secs
+                != transaction.raw_txn.expiration_timestamp_secs()
+        {
+            return Err(parse_error(
+                "chain id or expiration doesn't match the raw transaction".to_string(),
+            ));
+        }
+        Ok(transaction)
+    }
+
+    pub fn write(&self, path: &Path) -> CliTypedResult<()> {
+        write_to_file(path, "offline transaction", &bcs::to_bytes(self)?)
+    }
+
+    /// All accounts that have to sign the transaction, starting with the sender
+    pub fn signers(&self) -> Vec<AccountAddress> {
+        std::iter::once(self.raw_txn.sender())
+            .chain(self.secondary_signer_addresses.iter().copied())
+            .chain(self.fee_payer_address)
+            .collect()
+    }
+
+    /// Signers that haven't signed yet
+    pub fn missing_signatures(&self) -> Vec<AccountAddress> {
+        self.signers()
+            .into_iter()
+            .filter(|signer| !self.signatures.contains_key(signer))
+            .collect()
+    }
+
+    /// The data signed by every signer of a multi-agent or fee payer transaction
+    fn transaction_data(&self) -> Option<RawTransactionWithData> {
+        match self.fee_payer_address {
+            Some(fee_payer_address) => Some(RawTransactionWithData::new_fee_payer(
+                self.raw_txn.clone(),
+                self.secondary_signer_addresses.clone(),
+                fee_payer_address,
+            )),
+            None if !self.secondary_signer_addresses.is_empty() => {
+                Some(RawTransactionWithData::new_multi_agent(
+                    self.raw_txn.clone(),
+                    self.secondary_signer_addresses.clone(),
+                ))
+            },
+            None => None,
+        }
+    }
+
+    /// Signs the transaction with the signer of the profile, as `signer`
+    pub fn sign(
+        &mut self,
+        signer: AccountAddress,
+        txn_options: &TransactionOptions,
+    ) -> CliTypedResult<()> {
+        if !self.signers().contains(&signer) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Account {} is not a signer of this transaction",
+                signer.to_hex_literal()
+            )));
+        }
+        let authenticator = match self.transaction_data() {
+            Some(transaction_data) => txn_options.sign_message(&transaction_data)?,
+            None => txn_options.sign_message(&self.raw_txn)?,
+        };
+        self.signatures.insert(signer, authenticator);
+        Ok(())
+    }
+
+    /// Adds the signatures of another copy of the same transaction
+    pub fn merge(&mut self, other: OfflineTransaction) -> CliTypedResult<()> {
+        if self.raw_txn != other.raw_txn
+            || self.secondary_signer_addresses != other.secondary_signer_addresses
+            || self.fee_payer_address != other.fee_payer_address
+        {
+            return Err(CliError::CommandArgumentError(
+                "Offline transaction files are for different transactions".to_string(),
+            ));
+        }
+        self.signatures.extend(other.signatures);
+        Ok(())
+    }
+
+    /// Builds the signed transaction, once every signer has signed
+    pub fn signed_transaction(&self) -> CliTypedResult<SignedTransaction> {
+        let missing_signatures = self.missing_signatures();
+        if !missing_signatures.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Transaction is missing signatures of {}",
+                missing_signatures
+                    .iter()
+                    .map(|signer| signer.to_hex_literal())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let transaction = self.assemble();
+        transaction.verify_signature().map_err(|err| {
+            CliError::UnexpectedError(format!("Transaction has invalid signatures: {}", err))
+        })?;
+        Ok(transaction)
+    }
+
+    /// Builds the signed transaction out of the signatures, which have to be complete
+    fn assemble(&self) -> SignedTransaction {
+        let authenticator = |signer: &AccountAddress| self.signatures[signer].clone();
+        let sender = authenticator(&self.raw_txn.sender());
+        let secondary_signers = self
+            .secondary_signer_addresses
+            .iter()
+            .map(authenticator)
+            .collect();
+        match self.fee_payer_address {
+            Some(fee_payer_address) => SignedTransaction::new_fee_payer(
+                self.raw_txn.clone(),
+                sender,
+                self.secondary_signer_addresses.clone(),
+                secondary_signers,
+                fee_payer_address,
+                authenticator(&fee_payer_address),
+            ),
+            None if !self.secondary_signer_addresses.is_empty() => {
+                SignedTransaction::new_multi_agent(
+                    self.raw_txn.clone(),
+                    sender,
+                    self.secondary_signer_addresses.clone(),
+                    secondary_signers,
+                )
+            },
+            None => SignedTransaction::new_single_sender(self.raw_txn.clone(), sender),
+        }
+    }
+
+    pub fn summary(&self) -> OfflineTransactionSummary {
+        OfflineTransactionSummary {
+            sender: self.raw_txn.sender(),
+            sequence_number: self.raw_txn.sequence_number(),
+            chain_id: self.chain_id.id(),
+            expiration_timestamp_secs: self.expiration_timestamp_secs,
+            max_gas_amount: self.raw_txn.max_gas_amount(),
+            gas_unit_price: self.raw_txn.gas_unit_price(),
+            payload: describe_payload(self.raw_txn.payload()),
+            secondary_signers: self.secondary_signer_addresses.clone(),
+            fee_payer: self.fee_payer_address,
+            signed_by: self.signatures.keys().copied().collect(),
+            missing_signatures: self.missing_signatures(),
+        }
+    }
+}
+
+/// A human-readable summary of an [`OfflineTransaction`], shown before it is signed
+#[derive(Debug, Serialize)]
+pub struct OfflineTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub chain_id: u8,
+    pub expiration_timestamp_secs: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub payload: serde_json::Value,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secondary_signers: Vec<AccountAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<AccountAddress>,
+    pub signed_by: Vec<AccountAddress>,
+    pub missing_signatures: Vec<AccountAddress>,
+}
+
+fn describe_payload(payload: &TransactionPayload) -> serde_json::Value {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => json!({
+            "function": format!(
+                "{}::{}::{}",
+                entry_function.module().address().to_hex_literal(),
+                entry_function.module().name(),
+                entry_function.function()
+            ),
+            "type_arguments": entry_function
+                .ty_args()
+                .iter()
+                .map(|ty_arg| ty_arg.to_canonical_string())
+                .collect::<Vec<_>>(),
+            "arguments": entry_function
+                .args()
+                .iter()
+                .map(|arg| format!("0x{}", hex::encode(arg)))
+                .collect::<Vec<_>>(),
+        }),
+        TransactionPayload::Script(script) => json!({
+            "script_hash": HashValue::sha3_256_of(script.code()).to_hex_literal(),
+            "type_arguments": script
+                .ty_args()
+                .iter()
+                .map(|ty_arg| ty_arg.to_canonical_string())
+                .collect::<Vec<_>>(),
+        }),
+        payload => json!(format!("{:?}", payload)),
+    }
+}
+
+/// Build an unsigned transaction to sign offline
+///
+/// The sequence number, chain id and gas of the transaction are looked up now, and the
+/// transaction is written to a file for `tx sign`. Use `--expiration-secs` to leave enough time
+/// to sign it before it expires.
+#[derive(Debug, Parser)]
+pub struct Build {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+
+    /// Other accounts that have to sign a multi-agent transaction, in order
+    #[clap(long, num_args = 0.., value_parser = load_account_arg)]
+    pub(crate) secondary_signers: Vec<AccountAddress>,
+
+    /// Account paying for the gas of the transaction instead of the sender
+    #[clap(long, value_parser = load_account_arg)]
+    pub(crate) fee_payer: Option<AccountAddress>,
+
+    /// File to write the unsigned transaction to
+    #[clap(long, value_parser)]
+    pub(crate) output_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for Build {
+    fn command_name(&self) -> &'static str {
+        "BuildTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        check_if_file_exists(&self.output_file, self.txn_options.prompt_options)?;
+        let client = self.txn_options.rest_client()?;
+        let sender_address = self.txn_options.sender_address()?;
+        let payload = TransactionPayload::EntryFunction(self.entry_function_args.try_into()?);
+
+        // The signers' keys may not be available here, so the transaction is simulated without
+        // authenticators
+        let secondary_signers = self.secondary_signers.clone();
+        let fee_payer = self.fee_payer;
+        let TransactionParameters {
+            chain_id,
+            sequence_number,
+            gas_unit_price,
+            max_gas,
+            expiration_timestamp_secs,
+        } = self
+            .txn_options
+            .transaction_parameters(&client, sender_address, &payload, |unsigned_transaction| {
+                let mut transaction =
+                    OfflineTransaction::new(unsigned_transaction, secondary_signers, fee_payer);
+                for signer in transaction.signers() {
+                    transaction
+                        .signatures
+                        .insert(signer, AccountAuthenticator::NoAccountAuthenticator);
+                }
+                Ok(transaction.assemble())
+            })
+            .await?;
+
+        let raw_txn = TransactionFactory::new(chain_id)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .expiration_timestamp_secs(expiration_timestamp_secs)
+            .build();
+        let transaction = OfflineTransaction::new(raw_txn, self.secondary_signers, self.fee_payer);
+        transaction.write(&self.output_file)?;
+        Ok(transaction.summary())
+    }
+}
+
+/// Sign a transaction built with `tx build`, without connecting to the network
+///
+/// The transaction is signed by the account of the profile, or the one given by
+/// `--sender-account`, which has to be the sender, a secondary signer or the fee payer.
+#[derive(Debug, Parser)]
+pub struct Sign {
+    /// File with the transaction to sign
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    /// File to write the signed transaction to, instead of updating the transaction file
+    #[clap(long, value_parser)]
+    pub(crate) output_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for Sign {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        let mut transaction = OfflineTransaction::read(&self.transaction_file)?;
+        let signer = self.txn_options.sender_address()?;
+
+        eprintln!(
+            "{}",
+            serde_json::to_string_pretty(&transaction.summary())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+        );
+        if transaction.expiration_timestamp_secs <= now_secs()? {
+            eprintln!("Warning: this transaction has already expired");
+        }
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to sign this transaction as {}?",
+                signer.to_hex_literal()
+            ),
+            self.txn_options.prompt_options,
+        )?;
+
+        transaction.sign(signer, &self.txn_options)?;
+        let output_file = self.output_file.unwrap_or(self.transaction_file);
+        transaction.write(&output_file)?;
+        Ok(transaction.summary())
+    }
+}
+
+/// Submit a transaction signed with `tx sign`
+///
+/// Signatures of several copies of the same transaction, each signed by different signers, are
+/// combined before the transaction is submitted.
+#[derive(Debug, Parser)]
+pub struct Submit {
+    /// Files with the signed transaction
+    #[clap(long, num_args = 1.., required = true, value_parser)]
+    pub(crate) transaction_files: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Submit {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let mut files = self.transaction_files.iter();
+        let mut transaction =
+            OfflineTransaction::read(files.next().expect("at least one file is required"))?;
+        for file in files {
+            transaction.merge(OfflineTransaction::read(file)?)?;
+        }
+        if transaction.expiration_timestamp_secs <= now_secs()? {
+            return Err(CliError::CommandArgumentError(
+                "Transaction has expired, it has to be built and signed again".to_string(),
+            ));
+        }
+
+        let signed_transaction = transaction.signed_transaction()?;
+        let client = self.txn_options.rest_client()?;
+        self.txn_options
+            .submit_signed_transaction(&client, signed_transaction)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+fn now_secs() -> CliTypedResult<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+        .as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        account_keys::{AccountKeys, AnyPrivateKey},
+        types::{account_address_from_public_key, PrivateKeyInputOptions},
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_types::transaction::Script;
+
+    fn account_keys() -> (AccountKeys, AccountAddress) {
+        let keys = AccountKeys::single_key(AnyPrivateKey::Ed25519(Ed25519PrivateKey::generate(
+            &mut rand::thread_rng(),
+        )));
+        let address = keys.authentication_key().unwrap().account_address();
+        (keys, address)
+    }
+
+    /// Options signing with a private key given on the command line, and its account
+    fn txn_options() -> (TransactionOptions, AccountAddress) {
+        let private_key = Ed25519PrivateKey::generate(&mut rand::thread_rng());
+        let address = account_address_from_public_key(&private_key.public_key());
+        let txn_options = TransactionOptions {
+            private_key_options: PrivateKeyInputOptions::from_private_key(&private_key).unwrap(),
+            ..Default::default()
+        };
+        (txn_options, address)
+    }
+
+    fn raw_txn(sender: AccountAddress) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    #[test]
+    fn test_sign_message() {
+        let (txn_options, sender) = txn_options();
+        let raw_txn = raw_txn(sender);
+        let authenticator = txn_options.sign_message(&raw_txn).unwrap();
+        let signed_txn = SignedTransaction::new_single_sender(raw_txn, authenticator);
+        assert!(signed_txn.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn test_multi_agent_signatures() {
+        let (sender_options, sender) = txn_options();
+        let (secondary_options, secondary_signer) = txn_options();
+        let mut transaction =
+            OfflineTransaction::new(raw_txn(sender), vec![secondary_signer], None);
+
+        // Only the signers of the transaction can sign it
+        assert!(transaction
+            .sign(AccountAddress::random(), &sender_options)
+            .is_err());
+
+        transaction.sign(sender, &sender_options).unwrap();
+        assert_eq!(transaction.missing_signatures(), vec![secondary_signer]);
+        assert!(transaction.signed_transaction().is_err());
+
+        transaction
+            .sign(secondary_signer, &secondary_options)
+            .unwrap();
+        let signed_txn = transaction.signed_transaction().unwrap();
+        assert_eq!(
+            signed_txn.authenticator().secondary_signer_addresses(),
+            vec![secondary_signer]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_format_version() {
+        let transaction = OfflineTransaction::new(raw_txn(AccountAddress::random()), vec![], None);
+        let mut bytes = bcs::to_bytes(&transaction).unwrap();
+        assert_eq!(OfflineTransaction::from_bytes(&bytes).unwrap(), transaction);
+
+        bytes[0] = OFFLINE_TRANSACTION_FORMAT_VERSION + 1;
+        let err = OfflineTransaction::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains(&format!(
+            "unsupported format version {}",
+            OFFLINE_TRANSACTION_FORMAT_VERSION + 1
+        )));
+    }
+
+    #[test]
+    fn test_mismatched_chain_id() {
+        let mut transaction =
+            OfflineTransaction::new(raw_txn(AccountAddress::random()), vec![], None);
+        transaction.chain_id = ChainId::new(ChainId::test().id() + 1);
+        let err =
+            OfflineTransaction::from_bytes(&bcs::to_bytes(&transaction).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("chain id"));
+    }
+
+    #[test]
+    fn test_fee_payer_signatures_from_separate_copies() {
+        let (sender_keys, sender) = account_keys();
+        let (fee_payer_keys, fee_payer) = account_keys();
+        let transaction = OfflineTransaction::new(raw_txn(sender), vec![], Some(fee_payer));
+        let transaction: OfflineTransaction =
+            bcs::from_bytes(&bcs::to_bytes(&transaction).unwrap()).unwrap();
+        assert_eq!(transaction.missing_signatures(), vec![sender, fee_payer]);
+
+        // Each signer signs their own copy of the file
+        let data = transaction.transaction_data().unwrap();
+        let mut sender_copy = transaction.clone();
+        sender_copy
+            .signatures
+            .insert(sender, sender_keys.authenticator(&data).unwrap());
+        let mut fee_payer_copy = transaction;
+        fee_payer_copy
+            .signatures
+            .insert(fee_payer, fee_payer_keys.authenticator(&data).unwrap());
+        assert!(sender_copy.signed_transaction().is_err());
+
+        sender_copy.merge(fee_payer_copy).unwrap();
+        assert!(sender_copy.missing_signatures().is_empty());
+        assert!(sender_copy.signed_transaction().is_ok());
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_rest_client::{aptos_api_types, AptosBaseUrl, Client as RestClient};
+use aptos_rest_client::{aptos_api_types, AptosBaseUrl, Client as RestClient, RetryPolicy};
 use reqwest::Url;
 use std::fmt;
 
@@ -45,6 +45,7 @@ impl Instance {
 
     pub fn rest_client(&self) -> RestClient {
         let client = RestClient::builder(AptosBaseUrl::Custom(self.api_url()))
+            .retry_policy(RetryPolicy::exponential())
             .header(aptos_api_types::X_APTOS_CLIENT, X_APTOS_CLIENT_VALUE)
             .expect("Failed to initialize REST Client instance");
 
@@ -52,7 +52,7 @@ impl InternalIndexerDBService {
         );
 
         let internal_indexer_db_config =
-            InternalIndexerDBConfig::new(true, true, true, 0, true, 10_000);
+            InternalIndexerDBConfig::new(true, true, true, 0, true, false, 10_000);
         Some(InternalIndexerDB::new(arc_db, internal_indexer_db_config))
     }
 
@@ -138,6 +138,17 @@ impl InternalIndexerDBService {
             }
         }
 
+        if node_config.indexer_db_config.enable_event_by_type() {
+            let event_by_type_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_event_by_type_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != event_by_type_start_version {
+                panic!("Cannot start event by type indexer because the progress doesn't match.");
+            }
+        }
+
         if node_config.indexer_db_config.enable_event_v2_translation() {
             let event_v2_translation_start_version = self
                 .db_indexer
@@ -0,0 +1,186 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal of the transactions held in mempool, so that they survive node restarts.
+//!
+//! A transaction is written to the journal when it is inserted into the `TransactionStore`, and
+//! deleted when it leaves the store for any reason (commit, rejection, expiration, eviction or
+//! replacement by a gas upgrade). The writes are queued to a dedicated thread, which batches
+//! them, so that mempool never waits on the disk while holding its lock. On startup, the
+//! journaled transactions are resubmitted through VM validation, which drops the entries that
+//! expired or got committed in the meantime.
+
+mod schema;
+
+use crate::{
+    core_mempool::{
+        journal::schema::{TransactionSchema, TRANSACTION_CF_NAME},
+        transaction::{MempoolTransaction, SubmittedBy, TimelineState},
+    },
+    counters,
+    logging::{LogEntry, LogSchema},
+    network::BroadcastPeerPriority,
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_schemadb::{batch::SchemaBatch, Options, DB};
+use aptos_types::transaction::SignedTransaction;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    sync::{mpsc, Arc},
+    thread::JoinHandle,
+    time::Instant,
+};
+
+/// The name of the mempool journal db file
+pub const MEMPOOL_JOURNAL_DB_NAME: &str = "mempoolDB";
+
+/// A transaction as stored in the journal, with what is needed to insert it back into mempool.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub(crate) struct JournaledTransaction {
+    pub txn: SignedTransaction,
+    pub timeline_state: TimelineState,
+    pub client_submitted: bool,
+    pub priority_of_sender: Option<BroadcastPeerPriority>,
+}
+
+impl From<&MempoolTransaction> for JournaledTransaction {
+    fn from(txn: &MempoolTransaction) -> Self {
+        Self {
+            txn: txn.txn.clone(),
+            // The position in the broadcast timeline does not survive a restart, so the
+            // transaction has to become ready for broadcast again.
+            timeline_state: match txn.timeline_state {
+                TimelineState::Ready(_) => TimelineState::NotReady,
+                state => state,
+            },
+            client_submitted: txn.insertion_info.submitted_by == SubmittedBy::Client,
+            priority_of_sender: txn.priority_of_sender.clone(),
+        }
+    }
+}
+
+/// A write queued to the journal writer thread.
+enum JournalWrite {
+    Save(HashValue, JournaledTransaction),
+    Delete(HashValue),
+}
+
+pub(crate) struct MempoolJournal {
+    db: Arc<DB>,
+    sender: Option<mpsc::Sender<JournalWrite>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl MempoolJournal {
+    pub(crate) fn new<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join(MEMPOOL_JOURNAL_DB_NAME);
+        let instant = Instant::now();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = Arc::new(DB::open(
+            path.clone(),
+            MEMPOOL_JOURNAL_DB_NAME,
+            vec![TRANSACTION_CF_NAME],
+            &opts,
+        )?);
+
+        info!(
+            "Opened MempoolJournalDB at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("mempool-journal".to_string())
+            .spawn({
+                let db = db.clone();
+                move || Self::write_loop(&db, receiver)
+            })?;
+
+        Ok(Self {
+            db,
+            sender: Some(sender),
+            writer: Some(writer),
+        })
+    }
+
+    /// Records a transaction inserted into mempool.
+    ///
+    /// The journal is best effort and must not get in the way of mempool itself, so the write
+    /// happens in the background and failures are only logged.
+    pub(crate) fn save(&self, txn: &MempoolTransaction) {
+        self.send(JournalWrite::Save(
+            txn.get_committed_hash(),
+            JournaledTransaction::from(txn),
+        ));
+    }
+
+    /// Forgets a transaction that left mempool.
+    pub(crate) fn delete(&self, hash: &HashValue) {
+        self.send(JournalWrite::Delete(*hash));
+    }
+
+    /// Forgets a set of transactions at once, e.g. the ones that could not be restored.
+    pub(crate) fn delete_all(&self, hashes: impl IntoIterator<Item = HashValue>) {
+        for hash in hashes {
+            self.send(JournalWrite::Delete(hash));
+        }
+    }
+
+    /// Returns all journaled transactions.
+    pub(crate) fn get_all(&self) -> Result<Vec<JournaledTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>()?;
+        iter.seek_to_first();
+        iter.map(|res| res.map(|(_, txn)| txn).map_err(Into::into))
+            .collect()
+    }
+
+    fn send(&self, write: JournalWrite) {
+        if let Some(sender) = &self.sender {
+            // The writer only stops once the sender is dropped
+            let _ = sender.send(write);
+        }
+    }
+
+    /// Writes the queued writes until the journal is dropped, batching the ones that queued up
+    /// while the previous batch was being written.
+    fn write_loop(db: &DB, receiver: mpsc::Receiver<JournalWrite>) {
+        while let Ok(write) = receiver.recv() {
+            let result = (|| {
+                let mut batch = SchemaBatch::new();
+                for write in std::iter::once(write).chain(receiver.try_iter()) {
+                    match write {
+                        JournalWrite::Save(hash, txn) => {
+                            batch.put::<TransactionSchema>(&hash, &txn)?
+                        },
+                        JournalWrite::Delete(hash) => batch.delete::<TransactionSchema>(&hash)?,
+                    }
+                }
+                db.write_schemas(batch)
+            })();
+            Self::log_error(result.map_err(Into::into));
+        }
+    }
+
+    fn log_error(result: Result<()>) {
+        if let Err(e) = result {
+            error!(LogSchema::new(LogEntry::DBError).error(&e));
+            counters::DB_ERROR.inc();
+        }
+    }
+}
+
+impl Drop for MempoolJournal {
+    fn drop(&mut self) {
+        // Let the writer drain the queue, so that no write is lost on shutdown
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
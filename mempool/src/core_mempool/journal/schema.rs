@@ -0,0 +1,43 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::core_mempool::journal::JournaledTransaction;
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    schema::{KeyCodec, Schema, ValueCodec},
+    ColumnFamilyName,
+};
+
+pub(crate) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+/// Journaled transactions, keyed by their committed hash.
+#[derive(Debug)]
+pub(crate) struct TransactionSchema;
+
+impl Schema for TransactionSchema {
+    type Key = HashValue;
+    type Value = JournaledTransaction;
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = TRANSACTION_CF_NAME;
+}
+
+impl KeyCodec<TransactionSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<TransactionSchema> for JournaledTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
@@ -7,6 +7,7 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        journal::{JournaledTransaction, MempoolJournal},
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
         transaction_store::{sender_bucket, TransactionStore},
     },
@@ -42,8 +43,21 @@ pub struct Mempool {
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let journal = if config.mempool.enable_journal {
+            MempoolJournal::new(config.storage.dir())
+                .inspect_err(|e| {
+                    error!(
+                        LogSchema::new(LogEntry::DBError).error(e),
+                        "Failed to open the mempool journal, running without it"
+                    );
+                    counters::DB_ERROR.inc();
+                })
+                .ok()
+        } else {
+            None
+        };
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(&config.mempool, journal),
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
@@ -648,6 +662,19 @@ impl Mempool {
         self.transactions.gen_snapshot()
     }
 
+    /// Returns the transactions journaled before the node restarted, or `None` if the journal is
+    /// disabled.
+    pub(crate) fn journaled_transactions(
+        &self,
+    ) -> Option<anyhow::Result<Vec<JournaledTransaction>>> {
+        self.transactions.journaled_transactions()
+    }
+
+    /// Removes journaled transactions that could not be restored from the journal.
+    pub(crate) fn remove_from_journal(&self, hashes: Vec<HashValue>) {
+        self.transactions.remove_from_journal(hashes)
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
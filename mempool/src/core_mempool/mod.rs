@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
 pub mod transaction;
 mod transaction_store;
 
+pub(crate) use self::journal::JournaledTransaction;
 pub use self::{
     index::TimelineId, mempool::Mempool as CoreMempool, transaction::TimelineState,
     transaction_store::TXN_INDEX_ESTIMATED_BYTES,
@@ -8,6 +8,7 @@ use crate::{
             AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
             PriorityQueueIter, TTLIndex,
         },
+        journal::{JournaledTransaction, MempoolJournal},
         mempool::Mempool,
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
     },
@@ -99,10 +100,13 @@ pub struct TransactionStore {
     // eager expiration
     eager_expire_threshold: Option<Duration>,
     eager_expire_time: Duration,
+
+    // On-disk journal mirroring the transactions in the store, if enabled.
+    journal: Option<MempoolJournal>,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, journal: Option<MempoolJournal>) -> Self {
         let mut timeline_index = HashMap::new();
         for sender_bucket in 0..config.num_sender_buckets {
             timeline_index.insert(
@@ -138,6 +142,8 @@ impl TransactionStore {
             // eager expiration
             eager_expire_threshold: config.eager_expire_threshold_ms.map(Duration::from_millis),
             eager_expire_time: Duration::from_millis(config.eager_expire_time_ms),
+
+            journal,
         }
     }
 
@@ -353,6 +359,9 @@ impl TransactionStore {
                 self.account_sequence_numbers.insert(address, acc_seq_num);
             }
             self.size_bytes += txn.get_estimated_bytes();
+            if let Some(journal) = &self.journal {
+                journal.save(&txn);
+            }
             txns.insert(txn);
             self.track_indices();
         }
@@ -754,6 +763,9 @@ impl TransactionStore {
             .remove(txn);
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
+        if let Some(journal) = &self.journal {
+            journal.delete(&txn.get_committed_hash());
+        }
         self.size_bytes -= txn.get_estimated_bytes();
 
         // Remove account datastructures if there are no more transactions for the account.
@@ -1039,6 +1051,20 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Returns the transactions in the journal, or `None` if the journal is disabled.
+    pub(crate) fn journaled_transactions(
+        &self,
+    ) -> Option<anyhow::Result<Vec<JournaledTransaction>>> {
+        self.journal.as_ref().map(|journal| journal.get_all())
+    }
+
+    /// Removes journaled transactions that did not make it back into the store.
+    pub(crate) fn remove_from_journal(&self, hashes: Vec<HashValue>) {
+        if let Some(journal) = &self.journal {
+            journal.delete_all(hashes);
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
@@ -187,6 +187,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    RestoreJournal,
 }
 
 #[derive(Clone, Copy, Serialize)]
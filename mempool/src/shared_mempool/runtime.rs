@@ -7,11 +7,12 @@ use crate::{
     network::MempoolSyncMsg,
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        tasks::restore_journaled_transactions,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
 };
-use aptos_config::config::{NodeConfig, NodeType};
+use aptos_config::config::{transaction_filter_type::SharedFilter, NodeConfig, NodeType};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::Level;
@@ -28,7 +29,8 @@ use std::sync::Arc;
 use tokio::runtime::{Handle, Runtime};
 
 /// Bootstrap of SharedMempool.
-/// Creates a separate Tokio Runtime that runs the following routines:
+/// Restores the journaled transactions, if the journal is enabled, then creates a separate Tokio
+/// Runtime that runs the following routines:
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
@@ -46,6 +48,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     validator: Arc<RwLock<TransactionValidator>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter: SharedFilter,
 ) where
     TransactionValidator: TransactionValidation + 'static,
     ConfigProvider: OnChainConfigProvider,
@@ -60,7 +63,9 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             validator,
             subscribers,
             node_type,
+            transaction_filter,
         );
+    restore_journaled_transactions(&smp);
 
     executor.spawn(coordinator(
         smp,
@@ -97,6 +102,7 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter: SharedFilter,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("shared-mem".into(), None);
     let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
@@ -118,6 +124,7 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peers_and_metadata,
+        transaction_filter,
     );
     runtime
 }
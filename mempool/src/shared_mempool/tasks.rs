@@ -5,7 +5,7 @@
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use super::types::MempoolMessageId;
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{CoreMempool, JournaledTransaction, TimelineState},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
@@ -41,6 +41,7 @@ use futures::{channel::oneshot, stream::FuturesUnordered};
 use rayon::prelude::*;
 use std::{
     cmp,
+    collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -304,6 +305,26 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject the transactions denied by the transaction filter before doing any work on them
+    let transaction_filter = smp.transaction_filter.load();
+    let transactions: Vec<_> = transactions
+        .into_iter()
+        .filter_map(|(t, ready_time_at_sender, priority)| {
+            if transaction_filter.allows_transaction(&t) {
+                Some((t, ready_time_at_sender, priority))
+            } else {
+                statuses.push((
+                    t,
+                    (
+                        MempoolStatus::new(MempoolStatusCode::RejectedByFilter),
+                        None,
+                    ),
+                ));
+                None
+            }
+        })
+        .collect();
+
     let start_storage_read = Instant::now();
     let state_view = smp
         .db
@@ -382,6 +403,63 @@ where
     statuses
 }
 
+/// Resubmits the transactions journaled before the node restarted, if the journal is enabled.
+///
+/// The transactions go through the regular submission path, so the ones that expired or got
+/// committed in the meantime fail validation. Those are removed from the journal.
+pub(crate) fn restore_journaled_transactions<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let journaled = smp.mempool.lock().journaled_transactions();
+    let journaled = match journaled {
+        Some(Ok(journaled)) => journaled,
+        Some(Err(e)) => {
+            error!(LogSchema::new(LogEntry::DBError).error(&e));
+            counters::DB_ERROR.inc();
+            return;
+        },
+        None => return,
+    };
+    let num_journaled = journaled.len();
+
+    // Transactions are submitted in groups sharing the same timeline state and origin
+    let mut groups: HashMap<_, Vec<_>> = HashMap::new();
+    for JournaledTransaction {
+        txn,
+        timeline_state,
+        client_submitted,
+        priority_of_sender,
+    } in journaled
+    {
+        groups
+            .entry((timeline_state, client_submitted))
+            .or_default()
+            .push((txn, None, priority_of_sender));
+    }
+
+    let mut num_restored = 0;
+    for ((timeline_state, client_submitted), transactions) in groups {
+        let statuses =
+            process_incoming_transactions(smp, transactions, timeline_state, client_submitted);
+        let dropped: Vec<_> = statuses
+            .iter()
+            .filter(|(_, (status, _))| status.code != MempoolStatusCode::Accepted)
+            .map(|(txn, _)| txn.committed_hash())
+            .collect();
+        num_restored += statuses.len() - dropped.len();
+        smp.mempool.lock().remove_from_journal(dropped);
+    }
+
+    info!(
+        LogSchema::new(LogEntry::RestoreJournal).num_txns(num_restored),
+        num_journaled = num_journaled,
+        "Restored transactions from the mempool journal"
+    );
+}
+
 /// Perfoms VM validation on the transactions and inserts those that passes
 /// validation into the mempool.
 #[cfg(not(feature = "consensus-only-perf-test"))]
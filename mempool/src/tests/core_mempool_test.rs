@@ -13,6 +13,7 @@ use crate::{
 use aptos_config::config::{MempoolConfig, NodeConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatusCode,
@@ -21,7 +22,10 @@ use aptos_types::{
 };
 use itertools::Itertools;
 use maplit::btreemap;
-use std::time::{Duration, Instant, SystemTime};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant, SystemTime},
+};
 
 #[test]
 fn test_transaction_ordering_only_seqnos() {
@@ -1760,3 +1764,48 @@ fn test_include_gas_upgraded() {
     });
     assert_eq!(batch.len(), 0);
 }
+
+#[test]
+fn test_journal_tracks_mempool_contents() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.enable_journal = true;
+    config.storage.dir = tmp_dir.path().to_path_buf();
+
+    let mut pool = CoreMempool::new(&config);
+    let transactions = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+        TestTransaction::new(0, ReplayProtector::SequenceNumber(1), 1),
+        TestTransaction::new(1, ReplayProtector::Nonce(7), 1),
+    ]);
+    // Committed transactions leave the journal along with mempool
+    pool.commit_transaction(
+        &TestTransaction::get_address(0),
+        ReplayProtector::SequenceNumber(0),
+    );
+    drop(pool);
+
+    // The journal outlives the mempool it was written by
+    let pool = CoreMempool::new(&config);
+    let journaled: HashSet<_> = pool
+        .journaled_transactions()
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|journaled| journaled.txn.committed_hash())
+        .collect();
+    let expected: HashSet<_> = transactions[1..]
+        .iter()
+        .map(|txn| txn.committed_hash())
+        .collect();
+    assert_eq!(journaled, expected);
+
+    // Transactions that could not be restored are forgotten
+    pool.remove_from_journal(vec![transactions[1].committed_hash()]);
+    drop(pool);
+    let pool = CoreMempool::new(&config);
+    let journaled = pool.journaled_transactions().unwrap().unwrap();
+    assert_eq!(journaled.len(), 1);
+    assert_eq!(journaled[0].txn, transactions[2]);
+}
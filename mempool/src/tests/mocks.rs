@@ -11,7 +11,7 @@ use crate::{
 use anyhow::{format_err, Result};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NetworkConfig, NodeConfig},
+    config::{transaction_filter_type::SharedFilter, NetworkConfig, NodeConfig},
     network_id::NetworkId,
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
@@ -60,9 +60,15 @@ impl MockSharedMempool {
     /// Returns the runtime on which the shared mempool is running
     /// and the channel through which shared mempool receives client events.
     pub fn new() -> Self {
+        Self::new_with_config(Self::default_config())
+    }
+
+    /// Creates a mock of a running instance of shared mempool with the given node config.
+    pub fn new_with_config(config: NodeConfig) -> Self {
         // Create the shared mempool
-        let (ac_client, mempool, quorum_store_sender, mempool_notifier) = Self::start(
+        let (ac_client, mempool, quorum_store_sender, mempool_notifier) = Self::start_with_config(
             &Handle::current(),
+            config,
             &DbReaderWriter::new(MockDbReaderWriter),
             MockVMValidator,
         );
@@ -91,10 +97,19 @@ impl MockSharedMempool {
     pub fn new_in_runtime<V: TransactionValidation + 'static>(
         db: &DbReaderWriter,
         validator: V,
+    ) -> Self {
+        Self::new_in_runtime_with_config(Self::default_config(), db, validator)
+    }
+
+    /// Like `new_in_runtime`, with the given node config.
+    pub fn new_in_runtime_with_config<V: TransactionValidation + 'static>(
+        config: NodeConfig,
+        db: &DbReaderWriter,
+        validator: V,
     ) -> Self {
         let handle = Handle::current();
         let (ac_client, mempool, quorum_store_sender, mempool_notifier) =
-            Self::start(&handle, db, validator);
+            Self::start_with_config(&handle, config, db, validator);
         Self {
             _runtime: None,
             _handle: Some(handle),
@@ -105,6 +120,13 @@ impl MockSharedMempool {
         }
     }
 
+    /// The node config mock shared mempools run with, unless given one.
+    pub fn default_config() -> NodeConfig {
+        let mut config = NodeConfig::generate_random_config();
+        config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
+        config
+    }
+
     pub fn start<V: TransactionValidation + 'static>(
         handle: &Handle,
         db: &DbReaderWriter,
@@ -115,9 +137,20 @@ impl MockSharedMempool {
         mpsc::Sender<QuorumStoreRequest>,
         MempoolNotifier,
     ) {
-        let mut config = NodeConfig::generate_random_config();
-        config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
+        Self::start_with_config(handle, Self::default_config(), db, validator)
+    }
 
+    pub fn start_with_config<V: TransactionValidation + 'static>(
+        handle: &Handle,
+        config: NodeConfig,
+        db: &DbReaderWriter,
+        validator: V,
+    ) -> (
+        MempoolClientSender,
+        Arc<Mutex<CoreMempool>>,
+        mpsc::Sender<QuorumStoreRequest>,
+        MempoolNotifier,
+    ) {
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
         let (network_reqs_tx, _network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
         let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
@@ -169,6 +202,7 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peers_and_metadata,
+            SharedFilter::new(config.mempool.transaction_filter.clone()),
         );
 
         (ac_client, mempool, quorum_store_sender, mempool_notifier)
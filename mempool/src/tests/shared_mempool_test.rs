@@ -3,19 +3,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::sender_bucket,
+    core_mempool::{sender_bucket, CoreMempool},
     mocks::MockSharedMempool,
     network::BroadcastPeerPriority,
     tests::common::{batch_add_signed_txn, TestTransaction},
-    QuorumStoreRequest,
+    MempoolClientRequest, QuorumStoreRequest,
 };
-use aptos_config::config::MempoolConfig;
+use aptos_config::config::{transaction_filter_type::Filter, MempoolConfig};
 use aptos_consensus_types::common::RejectedTransactionSummary;
 use aptos_mempool_notifications::MempoolNotificationSender;
+use aptos_temppath::TempPath;
 use aptos_types::{
+    mempool_status::MempoolStatusCode,
     transaction::{ReplayProtector, Transaction},
     vm_status::DiscardedVMStatus,
 };
+use aptos_vm_validator::mocks::mock_vm_validator::{
+    SEQ_NUMBER_TOO_OLD_TEST_ADD, TXN_EXPIRATION_TIME_TEST_ADD,
+};
 use futures::{channel::oneshot, sink::SinkExt};
 use tokio::time::timeout;
 
@@ -160,3 +165,104 @@ async fn test_mempool_notify_committed_txns() {
         );
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_restore_journaled_transactions() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let mut config = MockSharedMempool::default_config();
+    config.mempool.enable_journal = true;
+    config.storage.dir = tmp_dir.path().to_path_buf();
+
+    // The mock validator fails transactions by sender, as if they had expired or got committed
+    // while the node was down
+    let valid_txn =
+        TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1).make_signed_transaction();
+    let expired_txn = TestTransaction::new_with_address(
+        TXN_EXPIRATION_TIME_TEST_ADD,
+        ReplayProtector::SequenceNumber(0),
+        1,
+    )
+    .make_signed_transaction();
+    let committed_txn = TestTransaction::new_with_address(
+        SEQ_NUMBER_TOO_OLD_TEST_ADD,
+        ReplayProtector::SequenceNumber(0),
+        1,
+    )
+    .make_signed_transaction();
+    {
+        let mut pool = CoreMempool::new(&config);
+        assert!(batch_add_signed_txn(&mut pool, vec![
+            valid_txn.clone(),
+            expired_txn.clone(),
+            committed_txn.clone()
+        ])
+        .is_ok());
+    }
+
+    // Restart, the transactions are restored before shared mempool starts
+    let smp = MockSharedMempool::new_with_config(config);
+    {
+        let pool = smp.mempool.lock();
+        assert_eq!(
+            pool.get_by_hash(valid_txn.committed_hash()),
+            Some(valid_txn.clone())
+        );
+        assert!(pool.get_by_hash(expired_txn.committed_hash()).is_none());
+        assert!(pool.get_by_hash(committed_txn.committed_hash()).is_none());
+    }
+
+    // The transactions that were not restored are forgotten by the journal in the background
+    let wait_for_journal = async {
+        loop {
+            let journaled = smp
+                .mempool
+                .lock()
+                .journaled_transactions()
+                .unwrap()
+                .unwrap();
+            if journaled.len() == 1 {
+                assert_eq!(journaled[0].txn, valid_txn);
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    };
+    if let Err(elapsed) = timeout(std::time::Duration::from_secs(5), wait_for_journal).await {
+        panic!("Journal was not cleaned up! {:?}", elapsed);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_filter_rejects_submission() {
+    let denied_txn =
+        TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1).make_signed_transaction();
+    let allowed_txn =
+        TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 1).make_signed_transaction();
+    let mut config = MockSharedMempool::default_config();
+    config.mempool.transaction_filter =
+        Filter::empty().add_sender_filter(false, denied_txn.sender());
+    let smp = MockSharedMempool::new_with_config(config);
+
+    for (txn, expected_code) in [
+        (denied_txn.clone(), MempoolStatusCode::RejectedByFilter),
+        (allowed_txn.clone(), MempoolStatusCode::Accepted),
+    ] {
+        let (callback, callback_rcv) = oneshot::channel();
+        let mut ac_client = smp.ac_client.clone();
+        assert!(ac_client
+            .send(MempoolClientRequest::SubmitTransaction(txn, callback))
+            .await
+            .is_ok());
+        let (status, _) = callback_rcv.await.unwrap().unwrap();
+        assert_eq!(status.code, expected_code);
+    }
+
+    // Only the allowed transaction made it into mempool
+    let pool = smp.mempool.lock();
+    assert!(pool.get_by_hash(denied_txn.committed_hash()).is_none());
+    assert_eq!(
+        pool.get_by_hash(allowed_txn.committed_hash()),
+        Some(allowed_txn)
+    );
+}
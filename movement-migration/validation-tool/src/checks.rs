@@ -4,3 +4,5 @@
 pub mod api;
 pub mod error;
 pub mod node;
+pub mod registry;
+pub mod suite;
@@ -6,7 +6,7 @@ use crate::{
 };
 use clap::Parser;
 
-mod active_feature_flags;
+pub mod active_feature_flags;
 
 #[derive(Parser)]
 #[clap(
@@ -30,7 +30,9 @@ impl Command {
         let movement_aptos_rest_client =
             MovementAptosRestClient::new(&self.movement_aptos_rest_api_url)?;
 
-        GlobalFeatureCheck::satisfies(&movement_aptos_rest_client).await?;
+        GlobalFeatureCheck::default()
+            .satisfies(&movement_aptos_rest_client)
+            .await?;
 
         Ok(())
     }
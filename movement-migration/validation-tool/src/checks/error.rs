@@ -7,4 +7,6 @@ pub enum ValidationError {
     Unsatisfied(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("criterion encountered an internal error: {0}")]
     Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("the criterion was skipped: {0}")]
+    Skipped(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    checks::node::global_storage_includes::GlobalStorageIncludes,
+    checks::node::global_storage_includes::{GlobalStorageIncludes, GlobalStorageIncludesParams},
     types::storage::{MovementAptosStorage, MovementStorage},
 };
 use clap::Parser;
 use std::path::PathBuf;
 
-mod global_storage_includes;
+pub mod fungible_asset_supply;
+pub mod global_storage_includes;
+pub mod transaction_history;
 
 #[derive(Parser)]
 #[clap(
@@ -23,6 +25,21 @@ pub struct Command {
         help = "The path to the movement Aptos database."
     )]
     pub movement_aptos_db: PathBuf,
+    #[clap(
+        long,
+        help = "The number of shards compared in parallel, defaults to the number of available cores."
+    )]
+    pub shards: Option<usize>,
+    #[clap(
+        long,
+        help = "The file the comparison progress is checkpointed to and resumed from."
+    )]
+    pub checkpoint: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "The file the JSON report of all state mismatches is written to."
+    )]
+    pub diff_report: Option<PathBuf>,
 }
 
 impl Command {
@@ -30,7 +47,14 @@ impl Command {
         let movement_storage = MovementStorage::open(&self.movement_db)?;
         let movement_aptos_storage = MovementAptosStorage::open(&self.movement_aptos_db)?;
 
-        GlobalStorageIncludes::satisfies(&movement_storage, &movement_aptos_storage)?;
+        GlobalStorageIncludes::new(GlobalStorageIncludesParams {
+            shards: self.shards,
+            chunk_size: None,
+            checkpoint_interval_secs: None,
+            checkpoint_file: self.checkpoint,
+            diff_report_file: self.diff_report,
+        })?
+        .satisfies(&movement_storage, &movement_aptos_storage)?;
 
         Ok(())
     }
@@ -0,0 +1,477 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    checks::{
+        error::ValidationError,
+        node::global_storage_includes::resource_group::decode_group,
+        registry::{parse_params, run_blocking, Check, CheckContext},
+    },
+    types::{
+        resources::{CoinInfoResourceUntyped, SupplyResource},
+        storage::Storage,
+    },
+};
+use aptos_types::{
+    access_path::Path,
+    account_config::{
+        CoinStoreResourceUntyped, ConcurrentFungibleBalanceResource, ConcurrentSupplyResource,
+        FungibleStoreResource,
+    },
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        state_value::StateValue,
+        TStateView,
+    },
+};
+use async_trait::async_trait;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+    move_resource::MoveStructType,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{debug, info};
+
+/// APT exists both as a coin and as the paired fungible asset at `0xa`, and the
+/// coin-to-FA migration moves balances from one to the other, so both are
+/// reconciled together.
+const APT_ASSET: &str = "APT (0x1::aptos_coin::AptosCoin and fungible asset 0xa)";
+const APT_COIN_TYPE: &str = "0x1::aptos_coin::AptosCoin";
+
+/// This check reconciles the total supply of every coin and fungible asset with
+/// the sum of its balances in the old Movement database and the new Aptos
+/// database, at the same state checkpoint version.
+///
+/// In each database, the tracked supply of a fungible asset has to equal the sum
+/// of the balances of its fungible stores. Coins can be held outside of coin
+/// stores, so for coins, and for APT which is both a coin and a fungible asset,
+/// only the supplies and the balances of both databases are compared.
+#[derive(Clone)]
+pub struct FungibleAssetSupply {
+    version: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FungibleAssetSupplyParams {
+    /// The state checkpoint version to reconcile at, defaults to the latest one of the Movement database.
+    #[serde(default)]
+    version: Option<u64>,
+}
+
+#[async_trait]
+impl Check for FungibleAssetSupply {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn run(&self, context: &CheckContext) -> Result<(), ValidationError> {
+        let check = self.clone();
+        let movement_storage = context.movement_storage()?.clone();
+        let movement_aptos_storage = context.movement_aptos_storage()?.clone();
+        run_blocking(move || check.satisfies(&movement_storage, &movement_aptos_storage)).await
+    }
+}
+
+/// The supply and balances of a single asset.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct AssetTotals {
+    /// The tracked supply, if the asset tracks one.
+    supply: Option<u128>,
+    balances: u128,
+}
+
+/// The totals of every asset in a database.
+#[derive(Debug, Default)]
+struct SupplyLedger {
+    assets: BTreeMap<String, AssetTotals>,
+}
+
+impl SupplyLedger {
+    fn add_supply(&mut self, asset: String, supply: u128) {
+        let totals = self.assets.entry(asset).or_default();
+        totals.supply = Some(totals.supply.unwrap_or(0) + supply);
+    }
+
+    fn add_balance(&mut self, asset: String, balance: u64) {
+        self.assets.entry(asset).or_default().balances += balance as u128;
+    }
+}
+
+impl FungibleAssetSupply {
+    pub const NAME: &'static str = "fungible-asset-supply";
+
+    pub fn from_params(params: &serde_json::Value) -> anyhow::Result<Self> {
+        let FungibleAssetSupplyParams { version } = parse_params(params)?;
+        Ok(Self { version })
+    }
+
+    pub fn satisfies(
+        &self,
+        movement_storage: &Storage,
+        movement_aptos_storage: &Storage,
+    ) -> Result<(), ValidationError> {
+        let version = match self.version {
+            Some(version) => version,
+            None => movement_storage
+                .latest_state_checkpoint_version()
+                .map_err(|e| ValidationError::Internal(e.into()))?,
+        };
+        info!("reconciling supplies and balances at version {}", version);
+
+        let movement_ledger = Self::collect(movement_storage, version)?;
+        let movement_aptos_ledger = Self::collect(movement_aptos_storage, version)?;
+        let errors = Self::reconcile(&movement_ledger, &movement_aptos_ledger);
+
+        info!(
+            "reconciled {} assets, {} discrepancies",
+            movement_ledger.assets.len(),
+            errors.len()
+        );
+        if !errors.is_empty() {
+            return Err(ValidationError::Unsatisfied(errors.join("\n").into()));
+        }
+
+        Ok(())
+    }
+
+    /// Lists the discrepancies between the supplies and balances of both databases.
+    fn reconcile(
+        movement_ledger: &SupplyLedger,
+        movement_aptos_ledger: &SupplyLedger,
+    ) -> Vec<String> {
+        let mut errors = vec![];
+        for (database, ledger) in [
+            ("Movement", &movement_ledger),
+            ("Movement Aptos", &movement_aptos_ledger),
+        ] {
+            for (asset, totals) in &ledger.assets {
+                if !Self::is_fungible_asset(asset) {
+                    continue;
+                }
+                if let Some(supply) = totals.supply.filter(|supply| *supply != totals.balances) {
+                    errors.push(format!(
+                        "{} supply of {} is {}, while the sum of its balances is {}",
+                        database, asset, supply, totals.balances
+                    ));
+                }
+            }
+        }
+
+        let assets: BTreeSet<_> = movement_ledger
+            .assets
+            .keys()
+            .chain(movement_aptos_ledger.assets.keys())
+            .collect();
+        for asset in assets {
+            let movement_totals = movement_ledger
+                .assets
+                .get(asset)
+                .cloned()
+                .unwrap_or_default();
+            let movement_aptos_totals = movement_aptos_ledger
+                .assets
+                .get(asset)
+                .cloned()
+                .unwrap_or_default();
+            if movement_totals.supply != movement_aptos_totals.supply {
+                errors.push(format!(
+                    "Movement supply of {} is {:?}, while Movement Aptos supply is {:?}",
+                    asset, movement_totals.supply, movement_aptos_totals.supply
+                ));
+            }
+            if movement_totals.balances != movement_aptos_totals.balances {
+                errors.push(format!(
+                    "Movement balances of {} sum up to {}, while Movement Aptos balances sum up to {}",
+                    asset, movement_totals.balances, movement_aptos_totals.balances
+                ));
+            }
+        }
+
+        errors
+    }
+
+    fn is_fungible_asset(asset: &str) -> bool {
+        asset.starts_with("fungible asset ")
+    }
+
+    fn coin_asset(coin_type: &TypeTag) -> String {
+        let coin_type = coin_type.to_canonical_string();
+        if coin_type == APT_COIN_TYPE {
+            APT_ASSET.to_string()
+        } else {
+            format!("coin {}", coin_type)
+        }
+    }
+
+    fn fungible_asset(metadata: AccountAddress) -> String {
+        if metadata == AccountAddress::TEN {
+            APT_ASSET.to_string()
+        } else {
+            format!("fungible asset 0x{}", metadata.short_str_lossless())
+        }
+    }
+
+    /// Sums up the supplies and balances of every asset in the state snapshot at `version`.
+    fn collect(storage: &Storage, version: u64) -> Result<SupplyLedger, ValidationError> {
+        let state_view = storage
+            .state_view_at_version(Some(version))
+            .map_err(|e| ValidationError::Internal(e.into()))?;
+        let mut ledger = SupplyLedger::default();
+
+        storage
+            .for_each_state_value(version, |state_key, state_value| {
+                Self::collect_state_value(&state_view, &mut ledger, &state_key, &state_value)
+            })
+            .map_err(|e| ValidationError::Internal(e.into()))?;
+
+        Ok(ledger)
+    }
+
+    fn collect_state_value(
+        state_view: &impl TStateView<Key = StateKey>,
+        ledger: &mut SupplyLedger,
+        state_key: &StateKey,
+        state_value: &StateValue,
+    ) -> anyhow::Result<()> {
+        let StateKeyInner::AccessPath(access_path) = state_key.inner() else {
+            return Ok(());
+        };
+        let bytes = state_value.bytes();
+
+        match access_path.get_path() {
+            Path::Resource(tag) if Self::is_coin_struct(&tag, "CoinStore") => {
+                let balance = bcs::from_bytes::<CoinStoreResourceUntyped>(bytes)?.coin();
+                ledger.add_balance(Self::coin_asset(&tag.type_args[0]), balance);
+            },
+            Path::Resource(tag) if Self::is_coin_struct(&tag, "CoinInfo") => {
+                let coin_info = bcs::from_bytes::<CoinInfoResourceUntyped>(bytes)?;
+                let Some(supply) = coin_info.supply else {
+                    return Ok(());
+                };
+                let supply = match (supply.integer, supply.aggregator) {
+                    (Some(integer), _) => integer.value,
+                    (None, Some(aggregator)) => {
+                        match state_view.get_state_value_bytes(&aggregator.state_key())? {
+                            Some(value) => bcs::from_bytes::<u128>(&value)?,
+                            None => 0,
+                        }
+                    },
+                    (None, None) => return Ok(()),
+                };
+                debug!("supply of coin {}: {}", tag.type_args[0], supply);
+                ledger.add_supply(Self::coin_asset(&tag.type_args[0]), supply);
+            },
+            Path::ResourceGroup(_) => {
+                let group = decode_group(bytes)?;
+                let mut store = None;
+                let mut balance = 0;
+                for (tag, member) in &group {
+                    if tag == &FungibleStoreResource::struct_tag() {
+                        let fungible_store = bcs::from_bytes::<FungibleStoreResource>(member)?;
+                        balance += fungible_store.balance();
+                        store = Some(fungible_store.metadata());
+                    } else if tag == &ConcurrentFungibleBalanceResource::struct_tag() {
+                        balance +=
+                            bcs::from_bytes::<ConcurrentFungibleBalanceResource>(member)?.balance();
+                    } else if tag == &ConcurrentSupplyResource::struct_tag() {
+                        let supply = *bcs::from_bytes::<ConcurrentSupplyResource>(member)?
+                            .current
+                            .get();
+                        ledger.add_supply(Self::fungible_asset(access_path.address), supply);
+                    } else if tag == &SupplyResource::struct_tag() {
+                        let supply = bcs::from_bytes::<SupplyResource>(member)?.current;
+                        ledger.add_supply(Self::fungible_asset(access_path.address), supply);
+                    }
+                }
+                if let Some(metadata) = store {
+                    ledger.add_balance(Self::fungible_asset(metadata), balance);
+                }
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    fn is_coin_struct(tag: &StructTag, name: &str) -> bool {
+        tag.address == CORE_CODE_ADDRESS
+            && tag.module.as_str() == "coin"
+            && tag.name.as_str() == name
+            && tag.type_args.len() == 1
+    }
+}
+
+#[test]
+fn apt_coin_and_fungible_asset_are_reconciled_together() {
+    use std::str::FromStr;
+
+    let apt = TypeTag::from_str(APT_COIN_TYPE).unwrap();
+    assert_eq!(FungibleAssetSupply::coin_asset(&apt), APT_ASSET);
+    assert_eq!(
+        FungibleAssetSupply::fungible_asset(AccountAddress::TEN),
+        APT_ASSET
+    );
+    assert!(!FungibleAssetSupply::is_fungible_asset(APT_ASSET));
+
+    let other =
+        FungibleAssetSupply::fungible_asset(AccountAddress::from_hex_literal("0xb").unwrap());
+    assert!(FungibleAssetSupply::is_fungible_asset(&other));
+}
+
+/// An in-memory state snapshot, standing in for a database.
+#[cfg(test)]
+struct TestState(std::collections::HashMap<StateKey, StateValue>);
+
+#[cfg(test)]
+impl TStateView for TestState {
+    type Key = StateKey;
+
+    fn get_state_value(
+        &self,
+        state_key: &StateKey,
+    ) -> aptos_types::state_store::StateViewResult<Option<StateValue>> {
+        Ok(self.0.get(state_key).cloned())
+    }
+
+    fn get_usage(
+        &self,
+    ) -> aptos_types::state_store::StateViewResult<
+        aptos_types::state_store::state_storage_usage::StateStorageUsage,
+    > {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+impl TestState {
+    /// A state with `apt_supply` APT, of which `apt_coins` are in a coin store and `apt_fa` in a
+    /// fungible store, and with `fa_balance` of the fungible asset at 0xb, whose supply is `fa_supply`.
+    fn new(
+        apt_supply: u128,
+        apt_coins: u64,
+        apt_fa: u64,
+        fa_supply: u128,
+        fa_balance: u64,
+    ) -> Self {
+        use aptos_types::{
+            account_config::{CoinInfoResource, CoinStoreResource, ObjectGroupResource},
+            event::{EventHandle, EventKey},
+            utility_coin::AptosCoinType,
+        };
+
+        let owner = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let fa = AccountAddress::from_hex_literal("0xb").unwrap();
+        let mut state = std::collections::HashMap::new();
+        let mut insert = |state_key: StateKey, bytes: Vec<u8>| {
+            state.insert(state_key, StateValue::new_legacy(bytes.into()));
+        };
+        let group = |tag: StructTag, member: Vec<u8>| {
+            let mut group = ObjectGroupResource::default();
+            group.insert(tag, member);
+            group.to_bytes().unwrap()
+        };
+        let group_key = |address: AccountAddress| {
+            StateKey::resource_group(&address, &ObjectGroupResource::struct_tag())
+        };
+
+        let coin_store = CoinStoreResource::<AptosCoinType>::new(
+            apt_coins,
+            false,
+            EventHandle::new(EventKey::new(0, owner), 0),
+            EventHandle::new(EventKey::new(1, owner), 0),
+        );
+        insert(
+            StateKey::resource_typed::<CoinStoreResource<AptosCoinType>>(&owner).unwrap(),
+            bcs::to_bytes(&coin_store).unwrap(),
+        );
+        // The coin supply is kept in an aggregator, outside of the coin info
+        let coin_info = CoinInfoResource::<AptosCoinType>::new(
+            AccountAddress::from_hex_literal("0xa99").unwrap(),
+            AccountAddress::from_hex_literal("0xa98").unwrap(),
+            u128::MAX,
+        );
+        insert(
+            coin_info.supply_aggregator_state_key(),
+            bcs::to_bytes(&apt_supply).unwrap(),
+        );
+        insert(
+            StateKey::resource_typed::<CoinInfoResource<AptosCoinType>>(&CORE_CODE_ADDRESS)
+                .unwrap(),
+            bcs::to_bytes(&coin_info).unwrap(),
+        );
+
+        for (store, metadata, balance) in [
+            (
+                AccountAddress::from_hex_literal("0x5a").unwrap(),
+                AccountAddress::TEN,
+                apt_fa,
+            ),
+            (
+                AccountAddress::from_hex_literal("0x5b").unwrap(),
+                fa,
+                fa_balance,
+            ),
+        ] {
+            let fungible_store = FungibleStoreResource::new(metadata, balance, false);
+            insert(
+                group_key(store),
+                group(
+                    FungibleStoreResource::struct_tag(),
+                    bcs::to_bytes(&fungible_store).unwrap(),
+                ),
+            );
+        }
+        let supply = SupplyResource {
+            current: fa_supply,
+            maximum: None,
+        };
+        insert(
+            group_key(fa),
+            group(
+                SupplyResource::struct_tag(),
+                bcs::to_bytes(&supply).unwrap(),
+            ),
+        );
+
+        Self(state)
+    }
+
+    fn ledger(&self) -> SupplyLedger {
+        let mut ledger = SupplyLedger::default();
+        for (state_key, state_value) in &self.0 {
+            FungibleAssetSupply::collect_state_value(self, &mut ledger, state_key, state_value)
+                .unwrap();
+        }
+        ledger
+    }
+}
+
+#[test]
+fn supplies_and_balances_are_reconciled() {
+    let movement = TestState::new(150, 100, 50, 40, 40).ledger();
+    assert_eq!(movement.assets.len(), 2);
+    let same = TestState::new(150, 100, 50, 40, 40).ledger();
+    assert!(FungibleAssetSupply::reconcile(&movement, &same).is_empty());
+
+    // Moving APT from coin stores to fungible stores keeps it reconciled.
+    let migrated = TestState::new(150, 0, 150, 40, 40).ledger();
+    assert!(FungibleAssetSupply::reconcile(&movement, &migrated).is_empty());
+
+    let mismatching = TestState::new(150, 90, 50, 41, 40).ledger();
+    assert_eq!(
+        FungibleAssetSupply::reconcile(&movement, &mismatching),
+        vec![
+        "Movement Aptos supply of fungible asset 0xb is 41, while the sum of its balances is 40"
+            .to_string(),
+        format!(
+            "Movement balances of {} sum up to 150, while Movement Aptos balances sum up to 140",
+            APT_ASSET
+        ),
+        "Movement supply of fungible asset 0xb is Some(40), while Movement Aptos supply is Some(41)"
+            .to_string(),
+    ]
+    );
+}
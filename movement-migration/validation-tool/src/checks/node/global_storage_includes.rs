@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    checks::error::ValidationError,
-    types::storage::{MovementAptosStorage, MovementStorage},
+    checks::{
+        error::ValidationError,
+        node::global_storage_includes::{
+            checkpoint::{shard_ranges, Checkpoint},
+            diff::{pretty_value, resource_type, MismatchKind, StateDiffReport, StateMismatch},
+        },
+        registry::{parse_params, run_blocking, Check, CheckContext},
+    },
+    types::storage::{MovementAptosStorage, MovementStorage, MAX_WRITE_SET_SIZE},
 };
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::state_store::state_view::db_state_view::DbStateView;
 use aptos_types::{
     access_path::Path,
     account_config::{AccountResource, CoinStoreResourceUntyped},
@@ -13,12 +22,32 @@ use aptos_types::{
         TStateView,
     },
 };
+use async_trait::async_trait;
 use bytes::Bytes;
 use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
-use std::str::FromStr;
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
 use tracing::{debug, info};
 
-/// This check iterates over all global state keys starting at ledger version 0.
+pub mod checkpoint;
+pub mod diff;
+pub mod resource_group;
+
+/// How often the progress is checkpointed by default, in seconds.
+const DEFAULT_CHECKPOINT_INTERVAL_SECS: u64 = 60;
+
+/// This check iterates over all global state keys written since the first
+/// version that still has a write set, which is ledger version 0 unless the
+/// ledger was pruned.
 /// For each state key it fetches the state view for the latest ledger version,
 /// from the old Movment database and the new Aptos database. The state view bytes
 /// from both databases need to match. If the state key has no value in the latest
@@ -26,120 +55,423 @@ use tracing::{debug, info};
 /// in the new Aptos database.
 /// Account Resources and Coin Stores are deserialized from BSC before comparison.
 /// In case of Coin Stores, only the balances are compared.
-pub struct GlobalStorageIncludes;
+/// Resource groups are compared member by member, see
+/// [resource_group::compare_resource_groups].
+///
+/// The version range is split into shards that are compared in parallel. Every
+/// mismatch is collected into a [StateDiffReport] instead of failing on the first
+/// one, and progress can be checkpointed to a file so an interrupted run resumes
+/// where it stopped.
+///
+/// A state key written in several versions is only compared once, which keeps the
+/// hash of every compared key in memory and in the checkpoint.
+#[derive(Clone)]
+pub struct GlobalStorageIncludes {
+    shards: usize,
+    chunk_size: u64,
+    checkpoint_interval: Duration,
+    checkpoint_file: Option<PathBuf>,
+    diff_report_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalStorageIncludesParams {
+    /// The number of shards compared in parallel, defaults to the number of available cores.
+    #[serde(default)]
+    pub shards: Option<usize>,
+    /// The number of versions whose state keys are read and compared at once.
+    #[serde(default)]
+    pub chunk_size: Option<u64>,
+    /// The minimum number of seconds between two checkpoints, defaults to 60.
+    #[serde(default)]
+    pub checkpoint_interval_secs: Option<u64>,
+    /// The file progress is checkpointed to and resumed from.
+    #[serde(default)]
+    pub checkpoint_file: Option<PathBuf>,
+    /// The file the JSON diff report is written to.
+    #[serde(default)]
+    pub diff_report_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Check for GlobalStorageIncludes {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn run(&self, context: &CheckContext) -> Result<(), ValidationError> {
+        let check = self.clone();
+        let movement_storage = context.movement_storage()?.clone();
+        let movement_aptos_storage = context.movement_aptos_storage()?.clone();
+        run_blocking(move || check.satisfies(&movement_storage, &movement_aptos_storage)).await
+    }
+}
+
+/// What the shards of a comparison share.
+struct Comparison<'a> {
+    movement_storage: &'a MovementStorage,
+    movement_state_view: DbStateView,
+    maptos_state_view: DbStateView,
+    account: StructTag,
+    coin: StructTag,
+    progress: Mutex<Checkpoint>,
+    /// When the progress was last saved to the checkpoint file.
+    checkpointed_at: Mutex<Instant>,
+    failed: AtomicBool,
+}
 
 impl GlobalStorageIncludes {
+    pub const NAME: &'static str = "global-storage-includes";
+
+    pub fn new(params: GlobalStorageIncludesParams) -> anyhow::Result<Self> {
+        let shards = match params.shards {
+            Some(shards) => shards,
+            None => std::thread::available_parallelism()?.get(),
+        };
+        let chunk_size = params.chunk_size.unwrap_or(MAX_WRITE_SET_SIZE);
+        if shards == 0 || chunk_size == 0 {
+            anyhow::bail!("shards and chunk_size must be greater than 0");
+        }
+
+        Ok(Self {
+            shards,
+            chunk_size,
+            checkpoint_interval: Duration::from_secs(
+                params
+                    .checkpoint_interval_secs
+                    .unwrap_or(DEFAULT_CHECKPOINT_INTERVAL_SECS),
+            ),
+            checkpoint_file: params.checkpoint_file,
+            diff_report_file: params.diff_report_file,
+        })
+    }
+
+    pub fn from_params(params: &serde_json::Value) -> anyhow::Result<Self> {
+        Self::new(parse_params(params)?)
+    }
+
     pub fn satisfies(
+        &self,
         movement_storage: &MovementStorage,
         movement_aptos_storage: &MovementAptosStorage,
     ) -> Result<(), ValidationError> {
-        let account = StructTag::from_str("0x1::account::Account").unwrap();
-        let coin = StructTag::from_str("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>").unwrap();
-
         // get the latest ledger version from the movement storage
         let movement_ledger_version = movement_storage
             .latest_ledger_version()
             .map_err(|e| ValidationError::Internal(e.into()))?;
+        let first_version = movement_storage
+            .first_write_set_version()
+            .map_err(|e| ValidationError::Internal(e.into()))?;
 
         info!("checking global state keys and values");
         debug!("movement_ledger_version: {:?}", movement_ledger_version);
 
-        // get the latest state view from the movement storage
-        let movement_state_view = movement_storage
-            .state_view_at_version(Some(movement_ledger_version))
-            .map_err(|e| ValidationError::Internal(e.into()))?;
+        let shards = shard_ranges(first_version..movement_ledger_version + 1, self.shards);
+        let checkpoint = Checkpoint::load_or_new(
+            self.checkpoint_file.as_deref(),
+            movement_ledger_version,
+            &shards,
+        )
+        .map_err(|e| ValidationError::Internal(e.into()))?;
+        if checkpoint.diff.keys_compared > 0 {
+            info!(
+                "resuming from checkpoint, {} of {} shards done, {} keys compared",
+                checkpoint
+                    .shards
+                    .iter()
+                    .filter(|shard| shard.is_done())
+                    .count(),
+                checkpoint.shards.len(),
+                checkpoint.diff.keys_compared
+            );
+        }
 
-        // get the latest state view from the maptos storage
-        let maptos_state_view = movement_aptos_storage
-            .state_view_at_version(Some(movement_ledger_version))
-            .map_err(|e| ValidationError::Internal(e.into()))?;
+        let comparison = Comparison {
+            movement_storage,
+            // get the latest state view from the movement storage
+            movement_state_view: movement_storage
+                .state_view_at_version(Some(movement_ledger_version))
+                .map_err(|e| ValidationError::Internal(e.into()))?,
+            // get the latest state view from the maptos storage
+            maptos_state_view: movement_aptos_storage
+                .state_view_at_version(Some(movement_ledger_version))
+                .map_err(|e| ValidationError::Internal(e.into()))?,
+            account: StructTag::from_str("0x1::account::Account").unwrap(),
+            coin: StructTag::from_str("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>").unwrap(),
+            progress: Mutex::new(checkpoint),
+            checkpointed_at: Mutex::new(Instant::now()),
+            failed: AtomicBool::new(false),
+        };
 
         // the movement state view is the domain, so the maptos state view is the codomain
-        let movement_global_state_keys_iterator =
-            movement_storage.global_state_keys_from_version(None);
-        let movement_global_state_keys = movement_global_state_keys_iterator
-            .iter()
-            .map_err(|e| ValidationError::Internal(e.into()))?;
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..shards.len())
+                .map(|shard| {
+                    let comparison = &comparison;
+                    scope.spawn(move || {
+                        let result = self.compare_shard(shard, comparison);
+                        if result.is_err() {
+                            // Let the other shards stop at their next checkpoint.
+                            comparison.failed.store(true, Ordering::Relaxed);
+                        }
+                        result
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(ValidationError::Internal(
+                            "a state comparison shard panicked".into(),
+                        ))
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
 
-        let mut count = 0;
-        for movement_state_key in movement_global_state_keys {
+        let checkpoint = comparison
+            .progress
+            .into_inner()
+            .map_err(|_| ValidationError::Internal("state comparison progress is poisoned".into()))?;
+        if results.iter().any(Result::is_err) {
+            // Keep the progress made since the last checkpoint for the next run.
+            if let Some(path) = &self.checkpoint_file {
+                checkpoint
+                    .save(path)
+                    .map_err(|e| ValidationError::Internal(e.into()))?;
+            }
+        }
+        for result in results {
+            result?;
+        }
+
+        let diff = checkpoint.diff;
+        if let Some(path) = &self.diff_report_file {
+            diff.write(path)
+                .map_err(|e| ValidationError::Internal(e.into()))?;
+        }
+        // The diff report holds the result now, so a new run should start over.
+        if let Some(path) = self.checkpoint_file.as_ref().filter(|path| path.exists()) {
+            std::fs::remove_file(path).map_err(|e| ValidationError::Internal(e.into()))?;
+        }
+
+        info!(
+            "compared {} global state keys, {} mismatches",
+            diff.keys_compared,
+            diff.mismatch_count()
+        );
+        if !diff.is_empty() {
+            return Err(ValidationError::Unsatisfied(diff.summary().into()));
+        }
+
+        Ok(())
+    }
+
+    /// Compares the state keys written in a shard's versions chunk by chunk,
+    /// checkpointing once the checkpoint interval has passed.
+    fn compare_shard(&self, shard: usize, comparison: &Comparison) -> Result<(), ValidationError> {
+        let (mut next, end, ledger_version) = {
+            let progress = comparison.progress.lock().map_err(|_| {
+                ValidationError::Internal("state comparison progress is poisoned".into())
+            })?;
+            let shard_progress = &progress.shards[shard];
+            (
+                shard_progress.next,
+                shard_progress.end,
+                progress.diff.ledger_version,
+            )
+        };
+        let movement_annotator = AptosValueAnnotator::new(&comparison.movement_state_view);
+        let maptos_annotator = AptosValueAnnotator::new(&comparison.maptos_state_view);
+
+        while next < end && !comparison.failed.load(Ordering::Relaxed) {
+            let chunk_end = end.min(next + self.chunk_size);
             debug!(
-                "processing movement_state_key {}: {:?}",
-                count, movement_state_key
+                "shard {}: comparing versions {}..{}",
+                shard, next, chunk_end
             );
 
-            let movement_state_key =
-                movement_state_key.map_err(|e| ValidationError::Internal(e.into()))?;
-
-            let movement_value = movement_state_view
-                .get_state_value_bytes(&movement_state_key)
+            // A key can be written in many versions, of this shard or of others, but it
+            // only needs to be compared once.
+            let movement_global_state_keys = comparison
+                .movement_storage
+                .global_state_keys_in_range(next..chunk_end);
+            let movement_state_keys = movement_global_state_keys
+                .iter()
+                .map_err(|e| ValidationError::Internal(e.into()))?
+                .collect::<Result<HashSet<_>, _>>()
                 .map_err(|e| ValidationError::Internal(e.into()))?;
+            let movement_state_keys: Vec<_> = {
+                let progress = comparison.progress.lock().map_err(|_| {
+                    ValidationError::Internal("state comparison progress is poisoned".into())
+                })?;
+                movement_state_keys
+                    .into_iter()
+                    .filter(|key| !progress.compared_keys.contains(key.crypto_hash_ref()))
+                    .collect()
+            };
 
-            match movement_value {
-                Some(movement_value) => {
-                    let maptos_state_value = maptos_state_view
-                        .get_state_value_bytes(&movement_state_key)
-                        .map_err(|e| ValidationError::Internal(e.into()))?
-                        .ok_or(ValidationError::Unsatisfied(
-                            format!(
-                                "Movement Aptos is missing a value for {:?}",
-                                movement_state_key
-                            )
-                            .into(),
-                        ))?;
-
-                    if let StateKeyInner::AccessPath(p) = movement_state_key.inner() {
-                        match p.get_path() {
-                            Path::Resource(tag) if tag == account => Self::compare_accounts(
-                                p.address,
-                                movement_value,
-                                maptos_state_value,
-                            )?,
-                            Path::Resource(tag) if tag == coin => Self::compare_balances(
-                                p.address,
-                                movement_value,
-                                maptos_state_value,
-                            )?,
-                            _ => Self::compare_raw_state(
-                                movement_state_key,
-                                movement_value,
-                                maptos_state_value,
-                            )?,
-                        }
-                    } else {
-                        Self::compare_raw_state(
-                            movement_state_key,
-                            movement_value,
-                            maptos_state_value,
-                        )?;
-                    }
-                },
-                None => {
-                    debug!("Value from a previous version has been removed at the latest ledger version");
-
-                    match maptos_state_view
-                        .get_state_value(&movement_state_key)
-                        .map_err(|e| ValidationError::Internal(e.into()))?
-                    {
-                        Some(_) => {
-                            return Err(ValidationError::Unsatisfied(
-                                format!(
-                                    "Movement Aptos is unexpectedly not missing a value for {:?}",
-                                    movement_state_key
-                                )
-                                .into(),
-                            ));
-                        },
-                        None => {},
-                    }
-                },
+            let mut diff = StateDiffReport::new(ledger_version);
+            for movement_state_key in &movement_state_keys {
+                debug!("processing movement_state_key: {:?}", movement_state_key);
+
+                if let Some(mismatch) = Self::compare_state_key(
+                    comparison,
+                    &movement_annotator,
+                    &maptos_annotator,
+                    movement_state_key,
+                )? {
+                    diff.insert(resource_type(movement_state_key), mismatch);
+                }
             }
-            count += 1;
+
+            let mut progress = comparison.progress.lock().map_err(|_| {
+                ValidationError::Internal("state comparison progress is poisoned".into())
+            })?;
+            // Another shard may have compared some of the keys in the meantime.
+            for movement_state_key in &movement_state_keys {
+                if progress
+                    .compared_keys
+                    .insert(*movement_state_key.crypto_hash_ref())
+                {
+                    diff.keys_compared += 1;
+                }
+            }
+            progress.diff.merge(diff);
+            progress.shards[shard].next = chunk_end;
+            self.checkpoint_if_due(comparison, &progress)?;
+            next = chunk_end;
+        }
+
+        Ok(())
+    }
+
+    /// Saves the progress to the checkpoint file, unless it was saved less than the
+    /// checkpoint interval ago.
+    fn checkpoint_if_due(
+        &self,
+        comparison: &Comparison,
+        progress: &Checkpoint,
+    ) -> Result<(), ValidationError> {
+        let Some(path) = &self.checkpoint_file else {
+            return Ok(());
+        };
+        let mut checkpointed_at = comparison.checkpointed_at.lock().map_err(|_| {
+            ValidationError::Internal("state comparison progress is poisoned".into())
+        })?;
+        if checkpointed_at.elapsed() < self.checkpoint_interval {
+            return Ok(());
         }
 
+        progress
+            .save(path)
+            .map_err(|e| ValidationError::Internal(e.into()))?;
+        *checkpointed_at = Instant::now();
         Ok(())
     }
 
+    /// Compares the value of a state key in both databases, returning the mismatch if there is one.
+    fn compare_state_key(
+        comparison: &Comparison,
+        movement_annotator: &AptosValueAnnotator<DbStateView>,
+        maptos_annotator: &AptosValueAnnotator<DbStateView>,
+        movement_state_key: &StateKey,
+    ) -> Result<Option<StateMismatch>, ValidationError> {
+        let movement_value = comparison
+            .movement_state_view
+            .get_state_value_bytes(movement_state_key)
+            .map_err(|e| ValidationError::Internal(e.into()))?;
+        let maptos_state_value = comparison
+            .maptos_state_view
+            .get_state_value_bytes(movement_state_key)
+            .map_err(|e| ValidationError::Internal(e.into()))?;
+
+        let (kind, detail) = match (&movement_value, &maptos_state_value) {
+            (Some(movement_value), Some(maptos_state_value)) => {
+                match Self::compare_values(
+                    comparison,
+                    movement_state_key,
+                    movement_value.clone(),
+                    maptos_state_value.clone(),
+                ) {
+                    Ok(()) => return Ok(None),
+                    Err(ValidationError::Unsatisfied(e)) => {
+                        (MismatchKind::Different, Some(e.to_string()))
+                    },
+                    Err(e) => return Err(e),
+                }
+            },
+            (Some(_), None) => (
+                MismatchKind::Missing,
+                Some(format!(
+                    "Movement Aptos is missing a value for {:?}",
+                    movement_state_key
+                )),
+            ),
+            (None, Some(_)) => {
+                debug!(
+                    "Value from a previous version has been removed at the latest ledger version"
+                );
+                (
+                    MismatchKind::Unexpected,
+                    Some(format!(
+                        "Movement Aptos is unexpectedly not missing a value for {:?}",
+                        movement_state_key
+                    )),
+                )
+            },
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(StateMismatch {
+            state_key: format!("{:?}", movement_state_key),
+            kind,
+            detail,
+            movement_value: movement_value
+                .map(|value| pretty_value(movement_annotator, movement_state_key, &value)),
+            movement_aptos_value: maptos_state_value
+                .map(|value| pretty_value(maptos_annotator, movement_state_key, &value)),
+        }))
+    }
+
+    /// Compares two values of the same state key, decoding the ones that are compared semantically.
+    fn compare_values(
+        comparison: &Comparison,
+        movement_state_key: &StateKey,
+        movement_value: Bytes,
+        maptos_state_value: Bytes,
+    ) -> Result<(), ValidationError> {
+        if let StateKeyInner::AccessPath(p) = movement_state_key.inner() {
+            match p.get_path() {
+                Path::Resource(tag) if tag == comparison.account => {
+                    Self::compare_accounts(p.address, movement_value, maptos_state_value)
+                },
+                Path::Resource(tag) if tag == comparison.coin => {
+                    Self::compare_balances(p.address, movement_value, maptos_state_value)
+                },
+                Path::ResourceGroup(tag) => resource_group::compare_resource_groups(
+                    p.address,
+                    &tag,
+                    &movement_value,
+                    &maptos_state_value,
+                ),
+                _ => Self::compare_raw_state(
+                    movement_state_key.clone(),
+                    movement_value,
+                    maptos_state_value,
+                ),
+            }
+        } else {
+            Self::compare_raw_state(
+                movement_state_key.clone(),
+                movement_value,
+                maptos_state_value,
+            )
+        }
+    }
+
     fn compare_raw_state(
         movement_state_key: StateKey,
         movement_value: Bytes,
@@ -148,12 +480,10 @@ impl GlobalStorageIncludes {
         if movement_value != maptos_state_value {
             Err(ValidationError::Unsatisfied(
                 format!(
-                    "Movement state value for {:?} is {:?}, while Movement Aptos state value is {:?}",
+                    "Movement state value for {:?} differs from Movement Aptos state value",
                     movement_state_key,
-                    movement_value,
-                    maptos_state_value
                 )
-                    .into(),
+                .into(),
             ))
         } else {
             Ok(())
@@ -0,0 +1,146 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::checks::node::global_storage_includes::diff::StateDiffReport;
+use anyhow::Context;
+use aptos_crypto::HashValue;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, ops::Range, path::Path};
+
+/// The progress of a single shard of the version range.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ShardProgress {
+    pub start: u64,
+    pub end: u64,
+    /// The first version whose write set has not been compared yet.
+    pub next: u64,
+}
+
+impl ShardProgress {
+    pub fn is_done(&self) -> bool {
+        self.next >= self.end
+    }
+}
+
+/// The state of an interrupted comparison, so that a later run can resume it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Checkpoint {
+    pub shards: Vec<ShardProgress>,
+    pub diff: StateDiffReport,
+    /// The hashes of the state keys compared so far, as a key written in several
+    /// chunks or shards is only compared and counted once.
+    #[serde(default)]
+    pub compared_keys: HashSet<HashValue>,
+}
+
+impl Checkpoint {
+    pub fn new(ledger_version: u64, shards: &[Range<u64>]) -> Self {
+        Self {
+            shards: shards
+                .iter()
+                .map(|range| ShardProgress {
+                    start: range.start,
+                    end: range.end,
+                    next: range.start,
+                })
+                .collect(),
+            diff: StateDiffReport::new(ledger_version),
+            compared_keys: HashSet::new(),
+        }
+    }
+
+    /// Loads the checkpoint at `path` if there is one, or starts a new one.
+    ///
+    /// A checkpoint that was written for another ledger version or shard layout
+    /// cannot be resumed and is rejected.
+    pub fn load_or_new(
+        path: Option<&Path>,
+        ledger_version: u64,
+        shards: &[Range<u64>],
+    ) -> anyhow::Result<Self> {
+        let new = Self::new(ledger_version, shards);
+        let Some(path) = path.filter(|path| path.exists()) else {
+            return Ok(new);
+        };
+
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read checkpoint {}", path.display()))?;
+        let checkpoint: Self = serde_json::from_slice(&bytes)
+            .with_context(|| format!("failed to parse checkpoint {}", path.display()))?;
+
+        let same_layout = checkpoint.diff.ledger_version == ledger_version
+            && checkpoint.shards.len() == new.shards.len()
+            && checkpoint
+                .shards
+                .iter()
+                .zip(&new.shards)
+                .all(|(resumed, new)| resumed.start == new.start && resumed.end == new.end);
+        if !same_layout {
+            anyhow::bail!(
+                "checkpoint {} was written for another ledger version or number of shards, remove it to start over",
+                path.display()
+            );
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// Writes the checkpoint to a temporary file first, so an interruption never
+    /// leaves a truncated checkpoint behind.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)
+            .with_context(|| format!("failed to write checkpoint {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("failed to move checkpoint to {}", path.display()))
+    }
+}
+
+/// Splits a version range into at most `count` contiguous shards of similar size.
+pub fn shard_ranges(versions: Range<u64>, count: usize) -> Vec<Range<u64>> {
+    let total = versions.end.saturating_sub(versions.start);
+    let count = (count.max(1) as u64).min(total.max(1));
+    let size = total.div_ceil(count).max(1);
+
+    (0..count)
+        .map(|shard| {
+            let start = (versions.start + shard * size).min(versions.end);
+            let end = (start + size).min(versions.end);
+            start..end
+        })
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+#[test]
+fn shard_ranges_cover_all_versions() {
+    for (versions, count) in [(0..100, 8), (5..6, 4), (0..7, 7), (10..10_000, 3)] {
+        let shards = shard_ranges(versions.clone(), count);
+        assert!(shards.len() <= count);
+        assert_eq!(shards.first().unwrap().start, versions.start);
+        assert_eq!(shards.last().unwrap().end, versions.end);
+        for pair in shards.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+    }
+    assert!(shard_ranges(3..3, 4).is_empty());
+}
+
+#[test]
+fn checkpoint_resumes_only_the_same_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.json");
+    let shards = shard_ranges(0..100, 4);
+
+    let mut checkpoint = Checkpoint::load_or_new(Some(&path), 99, &shards).unwrap();
+    checkpoint.shards[1].next = 40;
+    checkpoint.diff.keys_compared = 1;
+    checkpoint.compared_keys.insert(HashValue::random());
+    checkpoint.save(&path).unwrap();
+
+    let resumed = Checkpoint::load_or_new(Some(&path), 99, &shards).unwrap();
+    assert_eq!(resumed, checkpoint);
+
+    assert!(Checkpoint::load_or_new(Some(&path), 100, &shards).is_err());
+    assert!(Checkpoint::load_or_new(Some(&path), 99, &shard_ranges(0..100, 2)).is_err());
+}
@@ -0,0 +1,200 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::checks::node::global_storage_includes::resource_group::decode_group;
+use anyhow::Context;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_types::{
+    access_path::Path,
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        StateView,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The number of mismatches listed in the error message of the check.
+const SUMMARY_MISMATCHES: usize = 10;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// The Movement Aptos database has no value for a key the Movement database has.
+    Missing,
+    /// The Movement Aptos database has a value for a key the Movement database has removed.
+    Unexpected,
+    /// Both databases have a value for the key, but the values differ.
+    Different,
+}
+
+/// A single state key whose value differs between the two databases.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StateMismatch {
+    pub state_key: String,
+    pub kind: MismatchKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movement_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movement_aptos_value: Option<String>,
+}
+
+/// Every mismatch found by [super::GlobalStorageIncludes], grouped by resource type.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StateDiffReport {
+    pub ledger_version: u64,
+    /// The number of distinct state keys compared.
+    pub keys_compared: u64,
+    /// Mismatches by resource type and then by state key, so a key that was written
+    /// in several versions is only reported once.
+    pub mismatches: BTreeMap<String, BTreeMap<String, StateMismatch>>,
+}
+
+impl StateDiffReport {
+    pub fn new(ledger_version: u64) -> Self {
+        Self {
+            ledger_version,
+            ..Default::default()
+        }
+    }
+
+    pub fn insert(&mut self, resource_type: String, mismatch: StateMismatch) {
+        self.mismatches
+            .entry(resource_type)
+            .or_default()
+            .insert(mismatch.state_key.clone(), mismatch);
+    }
+
+    /// Adds the keys and mismatches of another report, such as the one of a shard.
+    pub fn merge(&mut self, other: StateDiffReport) {
+        self.keys_compared += other.keys_compared;
+        for (resource_type, mismatches) in other.mismatches {
+            self.mismatches
+                .entry(resource_type)
+                .or_default()
+                .extend(mismatches);
+        }
+    }
+
+    pub fn mismatch_count(&self) -> usize {
+        self.mismatches.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Writes the report as pretty-printed JSON.
+    pub fn write(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let json = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("failed to write state diff report {}", path.display()))
+    }
+
+    /// Summarizes the report as the number of mismatches per resource type, followed
+    /// by the first few mismatches.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} of {} compared state keys differ at ledger version {}:",
+            self.mismatch_count(),
+            self.keys_compared,
+            self.ledger_version
+        );
+        for (resource_type, mismatches) in &self.mismatches {
+            let _ = write!(summary, "\n  {}: {}", resource_type, mismatches.len());
+        }
+        for mismatch in self
+            .mismatches
+            .values()
+            .flat_map(BTreeMap::values)
+            .take(SUMMARY_MISMATCHES)
+        {
+            let _ = write!(
+                summary,
+                "\n{:?} {}{}",
+                mismatch.kind,
+                mismatch.state_key,
+                mismatch
+                    .detail
+                    .as_ref()
+                    .map(|detail| format!(": {}", detail))
+                    .unwrap_or_default()
+            );
+        }
+        summary
+    }
+}
+
+/// Gets the name under which mismatches of a state key are grouped.
+pub fn resource_type(state_key: &StateKey) -> String {
+    match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(_) => "module".to_string(),
+            Path::Resource(tag) => tag.to_canonical_string(),
+            Path::ResourceGroup(tag) => format!("resource_group {}", tag.to_canonical_string()),
+        },
+        StateKeyInner::TableItem { .. } => "table_item".to_string(),
+        StateKeyInner::Raw(_) => "raw".to_string(),
+    }
+}
+
+/// Pretty-prints a state value with the [AptosValueAnnotator], falling back to hex
+/// for modules, table items and values that cannot be annotated.
+pub fn pretty_value<S: StateView>(
+    annotator: &AptosValueAnnotator<S>,
+    state_key: &StateKey,
+    bytes: &[u8],
+) -> String {
+    let annotated = match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Resource(tag) => annotator
+                .view_resource(&tag, bytes)
+                .map(|resource| resource.to_string())
+                .ok(),
+            Path::ResourceGroup(_) => decode_group(bytes)
+                .ok()
+                .and_then(|group| {
+                    group
+                        .iter()
+                        .map(|(tag, member)| {
+                            annotator.view_resource(tag, member).map(|resource| {
+                                format!("{}: {}", tag.to_canonical_string(), resource)
+                            })
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()
+                        .ok()
+                })
+                .map(|members| members.join("\n")),
+            Path::Code(_) => None,
+        },
+        _ => None,
+    };
+    annotated.unwrap_or_else(|| format!("0x{}", hex::encode(bytes)))
+}
+
+#[test]
+fn merge_deduplicates_state_keys() {
+    let mismatch = |state_key: &str| StateMismatch {
+        state_key: state_key.to_string(),
+        kind: MismatchKind::Missing,
+        detail: None,
+        movement_value: None,
+        movement_aptos_value: None,
+    };
+
+    let mut report = StateDiffReport::new(10);
+    report.keys_compared = 2;
+    report.insert("0x1::account::Account".to_string(), mismatch("a"));
+
+    let mut shard = StateDiffReport::new(10);
+    shard.keys_compared = 3;
+    shard.insert("0x1::account::Account".to_string(), mismatch("a"));
+    shard.insert("table_item".to_string(), mismatch("b"));
+    report.merge(shard);
+
+    assert_eq!(report.keys_compared, 5);
+    assert_eq!(report.mismatch_count(), 2);
+    assert_eq!(report.mismatches.len(), 2);
+}
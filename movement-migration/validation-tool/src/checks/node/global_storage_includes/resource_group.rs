@@ -0,0 +1,235 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{checks::error::ValidationError, types::resources::SupplyResource};
+use aptos_types::{
+    account_address::create_derived_object_address,
+    account_config::{
+        ConcurrentFungibleBalanceResource, ConcurrentSupplyResource, FungibleStoreResource,
+        ObjectCoreResource,
+    },
+};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::StructTag, move_resource::MoveStructType,
+};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::debug;
+
+/// Compares two resource groups member by member instead of byte by byte, so a
+/// group is only reported when a member differs, not when the members are merely
+/// serialized in another order.
+///
+/// Fungible stores, balances and supplies are compared by their amounts and
+/// object cores by their ownership, everything else by the member bytes.
+pub fn compare_resource_groups(
+    address: AccountAddress,
+    group_tag: &StructTag,
+    movement_value: &[u8],
+    maptos_state_value: &[u8],
+) -> Result<(), ValidationError> {
+    let movement_group = decode_group(movement_value)?;
+    let movement_aptos_group = decode_group(maptos_state_value)?;
+    let label = group_label(address, group_tag, &movement_group)?;
+
+    let mut errors = vec![];
+    let tags: BTreeSet<_> = movement_group
+        .keys()
+        .chain(movement_aptos_group.keys())
+        .collect();
+    for tag in tags {
+        match (movement_group.get(tag), movement_aptos_group.get(tag)) {
+            (Some(movement_member), Some(movement_aptos_member)) => {
+                if let Some(error) = compare_member(tag, movement_member, movement_aptos_member)? {
+                    errors.push(format!("{}: {}", tag.to_canonical_string(), error));
+                }
+            },
+            (Some(_), None) => errors.push(format!(
+                "{}: Movement Aptos is missing the member",
+                tag.to_canonical_string()
+            )),
+            (None, Some(_)) => errors.push(format!(
+                "{}: Movement Aptos has a member Movement does not have",
+                tag.to_canonical_string()
+            )),
+            (None, None) => {},
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::Unsatisfied(
+            format!("{} differs: {}", label, errors.join("; ")).into(),
+        ))
+    }
+}
+
+/// Compares a single group member, returning a description of the difference if there is one.
+fn compare_member(
+    tag: &StructTag,
+    movement_member: &[u8],
+    movement_aptos_member: &[u8],
+) -> Result<Option<String>, ValidationError> {
+    if tag == &FungibleStoreResource::struct_tag() {
+        let movement_store = decode::<FungibleStoreResource>(movement_member)?;
+        let movement_aptos_store = decode::<FungibleStoreResource>(movement_aptos_member)?;
+        debug!("movement fungible store: {:?}", movement_store);
+
+        Ok((movement_store != movement_aptos_store).then(|| {
+            format!(
+                "Movement store is {:?}, while Movement Aptos store is {:?}",
+                movement_store, movement_aptos_store
+            )
+        }))
+    } else if tag == &ConcurrentFungibleBalanceResource::struct_tag() {
+        let movement_balance =
+            decode::<ConcurrentFungibleBalanceResource>(movement_member)?.balance();
+        let movement_aptos_balance =
+            decode::<ConcurrentFungibleBalanceResource>(movement_aptos_member)?.balance();
+
+        Ok((movement_balance != movement_aptos_balance).then(|| {
+            format!(
+                "Movement balance is {}, while Movement Aptos balance is {}",
+                movement_balance, movement_aptos_balance
+            )
+        }))
+    } else if tag == &ConcurrentSupplyResource::struct_tag() {
+        let movement_supply = *decode::<ConcurrentSupplyResource>(movement_member)?
+            .current
+            .get();
+        let movement_aptos_supply = *decode::<ConcurrentSupplyResource>(movement_aptos_member)?
+            .current
+            .get();
+
+        Ok((movement_supply != movement_aptos_supply).then(|| {
+            format!(
+                "Movement supply is {}, while Movement Aptos supply is {}",
+                movement_supply, movement_aptos_supply
+            )
+        }))
+    } else if tag == &SupplyResource::struct_tag() {
+        let movement_supply = decode::<SupplyResource>(movement_member)?;
+        let movement_aptos_supply = decode::<SupplyResource>(movement_aptos_member)?;
+
+        Ok((movement_supply != movement_aptos_supply).then(|| {
+            format!(
+                "Movement supply is {:?}, while Movement Aptos supply is {:?}",
+                movement_supply, movement_aptos_supply
+            )
+        }))
+    } else if tag == &ObjectCoreResource::struct_tag() {
+        let movement_object = decode::<ObjectCoreResource>(movement_member)?;
+        let movement_aptos_object = decode::<ObjectCoreResource>(movement_aptos_member)?;
+
+        let same_ownership = movement_object.owner == movement_aptos_object.owner
+            && movement_object.allow_ungated_transfer
+                == movement_aptos_object.allow_ungated_transfer;
+        Ok((!same_ownership).then(|| {
+            format!(
+                "Movement object is owned by 0x{} (ungated transfer: {}), while Movement Aptos object is owned by 0x{} (ungated transfer: {})",
+                movement_object.owner.short_str_lossless(),
+                movement_object.allow_ungated_transfer,
+                movement_aptos_object.owner.short_str_lossless(),
+                movement_aptos_object.allow_ungated_transfer
+            )
+        }))
+    } else {
+        Ok((movement_member != movement_aptos_member).then(|| "member bytes differ".to_string()))
+    }
+}
+
+/// Describes a resource group for error messages, naming primary fungible stores
+/// after their owner and metadata.
+fn group_label(
+    address: AccountAddress,
+    group_tag: &StructTag,
+    group: &BTreeMap<StructTag, Vec<u8>>,
+) -> Result<String, ValidationError> {
+    let object = group.get(&ObjectCoreResource::struct_tag());
+    let store = group.get(&FungibleStoreResource::struct_tag());
+    if let (Some(object), Some(store)) = (object, store) {
+        let owner = decode::<ObjectCoreResource>(object)?.owner;
+        let metadata = decode::<FungibleStoreResource>(store)?.metadata();
+        if create_derived_object_address(owner, metadata) == address {
+            return Ok(format!(
+                "primary fungible store 0x{} of 0x{} for metadata 0x{}",
+                address.short_str_lossless(),
+                owner.short_str_lossless(),
+                metadata.short_str_lossless()
+            ));
+        }
+    }
+
+    Ok(format!(
+        "resource group {} at 0x{}",
+        group_tag.to_canonical_string(),
+        address.short_str_lossless()
+    ))
+}
+
+/// Decodes a resource group as a list of members first, because decoding it as a
+/// map directly rejects members that are not in canonical order.
+pub fn decode_group(bytes: &[u8]) -> Result<BTreeMap<StructTag, Vec<u8>>, ValidationError> {
+    Ok(decode::<Vec<(StructTag, Vec<u8>)>>(bytes)?
+        .into_iter()
+        .collect())
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ValidationError> {
+    bcs::from_bytes(bytes).map_err(|e| ValidationError::Internal(e.into()))
+}
+
+#[test]
+fn member_order_does_not_matter() {
+    use aptos_types::{
+        account_config::ObjectGroupResource,
+        event::{EventHandle, EventKey},
+    };
+
+    let owner = AccountAddress::from_hex_literal("0xcafe").unwrap();
+    let store_address = create_derived_object_address(owner, AccountAddress::TEN);
+    let store =
+        bcs::to_bytes(&FungibleStoreResource::new(AccountAddress::TEN, 100, false)).unwrap();
+    let object = bcs::to_bytes(&ObjectCoreResource::new(
+        owner,
+        false,
+        EventHandle::new(EventKey::new(0, owner), 0),
+    ))
+    .unwrap();
+
+    let mut group = ObjectGroupResource::default();
+    group.insert(FungibleStoreResource::struct_tag(), store);
+    group.insert(ObjectCoreResource::struct_tag(), object.clone());
+    let group_bytes = group.to_bytes().unwrap();
+
+    // Serialize the same members in the other order, as a differently built group would be.
+    let serialize = |members: Vec<(&StructTag, &Vec<u8>)>| bcs::to_bytes(&members).unwrap();
+    let forward = serialize(group.group.iter().collect());
+    let backward = serialize(group.group.iter().rev().collect());
+    let reordered = if forward == group_bytes {
+        backward
+    } else {
+        forward
+    };
+    assert_ne!(group_bytes, reordered);
+
+    let tag = ObjectGroupResource::struct_tag();
+    compare_resource_groups(store_address, &tag, &group_bytes, &reordered).unwrap();
+
+    let mut changed = ObjectGroupResource::default();
+    changed.insert(
+        FungibleStoreResource::struct_tag(),
+        bcs::to_bytes(&FungibleStoreResource::new(AccountAddress::TEN, 99, false)).unwrap(),
+    );
+    changed.insert(ObjectCoreResource::struct_tag(), object);
+    let error = compare_resource_groups(
+        store_address,
+        &tag,
+        &group_bytes,
+        &changed.to_bytes().unwrap(),
+    )
+    .unwrap_err();
+    assert!(matches!(error, ValidationError::Unsatisfied(_)));
+    assert!(error.to_string().contains("primary fungible store"));
+}
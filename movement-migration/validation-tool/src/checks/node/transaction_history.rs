@@ -0,0 +1,579 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    checks::{
+        error::ValidationError,
+        registry::{parse_params, run_blocking, Check, CheckContext},
+    },
+    types::storage::{MovementAptosStorage, MovementStorage, Storage, MAX_WRITE_SET_SIZE},
+};
+use anyhow::Context;
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{contract_event::ContractEvent, transaction::TransactionInfo};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write, ops::Range, path::PathBuf};
+use tracing::info;
+
+/// The number of mismatching versions listed in the error message of the check.
+const SUMMARY_VERSIONS: usize = 10;
+
+/// This check compares the transaction history of the old Movement database and
+/// the new Aptos database over a version range, which defaults to every version
+/// the Movement database has.
+///
+/// For each version, the [TransactionInfo]s need to have the same hash, and the
+/// events need to be the same, down to their event handles and sequence numbers.
+/// The transaction accumulator root hashes are compared at the end of every chunk
+/// of versions, or at every version when sampling.
+///
+/// Either every version of the range is compared, or only `sample_size` randomly
+/// chosen ones. Every mismatch is collected into a [HistoryReport] by version.
+#[derive(Clone)]
+pub struct TransactionHistory {
+    start_version: Option<u64>,
+    end_version: Option<u64>,
+    sample_size: Option<u64>,
+    seed: u64,
+    report_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionHistoryParams {
+    /// The first version to compare, defaults to the first version of the Movement database.
+    #[serde(default)]
+    pub start_version: Option<u64>,
+    /// The version to stop comparing at, exclusive, defaults to the one after the
+    /// latest version of the Movement database.
+    #[serde(default)]
+    pub end_version: Option<u64>,
+    /// The number of randomly chosen versions to compare, defaults to a full sweep of every version.
+    #[serde(default)]
+    pub sample_size: Option<u64>,
+    /// The seed of the random version sample, so a sample can be reproduced.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// The file the JSON report of all mismatches is written to.
+    #[serde(default)]
+    pub report_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Check for TransactionHistory {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn run(&self, context: &CheckContext) -> Result<(), ValidationError> {
+        let check = self.clone();
+        let movement_storage = context.movement_storage()?.clone();
+        let movement_aptos_storage = context.movement_aptos_storage()?.clone();
+        run_blocking(move || check.satisfies(&movement_storage, &movement_aptos_storage)).await
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMismatchKind {
+    /// The transaction infos of the version have different hashes.
+    TransactionInfo,
+    /// The events of the version differ.
+    Events,
+    /// The transaction accumulator root hashes at the version differ.
+    AccumulatorRoot,
+    /// A database returned fewer versions than requested, starting at the version.
+    MissingVersions,
+}
+
+/// A single difference between the transaction histories of the two databases.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HistoryMismatch {
+    pub kind: HistoryMismatchKind,
+    pub detail: String,
+}
+
+/// Every mismatch found by [TransactionHistory], grouped by version.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct HistoryReport {
+    pub start_version: u64,
+    pub end_version: u64,
+    pub versions_compared: u64,
+    pub mismatches: BTreeMap<u64, Vec<HistoryMismatch>>,
+}
+
+impl HistoryReport {
+    pub fn new(versions: Range<u64>) -> Self {
+        Self {
+            start_version: versions.start,
+            end_version: versions.end,
+            ..Default::default()
+        }
+    }
+
+    pub fn insert(&mut self, version: u64, kind: HistoryMismatchKind, detail: String) {
+        self.mismatches
+            .entry(version)
+            .or_default()
+            .push(HistoryMismatch { kind, detail });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Writes the report as pretty-printed JSON.
+    pub fn write(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let json = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("failed to write history report {}", path.display()))
+    }
+
+    /// Summarizes the report as the number of mismatching versions, followed by the
+    /// mismatches of the first few of them.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} of {} compared versions in [{}, {}) differ:",
+            self.mismatches.len(),
+            self.versions_compared,
+            self.start_version,
+            self.end_version
+        );
+        for (version, mismatches) in self.mismatches.iter().take(SUMMARY_VERSIONS) {
+            for mismatch in mismatches {
+                let _ = write!(
+                    summary,
+                    "\n{} {:?}: {}",
+                    version, mismatch.kind, mismatch.detail
+                );
+            }
+        }
+        summary
+    }
+}
+
+impl TransactionHistory {
+    pub const NAME: &'static str = "transaction-history";
+
+    pub fn new(params: TransactionHistoryParams) -> anyhow::Result<Self> {
+        if let (Some(start_version), Some(end_version)) = (params.start_version, params.end_version)
+        {
+            if start_version >= end_version {
+                anyhow::bail!("start_version must be lower than end_version");
+            }
+        }
+        if params.sample_size == Some(0) {
+            anyhow::bail!("sample_size must be greater than 0");
+        }
+
+        Ok(Self {
+            start_version: params.start_version,
+            end_version: params.end_version,
+            sample_size: params.sample_size,
+            seed: params.seed.unwrap_or(0),
+            report_file: params.report_file,
+        })
+    }
+
+    pub fn from_params(params: &serde_json::Value) -> anyhow::Result<Self> {
+        Self::new(parse_params(params)?)
+    }
+
+    pub fn satisfies(
+        &self,
+        movement_storage: &MovementStorage,
+        movement_aptos_storage: &MovementAptosStorage,
+    ) -> Result<(), ValidationError> {
+        let versions = self.version_range(movement_storage, movement_aptos_storage)?;
+        let mut report = HistoryReport::new(versions.clone());
+
+        match self.sample_size {
+            Some(sample_size) => {
+                let sample = sample_versions(versions, sample_size, self.seed);
+                info!("comparing the history of {} sampled versions", sample.len());
+                for version in sample {
+                    Self::compare_versions(
+                        movement_storage,
+                        movement_aptos_storage,
+                        version,
+                        1,
+                        &mut report,
+                    )
+                    .map_err(|e| ValidationError::Internal(e.into()))?;
+                }
+            },
+            None => {
+                info!(
+                    "comparing the history of every version in [{}, {})",
+                    versions.start, versions.end
+                );
+                for start_version in versions.clone().step_by(MAX_WRITE_SET_SIZE as usize) {
+                    let limit = MAX_WRITE_SET_SIZE.min(versions.end - start_version);
+                    Self::compare_versions(
+                        movement_storage,
+                        movement_aptos_storage,
+                        start_version,
+                        limit,
+                        &mut report,
+                    )
+                    .map_err(|e| ValidationError::Internal(e.into()))?;
+                }
+            },
+        }
+
+        info!(
+            "compared the history of {} versions, {} versions differ",
+            report.versions_compared,
+            report.mismatches.len()
+        );
+        if let Some(path) = &self.report_file {
+            report
+                .write(path)
+                .map_err(|e| ValidationError::Internal(e.into()))?;
+        }
+        if !report.is_empty() {
+            return Err(ValidationError::Unsatisfied(report.summary().into()));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the version range to compare, which both databases need to have
+    /// in full.
+    fn version_range(
+        &self,
+        movement_storage: &Storage,
+        movement_aptos_storage: &Storage,
+    ) -> Result<Range<u64>, ValidationError> {
+        let start_version = match self.start_version {
+            Some(version) => version,
+            None => movement_storage
+                .first_transaction_version()
+                .map_err(|e| ValidationError::Internal(e.into()))?,
+        };
+        let end_version = match self.end_version {
+            Some(version) => version,
+            None => {
+                movement_storage
+                    .latest_ledger_version()
+                    .map_err(|e| ValidationError::Internal(e.into()))?
+                    + 1
+            },
+        };
+        if start_version >= end_version {
+            return Err(ValidationError::Internal(
+                format!(
+                    "version range [{}, {}) is empty",
+                    start_version, end_version
+                )
+                .into(),
+            ));
+        }
+
+        for (database, storage) in [
+            ("Movement", movement_storage),
+            ("Movement Aptos", movement_aptos_storage),
+        ] {
+            let first_version = storage
+                .first_transaction_version()
+                .map_err(|e| ValidationError::Internal(e.into()))?;
+            let latest_version = storage
+                .latest_ledger_version()
+                .map_err(|e| ValidationError::Internal(e.into()))?;
+            if first_version > start_version || latest_version < end_version - 1 {
+                return Err(ValidationError::Unsatisfied(
+                    format!(
+                        "{} database has versions [{}, {}], which does not cover [{}, {})",
+                        database, first_version, latest_version, start_version, end_version
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        Ok(start_version..end_version)
+    }
+
+    /// Compares `limit` versions starting at `start_version`, and the accumulator
+    /// root hashes at the last of them.
+    fn compare_versions(
+        movement_storage: &Storage,
+        movement_aptos_storage: &Storage,
+        start_version: u64,
+        limit: u64,
+        report: &mut HistoryReport,
+    ) -> anyhow::Result<()> {
+        let movement_infos = movement_storage.transaction_infos(start_version, limit)?;
+        let movement_aptos_infos =
+            movement_aptos_storage.transaction_infos(start_version, limit)?;
+        let movement_events = movement_storage.events(start_version, limit)?;
+        let movement_aptos_events = movement_aptos_storage.events(start_version, limit)?;
+
+        check_version_count(
+            "Movement",
+            start_version,
+            limit,
+            movement_infos.len(),
+            movement_events.len(),
+            report,
+        );
+        check_version_count(
+            "Movement Aptos",
+            start_version,
+            limit,
+            movement_aptos_infos.len(),
+            movement_aptos_events.len(),
+            report,
+        );
+
+        let mut versions_compared = 0;
+        for (
+            offset,
+            ((movement_info, movement_aptos_info), (movement_events, movement_aptos_events)),
+        ) in movement_infos
+            .iter()
+            .zip(&movement_aptos_infos)
+            .zip(movement_events.iter().zip(&movement_aptos_events))
+            .enumerate()
+        {
+            let version = start_version + offset as u64;
+            compare_transaction_infos(version, movement_info, movement_aptos_info, report);
+            compare_events(version, movement_events, movement_aptos_events, report);
+            versions_compared += 1;
+        }
+        report.versions_compared += versions_compared;
+
+        let last_version = start_version + limit - 1;
+        let movement_root = movement_storage.accumulator_root_hash(last_version)?;
+        let movement_aptos_root = movement_aptos_storage.accumulator_root_hash(last_version)?;
+        if movement_root != movement_aptos_root {
+            report.insert(
+                last_version,
+                HistoryMismatchKind::AccumulatorRoot,
+                format!(
+                    "Movement accumulator root is {}, while Movement Aptos accumulator root is {}",
+                    movement_root, movement_aptos_root
+                ),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Chooses up to `sample_size` distinct versions of the range, in ascending order.
+fn sample_versions(versions: Range<u64>, sample_size: u64, seed: u64) -> Vec<u64> {
+    let length = versions.end - versions.start;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut sample: Vec<_> =
+        rand::seq::index::sample(&mut rng, length as usize, sample_size.min(length) as usize)
+            .into_iter()
+            .map(|offset| versions.start + offset as u64)
+            .collect();
+    sample.sort_unstable();
+    sample
+}
+
+/// Reports the versions missing if a database returned fewer than `limit` transaction infos
+/// or event lists, which would otherwise go unnoticed when comparing them pairwise.
+fn check_version_count(
+    database: &str,
+    start_version: u64,
+    limit: u64,
+    num_infos: usize,
+    num_events: usize,
+    report: &mut HistoryReport,
+) {
+    let num_versions = num_infos.min(num_events) as u64;
+    if num_versions < limit {
+        report.insert(
+            start_version + num_versions,
+            HistoryMismatchKind::MissingVersions,
+            format!(
+                "{} returned {} transaction infos and {} event lists for the {} versions starting at {}",
+                database, num_infos, num_events, limit, start_version
+            ),
+        );
+    }
+}
+
+/// Compares the transaction infos of a version by hash, naming the fields that differ.
+fn compare_transaction_infos(
+    version: u64,
+    movement_info: &TransactionInfo,
+    movement_aptos_info: &TransactionInfo,
+    report: &mut HistoryReport,
+) {
+    if movement_info.hash() == movement_aptos_info.hash() {
+        return;
+    }
+
+    let mut fields = vec![];
+    if movement_info.transaction_hash() != movement_aptos_info.transaction_hash() {
+        fields.push("transaction hash");
+    }
+    if movement_info.state_change_hash() != movement_aptos_info.state_change_hash() {
+        fields.push("state change hash");
+    }
+    if movement_info.event_root_hash() != movement_aptos_info.event_root_hash() {
+        fields.push("event root hash");
+    }
+    if movement_info.state_checkpoint_hash() != movement_aptos_info.state_checkpoint_hash() {
+        fields.push("state checkpoint hash");
+    }
+    if movement_info.gas_used() != movement_aptos_info.gas_used() {
+        fields.push("gas used");
+    }
+    if movement_info.status() != movement_aptos_info.status() {
+        fields.push("status");
+    }
+
+    report.insert(
+        version,
+        HistoryMismatchKind::TransactionInfo,
+        format!(
+            "transaction info hashes differ ({}): Movement {:?}, Movement Aptos {:?}",
+            if fields.is_empty() {
+                "other fields".to_string()
+            } else {
+                fields.join(", ")
+            },
+            movement_info,
+            movement_aptos_info
+        ),
+    );
+}
+
+/// Compares the events of a version one by one, including the event handles and
+/// sequence numbers of module events.
+fn compare_events(
+    version: u64,
+    movement_events: &[ContractEvent],
+    movement_aptos_events: &[ContractEvent],
+    report: &mut HistoryReport,
+) {
+    if movement_events.len() != movement_aptos_events.len() {
+        report.insert(
+            version,
+            HistoryMismatchKind::Events,
+            format!(
+                "Movement has {} events, while Movement Aptos has {}",
+                movement_events.len(),
+                movement_aptos_events.len()
+            ),
+        );
+        return;
+    }
+
+    for (index, (movement_event, movement_aptos_event)) in movement_events
+        .iter()
+        .zip(movement_aptos_events)
+        .enumerate()
+    {
+        if movement_event == movement_aptos_event {
+            continue;
+        }
+        let detail = match (movement_event, movement_aptos_event) {
+            (ContractEvent::V1(movement_event), ContractEvent::V1(movement_aptos_event))
+                if movement_event.key() != movement_aptos_event.key() =>
+            {
+                format!(
+                    "event handle is {}, while Movement Aptos event handle is {}",
+                    movement_event.key(),
+                    movement_aptos_event.key()
+                )
+            },
+            (ContractEvent::V1(movement_event), ContractEvent::V1(movement_aptos_event))
+                if movement_event.sequence_number() != movement_aptos_event.sequence_number() =>
+            {
+                format!(
+                    "sequence number of {} is {}, while Movement Aptos sequence number is {}",
+                    movement_event.key(),
+                    movement_event.sequence_number(),
+                    movement_aptos_event.sequence_number()
+                )
+            },
+            _ => format!(
+                "Movement event is {}, while Movement Aptos event is {}",
+                movement_event, movement_aptos_event
+            ),
+        };
+        report.insert(
+            version,
+            HistoryMismatchKind::Events,
+            format!("event {}: {}", index, detail),
+        );
+    }
+}
+
+#[test]
+fn mismatches_are_reported_by_version() {
+    use aptos_crypto::HashValue;
+    use aptos_types::{event::EventKey, transaction::ExecutionStatus};
+    use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+
+    let info = |gas_used| {
+        TransactionInfo::new(
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            None,
+            gas_used,
+            ExecutionStatus::Success,
+        )
+    };
+    let event = |sequence_number| {
+        ContractEvent::new_v1(
+            EventKey::new(0, AccountAddress::ONE),
+            sequence_number,
+            TypeTag::U64,
+            vec![],
+        )
+        .unwrap()
+    };
+
+    let mut report = HistoryReport::new(10..13);
+    compare_transaction_infos(10, &info(1), &info(1), &mut report);
+    compare_events(10, &[event(0)], &[event(0)], &mut report);
+    assert!(report.is_empty());
+
+    compare_transaction_infos(11, &info(1), &info(2), &mut report);
+    compare_events(12, &[event(0)], &[event(1)], &mut report);
+    compare_events(12, &[event(0)], &[], &mut report);
+    assert_eq!(report.mismatches.keys().copied().collect::<Vec<_>>(), [
+        11, 12
+    ]);
+    assert!(report.mismatches[&11][0].detail.contains("gas used"));
+    assert!(report.mismatches[&12][0].detail.contains("sequence number"));
+    assert_eq!(report.mismatches[&12].len(), 2);
+}
+
+#[test]
+fn missing_versions_are_reported() {
+    let mut report = HistoryReport::new(10..20);
+    check_version_count("Movement", 10, 10, 10, 10, &mut report);
+    assert!(report.is_empty());
+
+    check_version_count("Movement Aptos", 10, 10, 7, 8, &mut report);
+    assert_eq!(report.mismatches.keys().copied().collect::<Vec<_>>(), [17]);
+    assert_eq!(
+        report.mismatches[&17][0].kind,
+        HistoryMismatchKind::MissingVersions
+    );
+    assert!(report.mismatches[&17][0]
+        .detail
+        .starts_with("Movement Aptos"));
+}
+
+#[test]
+fn samples_are_distinct_and_reproducible() {
+    let sample = sample_versions(100..200, 10, 7);
+    assert_eq!(sample.len(), 10);
+    assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(sample.iter().all(|version| (100..200).contains(version)));
+    assert_eq!(sample, sample_versions(100..200, 10, 7));
+
+    assert_eq!(sample_versions(5..8, 10, 7), [5, 6, 7]);
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    checks::{
+        api::active_feature_flags::GlobalFeatureCheck,
+        error::ValidationError,
+        node::{
+            fungible_asset_supply::FungibleAssetSupply,
+            global_storage_includes::GlobalStorageIncludes,
+            transaction_history::TransactionHistory,
+        },
+    },
+    types::{
+        api::MovementAptosRestClient,
+        storage::{MovementAptosStorage, MovementStorage},
+    },
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+
+/// A single validation criterion that can be selected by name from a validation spec.
+#[async_trait]
+pub trait Check: Send + Sync {
+    /// The name under which the check is registered.
+    fn name(&self) -> &'static str;
+
+    /// Runs the check against the resources available in the [CheckContext].
+    ///
+    /// A check that needs a resource which was not configured for this run
+    /// returns [ValidationError::Skipped].
+    async fn run(&self, context: &CheckContext) -> Result<(), ValidationError>;
+}
+
+/// Runs the blocking part of a check, such as a walk over a database, on a thread
+/// of the blocking pool, so that it does not stall the async runtime.
+pub async fn run_blocking(
+    f: impl FnOnce() -> Result<(), ValidationError> + Send + 'static,
+) -> Result<(), ValidationError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ValidationError::Internal(e.into()))?
+}
+
+/// Builds a [Check] from the parameters given for it in a validation spec.
+pub type CheckFactory = fn(&serde_json::Value) -> anyhow::Result<Box<dyn Check>>;
+
+/// The databases and endpoints a validation run has access to.
+#[derive(Default)]
+pub struct CheckContext {
+    movement_storage: Option<MovementStorage>,
+    movement_aptos_storage: Option<MovementAptosStorage>,
+    movement_aptos_rest_client: Option<MovementAptosRestClient>,
+}
+
+impl CheckContext {
+    pub fn with_movement_storage(mut self, storage: MovementStorage) -> Self {
+        self.movement_storage = Some(storage);
+        self
+    }
+
+    pub fn with_movement_aptos_storage(mut self, storage: MovementAptosStorage) -> Self {
+        self.movement_aptos_storage = Some(storage);
+        self
+    }
+
+    pub fn with_movement_aptos_rest_client(mut self, client: MovementAptosRestClient) -> Self {
+        self.movement_aptos_rest_client = Some(client);
+        self
+    }
+
+    /// Gets the old Movement database, or skips the check if none was configured.
+    pub fn movement_storage(&self) -> Result<&MovementStorage, ValidationError> {
+        self.movement_storage
+            .as_ref()
+            .ok_or_else(|| ValidationError::Skipped("no Movement database was configured".into()))
+    }
+
+    /// Gets the new Movement Aptos database, or skips the check if none was configured.
+    pub fn movement_aptos_storage(&self) -> Result<&MovementAptosStorage, ValidationError> {
+        self.movement_aptos_storage.as_ref().ok_or_else(|| {
+            ValidationError::Skipped("no Movement Aptos database was configured".into())
+        })
+    }
+
+    /// Gets the Movement Aptos REST client, or skips the check if none was configured.
+    pub fn movement_aptos_rest_client(&self) -> Result<&MovementAptosRestClient, ValidationError> {
+        self.movement_aptos_rest_client.as_ref().ok_or_else(|| {
+            ValidationError::Skipped("no Movement Aptos REST endpoint was configured".into())
+        })
+    }
+}
+
+/// Maps check names to the factories that build them.
+pub struct CheckRegistry {
+    factories: BTreeMap<&'static str, CheckFactory>,
+}
+
+impl CheckRegistry {
+    /// Creates a registry without any checks.
+    pub fn empty() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a check factory under the given name, replacing any previous one.
+    pub fn register(&mut self, name: &'static str, factory: CheckFactory) -> &mut Self {
+        self.factories.insert(name, factory);
+        self
+    }
+
+    /// Gets the names of all registered checks.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.factories.keys().copied()
+    }
+
+    /// Builds the check registered under `name` with the given parameters.
+    pub fn build(&self, name: &str, params: &serde_json::Value) -> anyhow::Result<Box<dyn Check>> {
+        let factory = self.factories.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown check {:?}, registered checks are: {}",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        factory(params).with_context(|| format!("invalid parameters for check {:?}", name))
+    }
+}
+
+impl Default for CheckRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(GlobalStorageIncludes::NAME, |params| {
+                Ok(Box::new(GlobalStorageIncludes::from_params(params)?))
+            })
+            .register(FungibleAssetSupply::NAME, |params| {
+                Ok(Box::new(FungibleAssetSupply::from_params(params)?))
+            })
+            .register(TransactionHistory::NAME, |params| {
+                Ok(Box::new(TransactionHistory::from_params(params)?))
+            })
+            .register(GlobalFeatureCheck::NAME, |params| {
+                Ok(Box::new(GlobalFeatureCheck::from_params(params)?))
+            });
+        registry
+    }
+}
+
+/// Deserializes check parameters, treating missing parameters as an empty table.
+pub fn parse_params<T: DeserializeOwned>(params: &serde_json::Value) -> anyhow::Result<T> {
+    let params = match params {
+        serde_json::Value::Null => serde_json::Value::Object(Default::default()),
+        params => params.clone(),
+    };
+    Ok(serde_json::from_value(params)?)
+}
+
+#[test]
+fn default_registry_builds_all_checks() {
+    let registry = CheckRegistry::default();
+    for name in registry.names() {
+        let check = registry.build(name, &serde_json::Value::Null).unwrap();
+        assert_eq!(check.name(), name);
+    }
+    assert!(registry
+        .build("no-such-check", &serde_json::Value::Null)
+        .is_err());
+}
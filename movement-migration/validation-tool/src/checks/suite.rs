@@ -0,0 +1,133 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    checks::registry::{CheckContext, CheckRegistry},
+    report::{CheckStatus, ValidationReport},
+    spec::ValidationSpec,
+    types::{
+        api::MovementAptosRestClient,
+        storage::{MovementAptosStorage, MovementStorage},
+    },
+};
+use clap::Parser;
+use std::{path::PathBuf, time::Instant};
+use tracing::{info, warn};
+
+#[derive(Parser)]
+#[clap(
+    name = "migration-suite-validation",
+    about = "Runs the checks selected by a validation spec and writes a JSON and JUnit report."
+)]
+pub struct Command {
+    #[clap(long, help = "The path to the TOML or YAML validation spec.")]
+    pub spec: PathBuf,
+    #[clap(
+        long,
+        help = "The network from the validation spec whose expected values are used."
+    )]
+    pub network: Option<String>,
+    #[clap(long = "movement", help = "The path to the movement database.")]
+    pub movement_db: Option<PathBuf>,
+    #[clap(
+        long = "movement-aptos",
+        help = "The path to the movement Aptos database."
+    )]
+    pub movement_aptos_db: Option<PathBuf>,
+    #[clap(
+        long = "movement-aptos-rest-url",
+        help = "The url of the Movement Aptos REST endpoint."
+    )]
+    pub movement_aptos_rest_api_url: Option<String>,
+    #[clap(
+        long,
+        default_value = ".",
+        help = "The directory the validation reports are written to."
+    )]
+    pub report_dir: PathBuf,
+}
+
+impl Command {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let spec = ValidationSpec::load(&self.spec)?;
+        let resolved = spec.resolve(self.network.as_deref())?;
+
+        // Build every check up front, so a typo in the spec does not surface halfway through a run.
+        let registry = CheckRegistry::default();
+        let checks = resolved
+            .into_iter()
+            .map(|check| -> anyhow::Result<_> {
+                let built = if check.enabled {
+                    Some(registry.build(&check.name, &check.params)?)
+                } else {
+                    None
+                };
+                Ok((check.name, built))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut context = CheckContext::default();
+        if let Some(path) = &self.movement_db {
+            context = context.with_movement_storage(MovementStorage::open(path)?);
+        }
+        if let Some(path) = &self.movement_aptos_db {
+            context = context.with_movement_aptos_storage(MovementAptosStorage::open(path)?);
+        }
+        if let Some(url) = &self.movement_aptos_rest_api_url {
+            context = context.with_movement_aptos_rest_client(MovementAptosRestClient::new(url)?);
+        }
+
+        let mut report = ValidationReport::new(self.network.clone());
+        for (name, check) in checks {
+            let Some(check) = check else {
+                info!("skipping check {}: disabled in the validation spec", name);
+                report.record_skipped(&name, "disabled in the validation spec");
+                continue;
+            };
+
+            info!("running check {}", name);
+            let start = Instant::now();
+            let result = check.run(&context).await;
+            if let Err(e) = &result {
+                warn!("check {}: {}", name, e);
+            }
+            report.record(&name, &result, start.elapsed());
+        }
+
+        report.write_to_dir(&self.report_dir)?;
+        info!(
+            "validation finished: {} passed, {} failed, {} skipped, reports written to {}",
+            report.count(CheckStatus::Pass),
+            report.count(CheckStatus::Fail),
+            report.count(CheckStatus::Skip),
+            self.report_dir.display()
+        );
+
+        if !report.is_success() {
+            anyhow::bail!(
+                "{} of {} checks failed",
+                report.count(CheckStatus::Fail),
+                report.checks.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Command::command().debug_assert()
+}
+
+#[test]
+fn example_spec_builds() {
+    let spec = ValidationSpec::from_toml(include_str!("../../specs/example.toml")).unwrap();
+    let registry = CheckRegistry::default();
+    for network in [None, Some("mainnet"), Some("testnet"), Some("local")] {
+        for check in spec.resolve(network).unwrap() {
+            registry.build(&check.name, &check.params).unwrap();
+        }
+    }
+}
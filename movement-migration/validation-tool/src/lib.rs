@@ -4,6 +4,8 @@
 use clap::Parser;
 
 pub mod checks;
+pub mod report;
+pub mod spec;
 mod types;
 
 #[derive(Parser)]
@@ -15,6 +17,7 @@ mod types;
 pub enum ValidationTool {
     Api(checks::api::Command),
     Node(checks::node::Command),
+    Suite(checks::suite::Command),
 }
 
 impl ValidationTool {
@@ -22,6 +25,7 @@ impl ValidationTool {
         match self {
             ValidationTool::Api(cmd) => cmd.run().await,
             ValidationTool::Node(cmd) => cmd.run().await,
+            ValidationTool::Suite(cmd) => cmd.run().await,
         }
     }
 }
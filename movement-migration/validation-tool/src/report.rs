@@ -0,0 +1,154 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::checks::error::ValidationError;
+use anyhow::Context;
+use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
+
+const JSON_REPORT_FILE: &str = "validation-report.json";
+const JUNIT_REPORT_FILE: &str = "validation-report.xml";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// The outcome of a single check.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// A machine-readable record of every check in a validation run.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ValidationReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ValidationReport {
+    pub fn new(network: Option<String>) -> Self {
+        Self {
+            network,
+            checks: vec![],
+        }
+    }
+
+    /// Records the result of a check that was run.
+    pub fn record(&mut self, name: &str, result: &Result<(), ValidationError>, duration: Duration) {
+        let (status, message) = match result {
+            Ok(()) => (CheckStatus::Pass, None),
+            Err(ValidationError::Skipped(e)) => (CheckStatus::Skip, Some(e.to_string())),
+            Err(e) => (CheckStatus::Fail, Some(e.to_string())),
+        };
+        self.checks.push(CheckResult {
+            name: name.to_string(),
+            status,
+            message,
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+
+    /// Records a check that was not run at all.
+    pub fn record_skipped(&mut self, name: &str, reason: &str) {
+        self.checks.push(CheckResult {
+            name: name.to_string(),
+            status: CheckStatus::Skip,
+            message: Some(reason.to_string()),
+            duration_ms: 0,
+        });
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    /// Renders the report as a JUnit XML document with one test case per check.
+    pub fn to_junit(&self) -> anyhow::Result<String> {
+        let mut report = Report::new("movement-migration-validation");
+        let mut suite = TestSuite::new(self.network.clone().unwrap_or_else(|| "default".into()));
+        for check in &self.checks {
+            let mut status = match check.status {
+                CheckStatus::Pass => TestCaseStatus::success(),
+                CheckStatus::Fail => TestCaseStatus::non_success(NonSuccessKind::Failure),
+                CheckStatus::Skip => TestCaseStatus::skipped(),
+            };
+            if let Some(message) = &check.message {
+                status.set_message(message.clone());
+            }
+            let mut test_case = TestCase::new(check.name.clone(), status);
+            test_case.set_time(Duration::from_millis(check.duration_ms));
+            suite.add_test_case(test_case);
+        }
+        report.add_test_suite(suite);
+
+        let mut buffer = vec![];
+        report.serialize(&mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// Writes the JSON and JUnit XML reports into the given directory.
+    pub fn write_to_dir(&self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create report directory {}", dir.display()))?;
+
+        let json_path = dir.join(JSON_REPORT_FILE);
+        std::fs::write(&json_path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", json_path.display()))?;
+
+        let junit_path = dir.join(JUNIT_REPORT_FILE);
+        std::fs::write(&junit_path, self.to_junit()?)
+            .with_context(|| format!("failed to write {}", junit_path.display()))?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn report_lists_every_status() {
+    let mut report = ValidationReport::new(Some("testnet".into()));
+    report.record("a", &Ok(()), Duration::from_millis(5));
+    report.record(
+        "b",
+        &Err(ValidationError::Unsatisfied("mismatch".into())),
+        Duration::from_millis(7),
+    );
+    report.record(
+        "c",
+        &Err(ValidationError::Skipped("no database".into())),
+        Duration::ZERO,
+    );
+    report.record_skipped("d", "disabled in spec");
+
+    assert_eq!(report.count(CheckStatus::Pass), 1);
+    assert_eq!(report.count(CheckStatus::Fail), 1);
+    assert_eq!(report.count(CheckStatus::Skip), 2);
+    assert!(!report.is_success());
+
+    let dir = tempfile::tempdir().unwrap();
+    report.write_to_dir(dir.path()).unwrap();
+    let json: ValidationReport =
+        serde_json::from_slice(&std::fs::read(dir.path().join(JSON_REPORT_FILE)).unwrap()).unwrap();
+    assert_eq!(json, report);
+
+    let junit = std::fs::read_to_string(dir.path().join(JUNIT_REPORT_FILE)).unwrap();
+    assert!(junit.contains("<failure"));
+    assert!(junit.contains("<skipped"));
+}
@@ -0,0 +1,199 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// Selects the checks of a validation run and the values they expect.
+///
+/// A spec is written in TOML or YAML. Every check lists its default parameters,
+/// and every network can override parameters or skip checks by name, so the
+/// same spec can be used against each migrated network.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidationSpec {
+    pub checks: Vec<CheckSpec>,
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkSpec>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckSpec {
+    /// The name of the check in the [crate::checks::registry::CheckRegistry].
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSpec {
+    /// Names of the checks that are skipped on this network.
+    #[serde(default)]
+    pub skip: BTreeSet<String>,
+    /// Parameters by check name, merged over the check's default parameters.
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_json::Value>,
+}
+
+/// A check with the parameters that apply to the selected network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedCheck {
+    pub name: String,
+    pub enabled: bool,
+    pub params: serde_json::Value,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ValidationSpec {
+    /// Loads a spec, choosing the format from the file extension.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read validation spec {}", path.display()))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml(&contents),
+            _ => anyhow::bail!(
+                "validation spec {} must have a .toml, .yaml or .yml extension",
+                path.display()
+            ),
+        }
+        .with_context(|| format!("failed to parse validation spec {}", path.display()))
+    }
+
+    pub fn from_toml(contents: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn from_yaml(contents: &str) -> anyhow::Result<Self> {
+        Ok(serde_yaml::from_str(contents)?)
+    }
+
+    /// Resolves the checks for the given network, or the defaults if no network is given.
+    pub fn resolve(&self, network: Option<&str>) -> anyhow::Result<Vec<ResolvedCheck>> {
+        let network_spec = match network {
+            Some(network) => Some(self.networks.get(network).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "network {:?} is not defined in the validation spec, defined networks are: {}",
+                    network,
+                    self.networks.keys().cloned().collect::<Vec<_>>().join(", ")
+                )
+            })?),
+            None => None,
+        };
+
+        if let Some(network_spec) = network_spec {
+            for name in network_spec.skip.iter().chain(network_spec.params.keys()) {
+                if !self.checks.iter().any(|check| &check.name == name) {
+                    anyhow::bail!(
+                        "network {:?} refers to check {:?}, which is not part of the spec",
+                        network.unwrap_or_default(),
+                        name
+                    );
+                }
+            }
+        }
+
+        Ok(self
+            .checks
+            .iter()
+            .map(|check| {
+                let mut params = check.params.clone();
+                let mut enabled = check.enabled;
+                if let Some(network_spec) = network_spec {
+                    if let Some(overrides) = network_spec.params.get(&check.name) {
+                        merge_params(&mut params, overrides);
+                    }
+                    enabled &= !network_spec.skip.contains(&check.name);
+                }
+                ResolvedCheck {
+                    name: check.name.clone(),
+                    enabled,
+                    params,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Merges the top-level keys of `overrides` into `params`. Anything other than
+/// a table replaces the parameters entirely.
+fn merge_params(params: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (params, overrides) {
+        (serde_json::Value::Object(params), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                params.insert(key.clone(), value.clone());
+            }
+        },
+        (params, overrides) => *params = overrides.clone(),
+    }
+}
+
+#[test]
+fn resolve_network_overrides() {
+    let spec = ValidationSpec::from_toml(
+        r#"
+        [[checks]]
+        name = "global-storage-includes"
+
+        [[checks]]
+        name = "global-feature-flags"
+        params = { expected_active = [1, 2], request_delay_ms = 0 }
+
+        [networks.testnet]
+        skip = ["global-storage-includes"]
+        params = { global-feature-flags = { expected_active = [3] } }
+        "#,
+    )
+    .unwrap();
+
+    let defaults = spec.resolve(None).unwrap();
+    assert!(defaults.iter().all(|check| check.enabled));
+    assert_eq!(
+        defaults[1].params,
+        serde_json::json!({ "expected_active": [1, 2], "request_delay_ms": 0 })
+    );
+
+    let testnet = spec.resolve(Some("testnet")).unwrap();
+    assert!(!testnet[0].enabled);
+    assert_eq!(
+        testnet[1].params,
+        serde_json::json!({ "expected_active": [3], "request_delay_ms": 0 })
+    );
+
+    assert!(spec.resolve(Some("mainnet")).is_err());
+}
+
+#[test]
+fn toml_and_yaml_specs_agree() {
+    let toml = ValidationSpec::from_toml(
+        r#"
+        [[checks]]
+        name = "global-feature-flags"
+        enabled = false
+        params = { expected_inactive = [71] }
+        "#,
+    )
+    .unwrap();
+    let yaml = ValidationSpec::from_yaml(
+        r#"
+checks:
+  - name: global-feature-flags
+    enabled: false
+    params:
+      expected_inactive: [71]
+"#,
+    )
+    .unwrap();
+    assert_eq!(toml, yaml);
+}
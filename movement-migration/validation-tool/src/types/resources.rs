@@ -0,0 +1,34 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_config::OptionalAggregatorV1Resource;
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::{Deserialize, Serialize};
+
+/// A Rust representation of the non-concurrent `0x1::fungible_asset::Supply`,
+/// which has no counterpart in `aptos_types`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SupplyResource {
+    pub current: u128,
+    pub maximum: Option<u128>,
+}
+
+impl MoveStructType for SupplyResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("fungible_asset");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Supply");
+}
+
+impl MoveResource for SupplyResource {}
+
+/// A Rust representation of `0x1::coin::CoinInfo<T>` for any coin type `T`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CoinInfoResourceUntyped {
+    pub name: Vec<u8>,
+    pub symbol: Vec<u8>,
+    pub decimals: u8,
+    pub supply: Option<OptionalAggregatorV1Resource>,
+}
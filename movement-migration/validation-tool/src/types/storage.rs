@@ -3,15 +3,26 @@
 
 use anyhow::Context;
 use aptos_config::config::{StorageConfig, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_crypto::HashValue;
 use aptos_db::AptosDB;
 use aptos_storage_interface::{
     state_store::state_view::db_state_view::{DbStateView, DbStateViewAtVersion},
     DbReader,
 };
-use aptos_types::state_store::state_key::StateKey;
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::TransactionInfo,
+};
 use either::Either;
-use std::{ops::Deref, path::PathBuf, sync::Arc};
+use std::{
+    ops::{Deref, Range},
+    path::PathBuf,
+    sync::Arc,
+};
 
+/// A database, cheap to clone as clones share it.
+#[derive(Clone)]
 pub struct Storage(Arc<dyn DbReader>);
 
 impl Storage {
@@ -57,15 +68,111 @@ impl Storage {
         Ok(state_view)
     }
 
-    /// Gets the all [StateKey]s in the global storage dating back to an original version. None is treated as 0 or all versions.
-    pub fn global_state_keys_from_version(&self, version: Option<u64>) -> GlobalStateKeyIterable {
+    /// Gets the first version that still has a transaction, which is 0 unless the ledger was pruned.
+    pub fn first_transaction_version(&self) -> Result<u64, anyhow::Error> {
+        let version = self
+            .db_reader()
+            .get_first_txn_version()
+            .context("failed to get first transaction version")?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Gets the [TransactionInfo]s of `limit` versions starting at `start_version`.
+    pub fn transaction_infos(
+        &self,
+        start_version: u64,
+        limit: u64,
+    ) -> Result<Vec<TransactionInfo>, anyhow::Error> {
+        let transaction_infos = self
+            .db_reader()
+            .get_transaction_info_iterator(start_version, limit)
+            .with_context(|| format!("failed to get transaction infos at {}", start_version))?
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("failed to read transaction infos at {}", start_version))?;
+
+        Ok(transaction_infos)
+    }
+
+    /// Gets the events of `limit` versions starting at `start_version`, one list per version.
+    pub fn events(
+        &self,
+        start_version: u64,
+        limit: u64,
+    ) -> Result<Vec<Vec<ContractEvent>>, anyhow::Error> {
+        let events = self
+            .db_reader()
+            .get_events_iterator(start_version, limit)
+            .with_context(|| format!("failed to get events at {}", start_version))?
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("failed to read events at {}", start_version))?;
+
+        Ok(events)
+    }
+
+    /// Gets the root hash of the transaction accumulator at a version.
+    pub fn accumulator_root_hash(&self, version: u64) -> Result<HashValue, anyhow::Error> {
+        self.db_reader()
+            .get_accumulator_root_hash(version)
+            .with_context(|| format!("failed to get accumulator root hash at {}", version))
+    }
+
+    /// Gets the first version that still has a write set, which is 0 unless the ledger was pruned.
+    pub fn first_write_set_version(&self) -> Result<u64, anyhow::Error> {
+        let version = self
+            .db_reader()
+            .get_first_write_set_version()
+            .context("failed to get first write set version")?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Gets the latest version for which a complete state snapshot exists.
+    pub fn latest_state_checkpoint_version(&self) -> Result<u64, anyhow::Error> {
+        self.db_reader()
+            .get_latest_state_checkpoint_version()
+            .context("failed to get latest state checkpoint version")?
+            .ok_or_else(|| anyhow::anyhow!("the db has no state checkpoint"))
+    }
+
+    /// Visits every state value of the state snapshot at a state checkpoint version.
+    pub fn for_each_state_value(
+        &self,
+        version: u64,
+        mut f: impl FnMut(StateKey, StateValue) -> Result<(), anyhow::Error>,
+    ) -> Result<(), anyhow::Error> {
+        let db_reader = self.db_reader();
+        let count = db_reader
+            .get_state_item_count(version)
+            .context("failed to get state item count")?;
+
+        let mut index = 0;
+        while index < count {
+            let chunk = db_reader
+                .get_state_value_chunk_with_proof(version, index, STATE_VALUE_CHUNK_SIZE)
+                .with_context(|| format!("failed to get state values from index {}", index))?;
+            if chunk.raw_values.is_empty() {
+                break;
+            }
+            index += chunk.raw_values.len();
+            for (key, value) in chunk.raw_values {
+                f(key, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the all [StateKey]s written to the global storage in a range of versions.
+    pub fn global_state_keys_in_range(&self, versions: Range<u64>) -> GlobalStateKeyIterable {
         GlobalStateKeyIterable {
             db_reader: self.db_reader(),
-            version: version.unwrap_or(0),
+            versions,
         }
     }
 }
 
+#[derive(Clone)]
 pub struct MovementStorage(Storage);
 
 impl MovementStorage {
@@ -82,6 +189,7 @@ impl Deref for MovementStorage {
         &self.0
     }
 }
+#[derive(Clone)]
 pub struct MovementAptosStorage(Storage);
 
 impl MovementAptosStorage {
@@ -99,37 +207,50 @@ impl Deref for MovementAptosStorage {
     }
 }
 
-/// An iterable of [StateKey]s in the global storage dating back to an original version.
+/// An iterable of [StateKey]s written to the global storage in a range of versions.
 ///
 /// This helps deal with lifetime issues.
 pub struct GlobalStateKeyIterable {
     db_reader: Arc<dyn DbReader>,
-    version: u64,
+    versions: Range<u64>,
 }
 
-const MAX_WRITE_SET_SIZE: u64 = 20_000;
+/// The maximum number of write sets that can be requested from the db at once.
+pub const MAX_WRITE_SET_SIZE: u64 = 20_000;
+
+/// The number of state values requested from the db at once.
+const STATE_VALUE_CHUNK_SIZE: usize = 10_000;
 
 impl GlobalStateKeyIterable {
     pub fn iter(
         &self,
     ) -> Result<Box<dyn Iterator<Item = Result<StateKey, anyhow::Error>> + '_>, anyhow::Error> {
-        let write_set_iterator = self
-            .db_reader
-            .get_write_set_iterator(self.version, MAX_WRITE_SET_SIZE)?;
-
-        // We want to iterate lazily over the write set iterator because there could be a lot of them.
-        let iter = write_set_iterator.flat_map(move |res| match res {
-            Ok(write_set) => {
-                // It should be okay to collect because there should not be that many state keys in a write set.
-                let items: Vec<_> = write_set
-                    .expect_v0()
-                    .iter()
-                    .map(|(key, _)| Ok(key.clone()))
-                    .collect();
-                Either::Left(items.into_iter())
-            },
-            Err(e) => Either::Right(std::iter::once(Err(e.into()))),
-        });
+        let end = self.versions.end;
+
+        // The db limits how many write sets can be requested at once, so request them in chunks.
+        let iter = (self.versions.start..end)
+            .step_by(MAX_WRITE_SET_SIZE as usize)
+            .flat_map(move |chunk_start| {
+                let limit = MAX_WRITE_SET_SIZE.min(end - chunk_start);
+                match self.db_reader.get_write_set_iterator(chunk_start, limit) {
+                    // We want to iterate lazily over the write set iterator because there could be a lot of them.
+                    Ok(write_set_iterator) => {
+                        Either::Left(write_set_iterator.flat_map(|res| match res {
+                            Ok(write_set) => {
+                                // It should be okay to collect because there should not be that many state keys in a write set.
+                                let items: Vec<_> = write_set
+                                    .expect_v0()
+                                    .iter()
+                                    .map(|(key, _)| Ok(key.clone()))
+                                    .collect();
+                                Either::Left(items.into_iter())
+                            },
+                            Err(e) => Either::Right(std::iter::once(Err(anyhow::Error::from(e)))),
+                        }))
+                    },
+                    Err(e) => Either::Right(std::iter::once(Err(anyhow::Error::from(e)))),
+                }
+            });
 
         Ok(Box::new(iter))
     }
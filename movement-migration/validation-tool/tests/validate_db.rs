@@ -34,6 +34,9 @@ async fn validate_node_aptos_db() -> Result<(), anyhow::Error> {
     let cmd = validation_tool::checks::node::Command {
         movement_db,
         movement_aptos_db,
+        shards: None,
+        checkpoint: None,
+        diff_report: None,
     };
     let node = validation_tool::ValidationTool::Node(cmd);
 
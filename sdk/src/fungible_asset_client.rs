@@ -0,0 +1,150 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bcs,
+    move_types::{
+        ident_str,
+        language_storage::{ModuleId, StructTag, TypeTag},
+    },
+    rest_client::{Client as ApiClient, PendingTransaction},
+    transaction_sender::{TransactionOptions, TransactionSender},
+    types::{
+        account_address::AccountAddress,
+        transaction::{EntryFunction, TransactionPayload},
+        LocalAccount,
+    },
+    view::view_single,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Client for fungible assets held in primary stores
+///
+/// Assets are identified by the address of their `0x1::fungible_asset::Metadata` object.
+#[derive(Clone, Debug)]
+pub struct FungibleAssetClient<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> FungibleAssetClient<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Transfers an amount of the asset, in its smallest unit, between primary stores
+    pub async fn transfer(
+        &self,
+        from_account: &LocalAccount,
+        metadata: AccountAddress,
+        to_account: AccountAddress,
+        amount: u64,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                from_account,
+                Self::transfer_payload(metadata, to_account, amount),
+                options,
+            )
+            .await
+    }
+
+    pub fn transfer_payload(
+        metadata: AccountAddress,
+        to_account: AccountAddress,
+        amount: u64,
+    ) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                AccountAddress::ONE,
+                ident_str!("primary_fungible_store").to_owned(),
+            ),
+            ident_str!("transfer").to_owned(),
+            vec![metadata_type()],
+            vec![
+                bcs::to_bytes(&metadata).unwrap(),
+                bcs::to_bytes(&to_account).unwrap(),
+                bcs::to_bytes(&amount).unwrap(),
+            ],
+        ))
+    }
+
+    /// The balance of the asset in the primary store of an account
+    pub async fn get_balance(
+        &self,
+        account: AccountAddress,
+        metadata: AccountAddress,
+    ) -> Result<u64> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "primary_fungible_store",
+            "balance",
+            vec![metadata_type()],
+            vec![account.to_vec(), metadata.to_vec()],
+        )
+        .await
+    }
+
+    pub async fn is_frozen(
+        &self,
+        account: AccountAddress,
+        metadata: AccountAddress,
+    ) -> Result<bool> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "primary_fungible_store",
+            "is_frozen",
+            vec![metadata_type()],
+            vec![account.to_vec(), metadata.to_vec()],
+        )
+        .await
+    }
+
+    pub async fn get_metadata(&self, metadata: AccountAddress) -> Result<FungibleAssetMetadata> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "fungible_asset",
+            "metadata",
+            vec![metadata_type()],
+            vec![metadata.to_vec()],
+        )
+        .await
+    }
+
+    /// The current supply of the asset, if it is tracked
+    pub async fn get_supply(&self, metadata: AccountAddress) -> Result<Option<u128>> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "fungible_asset",
+            "supply",
+            vec![metadata_type()],
+            vec![metadata.to_vec()],
+        )
+        .await
+    }
+}
+
+/// The `0x1::fungible_asset::Metadata` resource of an asset
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FungibleAssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon_uri: String,
+    pub project_uri: String,
+}
+
+/// The type tag of `0x1::fungible_asset::Metadata`
+fn metadata_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("fungible_asset").to_owned(),
+        name: ident_str!("Metadata").to_owned(),
+        type_args: vec![],
+    }))
+}
@@ -8,6 +8,7 @@
 //!
 //! This SDK provides all the necessary components for building on top of the Aptos Blockchain. Some of the important modules are:
 //!
+//! * `coin_client`, `fungible_asset_client`, `object_client`, `token_client` and `multisig_client` - High-level clients for common transactions and queries
 //! * `crypto` - Types used for signing and verifying
 //! * `move_types` - Includes types used when interacting with the Move VM
 //! * `rest_client` - The Aptos API Client, used for sending requests to the Aptos Blockchain.
@@ -24,6 +25,14 @@ pub use bcs;
 
 pub mod coin_client;
 
+pub mod fungible_asset_client;
+
+pub mod multisig_client;
+
+pub mod object_client;
+
+pub mod token_client;
+
 pub mod crypto {
     pub use aptos_crypto::*;
 }
@@ -38,4 +47,8 @@ pub mod rest_client {
 
 pub mod transaction_builder;
 
+pub mod transaction_sender;
+
 pub mod types;
+
+mod view;
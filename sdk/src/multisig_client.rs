@@ -0,0 +1,194 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bcs,
+    rest_client::{Client as ApiClient, PendingTransaction},
+    transaction_builder::aptos_stdlib,
+    transaction_sender::{TransactionOptions, TransactionSender},
+    types::{
+        account_address::AccountAddress,
+        transaction::{EntryFunction, Multisig, MultisigTransactionPayload, TransactionPayload},
+        LocalAccount,
+    },
+    view::view_single,
+};
+use anyhow::Result;
+
+/// Client for `0x1::multisig_account` (multisig v2) accounts
+///
+/// A transaction goes through being proposed by an owner, approved by enough owners, and
+/// executed by any owner. Transactions are identified by their sequence number in the multisig
+/// account, starting at 1.
+#[derive(Clone, Debug)]
+pub struct MultisigClient<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> MultisigClient<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Creates a multisig account owned by the creator and the additional owners
+    ///
+    /// The address of the new account is given by [Self::get_next_multisig_account_address]
+    /// before the transaction is committed.
+    pub async fn create_account(
+        &self,
+        creator: &LocalAccount,
+        additional_owners: Vec<AccountAddress>,
+        signatures_required: u64,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                creator,
+                aptos_stdlib::multisig_account_create_with_owners(
+                    additional_owners,
+                    signatures_required,
+                    vec![],
+                    vec![],
+                ),
+                options,
+            )
+            .await
+    }
+
+    /// Proposes a transaction, which counts as an approval by the proposing owner
+    pub async fn propose(
+        &self,
+        owner: &LocalAccount,
+        multisig_account: AccountAddress,
+        entry_function: EntryFunction,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let payload = bcs::to_bytes(&MultisigTransactionPayload::EntryFunction(entry_function))?;
+        TransactionSender::new(self.api_client)
+            .submit(
+                owner,
+                aptos_stdlib::multisig_account_create_transaction(multisig_account, payload),
+                options,
+            )
+            .await
+    }
+
+    pub async fn approve(
+        &self,
+        owner: &LocalAccount,
+        multisig_account: AccountAddress,
+        sequence_number: u64,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                owner,
+                aptos_stdlib::multisig_account_approve_transaction(
+                    multisig_account,
+                    sequence_number,
+                ),
+                options,
+            )
+            .await
+    }
+
+    pub async fn reject(
+        &self,
+        owner: &LocalAccount,
+        multisig_account: AccountAddress,
+        sequence_number: u64,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                owner,
+                aptos_stdlib::multisig_account_reject_transaction(
+                    multisig_account,
+                    sequence_number,
+                ),
+                options,
+            )
+            .await
+    }
+
+    /// Executes the next transaction of the multisig account, once it has enough approvals
+    ///
+    /// The entry function has to match the proposed one.
+    pub async fn execute(
+        &self,
+        owner: &LocalAccount,
+        multisig_account: AccountAddress,
+        entry_function: EntryFunction,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let payload = TransactionPayload::Multisig(Multisig {
+            multisig_address: multisig_account,
+            transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry_function)),
+        });
+        TransactionSender::new(self.api_client)
+            .submit(owner, payload, options)
+            .await
+    }
+
+    /// Removes the next transaction of the multisig account, once it has enough rejections
+    pub async fn execute_rejected(
+        &self,
+        owner: &LocalAccount,
+        multisig_account: AccountAddress,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                owner,
+                aptos_stdlib::multisig_account_execute_rejected_transaction(multisig_account),
+                options,
+            )
+            .await
+    }
+
+    /// The address of the next multisig account created by the creator
+    pub async fn get_next_multisig_account_address(
+        &self,
+        creator: AccountAddress,
+    ) -> Result<AccountAddress> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "multisig_account",
+            "get_next_multisig_account_address",
+            vec![],
+            vec![creator.to_vec()],
+        )
+        .await
+    }
+
+    /// The sequence number the next proposed transaction will get
+    pub async fn get_next_sequence_number(&self, multisig_account: AccountAddress) -> Result<u64> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "multisig_account",
+            "next_sequence_number",
+            vec![],
+            vec![multisig_account.to_vec()],
+        )
+        .await
+    }
+
+    /// Whether a transaction is next in line and has enough approvals
+    pub async fn can_be_executed(
+        &self,
+        multisig_account: AccountAddress,
+        sequence_number: u64,
+    ) -> Result<bool> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "multisig_account",
+            "can_be_executed",
+            vec![],
+            vec![multisig_account.to_vec(), bcs::to_bytes(&sequence_number)?],
+        )
+        .await
+    }
+}
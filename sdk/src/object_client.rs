@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    move_types::{
+        ident_str,
+        language_storage::{StructTag, TypeTag},
+    },
+    rest_client::{Client as ApiClient, PendingTransaction},
+    transaction_builder::aptos_stdlib,
+    transaction_sender::{TransactionOptions, TransactionSender},
+    types::{account_address::AccountAddress, LocalAccount},
+    view::view_single,
+};
+use anyhow::Result;
+
+/// Client for `0x1::object` objects, such as tokens and fungible asset metadata
+#[derive(Clone, Debug)]
+pub struct ObjectClient<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> ObjectClient<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Transfers an object owned directly by the sender
+    pub async fn transfer(
+        &self,
+        owner: &LocalAccount,
+        object: AccountAddress,
+        to_account: AccountAddress,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        TransactionSender::new(self.api_client)
+            .submit(
+                owner,
+                aptos_stdlib::object_transfer_call(object, to_account),
+                options,
+            )
+            .await
+    }
+
+    /// The direct owner of an object, which may itself be an object
+    pub async fn get_owner(&self, object: AccountAddress) -> Result<AccountAddress> {
+        view_single(
+            self.api_client,
+            AccountAddress::ONE,
+            "object",
+            "owner",
+            vec![object_core_type()],
+            vec![object.to_vec()],
+        )
+        .await
+    }
+}
+
+/// The type tag of `0x1::object::ObjectCore`
+fn object_core_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("object").to_owned(),
+        name: ident_str!("ObjectCore").to_owned(),
+        type_args: vec![],
+    }))
+}
@@ -0,0 +1,148 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    move_types::{
+        ident_str,
+        language_storage::{StructTag, TypeTag},
+    },
+    object_client::ObjectClient,
+    rest_client::{Client as ApiClient, PendingTransaction},
+    transaction_sender::{TransactionOptions, TransactionSender},
+    types::{account_address::AccountAddress, LocalAccount},
+    view::view_single,
+};
+use anyhow::Result;
+use aptos_cached_packages::aptos_token_objects_sdk_builder;
+use serde::de::DeserializeOwned;
+
+/// Address of the `aptos_token_objects` package
+const TOKEN_OBJECTS_ADDRESS: AccountAddress = AccountAddress::FOUR;
+
+/// Client for Token v2 NFTs, created through the `0x4::aptos_token` module
+#[derive(Clone, Debug)]
+pub struct TokenClient<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> TokenClient<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Creates a collection owned by the creator, with immutable collection and token data
+    pub async fn create_collection(
+        &self,
+        creator: &LocalAccount,
+        name: &str,
+        description: &str,
+        uri: &str,
+        max_supply: u64,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let payload = aptos_token_objects_sdk_builder::aptos_token_create_collection(
+            description.as_bytes().to_vec(),
+            max_supply,
+            name.as_bytes().to_vec(),
+            uri.as_bytes().to_vec(),
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+            1,
+        );
+        TransactionSender::new(self.api_client)
+            .submit(creator, payload, options)
+            .await
+    }
+
+    /// Mints a token into a collection of the creator, who receives the token
+    pub async fn mint(
+        &self,
+        creator: &LocalAccount,
+        collection: &str,
+        name: &str,
+        description: &str,
+        uri: &str,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let payload = aptos_token_objects_sdk_builder::aptos_token_mint(
+            collection.as_bytes().to_vec(),
+            description.as_bytes().to_vec(),
+            name.as_bytes().to_vec(),
+            uri.as_bytes().to_vec(),
+            vec![],
+            vec![],
+            vec![],
+        );
+        TransactionSender::new(self.api_client)
+            .submit(creator, payload, options)
+            .await
+    }
+
+    /// Transfers a token owned directly by the sender
+    pub async fn transfer(
+        &self,
+        owner: &LocalAccount,
+        token: AccountAddress,
+        to_account: AccountAddress,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        ObjectClient::new(self.api_client)
+            .transfer(owner, token, to_account, options)
+            .await
+    }
+
+    pub async fn get_token_data(&self, token: AccountAddress) -> Result<TokenData> {
+        Ok(TokenData {
+            owner: ObjectClient::new(self.api_client).get_owner(token).await?,
+            creator: self.view_token(token, "creator").await?,
+            collection: self.view_token(token, "collection_name").await?,
+            name: self.view_token(token, "name").await?,
+            description: self.view_token(token, "description").await?,
+            uri: self.view_token(token, "uri").await?,
+        })
+    }
+
+    async fn view_token<T: DeserializeOwned>(
+        &self,
+        token: AccountAddress,
+        function: &str,
+    ) -> Result<T> {
+        view_single(
+            self.api_client,
+            TOKEN_OBJECTS_ADDRESS,
+            "token",
+            function,
+            vec![token_type()],
+            vec![token.to_vec()],
+        )
+        .await
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenData {
+    pub owner: AccountAddress,
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub name: String,
+    pub description: String,
+    pub uri: String,
+}
+
+/// The type tag of `0x4::token::Token`
+fn token_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: TOKEN_OBJECTS_ADDRESS,
+        module: ident_str!("token").to_owned(),
+        name: ident_str!("Token").to_owned(),
+        type_args: vec![],
+    }))
+}
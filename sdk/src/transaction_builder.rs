@@ -17,6 +17,7 @@ use aptos_types::{
     transaction::{EntryFunction, Script},
 };
 
+#[derive(Clone)]
 pub struct TransactionBuilder {
     sender: Option<AccountAddress>,
     sequence_number: Option<u64>,
@@ -0,0 +1,507 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    rest_client::{aptos_api_types::UserTransaction, Client as ApiClient, PendingTransaction},
+    transaction_builder::TransactionBuilder,
+    types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{authenticator::AccountAuthenticator, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use anyhow::{bail, Context, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Builds, signs and submits transactions for the high-level clients
+///
+/// Single sender, multi-agent and fee-payer transactions are supported, and each of them can be
+/// simulated before being signed, see [TransactionOptions::simulate_first].
+#[derive(Clone, Debug)]
+pub struct TransactionSender<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> TransactionSender<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Submits a transaction signed by the sender alone
+    pub async fn submit(
+        &self,
+        sender: &LocalAccount,
+        payload: TransactionPayload,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let options = options.unwrap_or_default();
+        let builder = self.transaction_builder(payload, &options).await?;
+        if options.simulate_first {
+            self.check_simulation(sender, &[], None, builder.clone())
+                .await?;
+        }
+        let signed_txn = sender.sign_with_transaction_builder(builder);
+        self.submit_signed(&signed_txn).await
+    }
+
+    /// Submits a transaction also signed by secondary signers, e.g. to receive an object
+    pub async fn submit_multi_agent(
+        &self,
+        sender: &LocalAccount,
+        secondary_signers: Vec<&LocalAccount>,
+        payload: TransactionPayload,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let options = options.unwrap_or_default();
+        let builder = self.transaction_builder(payload, &options).await?;
+        if options.simulate_first {
+            self.check_simulation(sender, &secondary_signers, None, builder.clone())
+                .await?;
+        }
+        let signed_txn =
+            sender.sign_multi_agent_with_transaction_builder(secondary_signers, builder);
+        self.submit_signed(&signed_txn).await
+    }
+
+    /// Submits a transaction whose gas is paid by another account
+    pub async fn submit_fee_payer(
+        &self,
+        sender: &LocalAccount,
+        secondary_signers: Vec<&LocalAccount>,
+        fee_payer: &LocalAccount,
+        payload: TransactionPayload,
+        options: Option<TransactionOptions>,
+    ) -> Result<PendingTransaction> {
+        let options = options.unwrap_or_default();
+        let builder = self.transaction_builder(payload, &options).await?;
+        if options.simulate_first {
+            self.check_simulation(
+                sender,
+                &secondary_signers,
+                Some(fee_payer.address()),
+                builder.clone(),
+            )
+            .await?;
+        }
+        let signed_txn =
+            sender.sign_fee_payer_with_transaction_builder(secondary_signers, fee_payer, builder);
+        self.submit_signed(&signed_txn).await
+    }
+
+    /// Simulates a transaction without signing it or using up a sequence number
+    ///
+    /// A fee payer, if any, only needs to be known by address.
+    pub async fn simulate(
+        &self,
+        sender: &LocalAccount,
+        secondary_signers: &[&LocalAccount],
+        fee_payer: Option<AccountAddress>,
+        payload: TransactionPayload,
+        options: Option<TransactionOptions>,
+    ) -> Result<UserTransaction> {
+        let options = options.unwrap_or_default();
+        let builder = self.transaction_builder(payload, &options).await?;
+        self.simulate_builder(sender, secondary_signers, fee_payer, builder)
+            .await
+    }
+
+    async fn transaction_builder(
+        &self,
+        payload: TransactionPayload,
+        options: &TransactionOptions,
+    ) -> Result<TransactionBuilder> {
+        let chain_id = self
+            .api_client
+            .get_index()
+            .await
+            .context("Failed to get chain ID")?
+            .inner()
+            .chain_id;
+        Ok(TransactionBuilder::new(
+            payload,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + options.timeout_secs,
+            ChainId::new(chain_id),
+        )
+        .max_gas_amount(options.max_gas_amount)
+        .gas_unit_price(options.gas_unit_price))
+    }
+
+    async fn simulate_builder(
+        &self,
+        sender: &LocalAccount,
+        secondary_signers: &[&LocalAccount],
+        fee_payer: Option<AccountAddress>,
+        builder: TransactionBuilder,
+    ) -> Result<UserTransaction> {
+        let txn = Self::simulation_transaction(sender, secondary_signers, fee_payer, builder);
+        let mut simulated = self
+            .api_client
+            .simulate(&txn)
+            .await
+            .context("Failed to simulate transaction")?
+            .into_inner();
+        simulated
+            .pop()
+            .context("Simulation returned no transaction")
+    }
+
+    /// The transaction to simulate, laid out like the signed one would be
+    fn simulation_transaction(
+        sender: &LocalAccount,
+        secondary_signers: &[&LocalAccount],
+        fee_payer: Option<AccountAddress>,
+        builder: TransactionBuilder,
+    ) -> SignedTransaction {
+        // Simulation rejects valid signatures, so every signer is left without one
+        let sequence_number = if builder.has_nonce() {
+            u64::MAX
+        } else {
+            sender.sequence_number()
+        };
+        let raw_txn = builder
+            .sender(sender.address())
+            .sequence_number(sequence_number)
+            .build();
+        let secondary_signer_addresses: Vec<_> = secondary_signers
+            .iter()
+            .map(|signer| signer.address())
+            .collect();
+        let secondary_signer_authenticators =
+            vec![AccountAuthenticator::NoAccountAuthenticator; secondary_signers.len()];
+        match fee_payer {
+            Some(fee_payer) => SignedTransaction::new_fee_payer(
+                raw_txn,
+                AccountAuthenticator::NoAccountAuthenticator,
+                secondary_signer_addresses,
+                secondary_signer_authenticators,
+                fee_payer,
+                AccountAuthenticator::NoAccountAuthenticator,
+            ),
+            None if secondary_signers.is_empty() => SignedTransaction::new_single_sender(
+                raw_txn,
+                AccountAuthenticator::NoAccountAuthenticator,
+            ),
+            None => SignedTransaction::new_multi_agent(
+                raw_txn,
+                AccountAuthenticator::NoAccountAuthenticator,
+                secondary_signer_addresses,
+                secondary_signer_authenticators,
+            ),
+        }
+    }
+
+    async fn check_simulation(
+        &self,
+        sender: &LocalAccount,
+        secondary_signers: &[&LocalAccount],
+        fee_payer: Option<AccountAddress>,
+        builder: TransactionBuilder,
+    ) -> Result<()> {
+        let simulated = self
+            .simulate_builder(sender, secondary_signers, fee_payer, builder)
+            .await?;
+        if !simulated.info.success {
+            bail!(
+                "Transaction simulation failed: {}",
+                simulated.info.vm_status
+            );
+        }
+        Ok(())
+    }
+
+    async fn submit_signed(&self, signed_txn: &SignedTransaction) -> Result<PendingTransaction> {
+        Ok(self
+            .api_client
+            .submit(signed_txn)
+            .await
+            .context("Failed to submit transaction")?
+            .into_inner())
+    }
+}
+
+pub struct TransactionOptions {
+    pub max_gas_amount: u64,
+
+    pub gas_unit_price: u64,
+
+    /// This is the number of seconds from now you're willing to wait for the
+    /// transaction to be committed.
+    pub timeout_secs: u64,
+
+    /// Simulate the transaction before signing it, and fail without submitting it if the
+    /// simulation fails.
+    pub simulate_first: bool,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        Self {
+            max_gas_amount: 20_000,
+            gas_unit_price: 100,
+            timeout_secs: 30,
+            simulate_first: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rest_client::aptos_api_types::{
+            self as api, EntryFunctionId, HashValue, TransactionInfo, UserTransactionRequest,
+            X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_OLDEST_VERSION,
+            X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION, X_APTOS_OLDEST_BLOCK_HEIGHT,
+        },
+        transaction_builder::aptos_stdlib,
+        types::transaction::authenticator::TransactionAuthenticator,
+    };
+    use rand::rngs::OsRng;
+    use std::{
+        net::{Ipv4Addr, SocketAddr},
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+    use url::Url;
+    use warp::{
+        http::{Method, Response, StatusCode},
+        hyper::{body::Bytes, Body},
+        path::FullPath,
+        Filter,
+    };
+
+    const CHAIN_ID: u8 = 4;
+    const VM_STATUS: &str = "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): ";
+
+    fn transfer(to: AccountAddress) -> TransactionBuilder {
+        TransactionBuilder::new(
+            aptos_stdlib::aptos_account_transfer(to, 100),
+            u64::MAX,
+            ChainId::new(CHAIN_ID),
+        )
+    }
+
+    /// What a node returns for a simulation running out of funds
+    fn failed_simulation(txn: &SignedTransaction) -> UserTransaction {
+        let hash = HashValue::from(txn.committed_hash());
+        UserTransaction {
+            info: TransactionInfo {
+                version: 1.into(),
+                hash,
+                state_change_hash: hash,
+                event_root_hash: hash,
+                state_checkpoint_hash: None,
+                gas_used: 0.into(),
+                success: false,
+                vm_status: VM_STATUS.to_string(),
+                accumulator_root_hash: hash,
+                changes: vec![],
+                block_height: None,
+                epoch: None,
+            },
+            request: UserTransactionRequest {
+                sender: txn.sender().into(),
+                sequence_number: txn.sequence_number().into(),
+                max_gas_amount: txn.max_gas_amount().into(),
+                gas_unit_price: txn.gas_unit_price().into(),
+                expiration_timestamp_secs: txn.expiration_timestamp_secs().into(),
+                payload: api::TransactionPayload::EntryFunctionPayload(api::EntryFunctionPayload {
+                    function: EntryFunctionId::from_str("0x1::aptos_account::transfer").unwrap(),
+                    type_arguments: vec![],
+                    arguments: vec![],
+                }),
+                signature: None,
+                replay_protection_nonce: None,
+            },
+            events: vec![],
+            timestamp: 1.into(),
+        }
+    }
+
+    /// Serves the index and failing simulations, and records the requests it got
+    fn start_fake_node() -> (Url, Arc<Mutex<Vec<(String, Bytes)>>>) {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let route = warp::method()
+            .and(warp::path::full())
+            .and(warp::body::bytes())
+            .map({
+                let requests = requests.clone();
+                move |method: Method, path: FullPath, body: Bytes| -> Response<Body> {
+                    requests
+                        .lock()
+                        .unwrap()
+                        .push((path.as_str().to_string(), body.clone()));
+                    let json = match (method, path.as_str()) {
+                        (Method::GET, "/v1/") => serde_json::json!({
+                            "chain_id": CHAIN_ID,
+                            "epoch": "1",
+                            "ledger_version": "1",
+                            "oldest_ledger_version": "0",
+                            "ledger_timestamp": "1",
+                            "node_role": "full_node",
+                            "oldest_block_height": "0",
+                            "block_height": "1",
+                        }),
+                        (Method::POST, "/v1/transactions/simulate") => {
+                            let txn = bcs::from_bytes(&body).unwrap();
+                            serde_json::to_value(vec![failed_simulation(&txn)]).unwrap()
+                        },
+                        _ => {
+                            return Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::empty())
+                                .unwrap()
+                        },
+                    };
+                    let mut response = Response::builder().status(StatusCode::OK);
+                    for (header, value) in [
+                        (X_APTOS_CHAIN_ID, CHAIN_ID as u64),
+                        (X_APTOS_EPOCH, 1),
+                        (X_APTOS_LEDGER_VERSION, 1),
+                        (X_APTOS_LEDGER_OLDEST_VERSION, 0),
+                        (X_APTOS_LEDGER_TIMESTAMP, 1),
+                        (X_APTOS_BLOCK_HEIGHT, 1),
+                        (X_APTOS_OLDEST_BLOCK_HEIGHT, 0),
+                    ] {
+                        response = response.header(header, value.to_string());
+                    }
+                    response.body(Body::from(json.to_string())).unwrap()
+                }
+            });
+        let (addr, server) =
+            warp::serve(route).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+        tokio::spawn(server);
+        (
+            Url::parse(&format!("http://{}/v1", addr)).unwrap(),
+            requests,
+        )
+    }
+
+    #[test]
+    fn test_simulation_transaction_layout() {
+        let sender = LocalAccount::generate(&mut OsRng);
+        sender.set_sequence_number(7);
+        let alice = LocalAccount::generate(&mut OsRng);
+        let bob = LocalAccount::generate(&mut OsRng);
+        let fee_payer = LocalAccount::generate(&mut OsRng).address();
+        let no_signature = AccountAuthenticator::NoAccountAuthenticator;
+
+        let txn = TransactionSender::simulation_transaction(
+            &sender,
+            &[],
+            None,
+            transfer(alice.address()),
+        );
+        assert_eq!(txn.sender(), sender.address());
+        assert_eq!(txn.sequence_number(), 7);
+        assert_eq!(
+            txn.authenticator(),
+            TransactionAuthenticator::SingleSender {
+                sender: no_signature.clone(),
+            }
+        );
+
+        let txn = TransactionSender::simulation_transaction(
+            &sender,
+            &[&alice, &bob],
+            None,
+            transfer(alice.address()),
+        );
+        assert_eq!(txn.authenticator(), TransactionAuthenticator::MultiAgent {
+            sender: no_signature.clone(),
+            secondary_signer_addresses: vec![alice.address(), bob.address()],
+            secondary_signers: vec![no_signature.clone(); 2],
+        });
+
+        // A fee payer is only known by address, with or without secondary signers
+        let txn = TransactionSender::simulation_transaction(
+            &sender,
+            &[],
+            Some(fee_payer),
+            transfer(alice.address()),
+        );
+        assert_eq!(txn.authenticator(), TransactionAuthenticator::FeePayer {
+            sender: no_signature.clone(),
+            secondary_signer_addresses: vec![],
+            secondary_signers: vec![],
+            fee_payer_address: fee_payer,
+            fee_payer_signer: no_signature.clone(),
+        });
+
+        let txn = TransactionSender::simulation_transaction(
+            &sender,
+            &[&alice],
+            Some(fee_payer),
+            transfer(alice.address()),
+        );
+        assert_eq!(txn.authenticator(), TransactionAuthenticator::FeePayer {
+            sender: no_signature.clone(),
+            secondary_signer_addresses: vec![alice.address()],
+            secondary_signers: vec![no_signature.clone()],
+            fee_payer_address: fee_payer,
+            fee_payer_signer: no_signature,
+        });
+    }
+
+    #[tokio::test]
+    async fn test_failed_simulation_is_not_submitted() {
+        let (url, requests) = start_fake_node();
+        let api_client = ApiClient::new(url);
+        let transaction_sender = TransactionSender::new(&api_client);
+        let sender = LocalAccount::generate(&mut OsRng);
+        let receiver = LocalAccount::generate(&mut OsRng);
+        let fee_payer = LocalAccount::generate(&mut OsRng);
+        let options = || {
+            Some(TransactionOptions {
+                simulate_first: true,
+                ..Default::default()
+            })
+        };
+
+        let err = transaction_sender
+            .submit(
+                &sender,
+                aptos_stdlib::aptos_account_transfer(receiver.address(), 100),
+                options(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(VM_STATUS));
+
+        let err = transaction_sender
+            .submit_fee_payer(
+                &sender,
+                vec![&receiver],
+                &fee_payer,
+                aptos_stdlib::aptos_account_transfer(receiver.address(), 100),
+                options(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(VM_STATUS));
+
+        // Nothing was signed or submitted
+        assert_eq!(sender.sequence_number(), 0);
+        let requests = requests.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, [
+            "/v1/",
+            "/v1/transactions/simulate",
+            "/v1/",
+            "/v1/transactions/simulate"
+        ]);
+
+        // The fee payer was simulated in place, without a signature
+        let txn: SignedTransaction = bcs::from_bytes(&requests[3].1).unwrap();
+        assert!(matches!(
+            txn.authenticator(),
+            TransactionAuthenticator::FeePayer { fee_payer_address, .. }
+                if fee_payer_address == fee_payer.address()
+        ));
+    }
+}
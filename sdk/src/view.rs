@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    move_types::{
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag},
+    },
+    rest_client::{aptos_api_types::ViewFunction, Client as ApiClient},
+    types::account_address::AccountAddress,
+};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Calls a view function returning a single value, decoding it from BCS
+pub(crate) async fn view_single<T: DeserializeOwned>(
+    api_client: &ApiClient,
+    address: AccountAddress,
+    module: &str,
+    function: &str,
+    ty_args: Vec<TypeTag>,
+    args: Vec<Vec<u8>>,
+) -> Result<T> {
+    let view_function = ViewFunction {
+        module: ModuleId::new(address, Identifier::new(module)?),
+        function: Identifier::new(function)?,
+        ty_args,
+        args,
+    };
+    let values: Vec<T> = api_client
+        .view_bcs(&view_function, None)
+        .await
+        .with_context(|| format!("Failed to view {}::{}::{}", address, module, function))?
+        .into_inner();
+    values
+        .into_iter()
+        .next()
+        .with_context(|| format!("{}::{} returned no value", module, function))
+}
@@ -817,7 +817,7 @@ impl<
                     )
                     .await?
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if self.output_fallback_handler.in_fallback_mode() {
                     metrics::set_gauge(
                         &metrics::DRIVER_FALLBACK_MODE,
@@ -1293,7 +1293,7 @@ impl<
                     ));
                 }
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_list_with_proof) = transaction_list_with_proof {
                     utils::execute_transactions(
                         &mut self.storage_synchronizer,
@@ -1480,7 +1480,7 @@ impl<
                     ));
                 }
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_list_with_proof) = transaction_list_with_proof {
                     transaction_list_with_proof.transactions.len()
                 } else if let Some(output_list_with_proof) = transaction_outputs_with_proof {
@@ -1593,7 +1593,9 @@ impl<
             .await?;
 
         // Fallback to output syncing if we need to
-        if let BootstrappingMode::ExecuteOrApplyFromGenesis = self.get_bootstrapping_mode() {
+        if let BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup =
+            self.get_bootstrapping_mode()
+        {
             self.output_fallback_handler.fallback_to_outputs();
             metrics::set_gauge(
                 &metrics::DRIVER_FALLBACK_MODE,
@@ -16,7 +16,8 @@ use aptos_crypto::{
     HashValue,
 };
 use aptos_db_indexer_schemas::schema::{
-    event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+    event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+    event_by_version::EventByVersionSchema,
 };
 use aptos_schemadb::{
     batch::{SchemaBatch, WriteBatch},
@@ -189,11 +190,15 @@ impl EventDb {
 
     /// Deletes event indices, returns number of events per version, so `prune_events` doesn't need
     /// to iterate through evnets from DB again.
+    ///
+    /// The by type index only exists in the internal indexer db, so it is only pruned when
+    /// `prune_type_index` is set.
     pub(crate) fn prune_event_indices(
         &self,
         start: Version,
         end: Version,
         mut indices_batch: Option<&mut SchemaBatch>,
+        prune_type_index: bool,
     ) -> Result<Vec<usize>> {
         let mut ret = Vec::new();
 
@@ -204,7 +209,14 @@ impl EventDb {
             ret.push(events.len());
 
             if let Some(ref mut batch) = indices_batch {
-                for event in events {
+                for (idx, event) in events.into_iter().enumerate() {
+                    if prune_type_index {
+                        batch.delete::<EventByTypeSchema>(&(
+                            event.type_tag().clone(),
+                            current_version,
+                            idx as u64,
+                        ))?;
+                    }
                     if let ContractEvent::V1(v1) = event {
                         batch.delete::<EventByKeySchema>(&(*v1.key(), v1.sequence_number()))?;
                         batch.delete::<EventByVersionSchema>(&(
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::db::AptosDB;
+use aptos_config::config::RocksdbConfig;
+use aptos_db_indexer::db_ops::open_internal_indexer_db;
+use aptos_db_indexer_schemas::schema::event_by_type::EventByTypeSchema;
 use aptos_schemadb::batch::SchemaBatch;
 use aptos_storage_interface::Result;
 use aptos_temppath::TempPath;
@@ -64,4 +67,44 @@ proptest! {
             vec![events1, events2, events3]
         );
     }
+
+    #[test]
+    fn test_prune_event_indices_by_type(
+        events in vec(vec(any::<ContractEvent>().no_shrink(), 1..10), 4),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let event_db = &db.ledger_db.event_db();
+        let indexer_db =
+            open_internal_indexer_db(tmp_dir.path().join("indexer"), &RocksdbConfig::default())
+                .unwrap();
+
+        let mut batch = SchemaBatch::new();
+        let mut indexer_batch = SchemaBatch::new();
+        for (version, events) in events.iter().enumerate() {
+            event_db.put_events(version as u64, events, /*skip_index=*/false, &mut batch).unwrap();
+            for (idx, event) in events.iter().enumerate() {
+                let key = (event.type_tag().clone(), version as u64, idx as u64);
+                indexer_batch.put::<EventByTypeSchema>(&key, &()).unwrap();
+            }
+        }
+        event_db.write_schemas(batch).unwrap();
+        indexer_db.write_schemas(indexer_batch).unwrap();
+
+        let mut indexer_batch = SchemaBatch::new();
+        event_db
+            .prune_event_indices(0, 2, Some(&mut indexer_batch), /*prune_type_index=*/true)
+            .unwrap();
+        indexer_db.write_schemas(indexer_batch).unwrap();
+
+        for (version, events) in events.iter().enumerate() {
+            for (idx, event) in events.iter().enumerate() {
+                let key = (event.type_tag().clone(), version as u64, idx as u64);
+                prop_assert_eq!(
+                    indexer_db.get::<EventByTypeSchema>(&key).unwrap().is_some(),
+                    version >= 2
+                );
+            }
+        }
+    }
 }
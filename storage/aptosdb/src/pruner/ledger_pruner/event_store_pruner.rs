@@ -44,8 +44,11 @@ impl DBSubPruner for EventStorePruner {
         let mut batch = SchemaBatch::new();
         let mut indexer_batch = None;
 
+        let prune_type_index = self
+            .indexer_db()
+            .is_some_and(|indexer_db| indexer_db.event_by_type_enabled());
         let indices_batch = if let Some(indexer_db) = self.indexer_db() {
-            if indexer_db.event_enabled() {
+            if indexer_db.event_enabled() || indexer_db.event_by_type_enabled() {
                 indexer_batch = Some(SchemaBatch::new());
             }
             indexer_batch.as_mut()
@@ -56,6 +59,7 @@ impl DBSubPruner for EventStorePruner {
             current_progress,
             target_version,
             indices_batch,
+            prune_type_index,
         )?;
         self.ledger_db.event_db().prune_events(
             num_events_per_version,
@@ -536,6 +536,7 @@ fn delete_event_data(
                 // with the existence or placement of indices
                 // TODO: prune data from internal indices
                 None,
+                /*prune_type_index=*/ false,
             )?;
             ledger_db.event_db().prune_events(
                 num_events_per_version,
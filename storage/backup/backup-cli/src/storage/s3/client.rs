@@ -0,0 +1,550 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal client of the S3 REST API, covering what the backup storage needs. Requests are
+//! signed with AWS Signature Version 4, limited in concurrency and retried on transient errors.
+
+use crate::storage::s3::config::S3Config;
+use anyhow::{anyhow, bail, Result};
+use aptos_logger::prelude::*;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, ETAG},
+    Method, StatusCode, Url,
+};
+use sha2_0_10_6::{Digest, Sha256};
+use std::{collections::BTreeMap, env, time::Duration};
+use tokio::sync::Semaphore;
+
+/// Characters not percent-encoded in URIs, as specified for signing. `/` is added back for
+/// object keys.
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+const KEY_ENCODE_SET: &AsciiSet = &URI_ENCODE_SET.remove(b'/');
+
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(200);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Credentials {
+    /// Takes the credentials from the config, or else from the `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN` environment variables, once when the
+    /// client is created.
+    ///
+    /// Instance profiles, web identity tokens and the other providers of the AWS SDKs are not
+    /// supported, and temporary credentials are never refreshed, so they have to stay valid for
+    /// the whole run.
+    fn resolve(config: &S3Config) -> Result<Self> {
+        let from_env = |var: &str| env::var(var).ok().filter(|value| !value.is_empty());
+        Ok(Self {
+            access_key_id: config
+                .access_key_id
+                .clone()
+                .or_else(|| from_env("AWS_ACCESS_KEY_ID"))
+                .ok_or_else(|| anyhow!("S3 access key id is not configured."))?,
+            secret_access_key: config
+                .secret_access_key
+                .clone()
+                .or_else(|| from_env("AWS_SECRET_ACCESS_KEY"))
+                .ok_or_else(|| anyhow!("S3 secret access key is not configured."))?,
+            session_token: config
+                .session_token
+                .clone()
+                .or_else(|| from_env("AWS_SESSION_TOKEN")),
+        })
+    }
+}
+
+pub(super) struct S3Request<'a> {
+    method: Method,
+    key: &'a str,
+    query: Vec<(&'static str, String)>,
+    headers: Vec<(&'static str, String)>,
+    body: Bytes,
+    /// Some operations report failures in the body of a 200 response.
+    error_in_body: bool,
+    /// The first and last byte of the object a ranged GET asks for.
+    range: Option<(u64, u64)>,
+}
+
+impl<'a> S3Request<'a> {
+    pub(super) fn new(method: Method, key: &'a str) -> Self {
+        Self {
+            method,
+            key,
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Bytes::new(),
+            error_in_body: false,
+            range: None,
+        }
+    }
+
+    fn query(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.query.push((name, value.to_string()));
+        self
+    }
+
+    pub(super) fn header(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    fn body(mut self, body: Bytes) -> Self {
+        self.body = body;
+        self
+    }
+
+    fn error_in_body(mut self) -> Self {
+        self.error_in_body = true;
+        self
+    }
+
+    fn range(mut self, start: u64, end: u64) -> Self {
+        self.range = Some((start, end));
+        self.header("range", format!("bytes={start}-{end}"))
+    }
+}
+
+pub(super) struct S3Client {
+    config: S3Config,
+    credentials: Credentials,
+    http: reqwest::Client,
+    request_permits: Semaphore,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config) -> Result<Self> {
+        Ok(Self {
+            credentials: Credentials::resolve(&config)?,
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.request_timeout_secs))
+                .build()?,
+            request_permits: Semaphore::new(config.max_concurrent_requests),
+            config,
+        })
+    }
+
+    pub fn part_size(&self) -> usize {
+        self.config.part_size
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        self.execute(S3Request::new(Method::PUT, key).body(body))
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the bytes in `[start, end]` of the object, which have to exist.
+    pub async fn get_object_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        let (_, body) = self
+            .execute(S3Request::new(Method::GET, key).range(start, end))
+            .await?;
+        Ok(body)
+    }
+
+    pub async fn get_object_size(&self, key: &str) -> Result<u64> {
+        let (headers, _) = self.execute(S3Request::new(Method::HEAD, key)).await?;
+        Ok(headers
+            .get(CONTENT_LENGTH)
+            .ok_or_else(|| anyhow!("S3 HEAD {} returned no content length.", key))?
+            .to_str()?
+            .parse()?)
+    }
+
+    /// Lists the keys of all objects whose key starts with `prefix`.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut request = S3Request::new(Method::GET, "")
+                .query("list-type", 2)
+                .query("prefix", prefix);
+            if let Some(token) = continuation_token.take() {
+                request = request.query("continuation-token", token);
+            }
+            let (_, body) = self.execute(request).await?;
+            let body = String::from_utf8_lossy(&body);
+
+            keys.extend(xml_elements(&body, "Key"));
+            if xml_elements(&body, "IsTruncated")
+                .first()
+                .map(String::as_str)
+                != Some("true")
+            {
+                return Ok(keys);
+            }
+            continuation_token = Some(
+                xml_elements(&body, "NextContinuationToken")
+                    .pop()
+                    .ok_or_else(|| anyhow!("S3 truncated listing without continuation token."))?,
+            );
+        }
+    }
+
+    pub async fn copy_object(&self, source_key: &str, key: &str) -> Result<()> {
+        let copy_source = format!(
+            "/{}/{}",
+            self.config.bucket,
+            utf8_percent_encode(source_key, KEY_ENCODE_SET)
+        );
+        self.execute(
+            S3Request::new(Method::PUT, key)
+                .header("x-amz-copy-source", copy_source)
+                .error_in_body(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.execute(S3Request::new(Method::DELETE, key)).await?;
+        Ok(())
+    }
+
+    /// Starts a multipart upload and returns its upload id.
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let (_, body) = self
+            .execute(S3Request::new(Method::POST, key).query("uploads", ""))
+            .await?;
+        xml_elements(&String::from_utf8_lossy(&body), "UploadId")
+            .pop()
+            .ok_or_else(|| anyhow!("S3 returned no upload id for {}.", key))
+    }
+
+    /// Uploads a part of a multipart upload and returns its ETag.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let (headers, _) = self
+            .execute(
+                S3Request::new(Method::PUT, key)
+                    .query("partNumber", part_number)
+                    .query("uploadId", upload_id)
+                    .body(body),
+            )
+            .await?;
+        Ok(headers
+            .get(ETAG)
+            .ok_or_else(|| anyhow!("S3 returned no ETag for part {} of {}.", part_number, key))?
+            .to_str()?
+            .to_string())
+    }
+
+    /// Completes a multipart upload from the ETags of its parts, in part number order.
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    xml_escape(etag)
+                )
+            })
+            .collect();
+        let body = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+        );
+        self.execute(
+            S3Request::new(Method::POST, key)
+                .query("uploadId", upload_id)
+                .body(body.into())
+                .error_in_body(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.execute(S3Request::new(Method::DELETE, key).query("uploadId", upload_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn execute(&self, request: S3Request<'_>) -> Result<(HeaderMap, Bytes)> {
+        let _permit = self.request_permits.acquire().await?;
+
+        let mut delay = INITIAL_RETRY_DELAY;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let error = match self.try_execute(&request).await {
+                Ok((status, headers, body)) => match response_error(&request, status, &body) {
+                    None => return Ok((headers, body)),
+                    Some(error) if !is_retryable(&request, status) => return Err(error),
+                    Some(error) => error,
+                },
+                Err(error) => error,
+            };
+            if attempts > self.config.max_retries {
+                return Err(error.context(format!("Giving up after {} attempts.", attempts)));
+            }
+            warn!(
+                error = ?error,
+                attempts = attempts,
+                retry_delay = ?delay,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(delay).await;
+            delay = std::cmp::min(delay * 2, MAX_RETRY_DELAY);
+        }
+    }
+
+    async fn try_execute(&self, request: &S3Request<'_>) -> Result<(StatusCode, HeaderMap, Bytes)> {
+        let url = self.url(request)?;
+        let headers = self.sign(request, &url, Utc::now())?;
+        let response = self
+            .http
+            .request(request.method.clone(), url)
+            .headers(headers)
+            .body(request.body.clone())
+            .send()
+            .await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        Ok((status, headers, body))
+    }
+
+    pub(super) fn url(&self, request: &S3Request<'_>) -> Result<Url> {
+        let mut url = Url::parse(&self.config.endpoint())?;
+        let key = utf8_percent_encode(request.key, KEY_ENCODE_SET);
+        if self.config.force_path_style {
+            url.set_path(&format!("/{}/{}", self.config.bucket, key));
+        } else {
+            let host = url
+                .host_str()
+                .ok_or_else(|| anyhow!("S3 endpoint {} has no host.", url))?;
+            let host = format!("{}.{}", self.config.bucket, host);
+            url.set_host(Some(&host))?;
+            url.set_path(&format!("/{}", key));
+        }
+
+        // The query is sent exactly as it is signed, in canonical form.
+        let mut query: Vec<_> = request
+            .query
+            .iter()
+            .map(|(name, value)| {
+                (
+                    utf8_percent_encode(name, URI_ENCODE_SET).to_string(),
+                    utf8_percent_encode(value, URI_ENCODE_SET).to_string(),
+                )
+            })
+            .collect();
+        query.sort();
+        let query = query
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("&");
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+        Ok(url)
+    }
+
+    /// Returns the headers of the request including the signature at `now`, see
+    /// https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+    pub(super) fn sign(
+        &self,
+        request: &S3Request<'_>,
+        url: &Url,
+        now: DateTime<Utc>,
+    ) -> Result<HeaderMap> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let scope = format!(
+            "{}/{}/s3/aws4_request",
+            now.format("%Y%m%d"),
+            self.config.region
+        );
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => bail!("S3 url {} has no host.", url),
+        };
+
+        let mut headers: BTreeMap<String, String> = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.trim().to_string()))
+            .collect();
+        headers.insert(
+            "x-amz-content-sha256".to_string(),
+            hex::encode(Sha256::digest(&request.body)),
+        );
+        headers.insert("x-amz-date".to_string(), amz_date.clone());
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.insert("x-amz-security-token".to_string(), session_token.clone());
+        }
+        // Set by the HTTP client, but it has to be signed as well.
+        headers.insert("host".to_string(), host);
+
+        let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method,
+            url.path(),
+            url.query().unwrap_or_default(),
+            headers
+                .iter()
+                .map(|(name, value)| format!("{}:{}\n", name, value))
+                .collect::<String>(),
+            signed_headers,
+            headers["x-amz-content-sha256"],
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes())),
+        );
+        let signing_key = scope.split('/').fold(
+            format!("AWS4{}", self.credentials.secret_access_key).into_bytes(),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        headers.insert(
+            "authorization".to_string(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.credentials.access_key_id, scope, signed_headers, signature
+            ),
+        );
+        headers.remove("host");
+
+        headers
+            .into_iter()
+            .map(|(name, value)| -> Result<_> {
+                Ok((
+                    HeaderName::from_bytes(name.as_bytes())?,
+                    HeaderValue::from_str(&value)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size.");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Returns the error of a response, if it failed.
+fn response_error(
+    request: &S3Request<'_>,
+    status: StatusCode,
+    body: &[u8],
+) -> Option<anyhow::Error> {
+    if let Some((start, end)) = request.range.filter(|_| status.is_success()) {
+        let expected_len = end - start + 1;
+        return if status != StatusCode::PARTIAL_CONTENT {
+            Some(anyhow!(
+                "S3 GET {:?} ignored the range {}-{} and returned status {}.",
+                request.key,
+                start,
+                end,
+                status,
+            ))
+        } else if body.len() as u64 != expected_len {
+            Some(anyhow!(
+                "S3 GET {:?} returned {} bytes for the range {}-{}, expected {}.",
+                request.key,
+                body.len(),
+                start,
+                end,
+                expected_len,
+            ))
+        } else {
+            None
+        };
+    }
+    if status.is_success() && !request.error_in_body {
+        return None;
+    }
+    let body = String::from_utf8_lossy(body);
+    if status.is_success() && xml_elements(&body, "Error").is_empty() {
+        return None;
+    }
+
+    let code = xml_elements(&body, "Code").pop().unwrap_or_default();
+    let message = xml_elements(&body, "Message").pop().unwrap_or_default();
+    Some(anyhow!(
+        "S3 {} {:?} failed with status {}: {} {}",
+        request.method,
+        request.key,
+        status,
+        code,
+        message,
+    ))
+}
+
+/// Server errors and throttling are worth retrying, other client errors are not. Errors
+/// reported with a 200 status are retried as well, like truncated ranges, except for a store
+/// ignoring ranges, which it would do again.
+fn is_retryable(request: &S3Request<'_>, status: StatusCode) -> bool {
+    if request.range.is_some() && status == StatusCode::OK {
+        return false;
+    }
+    status.is_success()
+        || status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Returns the text of all `<tag>` elements of an XML document. The S3 responses used here are
+/// simple enough not to need a full XML parser.
+pub(super) fn xml_elements(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        match rest.find(&close) {
+            Some(end) => {
+                values.push(xml_unescape(&rest[..end]));
+                rest = &rest[end + close.len()..];
+            },
+            None => break,
+        }
+    }
+    values
+}
+
+pub(super) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// The smallest part S3 accepts in multipart uploads, except for the last one.
+pub const MIN_PART_SIZE: usize = 5 << 20; // 5 MiB
+
+/// Config of the `S3` backup storage, for example:
+///
+/// ```yaml
+/// bucket: "aptos-backup"
+/// prefix: "backup1/e1"
+/// region: "us-west-2"
+/// # For S3 compatible stores other than AWS, like MinIO:
+/// # endpoint: "http://localhost:9000"
+/// # force_path_style: true
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+    /// The bucket holding the backups.
+    pub bucket: String,
+    /// Key prefix everything is stored under, like a folder in the bucket.
+    pub prefix: String,
+    /// The region of the bucket, used for request signing.
+    pub region: String,
+    /// URL of the S3 API. Defaults to AWS S3 in `region`.
+    pub endpoint: Option<String>,
+    /// Address the bucket in the URL path instead of the host name, as most self-hosted
+    /// S3 compatible stores require.
+    pub force_path_style: bool,
+    /// Credentials, defaulting to the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+    /// `AWS_SESSION_TOKEN` environment variables. They are read once and never refreshed,
+    /// instance profiles and web identity tokens are not supported.
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub session_token: Option<String>,
+    /// Size in bytes of the parts of multipart uploads and of ranged reads. S3 requires parts
+    /// of at least 5 MiB, except for the last one.
+    pub part_size: usize,
+    /// Maximum number of requests in flight to the store, across all files.
+    pub max_concurrent_requests: usize,
+    /// Number of times a failed request is retried, with exponential backoff.
+    pub max_retries: usize,
+    /// Timeout of a single request, in seconds.
+    pub request_timeout_secs: u64,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: String::new(),
+            prefix: String::new(),
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            force_path_style: false,
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            part_size: 16 << 20, // 16 MiB
+            max_concurrent_requests: 32,
+            max_retries: 5,
+            request_timeout_secs: 300,
+        }
+    }
+}
+
+impl S3Config {
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        serde_yaml::from_slice::<Self>(&content)?.validate(MIN_PART_SIZE)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        serde_yaml::from_str::<Self>(content)?.validate(MIN_PART_SIZE)
+    }
+
+    /// Checks the config, with parts of at least `min_part_size` bytes, which is only lowered
+    /// below `MIN_PART_SIZE` in tests.
+    pub(super) fn validate(self, min_part_size: usize) -> Result<Self> {
+        ensure!(!self.bucket.is_empty(), "bucket must be set.");
+        ensure!(
+            self.part_size >= min_part_size,
+            "part_size must be at least {} bytes.",
+            min_part_size
+        );
+        ensure!(
+            self.max_concurrent_requests > 0,
+            "max_concurrent_requests must be positive."
+        );
+        Ok(self)
+    }
+
+    pub(super) fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.region))
+    }
+}
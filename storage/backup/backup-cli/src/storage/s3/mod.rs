@@ -0,0 +1,181 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+mod upload;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    s3::{
+        client::S3Client,
+        config::{S3Config, MIN_PART_SIZE},
+        upload::{S3ObjectWriter, MAX_PARTS_IN_FLIGHT_PER_FILE},
+    },
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{format_err, Result};
+use async_trait::async_trait;
+use clap::Parser;
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(long = "config", help = "Config file for the S3 backup store.")]
+    config: PathBuf,
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A BackupStorage talking to S3, or any S3 compatible object store, directly over its API.
+/// Files are laid out under the configured prefix like in `LocalFs`. See `S3Config`.
+pub struct S3 {
+    client: Arc<S3Client>,
+    prefix: String,
+}
+
+impl S3 {
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+
+    pub fn new(config: S3Config) -> Result<Self> {
+        Self::new_impl(config.validate(MIN_PART_SIZE)?)
+    }
+
+    /// Allows parts smaller than S3 accepts, to exercise multipart uploads with small files.
+    #[cfg(test)]
+    fn new_for_test(config: S3Config, min_part_size: usize) -> Result<Self> {
+        Self::new_impl(config.validate(min_part_size)?)
+    }
+
+    fn new_impl(config: S3Config) -> Result<Self> {
+        let prefix = config.prefix.trim_matches('/').to_string();
+        Ok(Self {
+            client: Arc::new(S3Client::new(config)?),
+            prefix,
+        })
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        let config = S3Config::load_from_file(&opt.config).await?;
+
+        Self::new(config)
+    }
+
+    /// The object key of a file handle.
+    fn key(&self, file_handle: &FileHandleRef) -> String {
+        if self.prefix.is_empty() {
+            file_handle.to_string()
+        } else {
+            format!("{}/{}", self.prefix, file_handle)
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // Object stores have no folders to create.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let writer = S3ObjectWriter::new(self.client.clone(), self.key(&file_handle));
+        Ok((file_handle, Box::new(writer)))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let key = self.key(file_handle);
+        let size = self.client.get_object_size(&key).await?;
+
+        // Read in ranges of the part size, a few of them ahead, so that a failure only
+        // retries the range it happened in.
+        let client = self.client.clone();
+        let part_size = client.part_size() as u64;
+        let reader = futures::stream::iter((0..size).step_by(part_size as usize))
+            .map(move |start| {
+                let (client, key) = (client.clone(), key.clone());
+                let end = std::cmp::min(start + part_size, size) - 1;
+                async move { client.get_object_range(&key, start, end).await }
+            })
+            .buffered(MAX_PARTS_IN_FLIGHT_PER_FILE)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .boxed()
+            .into_async_read()
+            .compat();
+        Ok(Box::new(reader))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let prefix = self.key(&format!("{}/", Self::METADATA_DIR));
+        let keys = self.client.list_objects(&prefix).await?;
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| {
+                key.strip_prefix(&prefix)
+                    .map(|name| format!("{}/{}", Self::METADATA_DIR, name))
+            })
+            .collect())
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let source_key = self.key(file_handle);
+        let key = self.key(&format!("{}/{}", Self::METADATA_BACKUP_DIR, name));
+
+        // S3 has no move, so copy and delete the original.
+        self.client.copy_object(&source_key, &key).await?;
+        self.client.delete_object(&source_key).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        self.client
+            .put_object(&self.key(&file_handle), content.into())
+            .await?;
+        Ok(file_handle)
+    }
+}
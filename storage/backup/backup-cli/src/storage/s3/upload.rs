@@ -0,0 +1,182 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::client::S3Client;
+use anyhow::{anyhow, Result};
+use aptos_logger::prelude::*;
+use bytes::Bytes;
+use futures::{
+    ready,
+    task::{Context, Poll},
+    Future,
+};
+use std::{collections::VecDeque, io, pin::Pin, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWrite, DuplexStream},
+    sync::oneshot,
+    task::JoinHandle,
+};
+
+/// Size of the buffer between the writer and the upload task.
+const PIPE_BUFFER_SIZE: usize = 1 << 20;
+/// Maximum number of parts of a single file buffered for and being uploaded.
+pub(super) const MAX_PARTS_IN_FLIGHT_PER_FILE: usize = 4;
+
+/// Uploads everything written to it to an S3 object, cut into parts uploaded concurrently
+/// unless it all fits into a single one. The object is only created on `shutdown()`, which
+/// returns the error of the upload if it failed; dropping the writer before that abandons
+/// the upload.
+pub(super) struct S3ObjectWriter {
+    pipe: DuplexStream,
+    finish_tx: Option<oneshot::Sender<()>>,
+    upload: Option<JoinHandle<Result<()>>>,
+}
+
+impl S3ObjectWriter {
+    pub fn new(client: Arc<S3Client>, key: String) -> Self {
+        let (pipe, upload_pipe) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+        let (finish_tx, finish_rx) = oneshot::channel();
+        Self {
+            pipe,
+            finish_tx: Some(finish_tx),
+            upload: Some(tokio::spawn(upload(client, key, upload_pipe, finish_rx))),
+        }
+    }
+
+    fn poll_upload(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let Some(upload) = self.upload.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+        let result = ready!(Pin::new(upload).poll(cx));
+        self.upload = None;
+        Poll::Ready(match result {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(io::Error::new(io::ErrorKind::Other, e)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        })
+    }
+
+    /// When the pipe breaks, the upload failed and its error is more useful.
+    fn upload_error_or(&mut self, cx: &mut Context<'_>, error: io::Error) -> io::Error {
+        match self.poll_upload(cx) {
+            Poll::Ready(Err(upload_error)) => upload_error,
+            _ => error,
+        }
+    }
+}
+
+impl AsyncWrite for S3ObjectWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match ready!(Pin::new(&mut self.pipe).poll_write(cx, buf)) {
+            Ok(written) => Poll::Ready(Ok(written)),
+            Err(e) => Poll::Ready(Err(self.upload_error_or(cx, e))),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match ready!(Pin::new(&mut self.pipe).poll_flush(cx)) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(e) => Poll::Ready(Err(self.upload_error_or(cx, e))),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(finish_tx) = self.finish_tx.take() {
+            // The upload task only fails to receive this if it already failed.
+            let _ = finish_tx.send(());
+        }
+        if let Err(e) = ready!(Pin::new(&mut self.pipe).poll_shutdown(cx)) {
+            return Poll::Ready(Err(self.upload_error_or(cx, e)));
+        }
+        self.poll_upload(cx)
+    }
+}
+
+async fn upload(
+    client: Arc<S3Client>,
+    key: String,
+    mut pipe: DuplexStream,
+    finish_rx: oneshot::Receiver<()>,
+) -> Result<()> {
+    let part_size = client.part_size();
+    let first_part = read_part(&mut pipe, part_size).await?;
+    if first_part.len() < part_size {
+        wait_for_finish(finish_rx, &key).await?;
+        return client.put_object(&key, first_part).await;
+    }
+
+    let upload_id = client.create_multipart_upload(&key).await?;
+    let result = upload_parts(&client, &key, &upload_id, first_part, pipe, finish_rx).await;
+    match result {
+        Ok(etags) => {
+            client
+                .complete_multipart_upload(&key, &upload_id, &etags)
+                .await
+        },
+        Err(e) => {
+            if let Err(abort_error) = client.abort_multipart_upload(&key, &upload_id).await {
+                warn!(
+                    key = key,
+                    error = ?abort_error,
+                    "Failed to abort multipart upload."
+                );
+            }
+            Err(e)
+        },
+    }
+}
+
+/// Uploads the parts of a multipart upload, returning their ETags.
+async fn upload_parts(
+    client: &Arc<S3Client>,
+    key: &str,
+    upload_id: &str,
+    first_part: Bytes,
+    mut pipe: DuplexStream,
+    finish_rx: oneshot::Receiver<()>,
+) -> Result<Vec<String>> {
+    let part_size = client.part_size();
+    let mut etags = Vec::new();
+    let mut in_flight: VecDeque<JoinHandle<Result<String>>> = VecDeque::new();
+    let mut part = first_part;
+    while !part.is_empty() {
+        if in_flight.len() >= MAX_PARTS_IN_FLIGHT_PER_FILE {
+            etags.push(in_flight.pop_front().unwrap().await??);
+        }
+        // Uploaded in the background while reading the next part
+        let part_number = etags.len() + in_flight.len() + 1;
+        let task_client = client.clone();
+        let (key, upload_id) = (key.to_string(), upload_id.to_string());
+        in_flight.push_back(tokio::spawn(async move {
+            task_client
+                .upload_part(&key, &upload_id, part_number, part)
+                .await
+        }));
+        part = read_part(&mut pipe, part_size).await?;
+    }
+    for upload in in_flight {
+        etags.push(upload.await??);
+    }
+
+    wait_for_finish(finish_rx, key).await?;
+    Ok(etags)
+}
+
+async fn read_part(pipe: &mut DuplexStream, part_size: usize) -> Result<Bytes> {
+    let mut buf = Vec::with_capacity(part_size);
+    pipe.take(part_size as u64).read_to_end(&mut buf).await?;
+    Ok(buf.into())
+}
+
+async fn wait_for_finish(finish_rx: oneshot::Receiver<()>, key: &str) -> Result<()> {
+    finish_rx.await.map_err(|_| {
+        anyhow!(
+            "Writer of {} dropped before shutdown, abandoning upload.",
+            key
+        )
+    })
+}
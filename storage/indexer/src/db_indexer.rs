@@ -9,8 +9,8 @@ use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
-        event_sequence_number::EventSequenceNumberSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema, event_sequence_number::EventSequenceNumberSchema,
         indexer_metadata::InternalIndexerMetadataSchema,
         ordered_transaction_by_account::OrderedTransactionByAccountSchema,
         state_keys::StateKeysSchema, translated_v1_event::TranslatedV1EventSchema,
@@ -38,6 +38,7 @@ use aptos_types::{
     transaction::{AccountOrderedTransactionsWithProof, ReplayProtector, Transaction, Version},
     write_set::{TransactionWrite, WriteSet},
 };
+use move_core_types::language_storage::TypeTag;
 use std::{
     cmp::min,
     collections::HashSet,
@@ -126,6 +127,10 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::EventV2TranslationVersion)
     }
 
+    pub fn get_event_by_type_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::EventByTypeVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -134,6 +139,10 @@ impl InternalIndexerDB {
         self.config.enable_event_v2_translation
     }
 
+    pub fn event_by_type_enabled(&self) -> bool {
+        self.config.enable_event_by_type
+    }
+
     pub fn transaction_enabled(&self) -> bool {
         self.config.enable_transaction
     }
@@ -243,6 +252,37 @@ impl InternalIndexerDB {
         Ok(result)
     }
 
+    /// Given `type_tag`, returns up to `limit` events of that type identified by transaction
+    /// version and index among all events emitted by the same transaction, starting from
+    /// `start_event_index` of `start_version` and before `end_version`, in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let mut iter = self.db.iter::<EventByTypeSchema>()?;
+        iter.seek(&(type_tag.clone(), start_version, start_event_index))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((tag, ver, idx), ()) = res?;
+            if &tag != type_tag || ver >= end_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn get_restore_version_and_progress(
         &self,
@@ -429,6 +469,17 @@ impl DBIndexer {
                 }
             }
 
+            if self.indexer_db.event_by_type_enabled() {
+                events.iter().enumerate().for_each(|(idx, event)| {
+                    batch
+                        .put::<EventByTypeSchema>(
+                            &(event.type_tag().clone(), version, idx as u64),
+                            &(),
+                        )
+                        .expect("Failed to put events by type to a batch");
+                });
+            }
+
             if self.indexer_db.event_enabled() {
                 events.iter().enumerate().try_for_each(|(idx, event)| {
                     if let ContractEvent::V1(v1) = event {
@@ -533,6 +584,12 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.indexer_db.event_by_type_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::EventByTypeVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
         if self.indexer_db.statekeys_enabled() {
             batch.put::<InternalIndexerMetadataSchema>(
                 &MetadataKey::StateVersion,
@@ -643,6 +700,47 @@ impl DBIndexer {
         self.get_events_by_event_key(event_key, start, order, limit, ledger_version)
     }
 
+    /// Returns up to `limit` events of the given type emitted from `start_event_index` of
+    /// `start_version` and before `end_version`, in ascending order, each with its index among
+    /// the events of its transaction.
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(EventWithVersion, u64)>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        let end_version = min(end_version, ledger_version.saturating_add(1));
+        self.indexer_db
+            .lookup_events_by_type(
+                type_tag,
+                start_version,
+                start_event_index,
+                end_version,
+                limit,
+            )?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event = self
+                    .main_db_reader
+                    .get_event_by_version_and_index(ver, idx)?;
+                ensure!(
+                    event.type_tag() == type_tag,
+                    "Index broken, expected type:{}, actual:{}",
+                    type_tag.to_canonical_string(),
+                    event.type_tag().to_canonical_string()
+                );
+                Ok((EventWithVersion::new(ver, event), idx))
+            })
+            .collect()
+    }
+
     pub fn get_events_by_event_key(
         &self,
         event_key: &EventKey,
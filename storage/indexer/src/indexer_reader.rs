@@ -15,6 +15,7 @@ use aptos_types::{
     },
     transaction::{AccountOrderedTransactionsWithProof, Version},
 };
+use move_core_types::language_storage::TypeTag;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -113,6 +114,32 @@ impl IndexerReader for IndexerReaders {
         anyhow::bail!("DB indexer reader is not available")
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<(EventWithVersion, u64)>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.event_by_type_enabled() {
+                return Ok(db_indexer_reader.get_events_by_type(
+                    type_tag,
+                    start_version,
+                    start_event_index,
+                    end_version,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal event by type index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
     fn get_account_ordered_transactions(
         &self,
         address: AccountAddress,
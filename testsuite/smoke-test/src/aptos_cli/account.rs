@@ -2,9 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::smoke_test_environment::SwarmBuilder;
-use aptos_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_crypto::{
+    ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, ValidCryptoMaterial,
+    ValidCryptoMaterialStringExt,
+};
 use aptos_keygen::KeyGen;
-use movement::{account::create::DEFAULT_FUNDED_COINS, common::types::GasOptions};
+use movement::{
+    account::create::DEFAULT_FUNDED_COINS,
+    common::{external_signer::ExternalSigner, types::GasOptions},
+};
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixListener,
+    thread,
+};
 
 #[tokio::test]
 async fn test_account_flow() {
@@ -136,3 +147,51 @@ async fn test_account_key_rotation() {
         .await
         .expect("New key should be able to transfer");
 }
+
+/// Answers a single request on a Unix socket like a signing daemon, signing with `key`
+fn serve_signature(listener: UnixListener, key: Ed25519PrivateKey) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request).unwrap();
+        let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+        let signing_message = request["signing_message"].as_str().unwrap();
+        let signing_message = hex::decode(signing_message.trim_start_matches("0x")).unwrap();
+
+        let signature = key.sign_arbitrary_message(&signing_message);
+        let response = serde_json::json!({ "signature": signature.to_encoded_string().unwrap() });
+        (&stream)
+            .write_all(response.to_string().as_bytes())
+            .unwrap();
+    })
+}
+
+#[tokio::test]
+async fn test_external_signer_profile() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    cli.save_external_signer_profile("external", 0, ExternalSigner::Socket { path })
+        .unwrap();
+
+    // The sender is known from the profile alone
+    assert_eq!(
+        cli.profile_sender_address("external").unwrap(),
+        cli.account_id(0)
+    );
+
+    // Local simulation gets its signature from the signer instead of a private key
+    let key = Ed25519PrivateKey::try_from(cli.private_key(0).to_bytes().as_ref()).unwrap();
+    let signer = serve_signature(listener, key);
+    let summary = cli
+        .simulate_transfer_locally("external", 1, 100)
+        .await
+        .unwrap();
+    signer.join().unwrap();
+    assert_eq!(summary.sender, Some(cli.account_id(0)));
+    assert_eq!(summary.success, Some(true));
+}
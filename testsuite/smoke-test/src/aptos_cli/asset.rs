@@ -0,0 +1,190 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::smoke_test_environment::SwarmBuilder;
+use aptos_forge::{NodeExt, Swarm};
+use aptos_rest_client::{aptos_api_types::WriteSetChange, Transaction};
+use aptos_types::account_address::{create_collection_address, create_object_address};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use movement::{common::types::TransactionSummary, test::CliTestFramework};
+use std::{collections::BTreeMap, str::FromStr};
+
+const ASSET_SYMBOL: &str = "YOLO";
+const COLLECTION: &str = "Test Collection";
+
+/// Publishes the `managed_fungible_asset` example, whose `coin_example` module creates a
+/// fungible asset managed by the publisher, and returns the address of its metadata object
+async fn publish_managed_fungible_asset(
+    cli: &mut CliTestFramework,
+    index: usize,
+) -> AccountAddress {
+    cli.init_move_dir();
+    let mut package_addresses = BTreeMap::new();
+    package_addresses.insert("example_addr", "_");
+    cli.init_package(
+        "ManagedFungibleAsset".to_string(),
+        package_addresses,
+        Some(CliTestFramework::aptos_framework_dir()),
+    )
+    .await
+    .unwrap();
+
+    cli.add_file_in_package(
+        "sources/managed_fungible_asset.move",
+        include_str!("../../../../aptos-move/move-examples/fungible_asset/managed_fungible_asset/sources/managed_fungible_asset.move").to_string(),
+    );
+    cli.add_file_in_package(
+        "sources/coin_example.move",
+        include_str!("../../../../aptos-move/move-examples/fungible_asset/managed_fungible_asset/sources/coin_example.move").to_string(),
+    );
+
+    let account = cli.account_id(index).to_hex_literal();
+    let mut named_addresses = BTreeMap::new();
+    named_addresses.insert("example_addr", account.as_str());
+    cli.publish_package(index, None, named_addresses, None)
+        .await
+        .unwrap();
+
+    create_object_address(cli.account_id(index), ASSET_SYMBOL.as_bytes())
+}
+
+/// Finds the address of the token created by a mint transaction
+async fn minted_token(swarm: &dyn Swarm, summary: &TransactionSummary) -> AccountAddress {
+    let transaction = swarm
+        .validators()
+        .next()
+        .unwrap()
+        .rest_client()
+        .get_transaction_by_hash(summary.transaction_hash.into())
+        .await
+        .unwrap()
+        .into_inner();
+    let Transaction::UserTransaction(transaction) = transaction else {
+        panic!("Expected a user transaction, got {:?}", transaction);
+    };
+    transaction
+        .info
+        .changes
+        .iter()
+        .find_map(|change| match change {
+            WriteSetChange::WriteResource(resource)
+                if resource.data.typ.address == AccountAddress::FOUR.into()
+                    && resource.data.typ.module.as_str() == "token"
+                    && resource.data.typ.name.as_str() == "Token" =>
+            {
+                Some((&resource.address).into())
+            },
+            _ => None,
+        })
+        .expect("Mint should create a token")
+}
+
+#[tokio::test]
+async fn test_fungible_asset_flow() {
+    let (_swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+
+    // The coin paired with the native coin lives at 0xa, and is read through 0x1
+    let native = cli
+        .fa_metadata(AccountAddress::from_hex_literal("0xa").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(native.metadata.symbol, "MOVE");
+    assert_eq!(native.metadata.decimals, 8);
+    let native_balance = cli.fa_balance(0, native.address).await.unwrap();
+    assert_eq!(native_balance.account, cli.account_id(0));
+    assert!(!native_balance.frozen);
+
+    let metadata = publish_managed_fungible_asset(&mut cli, 0).await;
+    let summary = cli.fa_metadata(metadata).await.unwrap();
+    assert_eq!(summary.metadata.name, "You only live once");
+    assert_eq!(summary.metadata.symbol, ASSET_SYMBOL);
+    assert_eq!(summary.metadata.decimals, 8);
+    assert_eq!(summary.metadata.project_uri, "http://example.com");
+    assert_eq!(summary.supply, Some(0));
+    assert_eq!(summary.maximum, None);
+
+    let manager = ModuleId::from_str(&format!(
+        "{}::managed_fungible_asset",
+        cli.account_id(0).to_hex_literal()
+    ))
+    .unwrap();
+
+    // Mint to another account than the admin
+    cli.fa_mint(0, manager.clone(), metadata, 1, 1000)
+        .await
+        .unwrap();
+    assert_eq!(cli.fa_balance(1, metadata).await.unwrap().balance, 1000);
+    assert_eq!(cli.fa_balance(0, metadata).await.unwrap().balance, 0);
+    assert_eq!(cli.fa_metadata(metadata).await.unwrap().supply, Some(1000));
+
+    // The holder can transfer, creating the receiver's primary store
+    cli.fa_transfer(1, 0, metadata, 300).await.unwrap();
+    assert_eq!(cli.fa_balance(1, metadata).await.unwrap().balance, 700);
+    assert_eq!(cli.fa_balance(0, metadata).await.unwrap().balance, 300);
+
+    // More than the balance can't be transferred
+    assert!(cli.fa_transfer(1, 0, metadata, 701).await.is_err());
+
+    // Only the admin can burn, from any account
+    assert!(cli
+        .fa_burn(1, manager.clone(), metadata, 1, 100)
+        .await
+        .is_err());
+    cli.fa_burn(0, manager, metadata, 1, 200).await.unwrap();
+    assert_eq!(cli.fa_balance(1, metadata).await.unwrap().balance, 500);
+    assert_eq!(cli.fa_metadata(metadata).await.unwrap().supply, Some(800));
+}
+
+#[tokio::test]
+async fn test_object_and_token_flow() {
+    let (swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+
+    cli.create_token_collection(0, COLLECTION).await.unwrap();
+    let summary = cli.mint_token(0, COLLECTION, "Token #1").await.unwrap();
+    let token = minted_token(&swarm, &summary).await;
+
+    let shown = cli.show_token(token).await.unwrap();
+    assert_eq!(shown.owner, cli.account_id(0));
+    assert_eq!(shown.creator, cli.account_id(0));
+    assert_eq!(shown.collection, COLLECTION);
+    assert_eq!(shown.name, "Token #1");
+    assert_eq!(shown.index, 1);
+
+    cli.transfer_token(0, token, 1).await.unwrap();
+    assert_eq!(
+        cli.show_token(token).await.unwrap().owner,
+        cli.account_id(1)
+    );
+
+    // Only the owner can transfer the token
+    assert!(cli.transfer_token(0, token, 0).await.is_err());
+
+    // Tokens are plain objects too
+    let object = cli.show_object(token).await.unwrap();
+    assert_eq!(object.owner, cli.account_id(1));
+    assert!(!object.burnt);
+    assert!(object.transferable);
+    assert!(object
+        .resources
+        .iter()
+        .any(|resource| resource == "0x4::token::Token"));
+
+    cli.transfer_object(1, token, 0).await.unwrap();
+    assert_eq!(
+        cli.show_object(token).await.unwrap().owner,
+        cli.account_id(0)
+    );
+
+    // Collections of `0x4::aptos_token` can't be transferred by their owner
+    let collection = create_collection_address(cli.account_id(0), COLLECTION);
+    let object = cli.show_object(collection).await.unwrap();
+    assert_eq!(object.owner, cli.account_id(0));
+    assert!(!object.transferable);
+    assert!(cli.transfer_object(0, collection, 1).await.is_err());
+}
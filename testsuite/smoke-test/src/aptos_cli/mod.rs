@@ -4,6 +4,8 @@
 #![allow(unexpected_cfgs)]
 
 mod account;
+mod asset;
 #[cfg(feature = "cli-framework-test-move")]
 mod r#move;
+mod multisig;
 pub mod validator;
@@ -0,0 +1,77 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::smoke_test_environment::SwarmBuilder;
+use movement::move_tool::ArgWithType;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_multisig_proposals() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(3)
+        .await;
+    let multisig = cli.create_multisig_account(0, vec![1, 2], 2).await.unwrap();
+
+    // The first proposal removes the third owner, the second one is created by that owner
+    cli.propose_multisig_transaction(0, multisig, "0x1::multisig_account::remove_owner", vec![
+        ArgWithType::address(cli.account_id(2)),
+    ])
+    .await
+    .unwrap();
+    cli.propose_multisig_transaction(2, multisig, "0x1::aptos_account::transfer", vec![
+        ArgWithType::address(cli.account_id(0)),
+        ArgWithType::u64(1),
+    ])
+    .await
+    .unwrap();
+
+    let pending = cli
+        .list_pending_multisig_transactions(multisig)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].sequence_number, 1);
+    assert_eq!(pending[0].creator, cli.account_id(0));
+    let payload = pending[0].payload.as_ref().unwrap();
+    assert_eq!(payload.function, "0x1::multisig_account::remove_owner");
+    let removed_owner = cli.account_id(2).to_hex_literal();
+    assert_eq!(payload.arguments, vec![json!(removed_owner)]);
+    assert_eq!(pending[0].approvals, vec![cli.account_id(0)]);
+    assert_eq!(pending[0].pending_owners, vec![
+        cli.account_id(1),
+        cli.account_id(2)
+    ]);
+    assert_eq!(pending[0].approvals_needed, 1);
+    assert!(!pending[0].can_be_executed);
+    assert_eq!(pending[1].sequence_number, 2);
+    assert_eq!(pending[1].approvals, vec![cli.account_id(2)]);
+
+    cli.approve_multisig_transaction(1, multisig, 1)
+        .await
+        .unwrap();
+    let proposal = cli.show_multisig_transaction(multisig, 1).await.unwrap();
+    assert_eq!(proposal.approvals, vec![
+        cli.account_id(0),
+        cli.account_id(1)
+    ]);
+    assert_eq!(proposal.approvals_needed, 0);
+    assert!(proposal.can_be_executed);
+
+    // Once the third owner is removed, its vote on the second proposal no longer counts
+    cli.execute_multisig_transaction(0, multisig).await.unwrap();
+    let pending = cli
+        .list_pending_multisig_transactions(multisig)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].sequence_number, 2);
+    assert_eq!(pending[0].creator, cli.account_id(2));
+    assert!(pending[0].approvals.is_empty());
+    assert_eq!(pending[0].pending_owners, vec![
+        cli.account_id(0),
+        cli.account_id(1)
+    ]);
+    assert_eq!(pending[0].approvals_needed, 2);
+    assert!(!pending[0].can_be_executed);
+}
@@ -0,0 +1,518 @@
+use crate::utils::{
+    decode_network_addresses, extract_genesis_transaction, extract_genesis_waypoint,
+    extract_waypoint, get_latest_version, open_db,
+};
+use anyhow::Result;
+use aptos_config::{
+    config::{
+        ConfigSanitizer, DiscoveryMethod, Identity, InitialSafetyRulesConfig, NetworkConfig,
+        NodeConfig, NodeType, OnDiskStorageConfig, Peer, PeerRole, PeerSet, RoleType,
+        SecureBackend, WaypointConfig,
+    },
+    network_id::NetworkId,
+};
+use aptos_storage_interface::{
+    state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    network_address::{NetworkAddress, Protocol},
+    on_chain_config::{OnChainConfig, ValidatorSet},
+    validator_info::ValidatorInfo,
+};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// Port the validator network listens on if the on-chain address has none
+const DEFAULT_VALIDATOR_PORT: u16 = 6180;
+/// Port the public fullnode network listens on if the on-chain address has none
+const DEFAULT_FULLNODE_PORT: u16 = 6182;
+
+/// Where the generated node configs expect files on the nodes
+pub struct BootstrapOptions {
+    /// Directory holding genesis.blob, the waypoints and the identity files
+    pub genesis_dir: PathBuf,
+    /// Data directory of the nodes
+    pub data_dir: PathBuf,
+    /// Port validators listen on for their validator fullnodes
+    pub vfn_port: u16,
+}
+
+/// A validator of the on-chain validator set with its decoded network addresses
+struct BootstrapValidator {
+    account_address: AccountAddress,
+    info: ValidatorInfo,
+    validator_addresses: Vec<NetworkAddress>,
+    fullnode_addresses: Vec<NetworkAddress>,
+}
+
+/// The public keys and addresses a validator registered on chain, so that the
+/// operator can check the private keys in its identity files against them
+#[derive(Serialize)]
+struct ExpectedIdentity {
+    account_address: String,
+    consensus_public_key: String,
+    validator_network_public_key: Option<String>,
+    fullnode_network_public_key: Option<String>,
+    validator_network_addresses: Vec<String>,
+    fullnode_network_addresses: Vec<String>,
+    voting_power: u64,
+}
+
+/// Generate a bootstrap bundle for every validator in the on-chain validator set
+///
+/// The bundle holds genesis.blob and the waypoints at the top level, and a
+/// directory per validator with a sanitized validator.yaml and fullnode.yaml.
+/// The validator network uses on-chain discovery, and the fullnode networks are
+/// seeded with the addresses decoded from the validator set. Private keys are
+/// not on chain, so the configs load them from validator-identity.yaml and
+/// validator-full-node-identity.yaml in the genesis directory, which the
+/// operator has to provide. expected-identity.yaml lists the public keys these
+/// have to match.
+pub fn generate_bootstrap_bundle(
+    db_path: &str,
+    output_dir: &str,
+    options: &BootstrapOptions,
+) -> Result<()> {
+    let db = Arc::new(open_db(db_path)?);
+    let latest_ver = get_latest_version(&db)?;
+
+    // The genesis and waypoint files are shared by every node
+    extract_genesis_transaction(&db, latest_ver, output_dir)?;
+    extract_genesis_waypoint(&db, latest_ver, output_dir)?;
+    extract_waypoint(&db, output_dir)?;
+
+    // Read the validator set and chain ID at the latest version
+    let db_reader: Arc<dyn DbReader> = db;
+    let state_view = db_reader.state_view_at_version(Some(latest_ver))?;
+    let chain_id = ChainId::fetch_config(&state_view)
+        .ok_or_else(|| anyhow::anyhow!("Chain ID not found at version {}", latest_ver))?;
+    let validator_set = ValidatorSet::fetch_config(&state_view)
+        .ok_or_else(|| anyhow::anyhow!("Validator set not found at version {}", latest_ver))?;
+    println!(
+        "Found {} validators on chain {} at version {}",
+        validator_set.num_validators(),
+        chain_id,
+        latest_ver
+    );
+
+    let validators = validator_set
+        .payload()
+        .map(decode_validator)
+        .collect::<Result<Vec<_>>>()?;
+
+    for validator in &validators {
+        let validator_dir = Path::new(output_dir).join(validator.account_address.to_hex());
+        fs::create_dir_all(&validator_dir)?;
+
+        let mut validator_config = build_validator_config(validator, options)?;
+        NodeConfig::sanitize(&validator_config, NodeType::Validator, Some(chain_id)).map_err(
+            |e| {
+                anyhow::anyhow!(
+                    "Validator config of {} failed sanitization: {}",
+                    validator.account_address,
+                    e
+                )
+            },
+        )?;
+        validator_config.save_to_path(validator_dir.join("validator.yaml"))?;
+
+        let mut fullnode_config = build_fullnode_config(validator, &validators, options)?;
+        NodeConfig::sanitize(
+            &fullnode_config,
+            NodeType::ValidatorFullnode,
+            Some(chain_id),
+        )
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Fullnode config of {} failed sanitization: {}",
+                validator.account_address,
+                e
+            )
+        })?;
+        fullnode_config.save_to_path(validator_dir.join("fullnode.yaml"))?;
+
+        let expected_identity = expected_identity(validator);
+        fs::write(
+            validator_dir.join("expected-identity.yaml"),
+            serde_yaml::to_string(&expected_identity)?,
+        )?;
+
+        println!(
+            "Bootstrap bundle for validator {} written to: {}",
+            validator.account_address,
+            validator_dir.display()
+        );
+    }
+
+    println!("✓ Bootstrap bundle generated successfully!");
+    println!("  - genesis.blob, genesis_waypoint.txt, waypoint.txt: Shared by every node");
+    println!("  - <validator>/validator.yaml: Validator node config");
+    println!("  - <validator>/fullnode.yaml: Validator fullnode config");
+    println!("  - <validator>/expected-identity.yaml: Public keys the identity files must match");
+    println!(
+        "Copy the shared files, validator-identity.yaml and validator-full-node-identity.yaml to {} on each node",
+        options.genesis_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Decode the network addresses of a validator with the same logic as `network-address decode`
+fn decode_validator(info: &ValidatorInfo) -> Result<BootstrapValidator> {
+    let account_address = *info.account_address();
+    let config = info.config();
+    let validator_addresses = decode_network_addresses(&config.validator_network_addresses)
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid validator network addresses of {}: {}",
+                account_address,
+                e
+            )
+        })?;
+    let fullnode_addresses = if config.fullnode_network_addresses.is_empty() {
+        vec![]
+    } else {
+        decode_network_addresses(&config.fullnode_network_addresses).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid fullnode network addresses of {}: {}",
+                account_address,
+                e
+            )
+        })?
+    };
+
+    if validator_addresses.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Validator {} has no validator network address",
+            account_address
+        ));
+    }
+    for address in &validator_addresses {
+        if address.find_noise_proto().is_none() {
+            return Err(anyhow::anyhow!(
+                "Validator network address {} of {} has no noise-ik public key",
+                address,
+                account_address
+            ));
+        }
+    }
+
+    Ok(BootstrapValidator {
+        account_address,
+        info: info.clone(),
+        validator_addresses,
+        fullnode_addresses,
+    })
+}
+
+/// Build the config of a validator, which finds its peers through on-chain discovery
+fn build_validator_config(
+    validator: &BootstrapValidator,
+    options: &BootstrapOptions,
+) -> Result<NodeConfig> {
+    let mut node_config = base_config(RoleType::Validator, options);
+    let validator_identity = options.genesis_dir.join("validator-identity.yaml");
+
+    let mut safety_rules_backend = OnDiskStorageConfig::default();
+    safety_rules_backend.path = PathBuf::from("secure-data.json");
+    let safety_rules = &mut node_config.consensus.safety_rules;
+    safety_rules.backend = SecureBackend::OnDiskStorage(safety_rules_backend);
+    safety_rules.initial_safety_rules_config = InitialSafetyRulesConfig::from_file(
+        validator_identity.clone(),
+        vec![],
+        WaypointConfig::FromFile(options.genesis_dir.join("waypoint.txt")),
+    );
+
+    let mut validator_network = NetworkConfig::network_with_id(NetworkId::Validator);
+    validator_network.discovery_method = DiscoveryMethod::Onchain;
+    validator_network.mutual_authentication = true;
+    validator_network.identity = Identity::from_file(validator_identity.clone());
+    validator_network.listen_address = listen_address(
+        validator.validator_addresses[0]
+            .find_port()
+            .unwrap_or(DEFAULT_VALIDATOR_PORT),
+    )?;
+    node_config.validator_network = Some(validator_network);
+
+    // The validator fullnode connects to the validator over the private VFN network
+    let mut vfn_network = NetworkConfig::network_with_id(NetworkId::Vfn);
+    vfn_network.identity = Identity::from_file(validator_identity);
+    vfn_network.listen_address = listen_address(options.vfn_port)?;
+    vfn_network.max_outbound_connections = 0;
+    node_config.full_node_networks = vec![vfn_network];
+
+    node_config.set_data_dir(options.data_dir.clone());
+    Ok(node_config)
+}
+
+/// Build the config of a validator fullnode, seeded with its validator on the VFN
+/// network and with the fullnodes of the other validators on the public network
+fn build_fullnode_config(
+    validator: &BootstrapValidator,
+    validators: &[BootstrapValidator],
+    options: &BootstrapOptions,
+) -> Result<NodeConfig> {
+    let mut node_config = base_config(RoleType::FullNode, options);
+    node_config.execution.genesis_waypoint = Some(WaypointConfig::FromFile(
+        options.genesis_dir.join("genesis_waypoint.txt"),
+    ));
+
+    let vfn_addresses = validator
+        .validator_addresses
+        .iter()
+        .map(|address| with_port(address, options.vfn_port))
+        .collect::<Result<Vec<_>>>()?;
+    let mut vfn_network = NetworkConfig::network_with_id(NetworkId::Vfn);
+    vfn_network.listen_address = listen_address(options.vfn_port)?;
+    vfn_network.max_outbound_connections = 1;
+    vfn_network.seeds = PeerSet::from([(
+        validator.account_address,
+        Peer::from_addrs(PeerRole::Validator, vfn_addresses),
+    )]);
+
+    let mut public_network = NetworkConfig::network_with_id(NetworkId::Public);
+    public_network.discovery_method = DiscoveryMethod::Onchain;
+    public_network.identity = Identity::from_file(
+        options
+            .genesis_dir
+            .join("validator-full-node-identity.yaml"),
+    );
+    public_network.listen_address = listen_address(
+        validator
+            .fullnode_addresses
+            .first()
+            .and_then(NetworkAddress::find_port)
+            .unwrap_or(DEFAULT_FULLNODE_PORT),
+    )?;
+    public_network.seeds = validators
+        .iter()
+        .filter(|other| {
+            other.account_address != validator.account_address
+                && !other.fullnode_addresses.is_empty()
+        })
+        .map(|other| {
+            (
+                other.account_address,
+                Peer::from_addrs(
+                    PeerRole::ValidatorFullNode,
+                    other.fullnode_addresses.clone(),
+                ),
+            )
+        })
+        .collect();
+
+    node_config.full_node_networks = vec![public_network, vfn_network];
+
+    node_config.set_data_dir(options.data_dir.clone());
+    Ok(node_config)
+}
+
+/// Build the parts of a node config that validators and fullnodes share
+fn base_config(role: RoleType, options: &BootstrapOptions) -> NodeConfig {
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = role;
+    node_config.base.waypoint = WaypointConfig::FromFile(options.genesis_dir.join("waypoint.txt"));
+    node_config.execution.genesis_file_location = options.genesis_dir.join("genesis.blob");
+    node_config
+}
+
+/// Collect the public keys and addresses a validator registered on chain
+fn expected_identity(validator: &BootstrapValidator) -> ExpectedIdentity {
+    let to_strings = |addresses: &[NetworkAddress]| -> Vec<String> {
+        addresses.iter().map(ToString::to_string).collect()
+    };
+
+    ExpectedIdentity {
+        account_address: validator.account_address.to_hex_literal(),
+        consensus_public_key: validator.info.consensus_public_key().to_string(),
+        validator_network_public_key: validator.validator_addresses[0]
+            .find_noise_proto()
+            .map(|key| key.to_string()),
+        fullnode_network_public_key: validator
+            .fullnode_addresses
+            .iter()
+            .find_map(NetworkAddress::find_noise_proto)
+            .map(|key| key.to_string()),
+        validator_network_addresses: to_strings(&validator.validator_addresses),
+        fullnode_network_addresses: to_strings(&validator.fullnode_addresses),
+        voting_power: validator.info.consensus_voting_power(),
+    }
+}
+
+/// Listen on all interfaces at the given port
+fn listen_address(port: u16) -> Result<NetworkAddress> {
+    NetworkAddress::from_str(&format!("/ip4/0.0.0.0/tcp/{}", port))
+        .map_err(|e| anyhow::anyhow!("Invalid listen port {}: {}", port, e))
+}
+
+/// Replace the TCP port of a network address
+fn with_port(address: &NetworkAddress, port: u16) -> Result<NetworkAddress> {
+    let protocols = address
+        .as_slice()
+        .iter()
+        .map(|protocol| match protocol {
+            Protocol::Tcp(_) => Protocol::Tcp(port),
+            protocol => protocol.clone(),
+        })
+        .collect();
+    NetworkAddress::from_protocols(protocols)
+        .map_err(|e| anyhow::anyhow!("Invalid network address {}: {}", address, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{bls12381, x25519, PrivateKey, Uniform};
+    use aptos_types::validator_config::ValidatorConfig;
+
+    fn options() -> BootstrapOptions {
+        BootstrapOptions {
+            genesis_dir: PathBuf::from("/opt/aptos/genesis"),
+            data_dir: PathBuf::from("/opt/aptos/data"),
+            vfn_port: 6181,
+        }
+    }
+
+    fn address(host: &str, port: u16) -> NetworkAddress {
+        NetworkAddress::from_str(&format!("/dns/{}/tcp/{}", host, port))
+            .unwrap()
+            .append_prod_protos(x25519::PrivateKey::generate_for_testing().public_key(), 0)
+    }
+
+    fn validator_info(index: u8, fullnode_addresses: Vec<NetworkAddress>) -> ValidatorInfo {
+        let validator_addresses = vec![address(&format!("validator{}", index), 6180)];
+        validator_info_with_addresses(index, validator_addresses, fullnode_addresses)
+    }
+
+    fn validator_info_with_addresses(
+        index: u8,
+        validator_addresses: Vec<NetworkAddress>,
+        fullnode_addresses: Vec<NetworkAddress>,
+    ) -> ValidatorInfo {
+        let fullnode_addresses = if fullnode_addresses.is_empty() {
+            vec![]
+        } else {
+            bcs::to_bytes(&fullnode_addresses).unwrap()
+        };
+        ValidatorInfo::new(
+            AccountAddress::new([index; AccountAddress::LENGTH]),
+            10,
+            ValidatorConfig::new(
+                bls12381::PrivateKey::generate_for_testing().public_key(),
+                bcs::to_bytes(&validator_addresses).unwrap(),
+                fullnode_addresses,
+                index as u64,
+            ),
+        )
+    }
+
+    #[test]
+    fn test_with_port() {
+        let original = address("validator.example.com", 6180);
+        let replaced = with_port(&original, 6181).unwrap();
+        assert_eq!(replaced.find_port(), Some(6181));
+        assert_eq!(replaced.find_noise_proto(), original.find_noise_proto());
+        assert_eq!(
+            replaced.to_string(),
+            original.to_string().replace("/tcp/6180/", "/tcp/6181/")
+        );
+
+        // Addresses without a TCP port are left as they are
+        let memory = NetworkAddress::mock();
+        assert_eq!(with_port(&memory, 6181).unwrap(), memory);
+    }
+
+    #[test]
+    fn test_decode_validator() {
+        let info = validator_info(1, vec![address("fullnode1", 6182)]);
+        let validator = decode_validator(&info).unwrap();
+        assert_eq!(validator.account_address, *info.account_address());
+        assert_eq!(validator.validator_addresses.len(), 1);
+        assert_eq!(validator.fullnode_addresses.len(), 1);
+
+        let validator = decode_validator(&validator_info(2, vec![])).unwrap();
+        assert!(validator.fullnode_addresses.is_empty());
+
+        // Validator network addresses have to carry the noise public key
+        let info = validator_info_with_addresses(
+            3,
+            vec![NetworkAddress::from_str("/dns/validator3/tcp/6180").unwrap()],
+            vec![],
+        );
+        assert!(decode_validator(&info).is_err());
+        assert!(decode_validator(&validator_info_with_addresses(4, vec![], vec![])).is_err());
+    }
+
+    #[test]
+    fn test_validator_config() {
+        let validator = decode_validator(&validator_info(1, vec![])).unwrap();
+        let node_config = build_validator_config(&validator, &options()).unwrap();
+        NodeConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::test())).unwrap();
+
+        let validator_network = node_config.validator_network.as_ref().unwrap();
+        assert_eq!(validator_network.discovery_method, DiscoveryMethod::Onchain);
+        assert_eq!(
+            validator_network.listen_address,
+            listen_address(6180).unwrap()
+        );
+        assert_eq!(node_config.full_node_networks.len(), 1);
+        assert_eq!(node_config.full_node_networks[0].network_id, NetworkId::Vfn);
+        assert_eq!(
+            node_config.full_node_networks[0].listen_address,
+            listen_address(6181).unwrap()
+        );
+        assert_eq!(
+            node_config.execution.genesis_file_location,
+            PathBuf::from("/opt/aptos/genesis/genesis.blob")
+        );
+        assert_eq!(
+            node_config.storage.dir(),
+            PathBuf::from("/opt/aptos/data/db")
+        );
+    }
+
+    #[test]
+    fn test_fullnode_config() {
+        let validators = [
+            validator_info(1, vec![address("fullnode1", 6192)]),
+            validator_info(2, vec![address("fullnode2", 6182)]),
+            validator_info(3, vec![]),
+        ]
+        .iter()
+        .map(decode_validator)
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+        let node_config = build_fullnode_config(&validators[0], &validators, &options()).unwrap();
+        NodeConfig::sanitize(
+            &node_config,
+            NodeType::ValidatorFullnode,
+            Some(ChainId::test()),
+        )
+        .unwrap();
+
+        let [public_network, vfn_network] = &node_config.full_node_networks[..] else {
+            panic!("Expected a public and a VFN network");
+        };
+        assert_eq!(public_network.network_id, NetworkId::Public);
+        assert_eq!(public_network.listen_address, listen_address(6192).unwrap());
+        // Only the other validators with fullnode addresses are seeds
+        assert_eq!(public_network.seeds.len(), 1);
+        assert_eq!(
+            public_network.seeds[&validators[1].account_address].addresses,
+            validators[1].fullnode_addresses
+        );
+
+        // The VFN network connects to the validator at the VFN port
+        assert_eq!(vfn_network.network_id, NetworkId::Vfn);
+        let validator_peer = &vfn_network.seeds[&validators[0].account_address];
+        assert_eq!(validator_peer.role, PeerRole::Validator);
+        assert_eq!(validator_peer.addresses[0].find_port(), Some(6181));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod bootstrap;
 pub mod utils;
 
+pub use bootstrap::generate_bootstrap_bundle;
 pub use utils::extract_genesis_and_waypoint;
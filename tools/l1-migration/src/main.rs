@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::Parser;
 use l1_migration::{
-    extract_genesis_and_waypoint,
+    bootstrap::BootstrapOptions,
+    extract_genesis_and_waypoint, generate_bootstrap_bundle,
     utils::{decode_network_address, encode_network_address},
 };
 use std::path::PathBuf;
@@ -25,6 +26,22 @@ enum Commands {
         /// Destination directory for extracted files
         destination_path: PathBuf,
     },
+    /// Generate per-validator node configs from the validator set in the database
+    GenerateBootstrapBundle {
+        /// Path to the database directory
+        db_path: PathBuf,
+        /// Destination directory for the bundle
+        destination_path: PathBuf,
+        /// Directory the genesis and identity files are placed in on the nodes
+        #[arg(long, default_value = "/opt/aptos/genesis")]
+        genesis_dir: PathBuf,
+        /// Data directory of the nodes
+        #[arg(long, default_value = "/opt/aptos/data")]
+        data_dir: PathBuf,
+        /// Port validators listen on for their validator fullnodes
+        #[arg(long, default_value_t = 6181)]
+        vfn_port: u16,
+    },
     /// Network address encoding/decoding tool
     #[command(about = "Convert between multiaddr strings and BCS hex format")]
     NetworkAddress {
@@ -81,6 +98,36 @@ fn main() -> Result<()> {
 
             extract_genesis_and_waypoint(&db_path_str, &destination_path_str)
         },
+        Commands::GenerateBootstrapBundle {
+            db_path,
+            destination_path,
+            genesis_dir,
+            data_dir,
+            vfn_port,
+        } => {
+            if !db_path.exists() {
+                eprintln!(
+                    "Error: Database path '{}' does not exist",
+                    db_path.display()
+                );
+                std::process::exit(1);
+            }
+
+            if !destination_path.exists() {
+                std::fs::create_dir_all(&destination_path)?;
+            }
+
+            let options = BootstrapOptions {
+                genesis_dir,
+                data_dir,
+                vfn_port,
+            };
+            generate_bootstrap_bundle(
+                &db_path.to_string_lossy(),
+                &destination_path.to_string_lossy(),
+                &options,
+            )
+        },
         Commands::NetworkAddress { command } => match command {
             NetworkAddressCommands::Encode { multiaddr } => encode_network_address(&multiaddr),
             NetworkAddressCommands::Decode { hex } => decode_network_address(&hex),
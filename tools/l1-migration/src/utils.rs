@@ -7,6 +7,28 @@ use std::{fs, path::Path, str::FromStr};
 
 /// Extract genesis transaction and waypoint from an Aptos database
 pub fn extract_genesis_and_waypoint(db_path: &str, output_dir: &str) -> Result<()> {
+    let db = open_db(db_path)?;
+    let latest_ver = get_latest_version(&db)?;
+
+    // Extract genesis transaction
+    extract_genesis_transaction(&db, latest_ver, output_dir)?;
+
+    // Extract genesis waypoint
+    extract_genesis_waypoint(&db, latest_ver, output_dir)?;
+
+    // Extract waypoint
+    extract_waypoint(&db, output_dir)?;
+
+    println!("✓ Genesis extraction completed successfully!");
+    println!("  - genesis.blob: Contains the BCS-serialized genesis transaction");
+    println!("  - genesis_waypoint.txt: Contains the genesis waypoint for bootstrapping");
+    println!("  - waypoint.txt: Contains the initial waypoint for bootstrapping");
+
+    Ok(())
+}
+
+/// Open an Aptos database read-only
+pub fn open_db(db_path: &str) -> Result<AptosDB> {
     println!("Opening database at: {}", db_path);
 
     // Create storage directory paths
@@ -26,35 +48,24 @@ pub fn extract_genesis_and_waypoint(db_path: &str, output_dir: &str) -> Result<(
 
     println!("Database opened successfully");
 
+    Ok(db)
+}
+
+/// Get the latest synced version of the database
+pub fn get_latest_version(db: &AptosDB) -> Result<u64> {
     // Get the latest version to understand the database state
     let latest_version = db.get_synced_version()?;
     println!("Latest synced version: {:?}", latest_version);
 
-    if latest_version.is_none() {
-        return Err(anyhow::anyhow!("Database has no synced version"));
-    }
-
-    let latest_ver = latest_version.unwrap();
-
-    // Extract genesis transaction
-    extract_genesis_transaction(&db, latest_ver, output_dir)?;
-
-    // Extract genesis waypoint
-    extract_genesis_waypoint(&db, latest_ver, output_dir)?;
-
-    // Extract waypoint
-    extract_waypoint(&db, output_dir)?;
-
-    println!("✓ Genesis extraction completed successfully!");
-    println!("  - genesis.blob: Contains the BCS-serialized genesis transaction");
-    println!("  - genesis_waypoint.txt: Contains the genesis waypoint for bootstrapping");
-    println!("  - waypoint.txt: Contains the initial waypoint for bootstrapping");
-
-    Ok(())
+    latest_version.ok_or_else(|| anyhow::anyhow!("Database has no synced version"))
 }
 
 /// Extract the genesis transaction from the database
-fn extract_genesis_transaction(db: &AptosDB, latest_ver: u64, output_dir: &str) -> Result<()> {
+pub(crate) fn extract_genesis_transaction(
+    db: &AptosDB,
+    latest_ver: u64,
+    output_dir: &str,
+) -> Result<()> {
     println!("Extracting genesis transaction (version 0)...");
     let genesis_txn_with_proof = db.get_transaction_by_version(0, latest_ver, false)?;
     let genesis_transaction = genesis_txn_with_proof.transaction;
@@ -75,7 +86,11 @@ fn extract_genesis_transaction(db: &AptosDB, latest_ver: u64, output_dir: &str)
 }
 
 /// Extract the genesis waypoint from the database at version 0
-fn extract_genesis_waypoint(db: &AptosDB, _latest_ver: u64, output_dir: &str) -> Result<()> {
+pub(crate) fn extract_genesis_waypoint(
+    db: &AptosDB,
+    _latest_ver: u64,
+    output_dir: &str,
+) -> Result<()> {
     println!("Extracting genesis waypoint (version 0)...");
 
     // Get the epoch ending ledger info for version 0 (genesis)
@@ -99,7 +114,7 @@ fn extract_genesis_waypoint(db: &AptosDB, _latest_ver: u64, output_dir: &str) ->
 }
 
 /// Extract the waypoint from the database using proper waypoint conversion
-fn extract_waypoint(db: &AptosDB, output_dir: &str) -> Result<()> {
+pub(crate) fn extract_waypoint(db: &AptosDB, output_dir: &str) -> Result<()> {
     // Get the ledger info to extract waypoint
     let ledger_info_with_sigs = db.get_latest_ledger_info()?;
     let ledger_info = ledger_info_with_sigs.ledger_info();
@@ -194,19 +209,22 @@ pub fn decode_network_address(bcs_hex: &str) -> Result<()> {
         )
     })?;
 
-    // Try to decode as Vec<NetworkAddress> first (most common case)
-    if let Ok(addresses) = bcs::from_bytes::<Vec<NetworkAddress>>(&bcs_bytes) {
-        let addr_strings: Vec<String> = addresses.iter().map(|addr| addr.to_string()).collect();
-        let multiaddr_output = addr_strings.join(", ");
+    let addresses = decode_network_addresses(&bcs_bytes)?;
+    let addr_strings: Vec<String> = addresses.iter().map(|addr| addr.to_string()).collect();
+    println!("{}", addr_strings.join(", "));
 
-        println!("{}", multiaddr_output);
+    Ok(())
+}
 
-        Ok(())
-    } else if let Ok(network_address) = bcs::from_bytes::<NetworkAddress>(&bcs_bytes) {
+/// Decode BCS bytes holding either a `Vec<NetworkAddress>`, as stored in on-chain
+/// validator configs, or a single `NetworkAddress`
+pub fn decode_network_addresses(bcs_bytes: &[u8]) -> Result<Vec<NetworkAddress>> {
+    // Try to decode as Vec<NetworkAddress> first (most common case)
+    if let Ok(addresses) = bcs::from_bytes::<Vec<NetworkAddress>>(bcs_bytes) {
+        Ok(addresses)
+    } else if let Ok(network_address) = bcs::from_bytes::<NetworkAddress>(bcs_bytes) {
         // Try to decode as single NetworkAddress
-        println!("{}", network_address);
-
-        Ok(())
+        Ok(vec![network_address])
     } else {
         Err(anyhow::anyhow!("Failed to decode network address from BCS hex. Please ensure the input is a valid BCS-encoded NetworkAddress or Vec<NetworkAddress>"))
     }
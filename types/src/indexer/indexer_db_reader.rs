@@ -13,6 +13,7 @@ use crate::{
     transaction::{AccountOrderedTransactionsWithProof, Version},
 };
 use anyhow::Result;
+use move_core_types::language_storage::TypeTag;
 
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum Order {
@@ -43,6 +44,19 @@ pub trait IndexerReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<Vec<EventWithVersion>>;
 
+    /// Returns up to `limit` events of the given type emitted from `start_event_index` of
+    /// `start_version` and before `end_version`, in ascending order, each with its index among
+    /// the events of its transaction.
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(EventWithVersion, u64)>>;
+
     fn get_account_ordered_transactions(
         &self,
         address: AccountAddress,